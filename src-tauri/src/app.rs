@@ -6,8 +6,8 @@
 use crate::database::{create_pool, Repository};
 use crate::error::Result;
 use crate::services::{
-    AttachmentsService, BackupService, NotesService, RemindersService, SchedulerService,
-    SettingsService,
+    AttachmentsService, BackupService, ExportService, ImportService, JournalService, NotesService,
+    RemindersService, SchedulerService, SettingsService,
 };
 use crate::storage::BlobStore;
 use std::path::PathBuf;
@@ -33,6 +33,9 @@ pub struct AppState {
     pub notes_service: NotesService,
     pub attachments_service: AttachmentsService,
     pub backup_service: BackupService,
+    pub export_service: ExportService,
+    pub import_service: ImportService,
+    pub journal_service: JournalService,
     pub reminders_service: RemindersService,
     pub settings_service: SettingsService,
     pub scheduler_service: Option<Arc<SchedulerService>>,
@@ -59,15 +62,33 @@ impl AppState {
         blob_store.initialize().await?;
 
         // Initialize services
-        let notes_service = NotesService::new(db.clone());
+        let reminders_service = RemindersService::new(db.clone());
+        let notes_service = NotesService::new(db.clone()).with_reminders(reminders_service.clone());
+
+        // Read the links of notes written before link tracking (once)
+        if let Err(e) = notes_service.backfill_note_links().await {
+            tracing::warn!("Failed to backfill note links: {}", e);
+            // Don't fail startup if the link backfill fails
+        }
+
         let attachments_service = AttachmentsService::new(db.clone(), blob_store.clone());
         let backup_service =
             BackupService::new(db.clone(), blob_store.clone(), app_data_dir.clone());
-        let reminders_service = RemindersService::new(db.clone());
+        let export_service = ExportService::new(db.clone(), blob_store.clone());
+        let import_service = ImportService::new(db.clone(), attachments_service.clone());
+        let journal_service = JournalService::new(db.clone(), notes_service.clone());
         let settings_service = SettingsService::new(app_data_dir.clone());
 
         // Load backup directory from settings and apply it
         if let Ok(auto_backup_settings) = settings_service.get_auto_backup().await {
+            if let Err(e) = backup_service.set_incremental(auto_backup_settings.incremental) {
+                tracing::error!("Failed to apply incremental backup setting: {}", e);
+            }
+            if let Err(e) =
+                backup_service.set_verify_after_backup(auto_backup_settings.verify_after_backup)
+            {
+                tracing::error!("Failed to apply backup verification setting: {}", e);
+            }
             if let Some(backup_location) = auto_backup_settings.backup_location {
                 if let Err(e) = backup_service.set_backup_dir(PathBuf::from(backup_location)) {
                     tracing::error!("Failed to set custom backup directory: {}", e);
@@ -75,6 +96,26 @@ impl AppState {
             }
         }
 
+        // Apply revision history settings to the notes service
+        match settings_service.get_revisions().await {
+            Ok(revision_settings) => {
+                if let Err(e) = notes_service.set_revision_settings(revision_settings) {
+                    tracing::error!("Failed to apply revision settings: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to load revision settings: {}", e),
+        }
+
+        // Apply reminder settings (missed reminder defaults) to the reminders service
+        match settings_service.get_reminders().await {
+            Ok(reminder_settings) => {
+                if let Err(e) = reminders_service.set_reminder_settings(reminder_settings) {
+                    tracing::error!("Failed to apply reminder settings: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to load reminder settings: {}", e),
+        }
+
         // Initialize scheduler service for automatic backups
         let scheduler_service = match SchedulerService::new(backup_service.clone()).await {
             Ok(scheduler) => {
@@ -94,6 +135,9 @@ impl AppState {
             notes_service,
             attachments_service,
             backup_service,
+            export_service,
+            import_service,
+            journal_service,
             reminders_service,
             settings_service,
             scheduler_service,
@@ -172,6 +216,13 @@ pub fn setup(app: &mut App) -> Result<()> {
         });
     }
 
+    // Start scheduled blob garbage collection (runs only while enabled in settings)
+    let attachments_service = state.attachments_service.clone();
+    let settings_service = state.settings_service.clone();
+    tauri::async_runtime::spawn(async move {
+        run_scheduled_blob_gc(attachments_service, settings_service).await;
+    });
+
     // Setup system tray
     setup_tray(app)?;
 
@@ -229,6 +280,36 @@ pub fn setup(app: &mut App) -> Result<()> {
     Ok(())
 }
 
+/// Collect unreferenced blobs every `interval_hours` while scheduled blob GC
+/// is enabled. Settings are re-read hourly so changes apply without a restart.
+async fn run_scheduled_blob_gc(
+    attachments_service: AttachmentsService,
+    settings_service: SettingsService,
+) {
+    let mut intv = interval(Duration::from_secs(60 * 60));
+    let mut last_run = tokio::time::Instant::now();
+    loop {
+        intv.tick().await;
+
+        let settings = match settings_service.get_blob_gc().await {
+            Ok(settings) => settings,
+            Err(e) => {
+                tracing::warn!("Failed to load blob GC settings: {}", e);
+                continue;
+            }
+        };
+        let due = Duration::from_secs(u64::from(settings.interval_hours) * 60 * 60);
+        if !settings.enabled || last_run.elapsed() < due {
+            continue;
+        }
+
+        last_run = tokio::time::Instant::now();
+        if let Err(e) = attachments_service.collect_garbage(false).await {
+            tracing::error!("Scheduled blob garbage collection failed: {}", e);
+        }
+    }
+}
+
 /// Check for updates on application startup
 /// If an update is available, show the update-required window and hide the main window
 async fn check_for_update_on_startup(app: tauri::AppHandle, settings_service: SettingsService) {
@@ -395,6 +476,13 @@ fn setup_tray(app: &mut App) -> Result<()> {
         true,
         Some(hotkeys.quick_capture.as_str()),
     )?;
+    let daily_note_item = MenuItem::with_id(
+        app,
+        "daily_note",
+        "Today's Note",
+        true,
+        Some(hotkeys.daily_note.as_str()),
+    )?;
     let settings_item = MenuItem::with_id(
         app,
         "settings",
@@ -414,6 +502,7 @@ fn setup_tray(app: &mut App) -> Result<()> {
             &toggle_note_item,
             &toggle_all_notes_item,
             &quick_capture_item,
+            &daily_note_item,
             &settings_item,
             &about_item,
             &separator,
@@ -524,6 +613,18 @@ fn setup_tray(app: &mut App) -> Result<()> {
                         }
                     });
                 }
+                "daily_note" => {
+                    // Get or create today's note and open it
+                    let app_handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) =
+                            crate::commands::open_daily_note(app_handle.clone(), app_handle.state())
+                                .await
+                        {
+                            tracing::error!("Failed to open today's note from tray menu: {}", e);
+                        }
+                    });
+                }
                 "settings" => {
                     // Toggle settings window (show/hide)
                     if let Err(e) = crate::commands::toggle_settings_window(app.clone()) {
@@ -583,13 +684,14 @@ fn setup_global_hotkeys(app: &mut App) -> Result<()> {
         tokio::runtime::Runtime::new()?.block_on(async { settings_service.get_hotkeys().await })?;
 
     tracing::info!(
-        "Loaded hotkey settings: new_note={}, toggle_note={}, open_search={}, open_settings={}, toggle_all_notes={}, quick_capture={}",
+        "Loaded hotkey settings: new_note={}, toggle_note={}, open_search={}, open_settings={}, toggle_all_notes={}, quick_capture={}, daily_note={}",
         hotkeys.new_note,
         hotkeys.toggle_note,
         hotkeys.open_search,
         hotkeys.open_settings,
         hotkeys.toggle_all_notes,
-        hotkeys.quick_capture
+        hotkeys.quick_capture,
+        hotkeys.daily_note
     );
 
     // Unregister any existing hotkeys (from previous instance or crash)
@@ -600,6 +702,7 @@ fn setup_global_hotkeys(app: &mut App) -> Result<()> {
         &hotkeys.open_settings,
         &hotkeys.toggle_all_notes,
         &hotkeys.quick_capture,
+        &hotkeys.daily_note,
     ] {
         if app.global_shortcut().is_registered(shortcut.as_str()) {
             tracing::warn!(
@@ -789,6 +892,38 @@ fn setup_global_hotkeys(app: &mut App) -> Result<()> {
         hotkeys.quick_capture
     );
 
+    // Register hotkey for today's note
+    let daily_note_shortcut = hotkeys.daily_note.clone();
+    app.global_shortcut()
+        .on_shortcut(
+            daily_note_shortcut.as_str(),
+            move |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    tracing::info!("Daily note hotkey triggered");
+
+                    let app_handle = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) =
+                            crate::commands::open_daily_note(app_handle.clone(), app_handle.state())
+                                .await
+                        {
+                            tracing::error!("Failed to open today's note from hotkey: {}", e);
+                        }
+                    });
+                }
+            },
+        )
+        .map_err(|e| {
+            crate::error::AppError::Generic(format!(
+                "Failed to register daily note shortcut handler: {}",
+                e
+            ))
+        })?;
+    tracing::info!(
+        "Global hotkey daily_note ({}) registered successfully",
+        hotkeys.daily_note
+    );
+
     tracing::info!("Global hotkeys setup complete");
 
     Ok(())
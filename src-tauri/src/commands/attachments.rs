@@ -5,6 +5,7 @@
 use crate::app::AppState;
 use crate::database::Attachment;
 use crate::error::Result;
+use crate::services::BlobGcReport;
 use tauri::State;
 
 /// Create a new attachment for a note
@@ -48,3 +49,13 @@ pub async fn delete_attachment(state: State<'_, AppState>, attachment_id: String
         .delete_attachment(&attachment_id)
         .await
 }
+
+/// Delete blobs no longer referenced by any attachment, note or revision.
+/// With `dry_run`, only reports what would be reclaimed.
+#[tauri::command]
+pub async fn collect_blob_garbage(
+    state: State<'_, AppState>,
+    dry_run: bool,
+) -> Result<BlobGcReport> {
+    state.attachments_service.collect_garbage(dry_run).await
+}
@@ -1,18 +1,26 @@
 //! Backup-related commands
 //!
-//! Commands for creating, listing, restoring, and deleting backups.
+//! Commands for creating, listing, verifying, restoring, and deleting backups,
+//! and for restoring individual notes from a backup.
 
 use crate::app::AppState;
-use crate::database::Backup;
+use crate::database::{Backup, RestorableNote};
 use crate::error::{AppError, Result};
-use crate::services::CredentialManager;
-use std::path::Path;
+use crate::services::{
+    BackupVerificationReport, CredentialManager, NoteRestoreConflict, NoteRestoreReport,
+};
+use std::path::{Path, PathBuf};
 use tauri::{Emitter, State};
 
 /// Create an encrypted backup
-/// If password is None, uses the stored auto-backup password
+/// If password is None, uses the stored auto-backup password.
+/// If incremental is None, uses the auto-backup settings' mode.
 #[tauri::command]
-pub async fn create_backup(state: State<'_, AppState>, password: Option<String>) -> Result<String> {
+pub async fn create_backup(
+    state: State<'_, AppState>,
+    password: Option<String>,
+    incremental: Option<bool>,
+) -> Result<String> {
     // Use provided password or retrieve from credential manager
     let backup_password = match password {
         Some(pwd) if !pwd.is_empty() => pwd,
@@ -25,7 +33,15 @@ pub async fn create_backup(state: State<'_, AppState>, password: Option<String>)
         })?,
     };
 
-    let backup_path = state.backup_service.create_backup(&backup_password).await?;
+    let backup_path = match incremental {
+        Some(incremental) => {
+            state
+                .backup_service
+                .create_backup_with_mode(&backup_password, incremental)
+                .await?
+        }
+        None => state.backup_service.create_backup(&backup_password).await?,
+    };
     Ok(backup_path.to_string_lossy().to_string())
 }
 
@@ -46,7 +62,97 @@ pub async fn restore_backup(
     backup_path: String,
     password: String,
 ) -> Result<()> {
-    let path = Path::new(&backup_path);
+    let canonical_path = resolve_backup_path(&state, &backup_path)?;
+
+    state
+        .backup_service
+        .restore_backup(&canonical_path, &password)
+        .await?;
+
+    // The pool stays closed until the app restarts, so the scheduler can't
+    // resync here; it loads the restored reminders when it starts again.
+    // Emit event to notify frontend that restore completed
+    // Frontend should handle reconnection or app restart
+    if let Err(e) = app.emit("backup-restored", ()) {
+        tracing::warn!("Failed to emit backup-restored event: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Check a backup's checksums and database integrity without restoring it
+///
+/// Security: Same backups-directory restriction as `restore_backup`.
+#[tauri::command]
+pub async fn verify_backup(
+    state: State<'_, AppState>,
+    backup_path: String,
+    password: String,
+) -> Result<BackupVerificationReport> {
+    let canonical_path = resolve_backup_path(&state, &backup_path)?;
+
+    state
+        .backup_service
+        .verify_backup(&canonical_path, &password)
+        .await
+}
+
+/// List the notes contained in a backup
+///
+/// Security: Same backups-directory restriction as `restore_backup`.
+#[tauri::command]
+pub async fn list_backup_notes(
+    state: State<'_, AppState>,
+    backup_path: String,
+    password: String,
+) -> Result<Vec<RestorableNote>> {
+    let canonical_path = resolve_backup_path(&state, &backup_path)?;
+
+    state
+        .backup_service
+        .list_backup_notes(&canonical_path, &password)
+        .await
+}
+
+/// Restore selected notes from a backup without touching the rest of the library
+/// If conflict is None, notes that still exist are skipped.
+///
+/// Security: Same backups-directory restriction as `restore_backup`.
+#[tauri::command]
+pub async fn restore_backup_notes(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    backup_path: String,
+    password: String,
+    note_ids: Vec<String>,
+    conflict: Option<NoteRestoreConflict>,
+) -> Result<NoteRestoreReport> {
+    let canonical_path = resolve_backup_path(&state, &backup_path)?;
+
+    let report = state
+        .backup_service
+        .restore_backup_notes(
+            &canonical_path,
+            &password,
+            &note_ids,
+            conflict.unwrap_or_default(),
+        )
+        .await?;
+
+    if !report.restored.is_empty() {
+        state.reminders_service.resync_scheduler();
+        if let Err(e) = app.emit("notes-list-changed", ()) {
+            tracing::warn!("Failed to emit notes-list-changed event: {}", e);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Resolve a backup path, rejecting paths outside the backups directory
+/// to prevent path traversal attacks
+fn resolve_backup_path(state: &State<'_, AppState>, backup_path: &str) -> Result<PathBuf> {
+    let path = Path::new(backup_path);
 
     // Security: Validate that the backup path is within the allowed backups directory
     let backup_dir = state.backup_service.get_backup_directory()?;
@@ -71,18 +177,7 @@ pub async fn restore_backup(
         ));
     }
 
-    state
-        .backup_service
-        .restore_backup(&canonical_path, &password)
-        .await?;
-
-    // Emit event to notify frontend that restore completed
-    // Frontend should handle reconnection or app restart
-    if let Err(e) = app.emit("backup-restored", ()) {
-        tracing::warn!("Failed to emit backup-restored event: {}", e);
-    }
-
-    Ok(())
+    Ok(canonical_path)
 }
 
 /// Delete a backup (both file and database record)
@@ -0,0 +1,76 @@
+//! Export commands
+//!
+//! Markdown, HTML and bundle export of notes and collections.
+
+use crate::app::AppState;
+use crate::error::{AppError, Result};
+use crate::services::{BundleExportReport, ExportScope, HtmlExportReport, MarkdownExportReport};
+use std::path::Path;
+use tauri::State;
+
+/// Export notes as Markdown into a directory tree: one note (`note_id`),
+/// one collection (`collection_id`), or everything when neither is given
+#[tauri::command]
+pub async fn export_markdown(
+    state: State<'_, AppState>,
+    directory: String,
+    note_id: Option<String>,
+    collection_id: Option<String>,
+) -> Result<MarkdownExportReport> {
+    let scope = export_scope(note_id, collection_id)?;
+
+    tracing::info!("Markdown export of {:?} requested", scope);
+    state
+        .export_service
+        .export_markdown(scope, Path::new(&directory))
+        .await
+}
+
+/// Export notes as one self-contained HTML page in a directory: one note
+/// (`note_id`), one collection (`collection_id`), or everything when
+/// neither is given
+#[tauri::command]
+pub async fn export_html(
+    state: State<'_, AppState>,
+    directory: String,
+    note_id: Option<String>,
+    collection_id: Option<String>,
+) -> Result<HtmlExportReport> {
+    let scope = export_scope(note_id, collection_id)?;
+
+    tracing::info!("HTML export of {:?} requested", scope);
+    state
+        .export_service
+        .export_html(scope, Path::new(&directory))
+        .await
+}
+
+/// Export notes as a portable, unencrypted bundle (ZIP of JSON files and
+/// blobs) in a directory: one note (`note_id`), one collection
+/// (`collection_id`), or everything when neither is given
+#[tauri::command]
+pub async fn export_bundle(
+    state: State<'_, AppState>,
+    directory: String,
+    note_id: Option<String>,
+    collection_id: Option<String>,
+) -> Result<BundleExportReport> {
+    let scope = export_scope(note_id, collection_id)?;
+
+    tracing::info!("Bundle export of {:?} requested", scope);
+    state
+        .export_service
+        .export_bundle(scope, Path::new(&directory))
+        .await
+}
+
+fn export_scope(note_id: Option<String>, collection_id: Option<String>) -> Result<ExportScope> {
+    match (note_id, collection_id) {
+        (Some(_), Some(_)) => Err(AppError::Export(
+            "export either a note or a collection, not both".to_string(),
+        )),
+        (Some(id), None) => Ok(ExportScope::Note(id)),
+        (None, Some(id)) => Ok(ExportScope::Collection(id)),
+        (None, None) => Ok(ExportScope::All),
+    }
+}
@@ -0,0 +1,54 @@
+//! Import commands
+//!
+//! Cross-platform import from a folder of Markdown and text files, from
+//! Evernote `.enex` exports and from SwatNotes export bundles.
+
+use crate::app::AppState;
+use crate::error::Result;
+use crate::services::{EnexCollectionSource, ImportResult};
+use std::path::Path;
+use tauri::State;
+
+/// Import the `.md`, `.markdown` and `.txt` files under a directory as
+/// notes, mapping subfolders to collections
+#[tauri::command]
+pub async fn import_from_folder(
+    state: State<'_, AppState>,
+    directory: String,
+) -> Result<ImportResult> {
+    tracing::info!("Folder import from {} requested", directory);
+    state
+        .import_service
+        .import_folder(Path::new(&directory))
+        .await
+}
+
+/// Import an Evernote `.enex` export, or a directory of them, grouping notes
+/// into collections by notebook (default) or first tag
+#[tauri::command]
+pub async fn import_from_enex(
+    state: State<'_, AppState>,
+    path: String,
+    collections_by: Option<EnexCollectionSource>,
+) -> Result<ImportResult> {
+    tracing::info!("Evernote import from {} requested", path);
+    state
+        .import_service
+        .import_enex(Path::new(&path), collections_by.unwrap_or_default())
+        .await
+}
+
+/// Merge an export bundle (from `export_bundle`) into the library under new
+/// IDs
+#[tauri::command]
+pub async fn import_bundle(state: State<'_, AppState>, path: String) -> Result<ImportResult> {
+    tracing::info!("Bundle import from {} requested", path);
+    let result = state.import_service.import_bundle(Path::new(&path)).await?;
+
+    // Imported reminders are written straight to the database
+    if result.notes_imported > 0 {
+        state.reminders_service.resync_scheduler();
+    }
+
+    Ok(result)
+}
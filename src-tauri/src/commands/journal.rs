@@ -0,0 +1,61 @@
+//! Daily note (journal) commands
+//!
+//! Each local date has at most one daily note, created on first use from
+//! the journal settings (title format, collection, template).
+
+use crate::app::AppState;
+use crate::database::{JournalEntry, Note};
+use crate::error::Result;
+use crate::services::JournalService;
+use chrono::NaiveDate;
+use tauri::{AppHandle, State};
+
+/// Get the daily note of a date (default today), creating it if needed
+#[tauri::command]
+pub async fn get_daily_note(state: State<'_, AppState>, date: Option<NaiveDate>) -> Result<Note> {
+    let settings = state.settings_service.get_journal().await?;
+    state
+        .journal_service
+        .get_or_create_daily_note(date.unwrap_or_else(JournalService::today), &settings)
+        .await
+}
+
+/// Get or create today's note and open it in a sticky note window
+#[tauri::command]
+pub async fn open_daily_note(app: AppHandle, state: State<'_, AppState>) -> Result<Note> {
+    tracing::info!("Opening today's note");
+
+    let note = get_daily_note(state.clone(), None).await?;
+    crate::commands::open_note_window(app, state, note.id.clone()).await?;
+
+    Ok(note)
+}
+
+/// The nearest earlier date with a daily note, if any
+#[tauri::command]
+pub async fn get_previous_daily_note(
+    state: State<'_, AppState>,
+    date: NaiveDate,
+) -> Result<Option<JournalEntry>> {
+    state.journal_service.adjacent_daily_note(date, false).await
+}
+
+/// The nearest later date with a daily note, if any
+#[tauri::command]
+pub async fn get_next_daily_note(
+    state: State<'_, AppState>,
+    date: NaiveDate,
+) -> Result<Option<JournalEntry>> {
+    state.journal_service.adjacent_daily_note(date, true).await
+}
+
+/// The dates from `from` to `to` (inclusive) that have daily notes, for a
+/// calendar view
+#[tauri::command]
+pub async fn list_daily_notes(
+    state: State<'_, AppState>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<JournalEntry>> {
+    state.journal_service.list_daily_notes(from, to).await
+}
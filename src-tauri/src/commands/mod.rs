@@ -1,7 +1,7 @@
 //! Tauri commands exposed to the frontend
 //!
 //! This module organizes commands into logical submodules:
-//! - `notes`: Note CRUD operations and search
+//! - `notes`: Note CRUD operations, search and links
 //! - `windows`: Window management (sticky notes, settings)
 //! - `attachments`: Attachment operations
 //! - `backup`: Backup and restore operations
@@ -9,14 +9,24 @@
 //! - `settings`: Application settings
 //! - `updater`: Auto-update functionality
 //! - `collections`: Collection/folder operations
+//! - `tags`: Tag operations
+//! - `templates`: Note templates and creating notes from them
+//! - `export`: Markdown, HTML and bundle export
+//! - `journal`: Daily notes, their navigation and calendar
+//! - `import`: Markdown and text folder import, Evernote import, bundle import
 
 pub mod attachments;
 pub mod backup;
 pub mod collections;
+pub mod export;
+pub mod import;
+pub mod journal;
 pub mod notes;
 pub mod onenote;
 pub mod reminders;
 pub mod settings;
+pub mod tags;
+pub mod templates;
 pub mod updater;
 pub mod windows;
 
@@ -28,10 +38,15 @@ use tauri::State;
 pub use attachments::*;
 pub use backup::*;
 pub use collections::*;
+pub use export::*;
+pub use import::*;
+pub use journal::*;
 pub use notes::*;
 pub use onenote::*;
 pub use reminders::*;
 pub use settings::*;
+pub use tags::*;
+pub use templates::*;
 pub use updater::*;
 pub use windows::*;
 
@@ -3,8 +3,12 @@
 //! CRUD operations and search for notes.
 
 use crate::app::AppState;
-use crate::database::Note;
+use crate::database::{
+    Note, NoteLink, NoteListRequest, NotePage, NoteRevision, NoteRevisionSummary, NoteSort,
+    SearchResult,
+};
 use crate::error::Result;
+use crate::services::revisions::RevisionDiff;
 use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
@@ -34,6 +38,29 @@ pub async fn list_notes(state: State<'_, AppState>) -> Result<Vec<Note>> {
     state.notes_service.list_notes().await
 }
 
+/// List one page of note summaries (no content bodies), sorted and
+/// optionally filtered by collection or search query
+#[tauri::command]
+pub async fn list_note_summaries(
+    state: State<'_, AppState>,
+    sort: Option<NoteSort>,
+    descending: Option<bool>,
+    collection_id: Option<String>,
+    query: Option<String>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+) -> Result<NotePage> {
+    let req = NoteListRequest {
+        sort: sort.unwrap_or_default(),
+        descending,
+        collection_id,
+        query,
+        cursor,
+        limit,
+    };
+    state.notes_service.list_note_summaries(&req).await
+}
+
 /// Update a note
 #[tauri::command]
 pub async fn update_note(
@@ -61,6 +88,15 @@ pub async fn search_notes(state: State<'_, AppState>, query: String) -> Result<V
     state.notes_service.search_notes(&query).await
 }
 
+/// Search notes, returning highlighted match context for each result
+#[tauri::command]
+pub async fn search_notes_with_snippets(
+    state: State<'_, AppState>,
+    query: String,
+) -> Result<Vec<SearchResult>> {
+    state.notes_service.search_notes_with_snippets(&query).await
+}
+
 /// Get count of soft-deleted notes (in trash)
 #[tauri::command]
 pub async fn count_deleted_notes(state: State<'_, AppState>) -> Result<i64> {
@@ -73,6 +109,83 @@ pub async fn prune_deleted_notes(state: State<'_, AppState>) -> Result<i64> {
     state.notes_service.prune_deleted_notes().await
 }
 
+// ===== Revision History =====
+
+/// List a note's revisions, newest first
+#[tauri::command]
+pub async fn list_note_revisions(
+    state: State<'_, AppState>,
+    note_id: String,
+) -> Result<Vec<NoteRevisionSummary>> {
+    state.notes_service.list_revisions(&note_id).await
+}
+
+/// Get a single revision with its full content
+#[tauri::command]
+pub async fn get_note_revision(
+    state: State<'_, AppState>,
+    revision_id: String,
+) -> Result<NoteRevision> {
+    state.notes_service.get_revision(&revision_id).await
+}
+
+/// Diff two revisions (or a revision against the current note when `to_revision_id` is omitted)
+#[tauri::command]
+pub async fn diff_note_revisions(
+    state: State<'_, AppState>,
+    from_revision_id: String,
+    to_revision_id: Option<String>,
+) -> Result<RevisionDiff> {
+    state
+        .notes_service
+        .diff_revisions(&from_revision_id, to_revision_id.as_deref())
+        .await
+}
+
+/// Restore a revision as the note's current content
+#[tauri::command]
+pub async fn restore_note_revision(
+    state: State<'_, AppState>,
+    revision_id: String,
+) -> Result<Note> {
+    state.notes_service.restore_revision(&revision_id).await
+}
+
+/// Rename a note, optionally rewriting `[[...]]` links to it in other notes
+#[tauri::command]
+pub async fn rename_note(
+    state: State<'_, AppState>,
+    id: String,
+    title: String,
+    rewrite_links: Option<bool>,
+) -> Result<Note> {
+    state
+        .notes_service
+        .rename_note(&id, &title, rewrite_links.unwrap_or(false))
+        .await
+}
+
+/// List the links in a note
+#[tauri::command]
+pub async fn list_outgoing_links(
+    state: State<'_, AppState>,
+    note_id: String,
+) -> Result<Vec<NoteLink>> {
+    state.notes_service.list_outgoing_links(&note_id).await
+}
+
+/// List the links from other notes to a note
+#[tauri::command]
+pub async fn list_backlinks(state: State<'_, AppState>, note_id: String) -> Result<Vec<NoteLink>> {
+    state.notes_service.list_backlinks(&note_id).await
+}
+
+/// List links that don't resolve to any note
+#[tauri::command]
+pub async fn list_dangling_links(state: State<'_, AppState>) -> Result<Vec<NoteLink>> {
+    state.notes_service.list_dangling_links().await
+}
+
 /// Quick capture from clipboard - creates a new note from clipboard text
 #[tauri::command]
 pub async fn quick_capture_from_clipboard(
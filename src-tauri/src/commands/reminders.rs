@@ -1,10 +1,14 @@
 //! Reminder-related commands
 //!
-//! CRUD operations for note reminders.
+//! CRUD operations for note reminders, plus natural-language time
+//! previews, recurrence rules, the snooze / dismiss / acknowledge workflow
+//! with its history, and the handling of reminders missed while the app
+//! was closed.
 
 use crate::app::AppState;
-use crate::database::Reminder;
+use crate::database::{MissedReminderPolicy, Reminder, ReminderHistoryEntry};
 use crate::error::{AppError, Result};
+use crate::services::{MissedReminderSummary, ParsedReminderTime, SnoozeDuration};
 use tauri::State;
 
 /// Create a new reminder for a note
@@ -78,3 +82,99 @@ pub async fn update_reminder(
         )
         .await
 }
+
+/// Resolve a natural-language reminder time ("tomorrow 9am", "in 2 hours",
+/// "every monday 8:30") so the UI can show it before saving; the result
+/// feeds `create_reminder` and, for repeats, `set_reminder_recurrence`
+#[tauri::command]
+pub async fn preview_reminder_time(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<ParsedReminderTime> {
+    state.reminders_service.preview_reminder_time(&text)
+}
+
+/// Make a reminder recur, or stop it recurring when rule is None
+/// rule is an iCalendar RRULE subset, e.g. "FREQ=WEEKLY;BYDAY=MO,WE,FR".
+#[tauri::command]
+pub async fn set_reminder_recurrence(
+    state: State<'_, AppState>,
+    id: String,
+    rule: Option<String>,
+) -> Result<Reminder> {
+    state
+        .reminders_service
+        .set_reminder_recurrence(&id, rule.as_deref())
+        .await
+}
+
+/// Set how a reminder is handled if it comes due while the app is closed
+/// policy is "fire", "batch" or "skip"; None values fall back to the
+/// global reminder settings.
+#[tauri::command]
+pub async fn set_reminder_missed_policy(
+    state: State<'_, AppState>,
+    id: String,
+    policy: Option<MissedReminderPolicy>,
+    skip_after_hours: Option<u32>,
+) -> Result<Reminder> {
+    state
+        .reminders_service
+        .set_reminder_missed_policy(&id, policy, skip_after_hours)
+        .await
+}
+
+/// Get the reminders batched on startup because they came due while the
+/// app was closed (None if there were none)
+#[tauri::command]
+pub async fn get_missed_reminder_summary(
+    state: State<'_, AppState>,
+) -> Result<Option<MissedReminderSummary>> {
+    Ok(state.reminders_service.get_missed_reminder_summary().await)
+}
+
+/// Snooze a reminder
+/// duration is a preset ("five_minutes", "ten_minutes", "fifteen_minutes",
+/// "thirty_minutes", "one_hour", "tomorrow_morning") or {"minutes": n}.
+#[tauri::command]
+pub async fn snooze_reminder(
+    state: State<'_, AppState>,
+    id: String,
+    duration: SnoozeDuration,
+) -> Result<Reminder> {
+    state.reminders_service.snooze_reminder(&id, duration).await
+}
+
+/// Dismiss a fired reminder
+#[tauri::command]
+pub async fn dismiss_reminder(state: State<'_, AppState>, id: String) -> Result<Reminder> {
+    state.reminders_service.dismiss_reminder(&id).await
+}
+
+/// Acknowledge a fired reminder
+#[tauri::command]
+pub async fn acknowledge_reminder(state: State<'_, AppState>, id: String) -> Result<Reminder> {
+    state.reminders_service.acknowledge_reminder(&id).await
+}
+
+/// List reminder history (fired, snoozed, dismissed, acknowledged), newest first
+#[tauri::command]
+pub async fn list_reminder_history(
+    state: State<'_, AppState>,
+    reminder_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ReminderHistoryEntry>> {
+    state
+        .reminders_service
+        .list_reminder_history(reminder_id.as_deref(), limit)
+        .await
+}
+
+/// List fired reminders that were never snoozed, dismissed or acknowledged
+#[tauri::command]
+pub async fn list_missed_reminders(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+) -> Result<Vec<ReminderHistoryEntry>> {
+    state.reminders_service.list_missed_reminders(limit).await
+}
@@ -1,7 +1,8 @@
 //! Settings-related commands
 //!
 //! Commands for managing application settings including hotkeys, autostart,
-//! auto-backup configuration, behavior settings, and reminder settings.
+//! auto-backup configuration, behavior settings, reminder settings,
+//! note revision history, scheduled blob garbage collection, and daily notes.
 //!
 //! All update commands validate input against limits defined in `config.rs`
 //! before persisting (Rule 11b — Input Validation & Boundary Enforcement).
@@ -10,7 +11,8 @@ use crate::app::AppState;
 use crate::config;
 use crate::error::{AppError, Result};
 use crate::services::{
-    AutoBackupSettings, BehaviorSettings, CredentialManager, HotkeySettings, ReminderSettings,
+    journal, AutoBackupSettings, BehaviorSettings, BlobGcSettings, CredentialManager,
+    HotkeySettings, JournalService, JournalSettings, ReminderSettings, RevisionSettings,
 };
 use std::path::PathBuf;
 use tauri::State;
@@ -29,6 +31,7 @@ fn validate_hotkey_settings(hotkeys: &HotkeySettings) -> Result<()> {
         ("open_settings", &hotkeys.open_settings),
         ("toggle_all_notes", &hotkeys.toggle_all_notes),
         ("quick_capture", &hotkeys.quick_capture),
+        ("daily_note", &hotkeys.daily_note),
     ];
 
     for (name, value) in &fields {
@@ -100,6 +103,16 @@ fn validate_reminder_settings(settings: &ReminderSettings) -> Result<()> {
         ));
     }
 
+    if settings.missed_skip_after_hours < 1
+        || settings.missed_skip_after_hours > config::MAX_MISSED_SKIP_AFTER_HOURS
+    {
+        errors.push(format!(
+            "Missed reminder skip age must be between 1 and {} hours (got {})",
+            config::MAX_MISSED_SKIP_AFTER_HOURS,
+            settings.missed_skip_after_hours
+        ));
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -139,6 +152,61 @@ fn validate_behavior_settings(settings: &BehaviorSettings) -> Result<()> {
     }
 }
 
+/// Validate revision history settings against configured limits.
+/// Accumulates all errors before returning (Rule 11b batch feedback).
+fn validate_revision_settings(settings: &RevisionSettings) -> Result<()> {
+    let mut errors: Vec<String> = Vec::new();
+
+    if settings.min_interval_secs > config::MAX_REVISION_INTERVAL_SECS {
+        errors.push(format!(
+            "Revision interval must be at most {} seconds (got {})",
+            config::MAX_REVISION_INTERVAL_SECS,
+            settings.min_interval_secs
+        ));
+    }
+
+    if settings.max_per_note > config::MAX_REVISIONS_PER_NOTE {
+        errors.push(format!(
+            "Revisions per note must be at most {} (0 = unlimited, got {})",
+            config::MAX_REVISIONS_PER_NOTE,
+            settings.max_per_note
+        ));
+    }
+
+    if settings.thin_after_days > config::MAX_REVISION_THIN_AFTER_DAYS {
+        errors.push(format!(
+            "Revision thinning age must be at most {} days (0 = never thin, got {})",
+            config::MAX_REVISION_THIN_AFTER_DAYS,
+            settings.thin_after_days
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Generic(format!(
+            "Revision settings validation failed:\n- {}",
+            errors.join("\n- ")
+        )))
+    }
+}
+
+/// Validate blob garbage collection settings against configured limits.
+fn validate_blob_gc_settings(settings: &BlobGcSettings) -> Result<()> {
+    if !(config::MIN_BLOB_GC_INTERVAL_HOURS..=config::MAX_BLOB_GC_INTERVAL_HOURS)
+        .contains(&settings.interval_hours)
+    {
+        return Err(AppError::Generic(format!(
+            "Blob GC settings validation failed:\n- Interval must be between {} and {} hours (got {})",
+            config::MIN_BLOB_GC_INTERVAL_HOURS,
+            config::MAX_BLOB_GC_INTERVAL_HOURS,
+            settings.interval_hours
+        )));
+    }
+
+    Ok(())
+}
+
 /// Validate auto-backup settings against configured limits.
 /// Accumulates all errors before returning (Rule 11b batch feedback).
 fn validate_auto_backup_settings(settings: &AutoBackupSettings) -> Result<()> {
@@ -330,6 +398,11 @@ pub async fn update_auto_backup_settings(
         state.backup_service.set_backup_dir(default_dir)?;
     }
 
+    state.backup_service.set_incremental(settings.incremental)?;
+    state
+        .backup_service
+        .set_verify_after_backup(settings.verify_after_backup)?;
+
     // Update scheduler
     if let Some(scheduler) = &state.scheduler_service {
         use crate::services::scheduler::BackupFrequency;
@@ -370,11 +443,16 @@ pub async fn update_reminder_settings(
         .settings_service
         .update_reminders(settings.clone())
         .await?;
+    state
+        .reminders_service
+        .set_reminder_settings(settings.clone())?;
     tracing::info!(
         sound_enabled = settings.sound_enabled,
         shake_enabled = settings.shake_enabled,
         glow_enabled = settings.glow_enabled,
         sound_type = %settings.sound_type,
+        missed_policy = ?settings.missed_policy,
+        missed_skip_after_hours = settings.missed_skip_after_hours,
         "Reminder settings updated"
     );
     Ok(())
@@ -409,3 +487,101 @@ pub async fn update_behavior_settings(
     );
     Ok(())
 }
+
+// ===== Revision History Settings =====
+
+/// Get note revision history settings
+#[tauri::command]
+pub async fn get_revision_settings(state: State<'_, AppState>) -> Result<RevisionSettings> {
+    state.settings_service.get_revisions().await
+}
+
+/// Update note revision history settings (applied immediately)
+#[tauri::command]
+pub async fn update_revision_settings(
+    state: State<'_, AppState>,
+    settings: RevisionSettings,
+) -> Result<()> {
+    validate_revision_settings(&settings)?;
+
+    state
+        .settings_service
+        .update_revisions(settings.clone())
+        .await?;
+    state
+        .notes_service
+        .set_revision_settings(settings.clone())?;
+    tracing::info!(
+        enabled = settings.enabled,
+        min_interval_secs = settings.min_interval_secs,
+        max_per_note = settings.max_per_note,
+        thin_after_days = settings.thin_after_days,
+        "Revision settings updated"
+    );
+    Ok(())
+}
+
+// ===== Blob Garbage Collection Settings =====
+
+/// Get scheduled blob garbage collection settings
+#[tauri::command]
+pub async fn get_blob_gc_settings(state: State<'_, AppState>) -> Result<BlobGcSettings> {
+    state.settings_service.get_blob_gc().await
+}
+
+/// Update scheduled blob garbage collection settings
+/// (picked up by the background task within the hour)
+#[tauri::command]
+pub async fn update_blob_gc_settings(
+    state: State<'_, AppState>,
+    settings: BlobGcSettings,
+) -> Result<()> {
+    validate_blob_gc_settings(&settings)?;
+
+    state
+        .settings_service
+        .update_blob_gc(settings.clone())
+        .await?;
+    tracing::info!(
+        enabled = settings.enabled,
+        interval_hours = settings.interval_hours,
+        "Blob GC settings updated"
+    );
+    Ok(())
+}
+
+// ===== Journal Settings =====
+
+/// Get daily note settings
+#[tauri::command]
+pub async fn get_journal_settings(state: State<'_, AppState>) -> Result<JournalSettings> {
+    state.settings_service.get_journal().await
+}
+
+/// Update daily note settings (used for daily notes created from then on)
+#[tauri::command]
+pub async fn update_journal_settings(
+    state: State<'_, AppState>,
+    mut settings: JournalSettings,
+) -> Result<()> {
+    journal::format_title(&settings.title_format, JournalService::today())?;
+    settings.title_format = settings.title_format.trim().to_string();
+    if let Some(collection_id) = &settings.collection_id {
+        state.db.get_collection(collection_id).await?;
+    }
+    if let Some(template_id) = &settings.template_id {
+        state.notes_service.get_template(template_id).await?;
+    }
+
+    state
+        .settings_service
+        .update_journal(settings.clone())
+        .await?;
+    tracing::info!(
+        title_format = %settings.title_format,
+        collection_id = ?settings.collection_id,
+        template_id = ?settings.template_id,
+        "Journal settings updated"
+    );
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Tag-related commands
+//!
+//! Tags are labels that can be applied to any number of notes,
+//! independently of the note's collection.
+
+use crate::app::AppState;
+use crate::database::{Note, Tag, TagWithCount};
+use crate::error::Result;
+use tauri::State;
+
+/// Create a new tag
+#[tauri::command]
+pub async fn create_tag(
+    state: State<'_, AppState>,
+    name: String,
+    color: Option<String>,
+) -> Result<Tag> {
+    tracing::info!("Creating tag: {}", name);
+    state
+        .notes_service
+        .create_tag(&name, color.as_deref())
+        .await
+}
+
+/// List all tags with their note counts
+#[tauri::command]
+pub async fn list_tags(state: State<'_, AppState>) -> Result<Vec<TagWithCount>> {
+    state.notes_service.list_tags().await
+}
+
+/// Rename and/or recolor a tag
+#[tauri::command]
+pub async fn update_tag(
+    state: State<'_, AppState>,
+    id: String,
+    name: Option<String>,
+    color: Option<String>,
+) -> Result<Tag> {
+    tracing::info!("Updating tag: {}", id);
+    state
+        .notes_service
+        .update_tag(&id, name.as_deref(), color.as_deref())
+        .await
+}
+
+/// Delete a tag (notes are kept, only the tag assignment is removed)
+#[tauri::command]
+pub async fn delete_tag(state: State<'_, AppState>, id: String) -> Result<()> {
+    state.notes_service.delete_tag(&id).await
+}
+
+/// Merge one or more tags into a target tag
+#[tauri::command]
+pub async fn merge_tags(
+    state: State<'_, AppState>,
+    source_ids: Vec<String>,
+    target_id: String,
+) -> Result<Tag> {
+    state
+        .notes_service
+        .merge_tags(&source_ids, &target_id)
+        .await
+}
+
+/// Add a tag to a note by name, creating the tag if needed
+#[tauri::command]
+pub async fn add_tag_to_note(
+    state: State<'_, AppState>,
+    note_id: String,
+    tag_name: String,
+) -> Result<Tag> {
+    state
+        .notes_service
+        .add_tag_to_note(&note_id, &tag_name)
+        .await
+}
+
+/// Remove a tag from a note
+#[tauri::command]
+pub async fn remove_tag_from_note(
+    state: State<'_, AppState>,
+    note_id: String,
+    tag_id: String,
+) -> Result<()> {
+    state
+        .notes_service
+        .remove_tag_from_note(&note_id, &tag_id)
+        .await
+}
+
+/// List the tags applied to a note
+#[tauri::command]
+pub async fn list_note_tags(state: State<'_, AppState>, note_id: String) -> Result<Vec<Tag>> {
+    state.notes_service.list_note_tags(&note_id).await
+}
+
+/// List notes carrying a tag
+#[tauri::command]
+pub async fn list_notes_by_tag(state: State<'_, AppState>, tag_id: String) -> Result<Vec<Note>> {
+    state.notes_service.list_notes_by_tag(&tag_id).await
+}
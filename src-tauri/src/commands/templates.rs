@@ -0,0 +1,96 @@
+//! Template-related commands
+//!
+//! Templates are skeletons for notes created over and over (standups,
+//! meeting notes), with `{{variables}}` filled in at creation.
+
+use crate::app::AppState;
+use crate::database::{Note, NoteTemplate, SaveTemplateRequest};
+use crate::error::Result;
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Create a note template
+#[tauri::command]
+pub async fn create_template(
+    state: State<'_, AppState>,
+    name: String,
+    title: Option<String>,
+    content_json: String,
+    collection_id: Option<String>,
+    reminder_offset_minutes: Option<i64>,
+) -> Result<NoteTemplate> {
+    tracing::info!("Creating template: {}", name);
+    state
+        .notes_service
+        .create_template(SaveTemplateRequest {
+            name,
+            title: title.unwrap_or_default(),
+            content_json,
+            collection_id,
+            reminder_offset_minutes,
+        })
+        .await
+}
+
+/// Get a note template
+#[tauri::command]
+pub async fn get_template(state: State<'_, AppState>, id: String) -> Result<NoteTemplate> {
+    state.notes_service.get_template(&id).await
+}
+
+/// List all note templates
+#[tauri::command]
+pub async fn list_templates(state: State<'_, AppState>) -> Result<Vec<NoteTemplate>> {
+    state.notes_service.list_templates().await
+}
+
+/// Replace all fields of a note template
+#[tauri::command]
+pub async fn update_template(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+    title: Option<String>,
+    content_json: String,
+    collection_id: Option<String>,
+    reminder_offset_minutes: Option<i64>,
+) -> Result<NoteTemplate> {
+    tracing::info!("Updating template: {}", id);
+    state
+        .notes_service
+        .update_template(
+            &id,
+            SaveTemplateRequest {
+                name,
+                title: title.unwrap_or_default(),
+                content_json,
+                collection_id,
+                reminder_offset_minutes,
+            },
+        )
+        .await
+}
+
+/// Delete a note template
+#[tauri::command]
+pub async fn delete_template(state: State<'_, AppState>, id: String) -> Result<()> {
+    state.notes_service.delete_template(&id).await
+}
+
+/// Create a note from a template, optionally in another collection than
+/// the template's
+#[tauri::command]
+pub async fn create_note_from_template(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    template_id: String,
+    collection_id: Option<String>,
+) -> Result<Note> {
+    // {{clipboard}} is empty when the clipboard holds no text
+    let clipboard = app.clipboard().read_text().ok();
+
+    state
+        .notes_service
+        .create_note_from_template(&template_id, collection_id, clipboard)
+        .await
+}
@@ -53,6 +53,138 @@ pub const MAX_SHAKE_DURATION_MS: u32 = 5_000;
 /// Valid sound type presets for reminder notifications
 pub const VALID_SOUND_TYPES: &[&str] = &["whoosh", "chime", "bell", "gentle", "alert"];
 
+/// Maximum custom snooze in minutes (1 week)
+pub const MAX_SNOOZE_MINUTES: u32 = 10_080;
+
+/// Local hour the "tomorrow morning" snooze preset fires at
+pub const SNOOZE_MORNING_HOUR: u32 = 9;
+
+/// Minutes a fired reminder may go unanswered before it counts as missed
+/// (the popup may simply still be open)
+pub const MISSED_REMINDER_AFTER_MINUTES: i64 = 15;
+
+/// Reminder history entries returned when no limit is given
+pub const DEFAULT_REMINDER_HISTORY_LIMIT: u32 = 100;
+
+/// Maximum reminder history entries returned per request
+pub const MAX_REMINDER_HISTORY_LIMIT: u32 = 1_000;
+
+/// Maximum age in hours for the `skip` missed reminder policy (1 year)
+pub const MAX_MISSED_SKIP_AFTER_HOURS: u32 = 8_760;
+
+/// Longest the reminders scheduler sleeps between checks of the wall clock,
+/// bounding how late a clock change or resume from sleep is noticed
+pub const REMINDER_SCHEDULER_MAX_SLEEP_SECS: u64 = 30;
+
+/// Difference between wall-clock and monotonic time, in seconds, that
+/// counts as a clock change and makes the scheduler reload its queue
+pub const CLOCK_JUMP_TOLERANCE_SECS: i64 = 5;
+
+/// Local hour a natural-language reminder time fires at when it names a
+/// day but no time ("tomorrow", "every monday")
+pub const DEFAULT_REMINDER_HOUR: u32 = 9;
+
+/// Maximum length of a natural-language reminder time in characters
+pub const MAX_REMINDER_PHRASE_LENGTH: usize = 200;
+
+/// Word that starts an inline reminder marker in note text
+/// ("@remind 2026-11-03 14:00 call supplier"), matched case-insensitively
+pub const INLINE_REMINDER_MARKER: &str = "@remind";
+
+// ===== Tag Limits =====
+
+/// Maximum length of a tag name in characters
+pub const MAX_TAG_NAME_LENGTH: usize = 50;
+
+// ===== Revision History Settings Limits =====
+
+/// Maximum throttle interval between revision snapshots in seconds (1 day).
+/// Longer intervals would leave hours of edits without a restore point.
+pub const MAX_REVISION_INTERVAL_SECS: u32 = 86_400;
+
+/// Maximum revisions kept per note (0 means unlimited)
+pub const MAX_REVISIONS_PER_NOTE: u32 = 1_000;
+
+/// Maximum age in days before revisions are thinned to one per day (10 years)
+pub const MAX_REVISION_THIN_AFTER_DAYS: u32 = 3_650;
+
+// ===== Blob Garbage Collection =====
+
+/// Minimum age in seconds before an unreferenced blob may be collected (1 hour).
+/// Protects uploads whose attachment row or note embed isn't saved yet.
+pub const BLOB_GC_MIN_AGE_SECS: u64 = 3_600;
+
+/// Minimum interval between scheduled blob GC runs in hours
+pub const MIN_BLOB_GC_INTERVAL_HOURS: u32 = 1;
+
+/// Maximum interval between scheduled blob GC runs in hours (30 days)
+pub const MAX_BLOB_GC_INTERVAL_HOURS: u32 = 720;
+
+// ===== Note Templates =====
+
+/// Maximum length of a template name in characters
+pub const MAX_TEMPLATE_NAME_LENGTH: usize = 100;
+
+/// Maximum delay of a template's reminder after note creation in minutes
+/// (one year)
+pub const MAX_TEMPLATE_REMINDER_OFFSET_MINUTES: i64 = 365 * 24 * 60;
+
+// ===== Daily Notes =====
+
+/// Collection daily notes go into when no journal collection is chosen
+pub const JOURNAL_COLLECTION_NAME: &str = "Journal";
+
+/// Maximum length of the daily note title format in characters
+pub const MAX_JOURNAL_TITLE_FORMAT_LENGTH: usize = 100;
+
+// ===== Markdown Export =====
+
+/// Folder next to exported notes that their attachments are copied into
+pub const EXPORT_ATTACHMENTS_DIR: &str = "attachments";
+
+/// Maximum length of an exported note or collection file name in characters
+pub const MAX_EXPORT_FILE_NAME_LENGTH: usize = 120;
+
+// ===== Export Bundles =====
+
+/// Version of the bundle format `export_bundle` writes; bundles with a
+/// higher version are refused on import
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Maximum uncompressed size of a file read from a bundle, in bytes; above
+/// attachments' own limit, as `notes.jsonl` holds every note
+pub const MAX_BUNDLE_ENTRY_SIZE: u64 = 512 * 1024 * 1024;
+
+// ===== Note Links =====
+
+/// Prefix of Quill link attributes that point at a note by ID
+pub const NOTE_LINK_SCHEME: &str = "note://";
+
+// ===== Import =====
+
+/// File extensions (lowercase) that folder import reads as notes; all but
+/// `txt` are parsed as Markdown
+pub const IMPORT_FILE_EXTENSIONS: &[&str] = &["md", "markdown", "txt"];
+
+/// Colors cycled through for collections created by an import
+pub const IMPORT_COLLECTION_COLORS: &[&str] = &[
+    "#EF4444", // Red
+    "#22C55E", // Green
+    "#8B5CF6", // Violet
+    "#F97316", // Orange
+    "#06B6D4", // Cyan
+    "#EC4899", // Pink
+    "#EAB308", // Yellow
+    "#14B8A6", // Teal
+    "#6366F1", // Indigo
+    "#84CC16", // Lime
+    "#D946EF", // Fuchsia
+    "#F43F5E", // Rose
+    "#0EA5E9", // Sky
+    "#10B981", // Emerald
+    "#F59E0B", // Amber
+];
+
 // ===== Auto-Backup Settings Limits =====
 
 /// Minimum backup retention in days (at least 1 day)
@@ -2,18 +2,49 @@
 //!
 //! Provides AES-256-GCM encryption with Argon2id key derivation.
 //! All backups are encrypted with a user-provided password.
+//!
+//! Backups use the chunked streaming container (`StreamEncryptor` /
+//! `StreamDecryptor`) so archives never have to fit in memory:
+//!
+//! ```text
+//! header:  magic "SWNBAK" | version u8 | kdf u8 | m_cost u32 | t_cost u32
+//!          | p_cost u32 | salt [16] | base nonce [12] | chunk size u32
+//! chunk:   final flag u8 | ciphertext length u32 | ciphertext (incl. tag)
+//! ```
+//!
+//! Integers are little-endian. Chunk `i` is sealed with the base nonce XOR
+//! `i` and the header plus its final flag as associated data, so reordered,
+//! dropped or truncated chunks and header edits all fail authentication.
+//! `EncryptedData` (JSON) remains for small payloads and legacy backups.
 
 use crate::error::{AppError, Result};
 use aes_gcm::{
-    aead::{Aead, KeyInit, OsRng},
+    aead::{Aead, KeyInit, OsRng, Payload},
     Aes256Gcm, Nonce,
 };
 use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHasher};
+use argon2::{Algorithm, Argon2, Params, PasswordHasher, Version};
 use rand::RngCore;
+use std::io::{self, Read, Write};
 
 const NONCE_SIZE: usize = 12; // 96 bits for GCM
 const SALT_SIZE: usize = 16; // 128 bits
+const KEY_SIZE: usize = 32; // 256 bits for AES-256
+const TAG_SIZE: usize = 16; // GCM authentication tag
+
+/// Magic bytes at the start of a streaming container
+pub const STREAM_MAGIC: &[u8; 6] = b"SWNBAK";
+const STREAM_VERSION: u8 = 1;
+const KDF_ARGON2ID: u8 = 1;
+const STREAM_HEADER_SIZE: usize = 6 + 1 + 1 + 12 + SALT_SIZE + NONCE_SIZE + 4;
+/// Plaintext bytes per chunk
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+/// Largest chunk size accepted when reading (guards allocations)
+const MAX_STREAM_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+/// Upper bounds on header KDF params (guards against hostile headers)
+const MAX_KDF_M_COST: u32 = 1024 * 1024; // 1 GiB in KiB
+const MAX_KDF_T_COST: u32 = 16;
+const MAX_KDF_P_COST: u32 = 16;
 
 /// Encrypted data container
 #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -70,6 +101,354 @@ pub fn decrypt(encrypted: &EncryptedData, password: &str) -> Result<Vec<u8>> {
     Ok(plaintext)
 }
 
+/// Generate a random 256-bit key
+pub fn generate_key() -> Vec<u8> {
+    let mut key = vec![0u8; KEY_SIZE];
+    OsRng.fill_bytes(&mut key);
+    key
+}
+
+/// Encrypt data with AES-256-GCM under a raw key (no key derivation).
+/// Returns the random nonce followed by the ciphertext.
+pub fn encrypt_with_key(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce_bytes = [0u8; NONCE_SIZE];
+    OsRng.fill_bytes(&mut nonce_bytes);
+
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| AppError::Generic(format!("Cipher initialization failed: {}", e)))?;
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext)
+        .map_err(|e| AppError::Generic(format!("Encryption failed: {}", e)))?;
+
+    let mut output = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+    output.extend_from_slice(&nonce_bytes);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// Decrypt data produced by `encrypt_with_key`
+pub fn decrypt_with_key(data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < NONCE_SIZE {
+        return Err(AppError::Generic(
+            "Decryption failed: data too short".to_string(),
+        ));
+    }
+    let (nonce_bytes, ciphertext) = data.split_at(NONCE_SIZE);
+
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| AppError::Generic(format!("Cipher initialization failed: {}", e)))?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| AppError::Generic(format!("Decryption failed: {}", e)))
+}
+
+/// Whether data starts with the streaming container header
+pub fn is_stream_format(data: &[u8]) -> bool {
+    data.starts_with(STREAM_MAGIC)
+}
+
+/// Writer that encrypts everything written to it into the streaming
+/// container format. Call `finish` to write the final chunk.
+pub struct StreamEncryptor<W: Write> {
+    writer: W,
+    cipher: Aes256Gcm,
+    header: Vec<u8>,
+    base_nonce: [u8; NONCE_SIZE],
+    buffer: Vec<u8>,
+    counter: u64,
+}
+
+impl<W: Write> StreamEncryptor<W> {
+    /// Derive a key from the password and write the container header
+    pub fn new(mut writer: W, password: &str) -> Result<Self> {
+        let (m_cost, t_cost, p_cost) = (
+            Params::DEFAULT_M_COST,
+            Params::DEFAULT_T_COST,
+            Params::DEFAULT_P_COST,
+        );
+
+        let mut salt = [0u8; SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        let mut base_nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut base_nonce);
+
+        let mut header = Vec::with_capacity(STREAM_HEADER_SIZE);
+        header.extend_from_slice(STREAM_MAGIC);
+        header.push(STREAM_VERSION);
+        header.push(KDF_ARGON2ID);
+        header.extend_from_slice(&m_cost.to_le_bytes());
+        header.extend_from_slice(&t_cost.to_le_bytes());
+        header.extend_from_slice(&p_cost.to_le_bytes());
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&base_nonce);
+        header.extend_from_slice(&(STREAM_CHUNK_SIZE as u32).to_le_bytes());
+
+        let key = derive_stream_key(password, &salt, m_cost, t_cost, p_cost)?;
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| AppError::Generic(format!("Cipher initialization failed: {}", e)))?;
+
+        writer.write_all(&header)?;
+
+        Ok(Self {
+            writer,
+            cipher,
+            header,
+            base_nonce,
+            buffer: Vec::with_capacity(STREAM_CHUNK_SIZE),
+            counter: 0,
+        })
+    }
+
+    /// Write the final chunk and return the inner writer
+    pub fn finish(mut self) -> Result<W> {
+        let last = std::mem::take(&mut self.buffer);
+        self.write_chunk(&last, true)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_chunk(&mut self, plaintext: &[u8], last: bool) -> io::Result<()> {
+        let aad = chunk_aad(&self.header, last);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&chunk_nonce(&self.base_nonce, self.counter)),
+                Payload {
+                    msg: plaintext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|e| io::Error::other(format!("Encryption failed: {}", e)))?;
+        self.counter += 1;
+
+        self.writer.write_all(&[last as u8])?;
+        self.writer
+            .write_all(&(ciphertext.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&ciphertext)
+    }
+}
+
+impl<W: Write> Write for StreamEncryptor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        // Keep at least one byte buffered so `finish` never writes an
+        // empty final chunk after a full one
+        while self.buffer.len() > STREAM_CHUNK_SIZE {
+            let rest = self.buffer.split_off(STREAM_CHUNK_SIZE);
+            let chunk = std::mem::replace(&mut self.buffer, rest);
+            self.write_chunk(&chunk, false)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reader that decrypts a streaming container, failing with
+/// `InvalidData` on tampering, truncation or a wrong password
+pub struct StreamDecryptor<R: Read> {
+    reader: R,
+    cipher: Aes256Gcm,
+    header: Vec<u8>,
+    base_nonce: [u8; NONCE_SIZE],
+    chunk_size: usize,
+    plaintext: Vec<u8>,
+    position: usize,
+    counter: u64,
+    finished: bool,
+}
+
+impl<R: Read> StreamDecryptor<R> {
+    /// Read and validate the container header and derive the key
+    pub fn new(mut reader: R, password: &str) -> Result<Self> {
+        let mut header = vec![0u8; STREAM_HEADER_SIZE];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| AppError::Generic("Encrypted stream header is truncated".to_string()))?;
+
+        if !is_stream_format(&header) {
+            return Err(AppError::Generic(
+                "Not an encrypted stream (bad magic bytes)".to_string(),
+            ));
+        }
+        if header[6] != STREAM_VERSION {
+            return Err(AppError::Generic(format!(
+                "Unsupported encrypted stream version {}",
+                header[6]
+            )));
+        }
+        if header[7] != KDF_ARGON2ID {
+            return Err(AppError::Generic(format!(
+                "Unsupported key derivation function {}",
+                header[7]
+            )));
+        }
+
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([
+                header[offset],
+                header[offset + 1],
+                header[offset + 2],
+                header[offset + 3],
+            ])
+        };
+        let (m_cost, t_cost, p_cost) = (read_u32(8), read_u32(12), read_u32(16));
+        let salt_end = 20 + SALT_SIZE;
+        let nonce_end = salt_end + NONCE_SIZE;
+        let chunk_size = read_u32(nonce_end) as usize;
+
+        if m_cost > MAX_KDF_M_COST || t_cost > MAX_KDF_T_COST || p_cost > MAX_KDF_P_COST {
+            return Err(AppError::Generic(
+                "Encrypted stream key derivation parameters are out of range".to_string(),
+            ));
+        }
+        if chunk_size == 0 || chunk_size > MAX_STREAM_CHUNK_SIZE {
+            return Err(AppError::Generic(format!(
+                "Encrypted stream chunk size {} is out of range",
+                chunk_size
+            )));
+        }
+
+        let key = derive_stream_key(password, &header[20..salt_end], m_cost, t_cost, p_cost)?;
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| AppError::Generic(format!("Cipher initialization failed: {}", e)))?;
+
+        let mut base_nonce = [0u8; NONCE_SIZE];
+        base_nonce.copy_from_slice(&header[salt_end..nonce_end]);
+
+        Ok(Self {
+            reader,
+            cipher,
+            header,
+            base_nonce,
+            chunk_size,
+            plaintext: Vec::new(),
+            position: 0,
+            counter: 0,
+            finished: false,
+        })
+    }
+
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let mut frame = [0u8; 5];
+        self.reader.read_exact(&mut frame).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                invalid_data("Encrypted stream is truncated")
+            } else {
+                e
+            }
+        })?;
+
+        let last = match frame[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid_data("Invalid chunk flag in encrypted stream")),
+        };
+        let length = u32::from_le_bytes([frame[1], frame[2], frame[3], frame[4]]) as usize;
+        if length < TAG_SIZE || length > self.chunk_size + TAG_SIZE {
+            return Err(invalid_data("Invalid chunk length in encrypted stream"));
+        }
+
+        let mut ciphertext = vec![0u8; length];
+        self.reader.read_exact(&mut ciphertext).map_err(|e| {
+            if e.kind() == io::ErrorKind::UnexpectedEof {
+                invalid_data("Encrypted stream is truncated")
+            } else {
+                e
+            }
+        })?;
+
+        let aad = chunk_aad(&self.header, last);
+        self.plaintext = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&chunk_nonce(&self.base_nonce, self.counter)),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| invalid_data("Decryption failed: wrong password or corrupted data"))?;
+        self.position = 0;
+        self.counter += 1;
+
+        if last {
+            self.finished = true;
+            let mut trailing = [0u8; 1];
+            if self.reader.read(&mut trailing)? != 0 {
+                return Err(invalid_data(
+                    "Unexpected data after the final chunk of encrypted stream",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for StreamDecryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.plaintext.len() {
+            if self.finished {
+                return Ok(0);
+            }
+            self.read_chunk()?;
+        }
+
+        let count = buf.len().min(self.plaintext.len() - self.position);
+        buf[..count].copy_from_slice(&self.plaintext[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Nonce for chunk `counter`: base nonce with the counter XORed into its
+/// last eight bytes
+fn chunk_nonce(base: &[u8; NONCE_SIZE], counter: u64) -> [u8; NONCE_SIZE] {
+    let mut nonce = *base;
+    for (byte, counter_byte) in nonce[NONCE_SIZE - 8..]
+        .iter_mut()
+        .zip(counter.to_be_bytes())
+    {
+        *byte ^= counter_byte;
+    }
+    nonce
+}
+
+/// Associated data for a chunk: the full header plus its final flag
+fn chunk_aad(header: &[u8], last: bool) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(header.len() + 1);
+    aad.extend_from_slice(header);
+    aad.push(last as u8);
+    aad
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Derive a 256-bit key with explicit Argon2id parameters (streaming format)
+fn derive_stream_key(
+    password: &str,
+    salt: &[u8],
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+) -> Result<[u8; KEY_SIZE]> {
+    let params = Params::new(m_cost, t_cost, p_cost, Some(KEY_SIZE))
+        .map_err(|e| AppError::Generic(format!("Invalid key derivation parameters: {}", e)))?;
+
+    let mut key = [0u8; KEY_SIZE];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Generic(format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
 /// Derive 256-bit key from password using Argon2id
 fn derive_key(password: &str, salt: &[u8]) -> Result<Vec<u8>> {
     let argon2 = Argon2::default();
@@ -208,6 +587,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encrypt_with_key() {
+        let key = generate_key();
+        let plaintext = b"Blob contents";
+
+        let encrypted = encrypt_with_key(plaintext, &key).unwrap();
+        assert_eq!(decrypt_with_key(&encrypted, &key).unwrap(), plaintext);
+
+        // Wrong key, tampering and truncation are all rejected
+        assert!(decrypt_with_key(&encrypted, &generate_key()).is_err());
+        let mut tampered = encrypted.clone();
+        tampered[NONCE_SIZE] ^= 0xFF;
+        assert!(decrypt_with_key(&tampered, &key).is_err());
+        assert!(decrypt_with_key(&encrypted[..4], &key).is_err());
+    }
+
+    fn stream_encrypt(plaintext: &[u8], password: &str) -> Vec<u8> {
+        let mut encryptor = StreamEncryptor::new(Vec::new(), password).unwrap();
+        // Write in odd-sized pieces to exercise chunk buffering
+        for piece in plaintext.chunks(10_007) {
+            encryptor.write_all(piece).unwrap();
+        }
+        encryptor.finish().unwrap()
+    }
+
+    fn stream_decrypt(data: &[u8], password: &str) -> Result<Vec<u8>> {
+        let mut decryptor = StreamDecryptor::new(data, password)?;
+        let mut plaintext = Vec::new();
+        decryptor
+            .read_to_end(&mut plaintext)
+            .map_err(|e| AppError::Generic(e.to_string()))?;
+        Ok(plaintext)
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        let password = "stream_password";
+        for size in [0, 1, STREAM_CHUNK_SIZE, STREAM_CHUNK_SIZE * 3 + 17] {
+            let plaintext: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+
+            let encrypted = stream_encrypt(&plaintext, password);
+            assert!(is_stream_format(&encrypted));
+            // Overhead is the header plus a frame and tag per chunk
+            let chunks = size.div_ceil(STREAM_CHUNK_SIZE).max(1);
+            assert_eq!(
+                encrypted.len(),
+                STREAM_HEADER_SIZE + size + chunks * (5 + TAG_SIZE)
+            );
+
+            assert_eq!(stream_decrypt(&encrypted, password).unwrap(), plaintext);
+        }
+    }
+
+    #[test]
+    fn test_stream_rejects_tampering() {
+        let password = "stream_password";
+        let plaintext = vec![0x42u8; STREAM_CHUNK_SIZE * 2 + 5];
+        let encrypted = stream_encrypt(&plaintext, password);
+
+        assert!(stream_decrypt(&encrypted, "wrong_password").is_err());
+
+        // Header, chunk body and tag edits
+        for offset in [10, STREAM_HEADER_SIZE + 20, encrypted.len() - 1] {
+            let mut tampered = encrypted.clone();
+            tampered[offset] ^= 0x01;
+            assert!(stream_decrypt(&tampered, password).is_err());
+        }
+
+        // Dropping the final chunk or appending data
+        let first_chunk_end = STREAM_HEADER_SIZE + 5 + STREAM_CHUNK_SIZE + TAG_SIZE;
+        assert!(stream_decrypt(&encrypted[..first_chunk_end], password).is_err());
+        let mut extended = encrypted.clone();
+        extended.push(0);
+        assert!(stream_decrypt(&extended, password).is_err());
+
+        assert!(!is_stream_format(b"{\"salt\":[1,2,3]}"));
+    }
+
     #[test]
     fn test_special_characters_in_password() {
         let plaintext = b"Secret data";
@@ -0,0 +1,14 @@
+-- Note revision history
+-- Each row is a snapshot of a note's title and content taken before an update
+-- overwrote it, so accidental edits can be reviewed and rolled back.
+
+CREATE TABLE IF NOT EXISTS note_revisions (
+    id TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    content_json TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_revisions_note_id ON note_revisions(note_id, created_at DESC);
@@ -0,0 +1,5 @@
+-- Recurring reminders: an iCalendar RRULE subset plus the local wall-clock
+-- start of the series (NULL for one-off reminders)
+
+ALTER TABLE reminders ADD COLUMN recurrence_rule TEXT DEFAULT NULL;
+ALTER TABLE reminders ADD COLUMN recurrence_start TEXT DEFAULT NULL;
@@ -0,0 +1,18 @@
+-- Reminder history: each time a reminder fires and how the user responded
+-- (snoozed, dismissed, acknowledged), plus a reminder's pending snooze
+
+ALTER TABLE reminders ADD COLUMN snoozed_until TEXT DEFAULT NULL;
+
+CREATE TABLE IF NOT EXISTS reminder_history (
+    id TEXT PRIMARY KEY,
+    reminder_id TEXT NOT NULL,
+    note_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    occurred_at TEXT NOT NULL,
+    scheduled_for TEXT,
+    snoozed_until TEXT,
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reminder_history_reminder ON reminder_history(reminder_id, occurred_at);
+CREATE INDEX IF NOT EXISTS idx_reminder_history_occurred ON reminder_history(occurred_at);
@@ -0,0 +1,5 @@
+-- Per-reminder handling of reminders that came due while the app was closed
+-- (NULL means use the global reminder settings)
+
+ALTER TABLE reminders ADD COLUMN missed_policy TEXT DEFAULT NULL;
+ALTER TABLE reminders ADD COLUMN missed_skip_after_hours INTEGER DEFAULT NULL;
@@ -0,0 +1,7 @@
+-- Reminders created from "@remind" markers in note text, linked to the
+-- marker they came from (NULL for reminders created directly).
+-- Spans are UTF-16 offsets into the note's plain text.
+
+ALTER TABLE reminders ADD COLUMN inline_text TEXT DEFAULT NULL;
+ALTER TABLE reminders ADD COLUMN inline_start INTEGER DEFAULT NULL;
+ALTER TABLE reminders ADD COLUMN inline_end INTEGER DEFAULT NULL;
@@ -0,0 +1,12 @@
+-- Links between notes: the targets each note links to, as written
+-- ("[[Title]]" links or note IDs). Targets are resolved to notes when
+-- links are read, so links to notes created later start resolving.
+
+CREATE TABLE IF NOT EXISTS note_links (
+    source_note_id TEXT NOT NULL,
+    target TEXT NOT NULL COLLATE NOCASE,
+    PRIMARY KEY (source_note_id, target),
+    FOREIGN KEY (source_note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_links_target ON note_links(target);
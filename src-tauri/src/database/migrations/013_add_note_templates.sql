@@ -0,0 +1,14 @@
+-- Note templates: skeletons new notes are created from, kept apart from
+-- notes so they don't show up in note lists or search
+
+CREATE TABLE IF NOT EXISTS note_templates (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    content_json TEXT NOT NULL,
+    collection_id TEXT DEFAULT NULL,
+    reminder_offset_minutes INTEGER DEFAULT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE SET NULL
+);
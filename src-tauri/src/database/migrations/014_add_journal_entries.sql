@@ -0,0 +1,9 @@
+-- Daily notes: the journal note of each local date (YYYY-MM-DD), so a
+-- day's note is found again however it is renamed
+
+CREATE TABLE IF NOT EXISTS journal_entries (
+    entry_date TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+);
@@ -4,15 +4,20 @@
 //! - Schema and migrations
 //! - Model definitions
 //! - Repository layer for CRUD operations
+//! - Search query parsing
+//! - Cursor pagination for note listings
 
 pub mod migrations;
 pub mod models;
+pub mod pagination;
 pub mod repository;
 pub mod schema;
+pub mod search_query;
 
 pub use models::*;
 pub use repository::Repository;
 pub use schema::initialize_database;
+pub use search_query::SearchQuery;
 
 use crate::error::Result;
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
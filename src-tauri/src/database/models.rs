@@ -3,7 +3,7 @@
 //! Rust structs representing database entities.
 //! All models use serde for serialization to frontend.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -25,6 +25,112 @@ pub struct Note {
     pub collection_id: Option<String>,
 }
 
+/// A search hit with match context for result display.
+/// Highlight and snippet text is HTML-escaped, with matches wrapped in `<mark>`.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SearchResult {
+    #[sqlx(flatten)]
+    pub note: Note,
+    /// Title with matched terms highlighted
+    pub title_highlight: String,
+    /// Fragment of the note text around the matches (start of the note when
+    /// the query has no free-text terms)
+    pub content_snippet: String,
+    /// FTS5 bm25 score (lower is more relevant); None when not ranked by text
+    pub score: Option<f64>,
+    /// Whether an attachment filename matched the query
+    #[sqlx(default)]
+    pub attachment_match: bool,
+}
+
+/// Lightweight note listing entry (no content body)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteSummary {
+    pub id: String,
+    pub title: String,
+    /// Start of the note's plain text
+    pub preview: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub collection_id: Option<String>,
+    pub attachment_count: i64,
+}
+
+/// Sort key for note listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteSort {
+    /// Last modified (newest first by default)
+    #[default]
+    Updated,
+    /// Creation time (newest first by default)
+    Created,
+    /// Title, case-insensitive (A–Z by default)
+    Title,
+    /// FTS5 bm25 relevance (best first by default). Falls back to `Updated`
+    /// when the query has no free-text terms.
+    Relevance,
+}
+
+impl NoteSort {
+    /// Whether this key sorts descending unless the request says otherwise
+    pub fn descending_by_default(self) -> bool {
+        matches!(self, NoteSort::Updated | NoteSort::Created)
+    }
+}
+
+/// Paginated note listing request
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NoteListRequest {
+    #[serde(default)]
+    pub sort: NoteSort,
+    /// Sort direction (None = the key's default direction)
+    #[serde(default)]
+    pub descending: Option<bool>,
+    /// Only list notes in this collection
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    /// Search query (see `search_query`); empty lists all notes
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Cursor from the previous page's `next_cursor`
+    #[serde(default)]
+    pub cursor: Option<String>,
+    /// Page size (default 50, max 500)
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// One page of a note listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotePage {
+    pub items: Vec<NoteSummary>,
+    /// Cursor for the next page; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Snapshot of a note's title and content taken before an update
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteRevision {
+    pub id: String,
+    pub note_id: String,
+    pub title: String,
+    /// JSON-encoded Quill Delta format
+    pub content_json: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Lightweight revision listing entry (no content body)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteRevisionSummary {
+    pub id: String,
+    pub note_id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    /// Length of the revision's content JSON in characters
+    pub content_length: i64,
+}
+
 /// A collection/folder for organizing notes
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Collection {
@@ -58,6 +164,95 @@ pub struct UpdateCollectionRequest {
     pub sort_order: Option<i32>,
 }
 
+/// A tag that can be applied to any number of notes
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A tag with the number of (non-deleted) notes it is applied to
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TagWithCount {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    pub created_at: DateTime<Utc>,
+    pub note_count: i64,
+}
+
+/// Update tag request
+#[derive(Debug, Deserialize)]
+pub struct UpdateTagRequest {
+    pub id: String,
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+/// A link from one note to another, with its target resolved to a
+/// non-deleted note when one matches by ID, or else by title
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteLink {
+    pub source_note_id: String,
+    pub source_title: String,
+    /// The link target as written: a note title or ID
+    pub target: String,
+    /// The note the target resolves to; None for a dangling link
+    pub target_note_id: Option<String>,
+    pub target_title: Option<String>,
+}
+
+/// New content for a note whose links are rewritten by a rename
+#[derive(Debug, Clone)]
+pub struct LinkRewrite {
+    pub note_id: String,
+    pub content_json: String,
+    /// Link targets in the new content
+    pub links: Vec<String>,
+}
+
+/// A skeleton new notes are created from, with `{{variables}}` in its
+/// title and text filled in at creation (see `services::templates`)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteTemplate {
+    pub id: String,
+    pub name: String,
+    /// Title of notes created from the template; empty to let the title
+    /// follow the content
+    pub title: String,
+    pub content_json: String,
+    /// Collection new notes go into unless another one is chosen
+    pub collection_id: Option<String>,
+    /// Remind about new notes this many minutes after they are created
+    pub reminder_offset_minutes: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Fields of a template to create, or to replace an existing template's with
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveTemplateRequest {
+    pub name: String,
+    #[serde(default)]
+    pub title: String,
+    pub content_json: String,
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    #[serde(default)]
+    pub reminder_offset_minutes: Option<i64>,
+}
+
+/// A day's journal note
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JournalEntry {
+    /// Local date the note is for
+    pub date: NaiveDate,
+    pub note_id: String,
+    pub title: String,
+}
+
 /// Create note request
 #[derive(Debug, Deserialize)]
 pub struct CreateNoteRequest {
@@ -111,6 +306,76 @@ pub struct Reminder {
     /// Per-reminder glow effect setting (None = use global default)
     #[sqlx(default)]
     pub glow_enabled: Option<bool>,
+    /// Recurrence rule (iCalendar RRULE subset; None = one-off reminder)
+    #[sqlx(default)]
+    pub recurrence_rule: Option<String>,
+    /// Local wall-clock time of the series' first occurrence
+    #[sqlx(default)]
+    pub recurrence_start: Option<NaiveDateTime>,
+    /// Pending snooze; the reminder fires again at this time
+    #[sqlx(default)]
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// What to do if it came due while the app was closed (None = use global default)
+    #[sqlx(default)]
+    pub missed_policy: Option<MissedReminderPolicy>,
+    /// Age in hours after which `Skip` drops it (None = use global default)
+    #[sqlx(default)]
+    pub missed_skip_after_hours: Option<u32>,
+    /// The "@remind" marker in the note's text this reminder came from
+    /// (None = created directly)
+    #[sqlx(default)]
+    pub inline_text: Option<String>,
+    /// Start of the marker in the note's plain text (UTF-16 offset)
+    #[sqlx(default)]
+    pub inline_start: Option<u32>,
+    /// End of the marker in the note's plain text (UTF-16 offset, exclusive)
+    #[sqlx(default)]
+    pub inline_end: Option<u32>,
+}
+
+/// How to handle a reminder that came due while the app was closed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum MissedReminderPolicy {
+    /// Pop it up on its own, as if it were on time
+    Fire,
+    /// List it in the missed reminders summary
+    #[default]
+    Batch,
+    /// Drop it if it is older than the skip age, otherwise batch it
+    Skip,
+}
+
+/// What happened to a reminder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ReminderAction {
+    /// The reminder popped up
+    Fired,
+    Snoozed,
+    Dismissed,
+    Acknowledged,
+    /// Came due while the app was closed and was dropped on startup
+    Skipped,
+}
+
+/// Reminder history entry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReminderHistoryEntry {
+    pub id: String,
+    pub reminder_id: String,
+    pub note_id: String,
+    /// Title of the note (None once the note is gone)
+    #[sqlx(default)]
+    pub note_title: Option<String>,
+    pub action: ReminderAction,
+    pub occurred_at: DateTime<Utc>,
+    /// For `Fired`: the trigger or snooze time that fired
+    pub scheduled_for: Option<DateTime<Utc>>,
+    /// For `Snoozed`: when the reminder fires again
+    pub snoozed_until: Option<DateTime<Utc>>,
 }
 
 /// Backup record
@@ -123,6 +388,35 @@ pub struct Backup {
     pub manifest_hash: String,
 }
 
+/// A note found in a backup, for picking notes to restore
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RestorableNote {
+    pub id: String,
+    pub title: String,
+    pub collection_id: Option<String>,
+    /// Name of the note's collection in the backup
+    pub collection_name: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    /// Set when the note was in the trash when the backup was taken
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub attachment_count: i64,
+    /// Whether a note with this ID still exists in the library (including trash)
+    #[sqlx(default)]
+    pub in_library: bool,
+}
+
+/// A note together with its attachments, reminders, tags and collection
+#[derive(Debug, Clone)]
+pub struct NoteSnapshot {
+    pub note: Note,
+    pub attachments: Vec<Attachment>,
+    pub reminders: Vec<Reminder>,
+    pub tags: Vec<Tag>,
+    pub collection: Option<Collection>,
+    /// Date the note is the daily note of
+    pub journal_date: Option<NaiveDate>,
+}
+
 /// Application setting (reserved for future use)
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
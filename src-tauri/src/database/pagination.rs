@@ -0,0 +1,119 @@
+//! Cursor pagination for note listings
+//!
+//! Listings use keyset pagination: each page ends with an opaque cursor
+//! holding the sort key and id of its last row, and the next page starts
+//! strictly after that position. Unlike OFFSET paging this stays fast on
+//! large tables and doesn't skip or repeat rows when notes are added or
+//! removed between requests.
+
+use super::models::NoteSort;
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite};
+
+/// Page size when the request doesn't give one
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Largest page size a request may ask for
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Position after the last row of a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct NoteCursor {
+    pub sort: NoteSort,
+    pub descending: bool,
+    pub key: CursorKey,
+    pub id: String,
+}
+
+/// Sort key value of the last row of a page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum CursorKey {
+    /// Raw column text (timestamps and titles)
+    Text(String),
+    /// FTS5 bm25 score
+    Score(f64),
+}
+
+impl NoteCursor {
+    /// Encode as the opaque string handed to the frontend
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Decode a cursor, checking it belongs to a listing with the same order
+    pub fn decode(cursor: &str, sort: NoteSort, descending: bool) -> Result<Self> {
+        let decoded: Self = serde_json::from_str(cursor)
+            .map_err(|_| AppError::Generic("Invalid page cursor".to_string()))?;
+
+        let key_matches = matches!(
+            (&decoded.key, sort),
+            (CursorKey::Score(_), NoteSort::Relevance)
+                | (
+                    CursorKey::Text(_),
+                    NoteSort::Updated | NoteSort::Created | NoteSort::Title
+                )
+        );
+        if decoded.sort != sort || decoded.descending != descending || !key_matches {
+            return Err(AppError::Generic(
+                "Page cursor does not match the requested sort order".to_string(),
+            ));
+        }
+
+        Ok(decoded)
+    }
+
+    /// Bind the sort key value
+    pub fn push_key(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        match &self.key {
+            CursorKey::Text(text) => builder.push_bind(text.clone()),
+            CursorKey::Score(score) => builder.push_bind(*score),
+        };
+    }
+}
+
+/// Clamp a requested page size to `1..=MAX_PAGE_SIZE`
+pub fn page_size(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = NoteCursor {
+            sort: NoteSort::Relevance,
+            descending: false,
+            key: CursorKey::Score(-1.25),
+            id: "n1".to_string(),
+        };
+
+        let decoded = NoteCursor::decode(&cursor.encode(), NoteSort::Relevance, false).unwrap();
+        assert_eq!(decoded, cursor);
+    }
+
+    #[test]
+    fn test_cursor_rejects_other_order() {
+        let cursor = NoteCursor {
+            sort: NoteSort::Updated,
+            descending: true,
+            key: CursorKey::Text("2026-01-01T00:00:00+00:00".to_string()),
+            id: "n1".to_string(),
+        }
+        .encode();
+
+        assert!(NoteCursor::decode(&cursor, NoteSort::Updated, true).is_ok());
+        assert!(NoteCursor::decode(&cursor, NoteSort::Updated, false).is_err());
+        assert!(NoteCursor::decode(&cursor, NoteSort::Title, true).is_err());
+        assert!(NoteCursor::decode("not a cursor", NoteSort::Updated, true).is_err());
+    }
+
+    #[test]
+    fn test_page_size() {
+        assert_eq!(page_size(None), DEFAULT_PAGE_SIZE);
+        assert_eq!(page_size(Some(0)), 1);
+        assert_eq!(page_size(Some(10_000)), MAX_PAGE_SIZE);
+    }
+}
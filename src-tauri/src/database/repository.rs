@@ -4,10 +4,13 @@
 //! All operations use transactions for safety.
 
 use super::models::*;
+use super::pagination::{self, CursorKey, NoteCursor};
+use super::search_query::{self, SearchQuery};
 use crate::error::{AppError, Result};
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::Value;
-use sqlx::SqlitePool;
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 /// Explicit column list for the notes table.
@@ -17,9 +20,73 @@ use uuid::Uuid;
 const NOTE_COLUMNS: &str =
     "id, title, content_json, created_at, updated_at, deleted_at, title_modified, collection_id";
 
+/// `NOTE_COLUMNS` qualified with the `n` alias used by search queries.
+const ALIASED_NOTE_COLUMNS: &str =
+    "n.id, n.title, n.content_json, n.created_at, n.updated_at, n.deleted_at, n.title_modified, n.collection_id";
+
 /// Explicit column list for the reminders table.
 const REMINDER_COLUMNS: &str =
-    "id, note_id, trigger_time, triggered, created_at, sound_enabled, sound_type, shake_enabled, glow_enabled";
+    "id, note_id, trigger_time, triggered, created_at, sound_enabled, sound_type, shake_enabled, glow_enabled, recurrence_rule, recurrence_start, snoozed_until, missed_policy, missed_skip_after_hours, inline_text, inline_start, inline_end";
+
+/// Explicit column list for the note_templates table.
+const TEMPLATE_COLUMNS: &str =
+    "id, name, title, content_json, collection_id, reminder_offset_minutes, created_at, updated_at";
+
+/// Selects `NoteLink`s as `l`, resolving each target to the
+/// non-deleted note with that ID, or else the most recently updated one
+/// with that title. Links from deleted notes are left out.
+const NOTE_LINK_SELECT: &str = r#"
+    SELECT l.source_note_id, l.source_title, l.target,
+           t.id AS target_note_id, t.title AS target_title
+    FROM (
+        SELECT nl.source_note_id, s.title AS source_title,
+               s.updated_at AS source_updated_at, nl.target,
+               COALESCE(
+                   (
+                       SELECT n.id FROM notes n
+                       WHERE n.id = nl.target AND n.deleted_at IS NULL
+                   ),
+                   (
+                       SELECT n.id FROM notes n
+                       WHERE n.title = nl.target COLLATE NOCASE AND n.deleted_at IS NULL
+                       ORDER BY n.updated_at DESC
+                       LIMIT 1
+                   )
+               ) AS resolved_id
+        FROM note_links nl
+        INNER JOIN notes s ON s.id = nl.source_note_id AND s.deleted_at IS NULL
+    ) l
+    LEFT JOIN notes t ON t.id = l.resolved_id
+"#;
+
+/// Row of a note summary listing, before the preview is extracted
+#[derive(FromRow)]
+struct NoteSummaryRow {
+    id: String,
+    title: String,
+    content_json: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    collection_id: Option<String>,
+    attachment_count: i64,
+    /// Sort key column as stored (None for relevance)
+    sort_text: Option<String>,
+    score: Option<f64>,
+}
+
+impl NoteSummaryRow {
+    fn into_summary(self) -> NoteSummary {
+        NoteSummary {
+            preview: search_query::preview_text(&self.content_json),
+            id: self.id,
+            title: self.title,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+            collection_id: self.collection_id,
+            attachment_count: self.attachment_count,
+        }
+    }
+}
 
 /// Repository for database operations
 #[derive(Clone)]
@@ -173,6 +240,36 @@ impl Repository {
         self.get_note(&req.id).await
     }
 
+    /// Set the content and timestamps of a note an importer created, and
+    /// mark its title as chosen rather than generated from the content
+    pub async fn set_imported_note(
+        &self,
+        id: &str,
+        content_json: &str,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Result<Note> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE notes SET content_json = ?, created_at = ?, updated_at = ?, title_modified = 1
+            WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(content_json)
+        .bind(created_at)
+        .bind(updated_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(AppError::NoteNotFound(id.to_string()));
+        }
+
+        self.get_note(id).await
+    }
+
     /// Soft delete a note
     pub async fn delete_note(&self, id: &str) -> Result<()> {
         let now = Utc::now();
@@ -332,7 +429,7 @@ impl Repository {
 
     /// Mark reminder as triggered
     pub async fn mark_reminder_triggered(&self, id: &str) -> Result<()> {
-        sqlx::query("UPDATE reminders SET triggered = 1 WHERE id = ?")
+        sqlx::query("UPDATE reminders SET triggered = 1, snoozed_until = NULL WHERE id = ?")
             .bind(id)
             .execute(&self.pool)
             .await?;
@@ -383,6 +480,262 @@ impl Repository {
         Ok(reminder)
     }
 
+    /// Get a reminder by ID
+    pub async fn get_reminder(&self, id: &str) -> Result<Reminder> {
+        self.find_reminder(id)
+            .await?
+            .ok_or_else(|| AppError::Generic(format!("Reminder not found: {}", id)))
+    }
+
+    /// Get a reminder by ID, or `None` if there is none
+    pub async fn find_reminder(&self, id: &str) -> Result<Option<Reminder>> {
+        let sql = format!("SELECT {} FROM reminders WHERE id = ?", REMINDER_COLUMNS);
+        let reminder = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(reminder)
+    }
+
+    /// Set or clear an active reminder's recurrence, moving it to the
+    /// series' first occurrence
+    pub async fn set_reminder_recurrence(
+        &self,
+        id: &str,
+        trigger_time: chrono::DateTime<Utc>,
+        recurrence_rule: Option<&str>,
+        recurrence_start: Option<chrono::NaiveDateTime>,
+    ) -> Result<Reminder> {
+        let sql = format!(
+            "UPDATE reminders SET trigger_time = ?, recurrence_rule = ?, recurrence_start = ? WHERE id = ? AND triggered = 0 RETURNING {}",
+            REMINDER_COLUMNS
+        );
+        let reminder = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(trigger_time)
+            .bind(recurrence_rule)
+            .bind(recurrence_start)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| {
+                AppError::Generic(format!("Reminder not found or already triggered: {}", id))
+            })?;
+
+        tracing::debug!("Set recurrence of reminder {}: {:?}", id, recurrence_rule);
+        Ok(reminder)
+    }
+
+    /// Set how a reminder is handled if it comes due while the app is closed
+    pub async fn set_reminder_missed_policy(
+        &self,
+        id: &str,
+        missed_policy: Option<MissedReminderPolicy>,
+        missed_skip_after_hours: Option<u32>,
+    ) -> Result<Reminder> {
+        let sql = format!(
+            "UPDATE reminders SET missed_policy = ?, missed_skip_after_hours = ? WHERE id = ? RETURNING {}",
+            REMINDER_COLUMNS
+        );
+        let reminder = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(missed_policy)
+            .bind(missed_skip_after_hours)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::Generic(format!("Reminder not found: {}", id)))?;
+
+        tracing::debug!("Set missed policy of reminder {}: {:?}", id, missed_policy);
+        Ok(reminder)
+    }
+
+    /// List a note's reminders created from "@remind" markers, in text order
+    pub async fn list_inline_reminders(&self, note_id: &str) -> Result<Vec<Reminder>> {
+        let sql = format!(
+            "SELECT {} FROM reminders WHERE note_id = ? AND inline_text IS NOT NULL ORDER BY inline_start ASC",
+            REMINDER_COLUMNS
+        );
+        let reminders = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(note_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(reminders)
+    }
+
+    /// Link a reminder to the "@remind" marker it came from
+    pub async fn set_reminder_inline_source(
+        &self,
+        id: &str,
+        inline_text: &str,
+        inline_start: u32,
+        inline_end: u32,
+    ) -> Result<Reminder> {
+        let sql = format!(
+            "UPDATE reminders SET inline_text = ?, inline_start = ?, inline_end = ? WHERE id = ? RETURNING {}",
+            REMINDER_COLUMNS
+        );
+        let reminder = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(inline_text)
+            .bind(inline_start)
+            .bind(inline_end)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::Generic(format!("Reminder not found: {}", id)))?;
+
+        tracing::debug!(
+            "Linked reminder {} to marker at {}..{}",
+            id,
+            inline_start,
+            inline_end
+        );
+        Ok(reminder)
+    }
+
+    /// Move a recurring reminder to its next occurrence
+    pub async fn reschedule_reminder(
+        &self,
+        id: &str,
+        trigger_time: chrono::DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE reminders SET trigger_time = ?, triggered = 0, snoozed_until = NULL WHERE id = ?",
+        )
+            .bind(trigger_time)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        tracing::debug!("Rescheduled reminder {} to {}", id, trigger_time);
+        Ok(())
+    }
+
+    /// Set a reminder's trigger time, triggered flag and pending snooze
+    pub async fn update_reminder_state(
+        &self,
+        id: &str,
+        trigger_time: chrono::DateTime<Utc>,
+        triggered: bool,
+        snoozed_until: Option<chrono::DateTime<Utc>>,
+    ) -> Result<Reminder> {
+        let sql = format!(
+            "UPDATE reminders SET trigger_time = ?, triggered = ?, snoozed_until = ? WHERE id = ? RETURNING {}",
+            REMINDER_COLUMNS
+        );
+        let reminder = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(trigger_time)
+            .bind(triggered)
+            .bind(snoozed_until)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::Generic(format!("Reminder not found: {}", id)))?;
+
+        tracing::debug!(
+            "Updated reminder {} state: trigger {}, triggered {}, snoozed until {:?}",
+            id,
+            trigger_time,
+            triggered,
+            snoozed_until
+        );
+        Ok(reminder)
+    }
+
+    // ===== Reminder History Methods =====
+
+    /// Record something that happened to a reminder
+    pub async fn record_reminder_event(
+        &self,
+        reminder: &Reminder,
+        action: ReminderAction,
+        scheduled_for: Option<chrono::DateTime<Utc>>,
+        snoozed_until: Option<chrono::DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO reminder_history (id, reminder_id, note_id, action, occurred_at, scheduled_for, snoozed_until)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&reminder.id)
+        .bind(&reminder.note_id)
+        .bind(action)
+        .bind(Utc::now())
+        .bind(scheduled_for)
+        .bind(snoozed_until)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::debug!("Recorded reminder {} event: {:?}", reminder.id, action);
+        Ok(())
+    }
+
+    /// List reminder history, newest first, optionally for one reminder
+    pub async fn list_reminder_history(
+        &self,
+        reminder_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<ReminderHistoryEntry>> {
+        let entries = sqlx::query_as::<_, ReminderHistoryEntry>(
+            r#"
+            SELECT h.id, h.reminder_id, h.note_id, n.title AS note_title, h.action,
+                   h.occurred_at, h.scheduled_for, h.snoozed_until
+            FROM reminder_history h
+            LEFT JOIN notes n ON n.id = h.note_id
+            WHERE ?1 IS NULL OR h.reminder_id = ?1
+            ORDER BY h.occurred_at DESC
+            LIMIT ?2
+            "#,
+        )
+        .bind(reminder_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// List `Fired` entries from before `before` that got no snooze, dismiss
+    /// or acknowledge before the reminder fired again, newest first
+    pub async fn list_missed_reminders(
+        &self,
+        before: chrono::DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ReminderHistoryEntry>> {
+        let entries = sqlx::query_as::<_, ReminderHistoryEntry>(
+            r#"
+            SELECT h.id, h.reminder_id, h.note_id, n.title AS note_title, h.action,
+                   h.occurred_at, h.scheduled_for, h.snoozed_until
+            FROM reminder_history h
+            LEFT JOIN notes n ON n.id = h.note_id
+            WHERE h.action = 'fired'
+              AND h.occurred_at <= ?
+              AND NOT EXISTS (
+                  SELECT 1 FROM reminder_history r
+                  WHERE r.reminder_id = h.reminder_id
+                    AND r.action NOT IN ('fired', 'skipped')
+                    AND r.occurred_at >= h.occurred_at
+                    AND NOT EXISTS (
+                        SELECT 1 FROM reminder_history f
+                        WHERE f.reminder_id = h.reminder_id
+                          AND f.action IN ('fired', 'skipped')
+                          AND f.occurred_at > h.occurred_at
+                          AND f.occurred_at <= r.occurred_at
+                    )
+              )
+            ORDER BY h.occurred_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
     /// Get/set settings
     pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
         let value: Option<String> = sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
@@ -393,7 +746,6 @@ impl Repository {
         Ok(value)
     }
 
-    #[allow(dead_code)]
     pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
         sqlx::query(
             r#"
@@ -517,6 +869,56 @@ impl Repository {
         }
     }
 
+    /// Extract blob hashes referenced by inline attachment embeds
+    /// (`{"insert": {"attachment-image": {"blobHash": ...}}}`) in Quill Delta JSON
+    pub fn extract_blob_hashes_from_delta(content_json: &str) -> Vec<String> {
+        let Ok(json) = serde_json::from_str::<Value>(content_json) else {
+            return Vec::new();
+        };
+
+        json.get("ops")
+            .and_then(|o| o.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|op| op.get("insert").and_then(|insert| insert.as_object()))
+            .flat_map(|embed| embed.values())
+            .filter_map(|value| value.get("blobHash").and_then(|h| h.as_str()))
+            .filter(|hash| !hash.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Collect every blob hash still referenced: attachment rows plus inline
+    /// embeds in note content, revision history and templates. Soft-deleted
+    /// notes count as references, since they can still be restored from the
+    /// trash.
+    pub async fn list_referenced_blob_hashes(&self) -> Result<HashSet<String>> {
+        let mut hashes: HashSet<String> =
+            sqlx::query_scalar("SELECT DISTINCT blob_hash FROM attachments")
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .collect();
+
+        let contents: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT content_json FROM notes
+            UNION ALL
+            SELECT content_json FROM note_revisions
+            UNION ALL
+            SELECT content_json FROM note_templates
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for content_json in &contents {
+            hashes.extend(Self::extract_blob_hashes_from_delta(content_json));
+        }
+
+        Ok(hashes)
+    }
+
     /// Insert a note into the FTS index
     pub async fn insert_note_fts(
         &self,
@@ -591,32 +993,186 @@ impl Repository {
         Ok(())
     }
 
-    /// Search notes using FTS5 full-text search
-    /// Returns notes matching the query, ordered by relevance
-    pub async fn search_notes_fts(&self, query: &str) -> Result<Vec<Note>> {
-        // Escape special FTS5 characters and prepare query
-        // FTS5 uses MATCH for searching
-        let search_query = format!("{}*", query.replace('"', "\"\""));
+    /// Search notes with a parsed query (see `search_query`) using FTS5.
+    /// Results are ordered by FTS5 relevance when the query has free-text terms,
+    /// otherwise by most recently updated.
+    pub async fn search_notes_query(&self, query: &SearchQuery) -> Result<Vec<Note>> {
+        use sqlx::QueryBuilder;
 
-        let sql = format!(
-            "SELECT {} FROM notes n INNER JOIN notes_fts fts ON n.id = fts.note_id WHERE notes_fts MATCH ? AND n.deleted_at IS NULL ORDER BY bm25(notes_fts)",
-            NOTE_COLUMNS.replace("id,", "n.id,")
-                .replace("title,", "n.title,")
-                .replace("content_json,", "n.content_json,")
-                .replace("created_at,", "n.created_at,")
-                .replace("updated_at,", "n.updated_at,")
-                .replace("deleted_at,", "n.deleted_at,")
-                .replace("title_modified,", "n.title_modified,")
-                .replace("collection_id", "n.collection_id")
-        );
-        let notes = sqlx::query_as::<_, Note>(&sql)
-            .bind(&search_query)
+        let mut builder: QueryBuilder<sqlx::Sqlite> =
+            QueryBuilder::new(format!("SELECT {}", ALIASED_NOTE_COLUMNS));
+        Self::push_search_clauses(&mut builder, query);
+
+        let notes = builder
+            .build_query_as::<Note>()
             .fetch_all(&self.pool)
             .await?;
 
         Ok(notes)
     }
 
+    /// Search notes with a parsed query, returning highlighted titles,
+    /// content snippets and bm25 scores for result display
+    pub async fn search_notes_with_snippets(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<Vec<SearchResult>> {
+        use sqlx::QueryBuilder;
+
+        // FTS5 auxiliary functions need a MATCH; filter-only queries get a plain preview
+        if query.match_expression().is_none() {
+            let notes = self.search_notes_query(query).await?;
+            return Ok(notes
+                .into_iter()
+                .map(|note| search_query::preview_result(note, false))
+                .collect());
+        }
+
+        let mut builder: QueryBuilder<sqlx::Sqlite> = QueryBuilder::new(format!(
+            "SELECT {}, highlight(notes_fts, 1, char(2), char(3)) AS title_highlight, snippet(notes_fts, 2, char(2), char(3), '…', {}) AS content_snippet, bm25(notes_fts) AS score",
+            ALIASED_NOTE_COLUMNS,
+            search_query::SNIPPET_TOKENS
+        ));
+        Self::push_search_clauses(&mut builder, query);
+
+        let mut results = builder
+            .build_query_as::<SearchResult>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        for result in &mut results {
+            result.title_highlight = search_query::markup_matches(&result.title_highlight);
+            result.content_snippet = search_query::markup_matches(&result.content_snippet);
+        }
+
+        Ok(results)
+    }
+
+    /// Append FROM, WHERE and ORDER BY for a search query
+    fn push_search_clauses(
+        builder: &mut sqlx::QueryBuilder<'_, sqlx::Sqlite>,
+        query: &SearchQuery,
+    ) {
+        Self::push_search_conditions(builder, query);
+        builder.push(if query.match_expression().is_some() {
+            " ORDER BY bm25(notes_fts)"
+        } else {
+            " ORDER BY n.updated_at DESC"
+        });
+    }
+
+    /// Append FROM and WHERE for a search query.
+    /// `notes_fts` is joined only when the query has free-text terms.
+    fn push_search_conditions(
+        builder: &mut sqlx::QueryBuilder<'_, sqlx::Sqlite>,
+        query: &SearchQuery,
+    ) {
+        let match_expression = query.match_expression();
+
+        builder.push(" FROM notes n");
+        if match_expression.is_some() {
+            builder.push(" INNER JOIN notes_fts ON n.id = notes_fts.note_id");
+        }
+        builder.push(" WHERE n.deleted_at IS NULL");
+        if let Some(expression) = match_expression {
+            builder.push(" AND notes_fts MATCH ");
+            builder.push_bind(expression);
+        }
+        query.push_filters(builder);
+    }
+
+    /// List one page of note summaries matching a search query, using keyset
+    /// pagination (see `pagination`). Attachment-filename matches are not
+    /// included; use `search_notes_with_snippets` for those.
+    pub async fn list_note_summaries(
+        &self,
+        query: &SearchQuery,
+        req: &NoteListRequest,
+    ) -> Result<NotePage> {
+        use sqlx::QueryBuilder;
+
+        let ranked = query.match_expression().is_some();
+        let sort = match req.sort {
+            NoteSort::Relevance if !ranked => NoteSort::Updated,
+            sort => sort,
+        };
+        let descending = req
+            .descending
+            .unwrap_or_else(|| sort.descending_by_default());
+        let cursor = req
+            .cursor
+            .as_deref()
+            .map(|c| NoteCursor::decode(c, sort, descending))
+            .transpose()?;
+        let limit = pagination::page_size(req.limit);
+
+        let (sort_expr, key_column) = match sort {
+            NoteSort::Updated => ("n.updated_at", "n.updated_at"),
+            NoteSort::Created => ("n.created_at", "n.created_at"),
+            NoteSort::Title => ("n.title COLLATE NOCASE", "n.title"),
+            NoteSort::Relevance => ("bm25(notes_fts)", "NULL"),
+        };
+
+        let mut builder: QueryBuilder<sqlx::Sqlite> = QueryBuilder::new(format!(
+            "SELECT n.id, n.title, n.content_json, n.created_at, n.updated_at, n.collection_id, (SELECT COUNT(*) FROM attachments a WHERE a.note_id = n.id) AS attachment_count, {} AS sort_text, {} AS score",
+            key_column,
+            if ranked { "bm25(notes_fts)" } else { "NULL" }
+        ));
+        Self::push_search_conditions(&mut builder, query);
+
+        if let Some(collection_id) = &req.collection_id {
+            builder.push(" AND n.collection_id = ");
+            builder.push_bind(collection_id.clone());
+        }
+
+        if let Some(cursor) = cursor {
+            let cmp = if descending { " < " } else { " > " };
+            builder.push(format!(" AND ({}{}", sort_expr, cmp));
+            cursor.push_key(&mut builder);
+            builder.push(format!(" OR ({} = ", sort_expr));
+            cursor.push_key(&mut builder);
+            builder.push(format!(" AND n.id{}", cmp));
+            builder.push_bind(cursor.id);
+            builder.push("))");
+        }
+
+        let direction = if descending { "DESC" } else { "ASC" };
+        builder.push(format!(
+            " ORDER BY {} {}, n.id {} LIMIT ",
+            sort_expr, direction, direction
+        ));
+        // One extra row tells us whether there is a next page
+        builder.push_bind(i64::from(limit) + 1);
+
+        let mut rows = builder
+            .build_query_as::<NoteSummaryRow>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let next_cursor = if rows.len() > limit as usize {
+            rows.truncate(limit as usize);
+            rows.last().map(|last| {
+                NoteCursor {
+                    sort,
+                    descending,
+                    key: match (sort, last.score) {
+                        (NoteSort::Relevance, Some(score)) => CursorKey::Score(score),
+                        _ => CursorKey::Text(last.sort_text.clone().unwrap_or_default()),
+                    },
+                    id: last.id.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(NotePage {
+            items: rows.into_iter().map(NoteSummaryRow::into_summary).collect(),
+            next_cursor,
+        })
+    }
+
     /// Search notes by attachment filename using LIKE (for attachment search)
     pub async fn search_notes_by_attachment(&self, query: &str) -> Result<Vec<String>> {
         let pattern = format!("%{}%", query.to_lowercase());
@@ -716,7 +1272,7 @@ impl Repository {
                 .execute(&mut *tx)
                 .await?;
 
-            // Delete attachments metadata (blobs are handled separately)
+            // Delete attachments metadata (unreferenced blobs are reclaimed by blob GC)
             sqlx::query("DELETE FROM attachments WHERE note_id = ?")
                 .bind(note_id)
                 .execute(&mut *tx)
@@ -727,6 +1283,24 @@ impl Repository {
                 .bind(note_id)
                 .execute(&mut *tx)
                 .await?;
+
+            // Delete revision history
+            sqlx::query("DELETE FROM note_revisions WHERE note_id = ?")
+                .bind(note_id)
+                .execute(&mut *tx)
+                .await?;
+
+            // Delete tag assignments
+            sqlx::query("DELETE FROM note_tags WHERE note_id = ?")
+                .bind(note_id)
+                .execute(&mut *tx)
+                .await?;
+
+            // Delete reminder history
+            sqlx::query("DELETE FROM reminder_history WHERE note_id = ?")
+                .bind(note_id)
+                .execute(&mut *tx)
+                .await?;
         }
 
         // Finally delete all soft-deleted notes
@@ -740,533 +1314,2349 @@ impl Repository {
         Ok(count)
     }
 
-    // ===== Collections Methods =====
+    // ===== Note Revision Methods =====
 
-    /// Create a new collection
-    pub async fn create_collection(&self, req: CreateCollectionRequest) -> Result<Collection> {
+    /// Record a snapshot of a note's title and content
+    pub async fn create_note_revision(
+        &self,
+        note_id: &str,
+        title: &str,
+        content_json: &str,
+    ) -> Result<NoteRevision> {
         let id = Uuid::new_v4().to_string();
         let now = Utc::now();
 
-        // Get the highest sort order and add 1
-        let max_sort: Option<i32> = sqlx::query_scalar("SELECT MAX(sort_order) FROM collections")
-            .fetch_optional(&self.pool)
-            .await?
-            .flatten();
-        let sort_order = max_sort.unwrap_or(0) + 1;
-
-        let color = req.color.unwrap_or_else(|| "#6B7280".to_string());
-        let icon = req.icon.unwrap_or_else(|| "folder".to_string());
-
-        let collection = sqlx::query_as::<_, Collection>(
+        let revision = sqlx::query_as::<_, NoteRevision>(
             r#"
-            INSERT INTO collections (id, name, description, color, icon, sort_order, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            RETURNING *
+            INSERT INTO note_revisions (id, note_id, title, content_json, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id, note_id, title, content_json, created_at
             "#,
         )
         .bind(&id)
-        .bind(&req.name)
-        .bind(&req.description)
-        .bind(&color)
-        .bind(&icon)
-        .bind(sort_order)
+        .bind(note_id)
+        .bind(title)
+        .bind(content_json)
         .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::debug!("Created revision {} for note {}", id, note_id);
+        Ok(revision)
+    }
+
+    /// List revisions for a note, newest first
+    pub async fn list_note_revisions(&self, note_id: &str) -> Result<Vec<NoteRevisionSummary>> {
+        let revisions = sqlx::query_as::<_, NoteRevisionSummary>(
+            r#"
+            SELECT id, note_id, title, created_at, LENGTH(content_json) AS content_length
+            FROM note_revisions
+            WHERE note_id = ?
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(note_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    /// Get a single revision by ID
+    pub async fn get_note_revision(&self, id: &str) -> Result<NoteRevision> {
+        let revision = sqlx::query_as::<_, NoteRevision>(
+            r#"
+            SELECT id, note_id, title, content_json, created_at
+            FROM note_revisions WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::Generic(format!("Revision not found: {}", id)))?;
+
+        Ok(revision)
+    }
+
+    /// Get the timestamp of the most recent revision of a note, if any
+    pub async fn latest_note_revision_time(&self, note_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let latest: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            SELECT created_at FROM note_revisions
+            WHERE note_id = ?
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(note_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(latest)
+    }
+
+    /// Apply retention rules to a note's revisions.
+    ///
+    /// - `keep`: keep only the newest N revisions (0 = unlimited)
+    /// - `thin_before`: revisions older than this are thinned to the newest one per day
+    ///
+    /// Returns the number of revisions deleted.
+    pub async fn prune_note_revisions(
+        &self,
+        note_id: &str,
+        keep: u32,
+        thin_before: Option<DateTime<Utc>>,
+    ) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+
+        if let Some(cutoff) = thin_before {
+            deleted += sqlx::query(
+                r#"
+                DELETE FROM note_revisions
+                WHERE note_id = ? AND created_at < ? AND id NOT IN (
+                    SELECT id FROM (
+                        SELECT id, ROW_NUMBER() OVER (
+                            PARTITION BY substr(created_at, 1, 10)
+                            ORDER BY created_at DESC
+                        ) AS day_rank
+                        FROM note_revisions
+                        WHERE note_id = ?
+                    )
+                    WHERE day_rank = 1
+                )
+                "#,
+            )
+            .bind(note_id)
+            .bind(cutoff)
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        if keep > 0 {
+            deleted += sqlx::query(
+                r#"
+                DELETE FROM note_revisions
+                WHERE note_id = ? AND id NOT IN (
+                    SELECT id FROM note_revisions
+                    WHERE note_id = ?
+                    ORDER BY created_at DESC
+                    LIMIT ?
+                )
+                "#,
+            )
+            .bind(note_id)
+            .bind(note_id)
+            .bind(keep as i64)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+
+        if deleted > 0 {
+            tracing::debug!("Pruned {} revisions for note {}", deleted, note_id);
+        }
+        Ok(deleted)
+    }
+
+    // ===== Tag Methods =====
+
+    /// Create a new tag
+    pub async fn create_tag(&self, name: &str, color: Option<&str>) -> Result<Tag> {
+        if self.get_tag_by_name(name).await?.is_some() {
+            return Err(AppError::Generic(format!("Tag already exists: {}", name)));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let color = color.unwrap_or("#6B7280");
+
+        let tag = sqlx::query_as::<_, Tag>(
+            r#"
+            INSERT INTO tags (id, name, color, created_at)
+            VALUES (?, ?, ?, ?)
+            RETURNING id, name, color, created_at
+            "#,
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(color)
         .bind(now)
         .fetch_one(&self.pool)
         .await?;
 
-        tracing::debug!("Created collection: {}", id);
-        Ok(collection)
+        tracing::debug!("Created tag: {} ({})", name, id);
+        Ok(tag)
     }
 
-    /// Get a collection by ID
-    pub async fn get_collection(&self, id: &str) -> Result<Collection> {
-        let collection = sqlx::query_as::<_, Collection>(
+    /// Get a tag by ID
+    pub async fn get_tag(&self, id: &str) -> Result<Tag> {
+        let tag = sqlx::query_as::<_, Tag>(
             r#"
-            SELECT * FROM collections WHERE id = ?
+            SELECT id, name, color, created_at FROM tags WHERE id = ?
             "#,
         )
         .bind(id)
         .fetch_optional(&self.pool)
         .await?
-        .ok_or_else(|| AppError::Generic(format!("Collection not found: {}", id)))?;
+        .ok_or_else(|| AppError::Generic(format!("Tag not found: {}", id)))?;
 
-        Ok(collection)
+        Ok(tag)
     }
 
-    /// Get a collection by name (case-insensitive)
-    /// Useful for importing data where section/folder names should map to existing collections
-    #[allow(dead_code)]
-    pub async fn get_collection_by_name(&self, name: &str) -> Result<Option<Collection>> {
-        let collection = sqlx::query_as::<_, Collection>(
+    /// Get a tag by name (case-insensitive)
+    pub async fn get_tag_by_name(&self, name: &str) -> Result<Option<Tag>> {
+        let tag = sqlx::query_as::<_, Tag>(
             r#"
-            SELECT * FROM collections WHERE LOWER(name) = LOWER(?)
+            SELECT id, name, color, created_at FROM tags WHERE name = ? COLLATE NOCASE
             "#,
         )
         .bind(name)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(collection)
+        Ok(tag)
     }
 
-    /// List all collections ordered by sort_order
-    pub async fn list_collections(&self) -> Result<Vec<Collection>> {
-        let collections = sqlx::query_as::<_, Collection>(
+    /// List all tags with the number of non-deleted notes using each
+    pub async fn list_tags(&self) -> Result<Vec<TagWithCount>> {
+        let tags = sqlx::query_as::<_, TagWithCount>(
             r#"
-            SELECT * FROM collections ORDER BY sort_order ASC, created_at ASC
+            SELECT t.id, t.name, t.color, t.created_at, COUNT(n.id) AS note_count
+            FROM tags t
+            LEFT JOIN note_tags nt ON nt.tag_id = t.id
+            LEFT JOIN notes n ON n.id = nt.note_id AND n.deleted_at IS NULL
+            GROUP BY t.id
+            ORDER BY t.name COLLATE NOCASE ASC
             "#,
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(collections)
+        Ok(tags)
     }
 
-    /// Update a collection
-    pub async fn update_collection(&self, req: UpdateCollectionRequest) -> Result<Collection> {
+    /// Update a tag's name and/or color
+    pub async fn update_tag(&self, req: UpdateTagRequest) -> Result<Tag> {
         use sqlx::QueryBuilder;
 
-        let now = Utc::now();
+        if let Some(name) = &req.name {
+            if let Some(existing) = self.get_tag_by_name(name).await? {
+                if existing.id != req.id {
+                    return Err(AppError::Generic(format!(
+                        "Tag already exists: {} (merge the tags instead)",
+                        name
+                    )));
+                }
+            }
+        }
 
-        let mut builder: QueryBuilder<sqlx::Sqlite> =
-            QueryBuilder::new("UPDATE collections SET updated_at = ");
-        builder.push_bind(now.to_rfc3339());
+        let mut builder: QueryBuilder<sqlx::Sqlite> = QueryBuilder::new("UPDATE tags SET id = id");
 
         if let Some(name) = &req.name {
             builder.push(", name = ");
             builder.push_bind(name.clone());
         }
 
-        if let Some(description) = &req.description {
-            builder.push(", description = ");
-            builder.push_bind(description.clone());
-        }
-
         if let Some(color) = &req.color {
             builder.push(", color = ");
             builder.push_bind(color.clone());
         }
 
-        if let Some(icon) = &req.icon {
-            builder.push(", icon = ");
-            builder.push_bind(icon.clone());
-        }
-
-        if let Some(sort_order) = req.sort_order {
-            builder.push(", sort_order = ");
-            builder.push_bind(sort_order);
-        }
-
         builder.push(" WHERE id = ");
         builder.push_bind(req.id.clone());
 
         let rows_affected = builder.build().execute(&self.pool).await?.rows_affected();
 
         if rows_affected == 0 {
-            return Err(AppError::Generic(format!(
-                "Collection not found: {}",
-                req.id
-            )));
+            return Err(AppError::Generic(format!("Tag not found: {}", req.id)));
         }
 
-        self.get_collection(&req.id).await
+        self.get_tag(&req.id).await
     }
 
-    /// Delete a collection (notes in this collection will have collection_id set to NULL)
-    pub async fn delete_collection(&self, id: &str) -> Result<()> {
-        let rows = sqlx::query("DELETE FROM collections WHERE id = ?")
+    /// Delete a tag (removes it from all notes)
+    pub async fn delete_tag(&self, id: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM note_tags WHERE tag_id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+
+        let rows = sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
             .await?
             .rows_affected();
 
         if rows == 0 {
-            return Err(AppError::Generic(format!("Collection not found: {}", id)));
+            tx.rollback().await?;
+            return Err(AppError::Generic(format!("Tag not found: {}", id)));
         }
 
-        tracing::debug!("Deleted collection: {}", id);
+        tx.commit().await?;
+
+        tracing::debug!("Deleted tag: {}", id);
         Ok(())
     }
 
-    /// Update a note's collection
-    pub async fn update_note_collection(
-        &self,
-        note_id: &str,
-        collection_id: Option<&str>,
-    ) -> Result<Note> {
-        let now = Utc::now();
+    /// Merge source tags into a target tag.
+    /// Notes tagged with any source tag end up tagged with the target,
+    /// and the source tags are deleted. Returns the target tag.
+    pub async fn merge_tags(&self, source_ids: &[String], target_id: &str) -> Result<Tag> {
+        let target = self.get_tag(target_id).await?;
+        let mut tx = self.pool.begin().await?;
 
-        let rows = sqlx::query(
+        for source_id in source_ids.iter().filter(|id| id.as_str() != target_id) {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO note_tags (note_id, tag_id, created_at)
+                SELECT note_id, ?, created_at FROM note_tags WHERE tag_id = ?
+                "#,
+            )
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("DELETE FROM note_tags WHERE tag_id = ?")
+                .bind(source_id)
+                .execute(&mut *tx)
+                .await?;
+
+            let rows = sqlx::query("DELETE FROM tags WHERE id = ?")
+                .bind(source_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+            if rows == 0 {
+                tx.rollback().await?;
+                return Err(AppError::Generic(format!("Tag not found: {}", source_id)));
+            }
+        }
+
+        tx.commit().await?;
+
+        tracing::debug!("Merged {} tags into {}", source_ids.len(), target_id);
+        Ok(target)
+    }
+
+    /// Apply a tag to a note (no-op if already applied)
+    pub async fn add_tag_to_note(&self, note_id: &str, tag_id: &str) -> Result<()> {
+        // Ensure the note exists and isn't deleted
+        self.get_note(note_id).await?;
+
+        sqlx::query(
             r#"
-            UPDATE notes SET collection_id = ?, updated_at = ?
-            WHERE id = ? AND deleted_at IS NULL
+            INSERT OR IGNORE INTO note_tags (note_id, tag_id, created_at)
+            VALUES (?, ?, ?)
             "#,
         )
-        .bind(collection_id)
-        .bind(now)
         .bind(note_id)
+        .bind(tag_id)
+        .bind(Utc::now())
         .execute(&self.pool)
-        .await?
-        .rows_affected();
+        .await?;
 
-        if rows == 0 {
-            return Err(AppError::NoteNotFound(note_id.to_string()));
-        }
+        Ok(())
+    }
 
-        self.get_note(note_id).await
+    /// Remove a tag from a note
+    pub async fn remove_tag_from_note(&self, note_id: &str, tag_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM note_tags WHERE note_id = ? AND tag_id = ?")
+            .bind(note_id)
+            .bind(tag_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
     }
 
-    /// List notes in a collection
-    pub async fn list_notes_in_collection(&self, collection_id: &str) -> Result<Vec<Note>> {
+    /// List the tags applied to a note
+    pub async fn list_tags_for_note(&self, note_id: &str) -> Result<Vec<Tag>> {
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.id, t.name, t.color, t.created_at
+            FROM tags t
+            INNER JOIN note_tags nt ON nt.tag_id = t.id
+            WHERE nt.note_id = ?
+            ORDER BY t.name COLLATE NOCASE ASC
+            "#,
+        )
+        .bind(note_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// List non-deleted notes carrying a tag
+    pub async fn list_notes_with_tag(&self, tag_id: &str) -> Result<Vec<Note>> {
         let sql = format!(
-            "SELECT {} FROM notes WHERE collection_id = ? AND deleted_at IS NULL ORDER BY updated_at DESC",
+            "SELECT {} FROM notes WHERE deleted_at IS NULL AND id IN (SELECT note_id FROM note_tags WHERE tag_id = ?) ORDER BY updated_at DESC",
             NOTE_COLUMNS
         );
         let notes = sqlx::query_as::<_, Note>(&sql)
-            .bind(collection_id)
+            .bind(tag_id)
             .fetch_all(&self.pool)
             .await?;
 
         Ok(notes)
     }
 
-    /// List notes without a collection (uncategorized)
-    pub async fn list_uncategorized_notes(&self) -> Result<Vec<Note>> {
+    // ===== Note Link Methods =====
+
+    /// Replace the link targets stored for a note
+    pub async fn replace_note_links(&self, note_id: &str, targets: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM note_links WHERE source_note_id = ?")
+            .bind(note_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for target in targets {
+            sqlx::query("INSERT OR IGNORE INTO note_links (source_note_id, target) VALUES (?, ?)")
+                .bind(note_id)
+                .bind(target)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// List the links in a note
+    pub async fn list_outgoing_links(&self, note_id: &str) -> Result<Vec<NoteLink>> {
         let sql = format!(
-            "SELECT {} FROM notes WHERE collection_id IS NULL AND deleted_at IS NULL ORDER BY updated_at DESC",
-            NOTE_COLUMNS
+            "{} WHERE l.source_note_id = ? ORDER BY l.target COLLATE NOCASE ASC",
+            NOTE_LINK_SELECT
         );
-        let notes = sqlx::query_as::<_, Note>(&sql)
+        let links = sqlx::query_as::<_, NoteLink>(&sql)
+            .bind(note_id)
             .fetch_all(&self.pool)
             .await?;
 
-        Ok(notes)
+        Ok(links)
     }
 
-    /// Count notes in a collection
-    pub async fn count_notes_in_collection(&self, collection_id: &str) -> Result<i64> {
-        let count: (i64,) = sqlx::query_as(
-            r#"
-            SELECT COUNT(*) FROM notes
-            WHERE collection_id = ? AND deleted_at IS NULL
-            "#,
-        )
-        .bind(collection_id)
-        .fetch_one(&self.pool)
-        .await?;
+    /// List the links from other notes that resolve to a note
+    pub async fn list_backlinks(&self, note_id: &str) -> Result<Vec<NoteLink>> {
+        let sql = format!(
+            "{} WHERE t.id = ? AND l.source_note_id != t.id ORDER BY l.source_updated_at DESC",
+            NOTE_LINK_SELECT
+        );
+        let links = sqlx::query_as::<_, NoteLink>(&sql)
+            .bind(note_id)
+            .fetch_all(&self.pool)
+            .await?;
 
-        Ok(count.0)
+        Ok(links)
     }
-}
 
-#[cfg(test)]
-mod tests {
+    /// List links that don't resolve to any note
+    pub async fn list_dangling_links(&self) -> Result<Vec<NoteLink>> {
+        let sql = format!(
+            "{} WHERE t.id IS NULL ORDER BY l.source_updated_at DESC, l.target COLLATE NOCASE ASC",
+            NOTE_LINK_SELECT
+        );
+        let links = sqlx::query_as::<_, NoteLink>(&sql)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(links)
+    }
+
+    /// Retitle a note and rewrite the content of the notes linking to it,
+    /// together with their search index entries and stored links, in one
+    /// transaction
+    pub async fn rename_note_with_links(
+        &self,
+        id: &str,
+        title: &str,
+        rewrites: &[LinkRewrite],
+    ) -> Result<Note> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let rows_affected = sqlx::query(
+            "UPDATE notes SET title = ?, title_modified = 1, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(title)
+        .bind(now.to_rfc3339())
+        .bind(id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            tx.rollback().await?;
+            return Err(AppError::NoteNotFound(id.to_string()));
+        }
+
+        sqlx::query("UPDATE notes_fts SET title = ? WHERE note_id = ?")
+            .bind(title)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        for rewrite in rewrites {
+            let rewritten_title: Option<(String,)> = sqlx::query_as(
+                "UPDATE notes SET content_json = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL RETURNING title",
+            )
+            .bind(&rewrite.content_json)
+            .bind(now.to_rfc3339())
+            .bind(&rewrite.note_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some((rewritten_title,)) = rewritten_title else {
+                continue;
+            };
+
+            sqlx::query("DELETE FROM notes_fts WHERE note_id = ?")
+                .bind(&rewrite.note_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("INSERT INTO notes_fts (note_id, title, content_text) VALUES (?, ?, ?)")
+                .bind(&rewrite.note_id)
+                .bind(&rewritten_title)
+                .bind(Self::extract_text_from_delta(&rewrite.content_json))
+                .execute(&mut *tx)
+                .await?;
+
+            sqlx::query("DELETE FROM note_links WHERE source_note_id = ?")
+                .bind(&rewrite.note_id)
+                .execute(&mut *tx)
+                .await?;
+            for target in &rewrite.links {
+                sqlx::query(
+                    "INSERT OR IGNORE INTO note_links (source_note_id, target) VALUES (?, ?)",
+                )
+                .bind(&rewrite.note_id)
+                .bind(target)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        tracing::debug!(
+            "Renamed note {} and rewrote links in {} notes",
+            id,
+            rewrites.len()
+        );
+        self.get_note(id).await
+    }
+
+    // ===== Template Methods =====
+
+    /// Create a note template
+    pub async fn create_template(&self, req: &SaveTemplateRequest) -> Result<NoteTemplate> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        let sql = format!(
+            "INSERT INTO note_templates ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING {}",
+            TEMPLATE_COLUMNS, TEMPLATE_COLUMNS
+        );
+        let template = sqlx::query_as::<_, NoteTemplate>(&sql)
+            .bind(&id)
+            .bind(&req.name)
+            .bind(&req.title)
+            .bind(&req.content_json)
+            .bind(&req.collection_id)
+            .bind(req.reminder_offset_minutes)
+            .bind(now)
+            .bind(now)
+            .fetch_one(&self.pool)
+            .await?;
+
+        tracing::debug!("Created template: {} ({})", req.name, id);
+        Ok(template)
+    }
+
+    /// Get a note template by ID
+    pub async fn get_template(&self, id: &str) -> Result<NoteTemplate> {
+        let sql = format!(
+            "SELECT {} FROM note_templates WHERE id = ?",
+            TEMPLATE_COLUMNS
+        );
+        let template = sqlx::query_as::<_, NoteTemplate>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::Generic(format!("Template not found: {}", id)))?;
+
+        Ok(template)
+    }
+
+    /// List all note templates by name
+    pub async fn list_templates(&self) -> Result<Vec<NoteTemplate>> {
+        let sql = format!(
+            "SELECT {} FROM note_templates ORDER BY name COLLATE NOCASE ASC",
+            TEMPLATE_COLUMNS
+        );
+        let templates = sqlx::query_as::<_, NoteTemplate>(&sql)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(templates)
+    }
+
+    /// Replace all fields of a note template
+    pub async fn update_template(
+        &self,
+        id: &str,
+        req: &SaveTemplateRequest,
+    ) -> Result<NoteTemplate> {
+        let rows_affected = sqlx::query(
+            r#"
+            UPDATE note_templates
+            SET name = ?, title = ?, content_json = ?, collection_id = ?, reminder_offset_minutes = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&req.name)
+        .bind(&req.title)
+        .bind(&req.content_json)
+        .bind(&req.collection_id)
+        .bind(req.reminder_offset_minutes)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if rows_affected == 0 {
+            return Err(AppError::Generic(format!("Template not found: {}", id)));
+        }
+
+        self.get_template(id).await
+    }
+
+    /// Delete a note template (notes created from it are kept)
+    pub async fn delete_template(&self, id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM note_templates WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // ===== Journal Methods =====
+
+    /// Get the ID of a date's journal note, if it has one that isn't deleted
+    pub async fn get_journal_note_id(&self, date: NaiveDate) -> Result<Option<String>> {
+        let note_id = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT j.note_id FROM journal_entries j
+            INNER JOIN notes n ON n.id = j.note_id AND n.deleted_at IS NULL
+            WHERE j.entry_date = ?
+            "#,
+        )
+        .bind(date)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(note_id)
+    }
+
+    /// Make a note the journal note of a date, replacing any previous one
+    pub async fn set_journal_note(&self, date: NaiveDate, note_id: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO journal_entries (entry_date, note_id, created_at)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(date)
+        .bind(note_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List the journal notes of the dates from `from` to `to` (inclusive),
+    /// oldest first
+    pub async fn list_journal_entries(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<JournalEntry>> {
+        let entries = sqlx::query_as::<_, JournalEntry>(
+            r#"
+            SELECT j.entry_date AS date, j.note_id, n.title
+            FROM journal_entries j
+            INNER JOIN notes n ON n.id = j.note_id AND n.deleted_at IS NULL
+            WHERE j.entry_date BETWEEN ? AND ?
+            ORDER BY j.entry_date ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Get the nearest journal note before (or with `forward`, after) a date
+    pub async fn adjacent_journal_entry(
+        &self,
+        date: NaiveDate,
+        forward: bool,
+    ) -> Result<Option<JournalEntry>> {
+        let (comparison, order) = if forward { (">", "ASC") } else { ("<", "DESC") };
+        let sql = format!(
+            r#"
+            SELECT j.entry_date AS date, j.note_id, n.title
+            FROM journal_entries j
+            INNER JOIN notes n ON n.id = j.note_id AND n.deleted_at IS NULL
+            WHERE j.entry_date {} ?
+            ORDER BY j.entry_date {}
+            LIMIT 1
+            "#,
+            comparison, order
+        );
+        let entry = sqlx::query_as::<_, JournalEntry>(&sql)
+            .bind(date)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(entry)
+    }
+
+    // ===== Collections Methods =====
+
+    /// Create a new collection
+    pub async fn create_collection(&self, req: CreateCollectionRequest) -> Result<Collection> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        // Get the highest sort order and add 1
+        let max_sort: Option<i32> = sqlx::query_scalar("SELECT MAX(sort_order) FROM collections")
+            .fetch_optional(&self.pool)
+            .await?
+            .flatten();
+        let sort_order = max_sort.unwrap_or(0) + 1;
+
+        let color = req.color.unwrap_or_else(|| "#6B7280".to_string());
+        let icon = req.icon.unwrap_or_else(|| "folder".to_string());
+
+        let collection = sqlx::query_as::<_, Collection>(
+            r#"
+            INSERT INTO collections (id, name, description, color, icon, sort_order, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            RETURNING *
+            "#,
+        )
+        .bind(&id)
+        .bind(&req.name)
+        .bind(&req.description)
+        .bind(&color)
+        .bind(&icon)
+        .bind(sort_order)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::debug!("Created collection: {}", id);
+        Ok(collection)
+    }
+
+    /// Get a collection by ID
+    pub async fn get_collection(&self, id: &str) -> Result<Collection> {
+        let collection = sqlx::query_as::<_, Collection>(
+            r#"
+            SELECT * FROM collections WHERE id = ?
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::Generic(format!("Collection not found: {}", id)))?;
+
+        Ok(collection)
+    }
+
+    /// Get a collection by name (case-insensitive)
+    /// Useful for importing data where section/folder names should map to existing collections
+    #[allow(dead_code)]
+    pub async fn get_collection_by_name(&self, name: &str) -> Result<Option<Collection>> {
+        let collection = sqlx::query_as::<_, Collection>(
+            r#"
+            SELECT * FROM collections WHERE LOWER(name) = LOWER(?)
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(collection)
+    }
+
+    /// List all collections ordered by sort_order
+    pub async fn list_collections(&self) -> Result<Vec<Collection>> {
+        let collections = sqlx::query_as::<_, Collection>(
+            r#"
+            SELECT * FROM collections ORDER BY sort_order ASC, created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(collections)
+    }
+
+    /// Update a collection
+    pub async fn update_collection(&self, req: UpdateCollectionRequest) -> Result<Collection> {
+        use sqlx::QueryBuilder;
+
+        let now = Utc::now();
+
+        let mut builder: QueryBuilder<sqlx::Sqlite> =
+            QueryBuilder::new("UPDATE collections SET updated_at = ");
+        builder.push_bind(now.to_rfc3339());
+
+        if let Some(name) = &req.name {
+            builder.push(", name = ");
+            builder.push_bind(name.clone());
+        }
+
+        if let Some(description) = &req.description {
+            builder.push(", description = ");
+            builder.push_bind(description.clone());
+        }
+
+        if let Some(color) = &req.color {
+            builder.push(", color = ");
+            builder.push_bind(color.clone());
+        }
+
+        if let Some(icon) = &req.icon {
+            builder.push(", icon = ");
+            builder.push_bind(icon.clone());
+        }
+
+        if let Some(sort_order) = req.sort_order {
+            builder.push(", sort_order = ");
+            builder.push_bind(sort_order);
+        }
+
+        builder.push(" WHERE id = ");
+        builder.push_bind(req.id.clone());
+
+        let rows_affected = builder.build().execute(&self.pool).await?.rows_affected();
+
+        if rows_affected == 0 {
+            return Err(AppError::Generic(format!(
+                "Collection not found: {}",
+                req.id
+            )));
+        }
+
+        self.get_collection(&req.id).await
+    }
+
+    /// Delete a collection (notes in this collection will have collection_id set to NULL)
+    pub async fn delete_collection(&self, id: &str) -> Result<()> {
+        let rows = sqlx::query("DELETE FROM collections WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        if rows == 0 {
+            return Err(AppError::Generic(format!("Collection not found: {}", id)));
+        }
+
+        tracing::debug!("Deleted collection: {}", id);
+        Ok(())
+    }
+
+    /// Update a note's collection
+    pub async fn update_note_collection(
+        &self,
+        note_id: &str,
+        collection_id: Option<&str>,
+    ) -> Result<Note> {
+        let now = Utc::now();
+
+        let rows = sqlx::query(
+            r#"
+            UPDATE notes SET collection_id = ?, updated_at = ?
+            WHERE id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(collection_id)
+        .bind(now)
+        .bind(note_id)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if rows == 0 {
+            return Err(AppError::NoteNotFound(note_id.to_string()));
+        }
+
+        self.get_note(note_id).await
+    }
+
+    /// List notes in a collection
+    pub async fn list_notes_in_collection(&self, collection_id: &str) -> Result<Vec<Note>> {
+        let sql = format!(
+            "SELECT {} FROM notes WHERE collection_id = ? AND deleted_at IS NULL ORDER BY updated_at DESC",
+            NOTE_COLUMNS
+        );
+        let notes = sqlx::query_as::<_, Note>(&sql)
+            .bind(collection_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(notes)
+    }
+
+    /// List notes without a collection (uncategorized)
+    pub async fn list_uncategorized_notes(&self) -> Result<Vec<Note>> {
+        let sql = format!(
+            "SELECT {} FROM notes WHERE collection_id IS NULL AND deleted_at IS NULL ORDER BY updated_at DESC",
+            NOTE_COLUMNS
+        );
+        let notes = sqlx::query_as::<_, Note>(&sql)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(notes)
+    }
+
+    /// Count notes in a collection
+    pub async fn count_notes_in_collection(&self, collection_id: &str) -> Result<i64> {
+        let count: (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM notes
+            WHERE collection_id = ? AND deleted_at IS NULL
+            "#,
+        )
+        .bind(collection_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.0)
+    }
+
+    // ===== Selective Restore Methods =====
+
+    /// List every note, including trashed ones, for picking notes to restore
+    pub async fn list_restorable_notes(&self) -> Result<Vec<RestorableNote>> {
+        let notes = sqlx::query_as::<_, RestorableNote>(
+            r#"
+            SELECT n.id, n.title, n.collection_id, c.name AS collection_name,
+                   n.updated_at, n.deleted_at,
+                   (SELECT COUNT(*) FROM attachments a WHERE a.note_id = n.id) AS attachment_count
+            FROM notes n
+            LEFT JOIN collections c ON c.id = n.collection_id
+            ORDER BY n.updated_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Load a note (trashed or not) with everything that belongs to it
+    pub async fn get_note_snapshot(&self, id: &str) -> Result<Option<NoteSnapshot>> {
+        let sql = format!("SELECT {} FROM notes WHERE id = ?", NOTE_COLUMNS);
+        let Some(note) = sqlx::query_as::<_, Note>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let sql = format!(
+            "SELECT {} FROM reminders WHERE note_id = ? ORDER BY trigger_time ASC",
+            REMINDER_COLUMNS
+        );
+        let reminders = sqlx::query_as::<_, Reminder>(&sql)
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let collection = match &note.collection_id {
+            Some(collection_id) => {
+                sqlx::query_as::<_, Collection>("SELECT * FROM collections WHERE id = ?")
+                    .bind(collection_id)
+                    .fetch_optional(&self.pool)
+                    .await?
+            }
+            None => None,
+        };
+
+        let journal_date: Option<NaiveDate> =
+            sqlx::query_scalar("SELECT entry_date FROM journal_entries WHERE note_id = ?")
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(Some(NoteSnapshot {
+            attachments: self.list_attachments(id).await?,
+            tags: self.list_tags_for_note(id).await?,
+            note,
+            reminders,
+            collection,
+            journal_date,
+        }))
+    }
+
+    /// Return which of the given note IDs exist, including trashed notes
+    pub async fn existing_note_ids(&self, ids: &[String]) -> Result<HashSet<String>> {
+        use sqlx::QueryBuilder;
+
+        if ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let mut builder: QueryBuilder<sqlx::Sqlite> =
+            QueryBuilder::new("SELECT id FROM notes WHERE id IN (");
+        let mut separated = builder.separated(", ");
+        for id in ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+
+        let existing: Vec<String> = builder.build_query_scalar().fetch_all(&self.pool).await?;
+
+        Ok(existing.into_iter().collect())
+    }
+
+    /// Write a note snapshot (e.g. from a backup) in one transaction.
+    ///
+    /// With `as_copy` the note, attachments and reminders get new IDs.
+    /// Otherwise the note keeps its ID and replaces any existing note with
+    /// that ID; the replaced title and content are kept as a revision.
+    /// Missing collections are recreated, tags are matched by name, and
+    /// reminders that came due in the meantime are restored as triggered
+    /// so they don't all fire at once. A daily note gets its date back
+    /// unless another note (not deleted) has become that date's daily note.
+    /// Returns the note's ID.
+    pub async fn import_note_snapshot(
+        &self,
+        snapshot: &NoteSnapshot,
+        as_copy: bool,
+    ) -> Result<String> {
+        let now = Utc::now();
+        let note = &snapshot.note;
+        let note_id = if as_copy {
+            Uuid::new_v4().to_string()
+        } else {
+            note.id.clone()
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(collection) = &snapshot.collection {
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO collections (id, name, description, color, icon, sort_order, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&collection.id)
+            .bind(&collection.name)
+            .bind(&collection.description)
+            .bind(&collection.color)
+            .bind(&collection.icon)
+            .bind(collection.sort_order)
+            .bind(collection.created_at)
+            .bind(collection.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let collection_id = snapshot.collection.as_ref().map(|c| c.id.clone());
+
+        if !as_copy {
+            let existing: Option<(String, String)> =
+                sqlx::query_as("SELECT title, content_json FROM notes WHERE id = ?")
+                    .bind(&note_id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+            if let Some((title, content_json)) = existing {
+                sqlx::query(
+                    r#"
+                    INSERT INTO note_revisions (id, note_id, title, content_json, created_at)
+                    VALUES (?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(&note_id)
+                .bind(&title)
+                .bind(&content_json)
+                .bind(now)
+                .execute(&mut *tx)
+                .await?;
+
+                for table in ["attachments", "reminders", "note_tags"] {
+                    sqlx::query(&format!("DELETE FROM {} WHERE note_id = ?", table))
+                        .bind(&note_id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO notes (id, title, content_json, created_at, updated_at, deleted_at, title_modified, collection_id)
+            VALUES (?, ?, ?, ?, ?, NULL, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title,
+                content_json = excluded.content_json,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                deleted_at = NULL,
+                title_modified = excluded.title_modified,
+                collection_id = excluded.collection_id
+            "#,
+        )
+        .bind(&note_id)
+        .bind(&note.title)
+        .bind(&note.content_json)
+        .bind(note.created_at)
+        .bind(note.updated_at)
+        .bind(note.title_modified)
+        .bind(&collection_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for attachment in &snapshot.attachments {
+            let id = if as_copy {
+                Uuid::new_v4().to_string()
+            } else {
+                attachment.id.clone()
+            };
+            sqlx::query(
+                r#"
+                INSERT INTO attachments (id, note_id, blob_hash, filename, mime_type, size, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(&note_id)
+            .bind(&attachment.blob_hash)
+            .bind(&attachment.filename)
+            .bind(&attachment.mime_type)
+            .bind(attachment.size)
+            .bind(attachment.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for reminder in &snapshot.reminders {
+            let id = if as_copy {
+                Uuid::new_v4().to_string()
+            } else {
+                reminder.id.clone()
+            };
+            sqlx::query(&format!(
+                "INSERT INTO reminders ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL, ?, ?, ?, ?, ?)",
+                REMINDER_COLUMNS
+            ))
+            .bind(&id)
+            .bind(&note_id)
+            .bind(reminder.trigger_time)
+            .bind(reminder.triggered || reminder.trigger_time <= now)
+            .bind(reminder.created_at)
+            .bind(reminder.sound_enabled)
+            .bind(&reminder.sound_type)
+            .bind(reminder.shake_enabled)
+            .bind(reminder.glow_enabled)
+            .bind(&reminder.recurrence_rule)
+            .bind(reminder.recurrence_start)
+            .bind(reminder.missed_policy)
+            .bind(reminder.missed_skip_after_hours)
+            .bind(&reminder.inline_text)
+            .bind(reminder.inline_start)
+            .bind(reminder.inline_end)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for tag in &snapshot.tags {
+            let existing: Option<String> =
+                sqlx::query_scalar("SELECT id FROM tags WHERE name = ? COLLATE NOCASE")
+                    .bind(&tag.name)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            let tag_id = match existing {
+                Some(id) => id,
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    sqlx::query(
+                        "INSERT INTO tags (id, name, color, created_at) VALUES (?, ?, ?, ?)",
+                    )
+                    .bind(&id)
+                    .bind(&tag.name)
+                    .bind(&tag.color)
+                    .bind(now)
+                    .execute(&mut *tx)
+                    .await?;
+                    id
+                }
+            };
+
+            sqlx::query(
+                "INSERT OR IGNORE INTO note_tags (note_id, tag_id, created_at) VALUES (?, ?, ?)",
+            )
+            .bind(&note_id)
+            .bind(&tag_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(date) = snapshot.journal_date {
+            sqlx::query("DELETE FROM journal_entries WHERE note_id = ? AND entry_date != ?")
+                .bind(&note_id)
+                .bind(date)
+                .execute(&mut *tx)
+                .await?;
+            // Like INSERT OR IGNORE, except that a date whose note is
+            // deleted or gone is taken over
+            sqlx::query(
+                r#"
+                INSERT INTO journal_entries (entry_date, note_id, created_at)
+                VALUES (?, ?, ?)
+                ON CONFLICT(entry_date) DO UPDATE SET
+                    note_id = excluded.note_id,
+                    created_at = excluded.created_at
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM notes
+                    WHERE id = journal_entries.note_id AND deleted_at IS NULL
+                )
+                "#,
+            )
+            .bind(date)
+            .bind(&note_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("DELETE FROM notes_fts WHERE note_id = ?")
+            .bind(&note_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO notes_fts (note_id, title, content_text) VALUES (?, ?, ?)")
+            .bind(&note_id)
+            .bind(&note.title)
+            .bind(Self::extract_text_from_delta(&note.content_json))
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        tracing::debug!("Imported note snapshot {} as {}", note.id, note_id);
+        Ok(note_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
     use crate::database::schema::initialize_database;
     use sqlx::sqlite::SqlitePoolOptions;
 
-    async fn create_test_repo() -> Repository {
-        let pool = SqlitePoolOptions::new()
-            .connect("sqlite::memory:")
+    #[test]
+    fn test_aliased_note_columns() {
+        let aliased: Vec<String> = NOTE_COLUMNS
+            .split(", ")
+            .map(|column| format!("n.{}", column))
+            .collect();
+        assert_eq!(ALIASED_NOTE_COLUMNS, aliased.join(", "));
+    }
+
+    async fn search(repo: &Repository, query: &str) -> Result<Vec<Note>> {
+        repo.search_notes_query(&SearchQuery::parse(query)?).await
+    }
+
+    async fn create_test_repo() -> Repository {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        initialize_database(&pool).await.unwrap();
+
+        Repository::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_note() {
+        let repo = create_test_repo().await;
+
+        let req = CreateNoteRequest {
+            title: "Test Note".to_string(),
+            content_json: r#"{"ops":[{"insert":"Hello\n"}]}"#.to_string(),
+            collection_id: None,
+        };
+
+        let note = repo.create_note(req).await.unwrap();
+        assert_eq!(note.title, "Test Note");
+
+        let fetched = repo.get_note(&note.id).await.unwrap();
+        assert_eq!(fetched.id, note.id);
+        assert_eq!(fetched.title, note.title);
+    }
+
+    #[tokio::test]
+    async fn test_update_note() {
+        let repo = create_test_repo().await;
+
+        let create_req = CreateNoteRequest {
+            title: "Original".to_string(),
+            content_json: "{}".to_string(),
+            collection_id: None,
+        };
+
+        let note = repo.create_note(create_req).await.unwrap();
+
+        let update_req = UpdateNoteRequest {
+            id: note.id.clone(),
+            title: Some("Updated".to_string()),
+            content_json: None,
+            title_modified: None,
+        };
+
+        let updated = repo.update_note(update_req).await.unwrap();
+        assert_eq!(updated.title, "Updated");
+    }
+
+    #[tokio::test]
+    async fn test_list_notes() {
+        let repo = create_test_repo().await;
+
+        for i in 1..=3 {
+            let req = CreateNoteRequest {
+                title: format!("Note {}", i),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            };
+            repo.create_note(req).await.unwrap();
+        }
+
+        let notes = repo.list_notes().await.unwrap();
+        assert_eq!(notes.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_soft_delete() {
+        let repo = create_test_repo().await;
+
+        let req = CreateNoteRequest {
+            title: "To Delete".to_string(),
+            content_json: "{}".to_string(),
+            collection_id: None,
+        };
+
+        let note = repo.create_note(req).await.unwrap();
+
+        repo.delete_note(&note.id).await.unwrap();
+
+        let result = repo.get_note(&note.id).await;
+        assert!(result.is_err());
+
+        let notes = repo.list_notes().await.unwrap();
+        assert_eq!(notes.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_attachments() {
+        let repo = create_test_repo().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Note with attachments".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let attachment = repo
+            .create_attachment(&note.id, "abc123", "test.png", "image/png", 1024)
+            .await
+            .unwrap();
+
+        assert_eq!(attachment.filename, "test.png");
+
+        let attachments = repo.list_attachments(&note.id).await.unwrap();
+        assert_eq!(attachments.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reminders() {
+        let repo = create_test_repo().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Note with reminder".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let trigger_time = Utc::now() + chrono::Duration::hours(1);
+        let reminder = repo
+            .create_reminder(&note.id, trigger_time, None, None, None, None)
+            .await
+            .unwrap();
+
+        assert!(!reminder.triggered);
+
+        let active = repo.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+
+        repo.mark_reminder_triggered(&reminder.id).await.unwrap();
+
+        let active_after = repo.list_active_reminders().await.unwrap();
+        assert_eq!(active_after.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_settings() {
+        let repo = create_test_repo().await;
+
+        repo.set_setting("theme", "dark").await.unwrap();
+
+        let value = repo.get_setting("theme").await.unwrap();
+        assert_eq!(value, Some("dark".to_string()));
+
+        // Update existing
+        repo.set_setting("theme", "light").await.unwrap();
+
+        let updated = repo.get_setting("theme").await.unwrap();
+        assert_eq!(updated, Some("light".to_string()));
+    }
+
+    // ===== FTS (Full-Text Search) Tests =====
+
+    #[tokio::test]
+    async fn test_fts_search() {
+        let repo = create_test_repo().await;
+
+        // Create notes with FTS indexing
+        let note1 = repo
+            .create_note(CreateNoteRequest {
+                title: "Meeting Notes".to_string(),
+                content_json: r#"{"ops":[{"insert":"Discuss project timeline\n"}]}"#.to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        repo.insert_note_fts(&note1.id, &note1.title, &note1.content_json)
+            .await
+            .unwrap();
+
+        let note2 = repo
+            .create_note(CreateNoteRequest {
+                title: "Shopping List".to_string(),
+                content_json: r#"{"ops":[{"insert":"Buy groceries\n"}]}"#.to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        repo.insert_note_fts(&note2.id, &note2.title, &note2.content_json)
+            .await
+            .unwrap();
+
+        // Search by title
+        let results = search(&repo, "meeting").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Meeting Notes");
+
+        // Search by content
+        let results = search(&repo, "groceries").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Shopping List");
+    }
+
+    #[tokio::test]
+    async fn test_fts_update() {
+        let repo = create_test_repo().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Original Title".to_string(),
+                content_json: r#"{"ops":[{"insert":"Original content\n"}]}"#.to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        repo.insert_note_fts(&note.id, &note.title, &note.content_json)
             .await
             .unwrap();
 
-        initialize_database(&pool).await.unwrap();
+        // Update note
+        let _updated = repo
+            .update_note(UpdateNoteRequest {
+                id: note.id.clone(),
+                title: Some("Updated Title".to_string()),
+                content_json: Some(r#"{"ops":[{"insert":"Updated content\n"}]}"#.to_string()),
+                title_modified: None,
+            })
+            .await
+            .unwrap();
 
-        Repository::new(pool)
+        // Update FTS index
+        repo.update_note_fts(
+            &note.id,
+            Some("Updated Title"),
+            Some(r#"{"ops":[{"insert":"Updated content\n"}]}"#),
+        )
+        .await
+        .unwrap();
+
+        // Search should find updated content
+        let results = search(&repo, "Updated").await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Updated Title");
+
+        // Original content should not be found
+        let results = search(&repo, "Original").await.unwrap();
+        assert_eq!(results.len(), 0);
     }
 
     #[tokio::test]
-    async fn test_create_and_get_note() {
+    async fn test_fts_delete() {
         let repo = create_test_repo().await;
 
-        let req = CreateNoteRequest {
-            title: "Test Note".to_string(),
-            content_json: r#"{"ops":[{"insert":"Hello\n"}]}"#.to_string(),
-            collection_id: None,
-        };
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Deletable Note".to_string(),
+                content_json: r#"{"ops":[{"insert":"Will be deleted\n"}]}"#.to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
 
-        let note = repo.create_note(req).await.unwrap();
-        assert_eq!(note.title, "Test Note");
+        repo.insert_note_fts(&note.id, &note.title, &note.content_json)
+            .await
+            .unwrap();
 
-        let fetched = repo.get_note(&note.id).await.unwrap();
-        assert_eq!(fetched.id, note.id);
-        assert_eq!(fetched.title, note.title);
+        // Verify it's searchable
+        let results = search(&repo, "Deletable").await.unwrap();
+        assert_eq!(results.len(), 1);
+
+        // Delete from FTS
+        repo.delete_note_fts(&note.id).await.unwrap();
+
+        // Should not be found anymore
+        let results = search(&repo, "Deletable").await.unwrap();
+        assert_eq!(results.len(), 0);
     }
 
     #[tokio::test]
-    async fn test_update_note() {
+    async fn test_extract_text_from_delta() {
+        // Valid delta with text
+        let delta = r#"{"ops":[{"insert":"Hello "},{"insert":"World","attributes":{"bold":true}},{"insert":"\n"}]}"#;
+        let text = Repository::extract_text_from_delta(delta);
+        assert_eq!(text, "Hello World\n");
+
+        // Empty delta
+        let empty = r#"{"ops":[{"insert":"\n"}]}"#;
+        let text = Repository::extract_text_from_delta(empty);
+        assert_eq!(text, "\n");
+
+        // Invalid JSON
+        let invalid = "not valid json";
+        let text = Repository::extract_text_from_delta(invalid);
+        assert_eq!(text, "");
+
+        // Delta with embeds (images)
+        let embed = r#"{"ops":[{"insert":"Before "},{"insert":{"image":"data:..."}},{"insert":" After\n"}]}"#;
+        let text = Repository::extract_text_from_delta(embed);
+        assert_eq!(text, "Before  After\n");
+    }
+
+    #[tokio::test]
+    async fn test_list_referenced_blob_hashes() {
         let repo = create_test_repo().await;
+        let embed = |hash: &str| {
+            format!(
+                r#"{{"ops":[{{"insert":"x"}},{{"insert":{{"attachment-file":{{"blobHash":"{}"}}}}}}]}}"#,
+                hash
+            )
+        };
 
-        let create_req = CreateNoteRequest {
-            title: "Original".to_string(),
-            content_json: "{}".to_string(),
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Note".to_string(),
+                content_json: embed("in-content"),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        repo.create_note_revision(&note.id, "Old", &embed("in-revision"))
+            .await
+            .unwrap();
+        repo.create_attachment(&note.id, "in-attachment", "a.txt", "text/plain", 1)
+            .await
+            .unwrap();
+        // Trashed notes can still be restored, so their blobs stay referenced
+        repo.delete_note(&note.id).await.unwrap();
+
+        let hashes = repo.list_referenced_blob_hashes().await.unwrap();
+        let mut hashes: Vec<_> = hashes.into_iter().collect();
+        hashes.sort();
+        assert_eq!(hashes, vec!["in-attachment", "in-content", "in-revision"]);
+
+        assert!(Repository::extract_blob_hashes_from_delta("not json").is_empty());
+    }
+
+    // ===== Note Revision Tests =====
+
+    async fn create_note_with_title(repo: &Repository, title: &str) -> Note {
+        repo.create_note(CreateNoteRequest {
+            title: title.to_string(),
+            content_json: r#"{"ops":[{"insert":"Body\n"}]}"#.to_string(),
             collection_id: None,
-        };
+        })
+        .await
+        .unwrap()
+    }
 
-        let note = repo.create_note(create_req).await.unwrap();
+    #[tokio::test]
+    async fn test_note_revision_create_list_get() {
+        let repo = create_test_repo().await;
+        let note = create_note_with_title(&repo, "Revisioned").await;
 
-        let update_req = UpdateNoteRequest {
-            id: note.id.clone(),
-            title: Some("Updated".to_string()),
-            content_json: None,
-            title_modified: None,
-        };
+        assert!(repo
+            .latest_note_revision_time(&note.id)
+            .await
+            .unwrap()
+            .is_none());
 
-        let updated = repo.update_note(update_req).await.unwrap();
-        assert_eq!(updated.title, "Updated");
+        let revision = repo
+            .create_note_revision(&note.id, "Old title", r#"{"ops":[]}"#)
+            .await
+            .unwrap();
+
+        let listed = repo.list_note_revisions(&note.id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, revision.id);
+        assert_eq!(listed[0].content_length, 10);
+
+        let fetched = repo.get_note_revision(&revision.id).await.unwrap();
+        assert_eq!(fetched.title, "Old title");
+        assert!(repo
+            .latest_note_revision_time(&note.id)
+            .await
+            .unwrap()
+            .is_some());
+
+        assert!(repo.get_note_revision("missing").await.is_err());
     }
 
     #[tokio::test]
-    async fn test_list_notes() {
+    async fn test_prune_note_revisions_keep_n() {
         let repo = create_test_repo().await;
+        let note = create_note_with_title(&repo, "Busy").await;
 
-        for i in 1..=3 {
-            let req = CreateNoteRequest {
-                title: format!("Note {}", i),
-                content_json: "{}".to_string(),
-                collection_id: None,
-            };
-            repo.create_note(req).await.unwrap();
+        for i in 0..5 {
+            repo.create_note_revision(&note.id, &format!("v{}", i), "{}")
+                .await
+                .unwrap();
         }
 
-        let notes = repo.list_notes().await.unwrap();
-        assert_eq!(notes.len(), 3);
+        let deleted = repo.prune_note_revisions(&note.id, 3, None).await.unwrap();
+        assert_eq!(deleted, 2);
+
+        let titles: Vec<String> = repo
+            .list_note_revisions(&note.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.title)
+            .collect();
+        assert_eq!(titles, vec!["v4", "v3", "v2"]);
     }
 
     #[tokio::test]
-    async fn test_soft_delete() {
+    async fn test_prune_note_revisions_thin_by_age() {
         let repo = create_test_repo().await;
+        let note = create_note_with_title(&repo, "Old").await;
+
+        // Three revisions on one old day, two on another, one recent
+        let old_times = [
+            "2025-01-01T08:00:00+00:00",
+            "2025-01-01T12:00:00+00:00",
+            "2025-01-01T18:00:00+00:00",
+            "2025-01-02T09:00:00+00:00",
+            "2025-01-02T10:00:00+00:00",
+        ];
+        for (i, time) in old_times.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO note_revisions (id, note_id, title, content_json, created_at) VALUES (?, ?, ?, '{}', ?)",
+            )
+            .bind(format!("old-{}", i))
+            .bind(&note.id)
+            .bind(format!("old {}", i))
+            .bind(time)
+            .execute(&repo.pool)
+            .await
+            .unwrap();
+        }
+        repo.create_note_revision(&note.id, "recent", "{}")
+            .await
+            .unwrap();
 
-        let req = CreateNoteRequest {
-            title: "To Delete".to_string(),
-            content_json: "{}".to_string(),
-            collection_id: None,
+        let cutoff = Utc::now() - chrono::Duration::days(7);
+        let deleted = repo
+            .prune_note_revisions(&note.id, 0, Some(cutoff))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 3);
+
+        let titles: Vec<String> = repo
+            .list_note_revisions(&note.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.title)
+            .collect();
+        // Newest revision of each old day survives
+        assert_eq!(titles, vec!["recent", "old 4", "old 2"]);
+    }
+
+    // ===== Search Query Tests =====
+
+    async fn create_indexed_note(
+        repo: &Repository,
+        title: &str,
+        body: &str,
+        collection_id: Option<String>,
+    ) -> Note {
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: title.to_string(),
+                content_json: format!(r#"{{"ops":[{{"insert":"{}\n"}}]}}"#, body),
+                collection_id,
+            })
+            .await
+            .unwrap();
+        repo.insert_note_fts(&note.id, &note.title, &note.content_json)
+            .await
+            .unwrap();
+        note
+    }
+
+    #[tokio::test]
+    async fn test_search_query_language() {
+        let repo = create_test_repo().await;
+
+        let work = repo
+            .create_collection(CreateCollectionRequest {
+                name: "Work".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+
+        let meeting = create_indexed_note(
+            &repo,
+            "Meeting notes",
+            "agreed on the release plan",
+            Some(work.id.clone()),
+        )
+        .await;
+        let draft = create_indexed_note(&repo, "Release draft", "plan release notes", None).await;
+        let recipe = create_indexed_note(&repo, "Recipe", "bake the bread", None).await;
+
+        repo.create_attachment(&draft.id, "hash", "draft.pdf", "application/pdf", 10)
+            .await
+            .unwrap();
+        repo.create_reminder(
+            &recipe.id,
+            Utc::now() + chrono::Duration::hours(1),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let titles = |notes: Vec<Note>| {
+            let mut titles: Vec<String> = notes.into_iter().map(|n| n.title).collect();
+            titles.sort();
+            titles
         };
 
-        let note = repo.create_note(req).await.unwrap();
+        // Phrase vs. loose terms
+        let results = search(&repo, r#""release plan""#).await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes"]);
+        let results = search(&repo, "release plan").await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes", "Release draft"]);
+
+        // Exclusion and field scope
+        let results = search(&repo, "release -draft").await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes"]);
+        let results = search(&repo, "bread OR release -draft").await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes", "Recipe"]);
+        let results = search(&repo, "release -draft OR bake -bread")
+            .await
+            .unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes"]);
+        let results = search(&repo, "title:notes").await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes"]);
+        let results = search(&repo, "content:notes").await.unwrap();
+        assert_eq!(titles(results), vec!["Release draft"]);
+
+        // Filters, alone and negated
+        let results = search(&repo, "collection:work").await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes"]);
+        let results = search(&repo, "-collection:work").await.unwrap();
+        assert_eq!(titles(results), vec!["Recipe", "Release draft"]);
+        let results = search(&repo, "has:attachment").await.unwrap();
+        assert_eq!(titles(results), vec!["Release draft"]);
+        let results = search(&repo, "bread OR has:attachment").await.unwrap();
+        assert_eq!(titles(results), vec!["Recipe", "Release draft"]);
+        let results = search(&repo, "NOT has:reminder").await.unwrap();
+        assert_eq!(titles(results), vec!["Meeting notes", "Release draft"]);
+
+        // Date filters
+        let today = chrono::Local::now().date_naive();
+        let results = search(&repo, &format!("created:{}", today)).await.unwrap();
+        assert_eq!(results.len(), 3);
+        let results = search(&repo, &format!("release updated:>{}", today))
+            .await
+            .unwrap();
+        assert!(results.is_empty());
 
-        repo.delete_note(&note.id).await.unwrap();
+        // Malformed input is a query error, not a database error
+        let err = search(&repo, r#"title:"open"#).await.unwrap_err();
+        assert!(matches!(err, AppError::InvalidSearchQuery { .. }));
+        assert!(search(&repo, "NEAR(a b) ^c").await.is_ok());
 
-        let result = repo.get_note(&note.id).await;
-        assert!(result.is_err());
+        // Deleted notes never match
+        repo.delete_note(&meeting.id).await.unwrap();
+        let results = search(&repo, "collection:work").await.unwrap();
+        assert!(results.is_empty());
+    }
 
-        let notes = repo.list_notes().await.unwrap();
-        assert_eq!(notes.len(), 0);
+    #[tokio::test]
+    async fn test_search_notes_with_snippets() {
+        let repo = create_test_repo().await;
+        create_indexed_note(
+            &repo,
+            "Plan <draft>",
+            "first we gather requirements then we plan the rollout",
+            None,
+        )
+        .await;
+
+        let query = SearchQuery::parse("plan").unwrap();
+        let results = repo.search_notes_with_snippets(&query).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].title_highlight,
+            "<mark>Plan</mark> &lt;draft&gt;"
+        );
+        assert!(results[0]
+            .content_snippet
+            .contains("we <mark>plan</mark> the rollout"));
+        assert!(results[0].score.is_some());
+
+        // Filter-only queries fall back to a plain preview
+        let query = SearchQuery::parse("-has:tag").unwrap();
+        let results = repo.search_notes_with_snippets(&query).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content_snippet.starts_with("first we gather"));
+        assert!(results[0].score.is_none());
+    }
+
+    // ===== Note Summary Pagination Tests =====
+
+    async fn collect_pages(
+        repo: &Repository,
+        query: &SearchQuery,
+        mut req: NoteListRequest,
+    ) -> Vec<Vec<String>> {
+        let mut pages = Vec::new();
+        loop {
+            let page = repo.list_note_summaries(query, &req).await.unwrap();
+            pages.push(page.items.iter().map(|n| n.title.clone()).collect());
+            match page.next_cursor {
+                Some(cursor) => req.cursor = Some(cursor),
+                None => return pages,
+            }
+        }
     }
 
     #[tokio::test]
-    async fn test_attachments() {
+    async fn test_list_note_summaries_paginates() {
         let repo = create_test_repo().await;
+        for title in ["delta", "Alpha", "charlie", "Bravo", "echo"] {
+            create_indexed_note(&repo, title, "some body text", None).await;
+        }
 
-        let note = repo
-            .create_note(CreateNoteRequest {
-                title: "Note with attachments".to_string(),
-                content_json: "{}".to_string(),
-                collection_id: None,
-            })
+        let query = SearchQuery::parse("").unwrap();
+        let pages = collect_pages(
+            &repo,
+            &query,
+            NoteListRequest {
+                sort: NoteSort::Title,
+                limit: Some(2),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(
+            pages,
+            vec![
+                vec!["Alpha", "Bravo"],
+                vec!["charlie", "delta"],
+                vec!["echo"]
+            ]
+        );
+
+        // Newest first by default
+        let pages = collect_pages(
+            &repo,
+            &query,
+            NoteListRequest {
+                sort: NoteSort::Created,
+                limit: Some(3),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(
+            pages,
+            vec![vec!["echo", "Bravo", "charlie"], vec!["Alpha", "delta"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_note_summaries_projection() {
+        let repo = create_test_repo().await;
+        let note = create_indexed_note(&repo, "With file", "  first   line ", None).await;
+        repo.create_attachment(&note.id, "hash", "a.txt", "text/plain", 1)
+            .await
+            .unwrap();
+        repo.create_attachment(&note.id, "hash2", "b.txt", "text/plain", 1)
             .await
             .unwrap();
 
-        let attachment = repo
-            .create_attachment(&note.id, "abc123", "test.png", "image/png", 1024)
+        let page = repo
+            .list_note_summaries(&SearchQuery::parse("").unwrap(), &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].preview, "first line");
+        assert_eq!(page.items[0].attachment_count, 2);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_note_summaries_by_relevance_and_collection() {
+        let repo = create_test_repo().await;
+        let work = repo
+            .create_collection(CreateCollectionRequest {
+                name: "Work".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+        create_indexed_note(
+            &repo,
+            "Budget",
+            "budget budget budget",
+            Some(work.id.clone()),
+        )
+        .await;
+        create_indexed_note(
+            &repo,
+            "Notes",
+            "mentions budget once among many other words",
+            Some(work.id.clone()),
+        )
+        .await;
+        create_indexed_note(&repo, "Budget elsewhere", "budget", None).await;
+        create_indexed_note(&repo, "Unrelated", "nothing here", Some(work.id.clone())).await;
+
+        let query = SearchQuery::parse("budget").unwrap();
+        let pages = collect_pages(
+            &repo,
+            &query,
+            NoteListRequest {
+                sort: NoteSort::Relevance,
+                collection_id: Some(work.id.clone()),
+                limit: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(pages, vec![vec!["Budget"], vec!["Notes"]]);
+
+        // A cursor from one ordering is rejected by another
+        let page = repo
+            .list_note_summaries(
+                &query,
+                &NoteListRequest {
+                    sort: NoteSort::Relevance,
+                    limit: Some(1),
+                    ..Default::default()
+                },
+            )
             .await
             .unwrap();
-
-        assert_eq!(attachment.filename, "test.png");
-
-        let attachments = repo.list_attachments(&note.id).await.unwrap();
-        assert_eq!(attachments.len(), 1);
+        let result = repo
+            .list_note_summaries(
+                &query,
+                &NoteListRequest {
+                    sort: NoteSort::Title,
+                    cursor: page.next_cursor,
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(result.is_err());
     }
 
+    // ===== Tag Tests =====
+
     #[tokio::test]
-    async fn test_reminders() {
+    async fn test_tag_assignment_and_counts() {
         let repo = create_test_repo().await;
+        let note1 = create_note_with_title(&repo, "First").await;
+        let note2 = create_note_with_title(&repo, "Second").await;
 
-        let note = repo
-            .create_note(CreateNoteRequest {
-                title: "Note with reminder".to_string(),
-                content_json: "{}".to_string(),
-                collection_id: None,
-            })
-            .await
-            .unwrap();
+        let work = repo.create_tag("Work", None).await.unwrap();
+        let urgent = repo.create_tag("urgent", Some("#FF0000")).await.unwrap();
+        assert_eq!(work.color, "#6B7280");
 
-        let trigger_time = Utc::now() + chrono::Duration::hours(1);
-        let reminder = repo
-            .create_reminder(&note.id, trigger_time, None, None, None, None)
-            .await
-            .unwrap();
+        // Names are unique regardless of case
+        assert!(repo.create_tag("work", None).await.is_err());
+        assert_eq!(
+            repo.get_tag_by_name("WORK").await.unwrap().unwrap().id,
+            work.id
+        );
 
-        assert!(!reminder.triggered);
+        repo.add_tag_to_note(&note1.id, &work.id).await.unwrap();
+        repo.add_tag_to_note(&note1.id, &work.id).await.unwrap(); // idempotent
+        repo.add_tag_to_note(&note1.id, &urgent.id).await.unwrap();
+        repo.add_tag_to_note(&note2.id, &work.id).await.unwrap();
 
-        let active = repo.list_active_reminders().await.unwrap();
-        assert_eq!(active.len(), 1);
+        let tags = repo.list_tags().await.unwrap();
+        let count = |id: &str| tags.iter().find(|t| t.id == id).unwrap().note_count;
+        assert_eq!(count(&work.id), 2);
+        assert_eq!(count(&urgent.id), 1);
 
-        repo.mark_reminder_triggered(&reminder.id).await.unwrap();
+        let both = search(&repo, "tag:work tag:URGENT").await.unwrap();
+        assert_eq!(both.len(), 1);
+        assert_eq!(both[0].id, note1.id);
 
-        let active_after = repo.list_active_reminders().await.unwrap();
-        assert_eq!(active_after.len(), 0);
+        repo.remove_tag_from_note(&note1.id, &urgent.id)
+            .await
+            .unwrap();
+        assert_eq!(repo.list_tags_for_note(&note1.id).await.unwrap().len(), 1);
+
+        // Deleted notes are not counted
+        repo.delete_note(&note2.id).await.unwrap();
+        let tags = repo.list_tags().await.unwrap();
+        assert_eq!(tags.iter().find(|t| t.id == work.id).unwrap().note_count, 1);
     }
 
     #[tokio::test]
-    async fn test_settings() {
+    async fn test_merge_tags() {
         let repo = create_test_repo().await;
+        let note1 = create_note_with_title(&repo, "First").await;
+        let note2 = create_note_with_title(&repo, "Second").await;
 
-        repo.set_setting("theme", "dark").await.unwrap();
+        let target = repo.create_tag("todo", None).await.unwrap();
+        let dup = repo.create_tag("to-do", None).await.unwrap();
 
-        let value = repo.get_setting("theme").await.unwrap();
-        assert_eq!(value, Some("dark".to_string()));
+        repo.add_tag_to_note(&note1.id, &target.id).await.unwrap();
+        repo.add_tag_to_note(&note1.id, &dup.id).await.unwrap();
+        repo.add_tag_to_note(&note2.id, &dup.id).await.unwrap();
 
-        // Update existing
-        repo.set_setting("theme", "light").await.unwrap();
+        repo.merge_tags(std::slice::from_ref(&dup.id), &target.id)
+            .await
+            .unwrap();
 
-        let updated = repo.get_setting("theme").await.unwrap();
-        assert_eq!(updated, Some("light".to_string()));
+        assert!(repo.get_tag(&dup.id).await.is_err());
+        let notes = repo.list_notes_with_tag(&target.id).await.unwrap();
+        assert_eq!(notes.len(), 2);
     }
 
-    // ===== FTS (Full-Text Search) Tests =====
+    // ===== Note Link Tests =====
 
     #[tokio::test]
-    async fn test_fts_search() {
+    async fn test_note_links_resolve_by_id_and_title() {
         let repo = create_test_repo().await;
+        let source = create_note_with_title(&repo, "Source").await;
+        let trip = create_note_with_title(&repo, "Trip Plan").await;
+        let budget = create_note_with_title(&repo, "Budget").await;
+
+        repo.replace_note_links(
+            &source.id,
+            &[
+                "trip plan".to_string(),
+                budget.id.clone(),
+                "Packing list".to_string(),
+                "TRIP PLAN".to_string(),
+            ],
+        )
+        .await
+        .unwrap();
 
-        // Create notes with FTS indexing
-        let note1 = repo
-            .create_note(CreateNoteRequest {
-                title: "Meeting Notes".to_string(),
-                content_json: r#"{"ops":[{"insert":"Discuss project timeline\n"}]}"#.to_string(),
-                collection_id: None,
-            })
-            .await
-            .unwrap();
-
-        repo.insert_note_fts(&note1.id, &note1.title, &note1.content_json)
-            .await
-            .unwrap();
+        let outgoing = repo.list_outgoing_links(&source.id).await.unwrap();
+        assert_eq!(outgoing.len(), 3);
+        let by_target = |target: &str| {
+            outgoing
+                .iter()
+                .find(|l| l.target.eq_ignore_ascii_case(target))
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(
+            by_target("trip plan").target_note_id.as_deref(),
+            Some(trip.id.as_str())
+        );
+        assert_eq!(
+            by_target(&budget.id).target_title.as_deref(),
+            Some("Budget")
+        );
+        assert!(by_target("Packing list").target_note_id.is_none());
 
-        let note2 = repo
-            .create_note(CreateNoteRequest {
-                title: "Shopping List".to_string(),
-                content_json: r#"{"ops":[{"insert":"Buy groceries\n"}]}"#.to_string(),
-                collection_id: None,
-            })
-            .await
-            .unwrap();
+        let backlinks = repo.list_backlinks(&trip.id).await.unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].source_title, "Source");
 
-        repo.insert_note_fts(&note2.id, &note2.title, &note2.content_json)
-            .await
-            .unwrap();
+        let dangling = repo.list_dangling_links().await.unwrap();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].target, "Packing list");
 
-        // Search by title
-        let results = repo.search_notes_fts("meeting").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].title, "Meeting Notes");
+        // A note with the missing title makes the link resolve
+        let packing = create_note_with_title(&repo, "Packing List").await;
+        assert!(repo.list_dangling_links().await.unwrap().is_empty());
+        assert_eq!(repo.list_backlinks(&packing.id).await.unwrap().len(), 1);
 
-        // Search by content
-        let results = repo.search_notes_fts("groceries").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].title, "Shopping List");
+        // Deleted targets leave links dangling; deleted sources hide them
+        repo.delete_note(&trip.id).await.unwrap();
+        assert_eq!(
+            repo.list_dangling_links().await.unwrap()[0].target,
+            "trip plan"
+        );
+        repo.delete_note(&source.id).await.unwrap();
+        assert!(repo.list_dangling_links().await.unwrap().is_empty());
+        assert!(repo.list_backlinks(&budget.id).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_fts_update() {
+    async fn test_rename_note_with_links() {
         let repo = create_test_repo().await;
-
-        let note = repo
-            .create_note(CreateNoteRequest {
-                title: "Original Title".to_string(),
-                content_json: r#"{"ops":[{"insert":"Original content\n"}]}"#.to_string(),
-                collection_id: None,
-            })
+        let target = create_note_with_title(&repo, "Old").await;
+        let source = create_note_with_title(&repo, "Source").await;
+        repo.insert_note_fts(&source.id, &source.title, &source.content_json)
+            .await
+            .unwrap();
+        repo.replace_note_links(&source.id, &["Old".to_string()])
             .await
             .unwrap();
 
-        repo.insert_note_fts(&note.id, &note.title, &note.content_json)
+        let rewrites = [LinkRewrite {
+            note_id: source.id.clone(),
+            content_json: r#"{"ops":[{"insert":"See [[New]] again\n"}]}"#.to_string(),
+            links: vec!["New".to_string()],
+        }];
+        let renamed = repo
+            .rename_note_with_links(&target.id, "New", &rewrites)
             .await
             .unwrap();
+        assert_eq!(renamed.title, "New");
+        assert!(renamed.title_modified);
+
+        let source = repo.get_note(&source.id).await.unwrap();
+        assert!(source.content_json.contains("[[New]]"));
+        let backlinks = repo.list_backlinks(&target.id).await.unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].target, "New");
+
+        let found = search(&repo, "again").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, source.id);
+
+        assert!(matches!(
+            repo.rename_note_with_links("missing", "New", &[]).await,
+            Err(AppError::NoteNotFound(_))
+        ));
+    }
 
-        // Update note
-        let _updated = repo
-            .update_note(UpdateNoteRequest {
-                id: note.id.clone(),
-                title: Some("Updated Title".to_string()),
-                content_json: Some(r#"{"ops":[{"insert":"Updated content\n"}]}"#.to_string()),
-                title_modified: None,
+    // ===== Template Tests =====
+
+    #[tokio::test]
+    async fn test_template_crud() {
+        let repo = create_test_repo().await;
+        let collection = repo
+            .create_collection(CreateCollectionRequest {
+                name: "Meetings".to_string(),
+                description: None,
+                color: None,
+                icon: None,
             })
             .await
             .unwrap();
 
-        // Update FTS index
-        repo.update_note_fts(
-            &note.id,
-            Some("Updated Title"),
-            Some(r#"{"ops":[{"insert":"Updated content\n"}]}"#),
-        )
-        .await
-        .unwrap();
+        let mut req = SaveTemplateRequest {
+            name: "Standup".to_string(),
+            title: "Standup {{date}}".to_string(),
+            content_json: r#"{"ops":[{"insert":"Yesterday\n"}]}"#.to_string(),
+            collection_id: Some(collection.id.clone()),
+            reminder_offset_minutes: Some(30),
+        };
+        let standup = repo.create_template(&req).await.unwrap();
+        assert_eq!(standup.title, "Standup {{date}}");
+        assert_eq!(standup.reminder_offset_minutes, Some(30));
 
-        // Search should find updated content
-        let results = repo.search_notes_fts("Updated").await.unwrap();
-        assert_eq!(results.len(), 1);
-        assert_eq!(results[0].title, "Updated Title");
+        req.name = "Retro".to_string();
+        req.reminder_offset_minutes = None;
+        let retro = repo.create_template(&req).await.unwrap();
 
-        // Original content should not be found
-        let results = repo.search_notes_fts("Original").await.unwrap();
-        assert_eq!(results.len(), 0);
+        let names: Vec<String> = repo
+            .list_templates()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["Retro", "Standup"]);
+
+        req.name = "Weekly retro".to_string();
+        req.collection_id = None;
+        let updated = repo.update_template(&retro.id, &req).await.unwrap();
+        assert_eq!(updated.name, "Weekly retro");
+        assert_eq!(updated.collection_id, None);
+        assert_eq!(updated.reminder_offset_minutes, None);
+
+        // Deleting the collection keeps its templates
+        repo.delete_collection(&collection.id).await.unwrap();
+        let standup = repo.get_template(&standup.id).await.unwrap();
+        assert_eq!(standup.collection_id, None);
+
+        repo.delete_template(&standup.id).await.unwrap();
+        assert!(repo.get_template(&standup.id).await.is_err());
+        assert!(repo.update_template(&standup.id, &req).await.is_err());
     }
 
+    // ===== Journal Tests =====
+
     #[tokio::test]
-    async fn test_fts_delete() {
+    async fn test_journal_entries() {
         let repo = create_test_repo().await;
+        let date = |day: u32| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
 
-        let note = repo
-            .create_note(CreateNoteRequest {
-                title: "Deletable Note".to_string(),
-                content_json: r#"{"ops":[{"insert":"Will be deleted\n"}]}"#.to_string(),
-                collection_id: None,
-            })
-            .await
-            .unwrap();
+        let first = create_note_with_title(&repo, "Oct 1").await;
+        let fifth = create_note_with_title(&repo, "Oct 5").await;
+        let ninth = create_note_with_title(&repo, "Oct 9").await;
+        repo.set_journal_note(date(1), &first.id).await.unwrap();
+        repo.set_journal_note(date(5), &fifth.id).await.unwrap();
+        repo.set_journal_note(date(9), &ninth.id).await.unwrap();
 
-        repo.insert_note_fts(&note.id, &note.title, &note.content_json)
+        assert_eq!(
+            repo.get_journal_note_id(date(5)).await.unwrap(),
+            Some(fifth.id.clone())
+        );
+        assert_eq!(repo.get_journal_note_id(date(6)).await.unwrap(), None);
+
+        let entries = repo.list_journal_entries(date(2), date(9)).await.unwrap();
+        let dates: Vec<NaiveDate> = entries.iter().map(|e| e.date).collect();
+        assert_eq!(dates, vec![date(5), date(9)]);
+        assert_eq!(entries[0].title, "Oct 5");
+
+        let previous = repo.adjacent_journal_entry(date(5), false).await.unwrap();
+        assert_eq!(previous.unwrap().note_id, first.id);
+        let next = repo.adjacent_journal_entry(date(6), true).await.unwrap();
+        assert_eq!(next.unwrap().note_id, ninth.id);
+        assert!(repo
+            .adjacent_journal_entry(date(9), true)
             .await
-            .unwrap();
-
-        // Verify it's searchable
-        let results = repo.search_notes_fts("Deletable").await.unwrap();
-        assert_eq!(results.len(), 1);
+            .unwrap()
+            .is_none());
 
-        // Delete from FTS
-        repo.delete_note_fts(&note.id).await.unwrap();
+        // Deleted notes drop out, and the date can get a new note
+        repo.delete_note(&fifth.id).await.unwrap();
+        assert_eq!(repo.get_journal_note_id(date(5)).await.unwrap(), None);
+        let next = repo.adjacent_journal_entry(date(1), true).await.unwrap();
+        assert_eq!(next.unwrap().note_id, ninth.id);
 
-        // Should not be found anymore
-        let results = repo.search_notes_fts("Deletable").await.unwrap();
-        assert_eq!(results.len(), 0);
+        let again = create_note_with_title(&repo, "Oct 5 again").await;
+        repo.set_journal_note(date(5), &again.id).await.unwrap();
+        assert_eq!(
+            repo.get_journal_note_id(date(5)).await.unwrap(),
+            Some(again.id.clone())
+        );
     }
 
     #[tokio::test]
-    async fn test_extract_text_from_delta() {
-        // Valid delta with text
-        let delta = r#"{"ops":[{"insert":"Hello "},{"insert":"World","attributes":{"bold":true}},{"insert":"\n"}]}"#;
-        let text = Repository::extract_text_from_delta(delta);
-        assert_eq!(text, "Hello World\n");
-
-        // Empty delta
-        let empty = r#"{"ops":[{"insert":"\n"}]}"#;
-        let text = Repository::extract_text_from_delta(empty);
-        assert_eq!(text, "\n");
-
-        // Invalid JSON
-        let invalid = "not valid json";
-        let text = Repository::extract_text_from_delta(invalid);
-        assert_eq!(text, "");
+    async fn test_note_snapshot_restores_journal_date() {
+        let repo = create_test_repo().await;
+        let date = NaiveDate::from_ymd_opt(2026, 10, 5).unwrap();
+
+        let original = create_note_with_title(&repo, "Oct 5").await;
+        repo.set_journal_note(date, &original.id).await.unwrap();
+        let snapshot = repo.get_note_snapshot(&original.id).await.unwrap().unwrap();
+        assert_eq!(snapshot.journal_date, Some(date));
+
+        // Trashed and replaced: the replacement keeps the date
+        repo.delete_note(&original.id).await.unwrap();
+        let replacement = create_note_with_title(&repo, "Oct 5 again").await;
+        repo.set_journal_note(date, &replacement.id).await.unwrap();
+        repo.import_note_snapshot(&snapshot, false).await.unwrap();
+        assert_eq!(
+            repo.get_journal_note_id(date).await.unwrap(),
+            Some(replacement.id.clone())
+        );
+        let copy_id = repo.import_note_snapshot(&snapshot, true).await.unwrap();
+        assert_eq!(
+            repo.get_journal_note_id(date).await.unwrap(),
+            Some(replacement.id.clone())
+        );
 
-        // Delta with embeds (images)
-        let embed = r#"{"ops":[{"insert":"Before "},{"insert":{"image":"data:..."}},{"insert":" After\n"}]}"#;
-        let text = Repository::extract_text_from_delta(embed);
-        assert_eq!(text, "Before  After\n");
+        // Once the replacement is deleted too, the restored note takes the
+        // date back
+        repo.delete_note(&replacement.id).await.unwrap();
+        repo.delete_note(&copy_id).await.unwrap();
+        repo.import_note_snapshot(&snapshot, false).await.unwrap();
+        assert_eq!(
+            repo.get_journal_note_id(date).await.unwrap(),
+            Some(original.id)
+        );
     }
 
     // ===== Collections Tests =====
@@ -1819,6 +4209,174 @@ mod tests {
         assert_eq!(reminders.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_import_note_snapshot() {
+        let source = create_test_repo().await;
+        let target = create_test_repo().await;
+
+        let collection = source
+            .create_collection(CreateCollectionRequest {
+                name: "Work".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+        let note = source
+            .create_note(CreateNoteRequest {
+                title: "Backed up".to_string(),
+                content_json: r#"{"ops":[{"insert":"Quarterly plan\n"}]}"#.to_string(),
+                collection_id: Some(collection.id.clone()),
+            })
+            .await
+            .unwrap();
+        source
+            .create_attachment(&note.id, "hash1", "plan.pdf", "application/pdf", 10)
+            .await
+            .unwrap();
+        source
+            .create_reminder(
+                &note.id,
+                Utc::now() - chrono::Duration::hours(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        source
+            .create_reminder(
+                &note.id,
+                Utc::now() + chrono::Duration::hours(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let tag = source.create_tag("Planning", None).await.unwrap();
+        source.add_tag_to_note(&note.id, &tag.id).await.unwrap();
+
+        let snapshot = source.get_note_snapshot(&note.id).await.unwrap().unwrap();
+        assert!(source.get_note_snapshot("missing").await.unwrap().is_none());
+
+        // Fresh import keeps the ID and brings everything along
+        let id = target.import_note_snapshot(&snapshot, false).await.unwrap();
+        assert_eq!(id, note.id);
+        let imported = target.get_note(&id).await.unwrap();
+        assert_eq!(imported.collection_id, Some(collection.id.clone()));
+        assert_eq!(target.list_attachments(&id).await.unwrap().len(), 1);
+        assert_eq!(
+            target.list_tags_for_note(&id).await.unwrap()[0].name,
+            "Planning"
+        );
+        // The overdue reminder comes back as already triggered
+        assert_eq!(target.list_active_reminders().await.unwrap().len(), 1);
+        assert_eq!(search(&target, "quarterly").await.unwrap().len(), 1);
+
+        // Overwriting keeps the replaced version as a revision
+        target
+            .update_note(UpdateNoteRequest {
+                id: id.clone(),
+                title: Some("Edited".to_string()),
+                content_json: None,
+                title_modified: Some(true),
+            })
+            .await
+            .unwrap();
+        target.import_note_snapshot(&snapshot, false).await.unwrap();
+        assert_eq!(target.get_note(&id).await.unwrap().title, "Backed up");
+        assert_eq!(target.list_note_revisions(&id).await.unwrap().len(), 1);
+        assert_eq!(target.list_attachments(&id).await.unwrap().len(), 1);
+
+        // A copy gets a new ID and leaves the original alone
+        let copy_id = target.import_note_snapshot(&snapshot, true).await.unwrap();
+        assert_ne!(copy_id, id);
+        assert_eq!(target.list_attachments(&copy_id).await.unwrap().len(), 1);
+        assert_eq!(target.list_tags().await.unwrap().len(), 1);
+
+        let ids = vec![id.clone(), "missing".to_string()];
+        let existing = target.existing_note_ids(&ids).await.unwrap();
+        assert!(existing.contains(&id));
+        assert_eq!(existing.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reminder_history_and_missed() {
+        let repo = create_test_repo().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        let trigger_time = Utc::now();
+        let answered = repo
+            .create_reminder(&note.id, trigger_time, None, None, None, None)
+            .await
+            .unwrap();
+        let ignored = repo
+            .create_reminder(&note.id, trigger_time, None, None, None, None)
+            .await
+            .unwrap();
+
+        repo.record_reminder_event(&answered, ReminderAction::Fired, Some(trigger_time), None)
+            .await
+            .unwrap();
+        repo.record_reminder_event(&answered, ReminderAction::Acknowledged, None, None)
+            .await
+            .unwrap();
+        // Fired twice; the acknowledgement only answers the second time
+        repo.record_reminder_event(&ignored, ReminderAction::Fired, Some(trigger_time), None)
+            .await
+            .unwrap();
+        repo.record_reminder_event(&ignored, ReminderAction::Fired, Some(trigger_time), None)
+            .await
+            .unwrap();
+        repo.record_reminder_event(&ignored, ReminderAction::Dismissed, None, None)
+            .await
+            .unwrap();
+
+        let history = repo.list_reminder_history(None, 100).await.unwrap();
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0].action, ReminderAction::Dismissed);
+        assert_eq!(history[0].note_title.as_deref(), Some("Note"));
+        let history = repo
+            .list_reminder_history(Some(&answered.id), 1)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].action, ReminderAction::Acknowledged);
+
+        let missed = repo.list_missed_reminders(Utc::now(), 100).await.unwrap();
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].reminder_id, ignored.id);
+        assert_eq!(missed[0].scheduled_for, Some(trigger_time));
+
+        // Too recent to count as missed yet
+        let cutoff = Utc::now() - chrono::Duration::minutes(15);
+        assert!(repo
+            .list_missed_reminders(cutoff, 100)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Purging the note removes its history
+        repo.delete_note(&note.id).await.unwrap();
+        repo.prune_deleted_notes().await.unwrap();
+        assert!(repo
+            .list_reminder_history(None, 100)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_collections() {
         let repo = create_test_repo().await;
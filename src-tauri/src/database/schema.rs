@@ -77,6 +77,24 @@ fn get_migrations() -> Vec<(i32, &'static str)> {
         (3, include_str!("migrations/003_add_fts.sql")),
         (4, include_str!("migrations/004_add_collections.sql")),
         (5, include_str!("migrations/005_add_reminder_settings.sql")),
+        (6, include_str!("migrations/006_add_note_revisions.sql")),
+        (7, include_str!("migrations/007_add_tags.sql")),
+        (
+            8,
+            include_str!("migrations/008_add_reminder_recurrence.sql"),
+        ),
+        (9, include_str!("migrations/009_add_reminder_history.sql")),
+        (
+            10,
+            include_str!("migrations/010_add_missed_reminder_policy.sql"),
+        ),
+        (
+            11,
+            include_str!("migrations/011_add_inline_reminder_source.sql"),
+        ),
+        (12, include_str!("migrations/012_add_note_links.sql")),
+        (13, include_str!("migrations/013_add_note_templates.sql")),
+        (14, include_str!("migrations/014_add_journal_entries.sql")),
     ]
 }
 
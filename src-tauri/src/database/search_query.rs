@@ -0,0 +1,872 @@
+//! Search query language
+//!
+//! Parses user search input into an FTS5 MATCH expression plus SQL filters.
+//!
+//! Syntax:
+//! - `word` — prefix match on title or content
+//! - `"exact phrase"` — phrase match
+//! - `-word`, `NOT word` — exclusion
+//! - `a OR b`, `a AND b`, `( ... )` — boolean operators. Terms separated by
+//!   spaces are ANDed, and OR binds loosest (`a b OR c` is `(a b) OR c`)
+//! - `title:word`, `content:"a phrase"` — field-scoped terms
+//! - `collection:Work`, `tag:urgent` — collection / tag filters (case-insensitive)
+//! - `created:>2026-01-01`, `updated:<=2026-02-01` — date filters on local dates,
+//!   with `>`, `>=`, `<`, `<=`, `=` (or no operator for the whole day)
+//! - `has:attachment`, `has:reminder`, `has:tag`
+//!
+//! Every term is quoted before it is handed to FTS5, so user input can
+//! never inject FTS5 syntax. Malformed input is reported as
+//! `AppError::InvalidSearchQuery` with the 1-based character position.
+
+use super::models::{Note, SearchResult};
+use super::repository::Repository;
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use sqlx::{QueryBuilder, Sqlite};
+
+/// Field prefixes recognised in `name:value` terms
+const FIELDS: &[&str] = &[
+    "title",
+    "content",
+    "collection",
+    "tag",
+    "created",
+    "updated",
+    "has",
+];
+
+/// Number of tokens in FTS5 content snippets
+pub(crate) const SNIPPET_TOKENS: usize = 16;
+
+/// Characters of note text shown as the snippet when there is no FTS5 match
+const PREVIEW_CHARS: usize = 160;
+
+/// A parsed search query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchQuery {
+    root: Option<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Term {
+        text: String,
+        phrase: bool,
+        column: Option<Column>,
+    },
+    Filter(Filter),
+    Not(Box<Node>),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Title,
+    Content,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Collection(String),
+    Tag(String),
+    Date(DateField, CmpOp, NaiveDate),
+    Has(HasKind),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateField {
+    Created,
+    Updated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HasKind {
+    Attachment,
+    Reminder,
+    Tag,
+}
+
+impl SearchQuery {
+    /// Parse a search query
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Ok(Self { root: None });
+        }
+
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end_position: input.chars().count() + 1,
+        };
+        let root = parser.parse_or()?;
+
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(invalid(token.position, "Unexpected ')'"));
+        }
+
+        Ok(Self { root: Some(root) })
+    }
+
+    /// Whether the query has no terms or filters (matches every note)
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// The text of a query made only of unscoped terms and phrases.
+    /// Used for attachment filename matching, which doesn't support operators.
+    pub fn plain_text(&self) -> Option<String> {
+        let words: Option<Vec<&str>> = self
+            .conjuncts()
+            .iter()
+            .map(|node| match node {
+                Node::Term {
+                    text, column: None, ..
+                } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        words.filter(|w| !w.is_empty()).map(|w| w.join(" "))
+    }
+
+    /// FTS5 MATCH expression for the positive top-level text terms,
+    /// or None if the query has no such terms.
+    /// Used for the ranked join against `notes_fts`.
+    pub(crate) fn match_expression(&self) -> Option<String> {
+        let parts: Vec<String> = self.conjuncts().iter().filter_map(ranked_fts).collect();
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" AND "))
+        }
+    }
+
+    /// Append every condition not covered by `match_expression` as
+    /// ` AND <condition>`. Conditions reference the notes table as `n`.
+    pub(crate) fn push_filters(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        for node in self.conjuncts() {
+            if ranked_fts(node).is_none() {
+                builder.push(" AND ");
+                node.push_sql(builder);
+            }
+        }
+    }
+
+    fn conjuncts(&self) -> &[Node] {
+        match &self.root {
+            None => &[],
+            Some(Node::And(items)) => items,
+            Some(node) => std::slice::from_ref(node),
+        }
+    }
+}
+
+/// FTS5 expression for a top-level conjunct that can go into the ranked MATCH
+fn ranked_fts(node: &Node) -> Option<String> {
+    match node {
+        Node::Not(_) => None,
+        other => other.fts(),
+    }
+}
+
+impl Node {
+    /// Compile a text-only subtree to an FTS5 expression.
+    /// Returns None for filters and for negations FTS5 can't express on their own.
+    fn fts(&self) -> Option<String> {
+        match self {
+            Node::Term {
+                text,
+                phrase,
+                column,
+            } => {
+                let mut expr = format!("\"{}\"", text.replace('"', "\"\""));
+                if !phrase {
+                    expr.push('*');
+                }
+                Some(match column {
+                    Some(Column::Title) => format!("title : {}", expr),
+                    Some(Column::Content) => format!("content_text : {}", expr),
+                    None => expr,
+                })
+            }
+            Node::Filter(_) | Node::Not(_) => None,
+            Node::Or(items) => {
+                let parts: Option<Vec<String>> = items.iter().map(Node::fts).collect();
+                Some(format!("({})", parts?.join(" OR ")))
+            }
+            Node::And(items) => {
+                // FTS5 NOT is binary, so negations need at least one positive term
+                let mut positive = Vec::new();
+                let mut negative = Vec::new();
+                for item in items {
+                    match item {
+                        Node::Not(inner) => negative.push(inner.fts()?),
+                        other => positive.push(other.fts()?),
+                    }
+                }
+                if positive.is_empty() {
+                    return None;
+                }
+
+                let mut expr = format!("({})", positive.join(" AND "));
+                if negative.is_empty() {
+                    return Some(expr);
+                }
+                // Parenthesised whole, so it doesn't lean on NOT binding
+                // tighter than a surrounding OR
+                for n in negative {
+                    expr.push_str(&format!(" NOT ({})", n));
+                }
+                Some(format!("({})", expr))
+            }
+        }
+    }
+
+    /// Append this node as an SQL condition on `n` (the notes table)
+    fn push_sql(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        match (self, self.fts()) {
+            (_, Some(expr)) => {
+                builder.push("n.id IN (SELECT note_id FROM notes_fts WHERE notes_fts MATCH ");
+                builder.push_bind(expr);
+                builder.push(")");
+            }
+            (Node::Filter(filter), None) => filter.push_sql(builder),
+            (Node::Not(inner), None) => {
+                builder.push("NOT (");
+                inner.push_sql(builder);
+                builder.push(")");
+            }
+            (Node::And(items), None) => push_joined(builder, items, " AND "),
+            (Node::Or(items), None) => push_joined(builder, items, " OR "),
+            (Node::Term { .. }, None) => unreachable!("terms always compile to FTS5"),
+        }
+    }
+}
+
+fn push_joined(builder: &mut QueryBuilder<'_, Sqlite>, items: &[Node], separator: &str) {
+    builder.push("(");
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            builder.push(separator);
+        }
+        item.push_sql(builder);
+    }
+    builder.push(")");
+}
+
+impl Filter {
+    // EXISTS is used instead of IN so negated filters never evaluate to NULL
+    fn push_sql(&self, builder: &mut QueryBuilder<'_, Sqlite>) {
+        match self {
+            Filter::Collection(name) => {
+                builder.push(
+                    "EXISTS (SELECT 1 FROM collections c WHERE c.id = n.collection_id AND c.name = ",
+                );
+                builder.push_bind(name.clone());
+                builder.push(" COLLATE NOCASE)");
+            }
+            Filter::Tag(name) => {
+                builder.push(
+                    "EXISTS (SELECT 1 FROM note_tags nt INNER JOIN tags t ON t.id = nt.tag_id WHERE nt.note_id = n.id AND t.name = ",
+                );
+                builder.push_bind(name.clone());
+                builder.push(")");
+            }
+            Filter::Has(HasKind::Attachment) => {
+                builder.push("EXISTS (SELECT 1 FROM attachments a WHERE a.note_id = n.id)");
+            }
+            Filter::Has(HasKind::Reminder) => {
+                builder.push(
+                    "EXISTS (SELECT 1 FROM reminders r WHERE r.note_id = n.id AND r.triggered = 0)",
+                );
+            }
+            Filter::Has(HasKind::Tag) => {
+                builder.push("EXISTS (SELECT 1 FROM note_tags nt WHERE nt.note_id = n.id)");
+            }
+            Filter::Date(field, op, date) => {
+                let column = match field {
+                    DateField::Created => "n.created_at",
+                    DateField::Updated => "n.updated_at",
+                };
+                let day_start = local_day_start(*date);
+                let next_day_start = local_day_start(date.succ_opt().unwrap_or(NaiveDate::MAX));
+
+                let (lower, upper) = match op {
+                    CmpOp::Gt => (Some(next_day_start), None),
+                    CmpOp::Ge => (Some(day_start), None),
+                    CmpOp::Lt => (None, Some(day_start)),
+                    CmpOp::Le => (None, Some(next_day_start)),
+                    CmpOp::Eq => (Some(day_start), Some(next_day_start)),
+                };
+
+                builder.push("(1 = 1");
+                if let Some(lower) = lower {
+                    builder.push(format!(" AND {} >= ", column));
+                    builder.push_bind(lower);
+                }
+                if let Some(upper) = upper {
+                    builder.push(format!(" AND {} < ", column));
+                    builder.push_bind(upper);
+                }
+                builder.push(")");
+            }
+        }
+    }
+}
+
+/// Start of a local calendar day in UTC.
+/// If local midnight doesn't exist (DST gap), the day starts an hour later.
+fn local_day_start(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .or_else(|| {
+            Local
+                .from_local_datetime(&(midnight + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+fn invalid(position: usize, message: impl Into<String>) -> AppError {
+    AppError::InvalidSearchQuery {
+        message: message.into(),
+        position,
+    }
+}
+
+// ===== Result Markup =====
+
+/// Convert FTS5 `highlight()`/`snippet()` output, with matches delimited by
+/// `\u{2}`/`\u{3}`, into HTML-escaped text with `<mark>` tags
+pub(crate) fn markup_matches(raw: &str) -> String {
+    let mut html = String::with_capacity(raw.len() + 16);
+    for c in raw.chars() {
+        match c {
+            '\u{2}' => html.push_str("<mark>"),
+            '\u{3}' => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// Start of a note's plain text with whitespace collapsed, ellipsized after
+/// `PREVIEW_CHARS` characters
+pub fn preview_text(content_json: &str) -> String {
+    let text = Repository::extract_text_from_delta(content_json);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut preview: String = text.chars().take(PREVIEW_CHARS).collect();
+    if preview.len() < text.len() {
+        preview.push('…');
+    }
+    preview
+}
+
+/// Search result without FTS5 match context: the escaped title and the
+/// start of the note text
+pub fn preview_result(note: Note, attachment_match: bool) -> SearchResult {
+    SearchResult {
+        title_highlight: markup_matches(&note.title),
+        content_snippet: markup_matches(&preview_text(&note.content_json)),
+        score: None,
+        attachment_match,
+        note,
+    }
+}
+
+// ===== Tokenizer =====
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Minus,
+    Word(String),
+    Phrase(String),
+    Field {
+        name: String,
+        value: String,
+        quoted: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based character position in the input
+    position: usize,
+}
+
+/// Whether text contains anything the FTS5 tokenizer would index
+fn is_searchable(text: &str) -> bool {
+    text.chars().any(char::is_alphanumeric)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let position = i + 1;
+
+        let kind = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => {
+                i += 1;
+                TokenKind::LParen
+            }
+            ')' => {
+                i += 1;
+                TokenKind::RParen
+            }
+            '"' => {
+                let (text, next) = read_quoted(&chars, i)?;
+                i = next;
+                if !is_searchable(&text) {
+                    continue;
+                }
+                TokenKind::Phrase(text)
+            }
+            '-' if chars
+                .get(i + 1)
+                .is_some_and(|n| !n.is_whitespace() && *n != ')') =>
+            {
+                i += 1;
+                TokenKind::Minus
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && !matches!(chars[i], '(' | ')' | '"')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+
+                match split_field(&word) {
+                    Some((name, value)) => {
+                        if value.is_empty() && chars.get(i) == Some(&'"') {
+                            let (text, next) = read_quoted(&chars, i)?;
+                            i = next;
+                            TokenKind::Field {
+                                name,
+                                value: text,
+                                quoted: true,
+                            }
+                        } else {
+                            TokenKind::Field {
+                                name,
+                                value: value.to_string(),
+                                quoted: false,
+                            }
+                        }
+                    }
+                    None => match word.as_str() {
+                        "AND" => TokenKind::And,
+                        "OR" => TokenKind::Or,
+                        "NOT" => TokenKind::Not,
+                        // Punctuation-only words can't match anything; skip them
+                        _ if !is_searchable(&word) => continue,
+                        _ => TokenKind::Word(word.trim_end_matches('*').to_string()),
+                    },
+                }
+            }
+        };
+
+        tokens.push(Token { kind, position });
+    }
+
+    Ok(tokens)
+}
+
+/// Split `name:value` if `name` is a known field
+fn split_field(word: &str) -> Option<(String, &str)> {
+    let (name, value) = word.split_once(':')?;
+    let name = name.to_ascii_lowercase();
+    FIELDS.contains(&name.as_str()).then_some((name, value))
+}
+
+/// Read a double-quoted string starting at `open`.
+/// Returns the contents and the index after the closing quote.
+fn read_quoted(chars: &[char], open: usize) -> Result<(String, usize)> {
+    let close = chars[open + 1..]
+        .iter()
+        .position(|c| *c == '"')
+        .map(|offset| open + 1 + offset)
+        .ok_or_else(|| invalid(open + 1, "Unterminated quote"))?;
+
+    Ok((chars[open + 1..close].iter().collect(), close + 1))
+}
+
+// ===== Parser =====
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Position reported for errors at the end of input
+    end_position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Node> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek() == Some(&TokenKind::Or) {
+            self.pos += 1;
+            items.push(self.parse_and()?);
+        }
+
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Node::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Node> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                None | Some(TokenKind::RParen) | Some(TokenKind::Or) => break,
+                Some(TokenKind::And) => {
+                    self.pos += 1;
+                    items.push(self.parse_unary()?);
+                }
+                Some(_) => items.push(self.parse_unary()?),
+            }
+        }
+
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Node::And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Node> {
+        match self.peek() {
+            Some(TokenKind::Not) | Some(TokenKind::Minus) => {
+                self.pos += 1;
+                Ok(Node::Not(Box::new(self.parse_unary()?)))
+            }
+            _ => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Node> {
+        let Some(token) = self.next() else {
+            return Err(invalid(self.end_position, "Expected a search term"));
+        };
+
+        match token.kind {
+            TokenKind::LParen => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(inner),
+                    _ => Err(invalid(token.position, "Unclosed '('")),
+                }
+            }
+            TokenKind::RParen => Err(invalid(token.position, "Unexpected ')'")),
+            TokenKind::And | TokenKind::Or => Err(invalid(
+                token.position,
+                "Expected a search term before operator",
+            )),
+            TokenKind::Not | TokenKind::Minus => {
+                Err(invalid(token.position, "Expected a search term"))
+            }
+            TokenKind::Word(text) => Ok(Node::Term {
+                text,
+                phrase: false,
+                column: None,
+            }),
+            TokenKind::Phrase(text) => Ok(Node::Term {
+                text,
+                phrase: true,
+                column: None,
+            }),
+            TokenKind::Field {
+                name,
+                value,
+                quoted,
+            } => parse_field(&name, &value, quoted, token.position),
+        }
+    }
+}
+
+fn parse_field(name: &str, value: &str, quoted: bool, position: usize) -> Result<Node> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(invalid(position, format!("Missing value for '{}:'", name)));
+    }
+
+    match name {
+        "title" | "content" => {
+            if !is_searchable(value) {
+                return Err(invalid(
+                    position,
+                    format!("'{}:' needs a word to search for", name),
+                ));
+            }
+            let column = if name == "title" {
+                Column::Title
+            } else {
+                Column::Content
+            };
+            let text = if quoted {
+                value
+            } else {
+                value.trim_end_matches('*')
+            };
+            Ok(Node::Term {
+                text: text.to_string(),
+                phrase: quoted,
+                column: Some(column),
+            })
+        }
+        "collection" => Ok(Node::Filter(Filter::Collection(value.to_string()))),
+        "tag" => Ok(Node::Filter(Filter::Tag(value.to_string()))),
+        "has" => {
+            let kind = match value.to_ascii_lowercase().as_str() {
+                "attachment" | "attachments" => HasKind::Attachment,
+                "reminder" | "reminders" => HasKind::Reminder,
+                "tag" | "tags" => HasKind::Tag,
+                _ => {
+                    return Err(invalid(
+                        position,
+                        format!(
+                            "Unknown value '{}' for 'has:' (expected attachment, reminder or tag)",
+                            value
+                        ),
+                    ))
+                }
+            };
+            Ok(Node::Filter(Filter::Has(kind)))
+        }
+        "created" | "updated" => {
+            let field = if name == "created" {
+                DateField::Created
+            } else {
+                DateField::Updated
+            };
+            let (op, date) = if let Some(rest) = value.strip_prefix(">=") {
+                (CmpOp::Ge, rest)
+            } else if let Some(rest) = value.strip_prefix("<=") {
+                (CmpOp::Le, rest)
+            } else if let Some(rest) = value.strip_prefix('>') {
+                (CmpOp::Gt, rest)
+            } else if let Some(rest) = value.strip_prefix('<') {
+                (CmpOp::Lt, rest)
+            } else if let Some(rest) = value.strip_prefix('=') {
+                (CmpOp::Eq, rest)
+            } else {
+                (CmpOp::Eq, value)
+            };
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+                invalid(
+                    position,
+                    format!(
+                        "Invalid date '{}' for '{}:' (expected YYYY-MM-DD)",
+                        date, name
+                    ),
+                )
+            })?;
+            Ok(Node::Filter(Filter::Date(field, op, date)))
+        }
+        _ => unreachable!("split_field only accepts known fields"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_position(input: &str) -> usize {
+        match SearchQuery::parse(input) {
+            Err(AppError::InvalidSearchQuery { position, .. }) => position,
+            other => panic!(
+                "expected InvalidSearchQuery for {:?}, got {:?}",
+                input, other
+            ),
+        }
+    }
+
+    #[test]
+    fn test_empty_and_punctuation_only() {
+        assert!(SearchQuery::parse("").unwrap().is_empty());
+        assert!(SearchQuery::parse("  !!! \"\" ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_plain_terms_are_quoted_prefixes() {
+        let query = SearchQuery::parse(r#"meet "exact phrase" say"hi""#).unwrap();
+        assert_eq!(
+            query.match_expression().unwrap(),
+            r#""meet"* AND "exact phrase" AND "say"* AND "hi""#
+        );
+        assert_eq!(query.plain_text().unwrap(), "meet exact phrase say hi");
+    }
+
+    #[test]
+    fn test_fts_syntax_is_escaped() {
+        let query = SearchQuery::parse("NEAR(a* b:c) ^x").unwrap();
+        let expr = query.match_expression().unwrap();
+        assert_eq!(expr, r#""NEAR"* AND ("a"* AND "b:c"*) AND "^x"*"#);
+    }
+
+    #[test]
+    fn test_boolean_operators() {
+        let query = SearchQuery::parse("alpha beta OR gamma -delta").unwrap();
+        assert_eq!(
+            query.match_expression().unwrap(),
+            r#"(("alpha"* AND "beta"*) OR (("gamma"*) NOT ("delta"*)))"#
+        );
+        assert!(query.plain_text().is_none());
+
+        let query = SearchQuery::parse("alpha -beta OR gamma -delta -epsilon").unwrap();
+        assert_eq!(
+            query.match_expression().unwrap(),
+            r#"((("alpha"*) NOT ("beta"*)) OR (("gamma"*) NOT ("delta"*) NOT ("epsilon"*)))"#
+        );
+
+        let query = SearchQuery::parse("alpha AND (beta OR NOT gamma)").unwrap();
+        // The OR contains a bare negation, so it becomes an SQL condition
+        assert_eq!(query.match_expression().unwrap(), r#""alpha"*"#);
+    }
+
+    #[test]
+    fn test_field_scoped_terms() {
+        let query = SearchQuery::parse(r#"title:meeting content:"action items""#).unwrap();
+        assert_eq!(
+            query.match_expression().unwrap(),
+            r#"title : "meeting"* AND content_text : "action items""#
+        );
+        assert!(query.plain_text().is_none());
+    }
+
+    #[test]
+    fn test_filters() {
+        let query = SearchQuery::parse(
+            r#"collection:"Two Words" tag:urgent created:>2026-01-01 UPDATED:2026-02-03 has:attachments"#,
+        )
+        .unwrap();
+        assert!(query.match_expression().is_none());
+        assert_eq!(
+            query.conjuncts(),
+            &[
+                Node::Filter(Filter::Collection("Two Words".to_string())),
+                Node::Filter(Filter::Tag("urgent".to_string())),
+                Node::Filter(Filter::Date(
+                    DateField::Created,
+                    CmpOp::Gt,
+                    NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+                )),
+                Node::Filter(Filter::Date(
+                    DateField::Updated,
+                    CmpOp::Eq,
+                    NaiveDate::from_ymd_opt(2026, 2, 3).unwrap()
+                )),
+                Node::Filter(Filter::Has(HasKind::Attachment)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_prefix_is_plain_text() {
+        let query = SearchQuery::parse("10:30 https://example.com").unwrap();
+        assert_eq!(query.plain_text().unwrap(), "10:30 https://example.com");
+    }
+
+    #[test]
+    fn test_error_positions() {
+        assert_eq!(error_position(r#"one "two"#), 5);
+        assert_eq!(error_position("(one two"), 1);
+        assert_eq!(error_position("one)"), 4);
+        assert_eq!(error_position("one OR"), 7);
+        assert_eq!(error_position("OR one"), 1);
+        assert_eq!(error_position("one NOT"), 8);
+        assert_eq!(error_position("x has:everything"), 3);
+        assert_eq!(error_position("created:>01/02/2026"), 1);
+        assert_eq!(error_position("tag:"), 1);
+    }
+
+    #[test]
+    fn test_markup_matches_escapes_html() {
+        assert_eq!(
+            markup_matches("<b>\u{2}Tom\u{3} & \"Jerry\""),
+            "&lt;b&gt;<mark>Tom</mark> &amp; &quot;Jerry&quot;"
+        );
+    }
+
+    #[test]
+    fn test_preview_result_truncates() {
+        let long = "word ".repeat(100);
+        let note = Note {
+            id: "n1".to_string(),
+            title: "a < b".to_string(),
+            content_json: format!(r#"{{"ops":[{{"insert":"{}\n"}}]}}"#, long),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            deleted_at: None,
+            title_modified: true,
+            collection_id: None,
+        };
+
+        let result = preview_result(note, true);
+        assert_eq!(result.title_highlight, "a &lt; b");
+        assert_eq!(result.content_snippet.chars().count(), PREVIEW_CHARS + 1);
+        assert!(result.content_snippet.ends_with('…'));
+        assert!(result.attachment_match);
+        assert!(result.score.is_none());
+    }
+
+    #[test]
+    fn test_error_message() {
+        let err = SearchQuery::parse("has:nothing").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid search query at position 1: Unknown value 'nothing' for 'has:' (expected attachment, reminder or tag)"
+        );
+    }
+}
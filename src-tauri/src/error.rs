@@ -35,6 +35,21 @@ pub enum AppError {
     #[error("Blob store error: {0}")]
     BlobStore(String),
 
+    #[error("Invalid search query at position {position}: {message}")]
+    InvalidSearchQuery { message: String, position: usize },
+
+    #[error("Invalid recurrence rule: {0}")]
+    InvalidRecurrence(String),
+
+    #[error("Could not understand reminder time: {0}")]
+    InvalidReminderTime(String),
+
+    #[error("Export error: {0}")]
+    Export(String),
+
+    #[error("Import error: {0}")]
+    Import(String),
+
     #[error("{0}")]
     Generic(String),
 }
@@ -78,6 +93,48 @@ mod tests {
         assert_eq!(error.to_string(), "Blob store error: File not found");
     }
 
+    #[test]
+    fn test_invalid_search_query_error() {
+        let error = AppError::InvalidSearchQuery {
+            message: "Unterminated quote".to_string(),
+            position: 7,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Invalid search query at position 7: Unterminated quote"
+        );
+    }
+
+    #[test]
+    fn test_invalid_recurrence_error() {
+        let error = AppError::InvalidRecurrence("FREQ is required".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Invalid recurrence rule: FREQ is required"
+        );
+    }
+
+    #[test]
+    fn test_invalid_reminder_time_error() {
+        let error = AppError::InvalidReminderTime("\"9:5\" is not a time".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Could not understand reminder time: \"9:5\" is not a time"
+        );
+    }
+
+    #[test]
+    fn test_export_error() {
+        let error = AppError::Export("no export directory given".to_string());
+        assert_eq!(error.to_string(), "Export error: no export directory given");
+    }
+
+    #[test]
+    fn test_import_error() {
+        let error = AppError::Import("not a directory: notes.md".to_string());
+        assert_eq!(error.to_string(), "Import error: not a directory: notes.md");
+    }
+
     #[test]
     fn test_generic_error() {
         let error = AppError::Generic("Something went wrong".to_string());
@@ -48,12 +48,22 @@ fn main() {
             commands::create_note,
             commands::get_note,
             commands::list_notes,
+            commands::list_note_summaries,
             commands::update_note,
             commands::delete_note,
             commands::delete_note_and_close_window,
             commands::search_notes,
+            commands::search_notes_with_snippets,
             commands::count_deleted_notes,
             commands::prune_deleted_notes,
+            commands::list_note_revisions,
+            commands::get_note_revision,
+            commands::diff_note_revisions,
+            commands::restore_note_revision,
+            commands::rename_note,
+            commands::list_outgoing_links,
+            commands::list_backlinks,
+            commands::list_dangling_links,
             commands::open_note_window,
             commands::create_new_sticky_note,
             commands::set_last_focused_note_window,
@@ -64,14 +74,30 @@ fn main() {
             commands::list_attachments,
             commands::get_attachment_data,
             commands::delete_attachment,
+            commands::collect_blob_garbage,
             commands::create_backup,
             commands::list_backups,
             commands::restore_backup,
+            commands::verify_backup,
+            commands::list_backup_notes,
+            commands::restore_backup_notes,
             commands::delete_backup,
+            commands::export_markdown,
+            commands::export_html,
+            commands::export_bundle,
             commands::create_reminder,
             commands::list_active_reminders,
             commands::delete_reminder,
             commands::update_reminder,
+            commands::preview_reminder_time,
+            commands::set_reminder_recurrence,
+            commands::snooze_reminder,
+            commands::dismiss_reminder,
+            commands::acknowledge_reminder,
+            commands::list_reminder_history,
+            commands::list_missed_reminders,
+            commands::set_reminder_missed_policy,
+            commands::get_missed_reminder_summary,
             commands::get_hotkey_settings,
             commands::update_hotkey_settings,
             commands::get_autostart_state,
@@ -88,6 +114,12 @@ fn main() {
             commands::update_reminder_settings,
             commands::get_behavior_settings,
             commands::update_behavior_settings,
+            commands::get_revision_settings,
+            commands::update_revision_settings,
+            commands::get_blob_gc_settings,
+            commands::update_blob_gc_settings,
+            commands::get_journal_settings,
+            commands::update_journal_settings,
             commands::check_for_update,
             commands::download_and_install_update,
             commands::toggle_main_window,
@@ -103,7 +135,30 @@ fn main() {
             commands::list_notes_in_collection,
             commands::list_uncategorized_notes,
             commands::count_notes_in_collection,
+            commands::create_tag,
+            commands::list_tags,
+            commands::update_tag,
+            commands::delete_tag,
+            commands::merge_tags,
+            commands::add_tag_to_note,
+            commands::remove_tag_from_note,
+            commands::list_note_tags,
+            commands::list_notes_by_tag,
+            commands::create_template,
+            commands::get_template,
+            commands::list_templates,
+            commands::update_template,
+            commands::delete_template,
+            commands::create_note_from_template,
+            commands::get_daily_note,
+            commands::open_daily_note,
+            commands::get_previous_daily_note,
+            commands::get_next_daily_note,
+            commands::list_daily_notes,
             commands::import_from_onenote,
+            commands::import_from_folder,
+            commands::import_from_enex,
+            commands::import_bundle,
             commands::restart_app,
         ])
         .run(tauri::generate_context!())
@@ -3,9 +3,12 @@
 //! Handles file attachments and image storage for notes.
 //! Integrates Repository and BlobStore.
 
+use crate::config;
 use crate::database::{Attachment, Repository};
 use crate::error::{AppError, Result};
 use crate::storage::BlobStore;
+use serde::Serialize;
+use std::time::{Duration, SystemTime};
 
 /// Maximum attachment file size in bytes (100 MB)
 const MAX_ATTACHMENT_SIZE: usize = 100 * 1024 * 1024;
@@ -20,6 +23,23 @@ const ALLOWED_IMAGE_MIMES: &[&str] = &[
     "image/svg+xml",
 ];
 
+/// Outcome of a blob garbage collection pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BlobGcReport {
+    /// Whether this was a dry run (nothing deleted)
+    pub dry_run: bool,
+    /// Blobs found in the blob store
+    pub blobs_scanned: usize,
+    /// Blobs not referenced by any attachment, note or revision
+    pub unreferenced_count: usize,
+    /// Total size of the unreferenced blobs in bytes
+    pub reclaimable_bytes: u64,
+    /// Blobs actually deleted (0 for a dry run)
+    pub deleted_count: usize,
+    /// Bytes actually freed (0 for a dry run)
+    pub freed_bytes: u64,
+}
+
 /// Service for managing attachments
 #[derive(Clone)]
 pub struct AttachmentsService {
@@ -97,6 +117,17 @@ impl AttachmentsService {
         Ok(data)
     }
 
+    /// Store data in the blob store without an attachment row (e.g. a blob
+    /// a note embeds); returns its hash
+    pub async fn store_blob(&self, data: &[u8]) -> Result<String> {
+        self.blob_store.write(data).await
+    }
+
+    /// Whether the blob store holds a blob
+    pub async fn has_blob(&self, hash: &str) -> Result<bool> {
+        self.blob_store.exists(hash).await
+    }
+
     /// Get attachment by blob hash
     pub async fn get_attachment_by_hash(&self, hash: &str) -> Result<Vec<u8>> {
         self.blob_store.read(hash).await
@@ -111,21 +142,84 @@ impl AttachmentsService {
     pub async fn delete_attachment(&self, attachment_id: &str) -> Result<()> {
         tracing::info!("Deleting attachment: {}", attachment_id);
 
-        // Get blob hash before deleting
-        let _blob_hash = self.repo.delete_attachment(attachment_id).await?;
-
-        // Check if blob is still referenced by other attachments
-        // For simplicity, we'll keep blobs even if unreferenced (garbage collection is future work)
-        // In production, you'd implement reference counting
+        // The blob stays until blob GC runs: an inline embed in the note,
+        // a revision or another attachment may still reference the same hash
+        self.repo.delete_attachment(attachment_id).await?;
 
         tracing::info!("Attachment deleted: {}", attachment_id);
 
         Ok(())
     }
+
+    /// Mark-and-sweep garbage collection of the blob store.
+    ///
+    /// Blobs referenced by no attachment row and no inline embed in note
+    /// content or revision history are deleted (or only counted when
+    /// `dry_run`). Blobs younger than `config::BLOB_GC_MIN_AGE_SECS` are
+    /// skipped, since an upload may not have its attachment row yet.
+    pub async fn collect_garbage(&self, dry_run: bool) -> Result<BlobGcReport> {
+        self.collect_garbage_older_than(dry_run, Duration::from_secs(config::BLOB_GC_MIN_AGE_SECS))
+            .await
+    }
+
+    async fn collect_garbage_older_than(
+        &self,
+        dry_run: bool,
+        min_age: Duration,
+    ) -> Result<BlobGcReport> {
+        tracing::info!("Running blob garbage collection (dry run: {})", dry_run);
+
+        // List blobs before marking, so a blob written and referenced
+        // in between is never seen as garbage
+        let blobs = self.blob_store.list_all().await?;
+        let referenced = self.repo.list_referenced_blob_hashes().await?;
+
+        let mut report = BlobGcReport {
+            dry_run,
+            blobs_scanned: blobs.len(),
+            ..Default::default()
+        };
+        let now = SystemTime::now();
+
+        for hash in blobs.iter().filter(|h| !referenced.contains(*h)) {
+            let (size, modified) = match self.blob_store.metadata(hash).await {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!("Failed to stat blob {}: {}", hash, e);
+                    continue;
+                }
+            };
+            if now.duration_since(modified).unwrap_or_default() < min_age {
+                continue;
+            }
+
+            report.unreferenced_count += 1;
+            report.reclaimable_bytes += size;
+
+            if !dry_run {
+                match self.blob_store.delete(hash).await {
+                    Ok(()) => {
+                        report.deleted_count += 1;
+                        report.freed_bytes += size;
+                    }
+                    Err(e) => tracing::warn!("Failed to delete blob {}: {}", hash, e),
+                }
+            }
+        }
+
+        tracing::info!(
+            scanned = report.blobs_scanned,
+            unreferenced = report.unreferenced_count,
+            reclaimable_bytes = report.reclaimable_bytes,
+            deleted = report.deleted_count,
+            "Blob garbage collection complete"
+        );
+
+        Ok(report)
+    }
 }
 
 /// Check if a MIME type is an allowed image type
-#[allow(dead_code)]
 pub fn is_allowed_image_mime(mime_type: &str) -> bool {
     ALLOWED_IMAGE_MIMES.contains(&mime_type)
 }
@@ -145,7 +239,9 @@ fn sanitize_filename(filename: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::{initialize_database, CreateNoteRequest, Repository};
+    use crate::database::{
+        initialize_database, CreateNoteRequest, Repository, SaveTemplateRequest, UpdateNoteRequest,
+    };
     use crate::storage::BlobStore;
     use sqlx::sqlite::SqlitePoolOptions;
     use tempfile::TempDir;
@@ -200,6 +296,118 @@ mod tests {
         assert_eq!(retrieved_data, data);
     }
 
+    #[tokio::test]
+    async fn test_collect_garbage() {
+        let (service, _temp) = create_test_service().await;
+
+        let note = service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Test".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        // Referenced by an attachment row
+        let kept = service
+            .create_attachment(&note.id, "kept.txt", "text/plain", b"kept")
+            .await
+            .unwrap();
+        // Attachment row deleted, but still embedded inline in the note
+        let inline = service
+            .create_attachment(&note.id, "inline.png", "image/png", b"inline")
+            .await
+            .unwrap();
+        service.delete_attachment(&inline.id).await.unwrap();
+        service
+            .repo
+            .update_note(UpdateNoteRequest {
+                id: note.id.clone(),
+                title: None,
+                content_json: Some(format!(
+                    r#"{{"ops":[{{"insert":{{"attachment-image":{{"blobHash":"{}","mimeType":"image/png"}}}}}}]}}"#,
+                    inline.blob_hash
+                )),
+                title_modified: None,
+            })
+            .await
+            .unwrap();
+        // Unreferenced
+        let orphan = service
+            .create_attachment(&note.id, "orphan.txt", "text/plain", b"orphan!")
+            .await
+            .unwrap();
+        service.delete_attachment(&orphan.id).await.unwrap();
+
+        // Fresh blobs are protected by the minimum age
+        let report = service.collect_garbage(true).await.unwrap();
+        assert_eq!(report.blobs_scanned, 3);
+        assert_eq!(report.unreferenced_count, 0);
+
+        let report = service
+            .collect_garbage_older_than(true, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(report.unreferenced_count, 1);
+        assert_eq!(report.reclaimable_bytes, 7);
+        assert_eq!(report.deleted_count, 0);
+        assert!(service.blob_store.exists(&orphan.blob_hash).await.unwrap());
+
+        let report = service
+            .collect_garbage_older_than(false, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(report.deleted_count, 1);
+        assert_eq!(report.freed_bytes, 7);
+        assert!(!service.blob_store.exists(&orphan.blob_hash).await.unwrap());
+        assert!(service.blob_store.exists(&kept.blob_hash).await.unwrap());
+        assert!(service.blob_store.exists(&inline.blob_hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_keeps_template_images() {
+        let (service, _temp) = create_test_service().await;
+
+        let note = service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Test".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        // Only a template embeds this image
+        let logo = service
+            .create_attachment(&note.id, "logo.png", "image/png", b"logo")
+            .await
+            .unwrap();
+        service.delete_attachment(&logo.id).await.unwrap();
+        service
+            .repo
+            .create_template(&SaveTemplateRequest {
+                name: "Letterhead".to_string(),
+                title: String::new(),
+                content_json: format!(
+                    r#"{{"ops":[{{"insert":{{"attachment-image":{{"blobHash":"{}","mimeType":"image/png"}}}}}}]}}"#,
+                    logo.blob_hash
+                ),
+                collection_id: None,
+                reminder_offset_minutes: None,
+            })
+            .await
+            .unwrap();
+
+        let report = service
+            .collect_garbage_older_than(false, Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(report.deleted_count, 0);
+        assert!(service.blob_store.exists(&logo.blob_hash).await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("normal.txt"), "normal.txt");
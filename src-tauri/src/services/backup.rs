@@ -3,15 +3,24 @@
 //! Creates consistent snapshots of the database and blob store.
 //! Packages backups as ZIP files with manifest and checksums.
 //! All backups are encrypted with AES-256-GCM.
+//!
+//! Incremental backups leave blobs out of the archive and store each one
+//! once in the shared encrypted blob pack (see `storage::blob_pack`); the
+//! manifest lists the pack blobs needed to restore that point in time.
 
 use crate::crypto;
-use crate::database::Repository;
+use crate::database::{Repository, RestorableNote};
 use crate::error::{AppError, Result};
-use crate::storage::BlobStore;
-use chrono::Utc;
+use crate::services::{note_links, recurrence};
+use crate::storage::{blob_pack, BlobPack, BlobStore};
+use chrono::{Local, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::Connection;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
 use zip::write::FileOptions;
 use zip::ZipWriter;
@@ -24,6 +33,10 @@ pub struct BackupManifest {
     pub version: String,
     pub timestamp: String,
     pub files: Vec<FileEntry>,
+    /// Blobs stored in the blob pack instead of the archive (incremental
+    /// backups only); the checksum is the blob hash
+    #[serde(default)]
+    pub pack_blobs: Vec<FileEntry>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,6 +46,54 @@ pub struct FileEntry {
     pub checksum: String,
 }
 
+/// Result of checking a backup without restoring it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerificationReport {
+    /// Whether every check passed
+    pub valid: bool,
+    /// App version that created the backup
+    pub version: String,
+    /// When the backup was created (RFC 3339)
+    pub timestamp: String,
+    /// Archive files whose checksum matched
+    pub files_verified: usize,
+    /// Blob pack entries (incremental backups) whose hash matched
+    pub pack_blobs_verified: usize,
+    /// Problems found (checksum mismatches, missing files, database errors)
+    pub errors: Vec<String>,
+    /// `PRAGMA integrity_check` result ("ok" when healthy); None when the
+    /// database couldn't be checked
+    pub integrity_check: Option<String>,
+    /// Notes (excluding trash) in the backed-up database
+    pub note_count: Option<i64>,
+}
+
+/// What to do when a note being restored still exists in the library
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoteRestoreConflict {
+    /// Leave the existing note alone
+    #[default]
+    Skip,
+    /// Replace the existing note (its current version is kept as a revision)
+    Overwrite,
+    /// Import the backed-up note as a new note
+    Copy,
+}
+
+/// Outcome of restoring selected notes from a backup
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NoteRestoreReport {
+    /// Library IDs of the restored notes (new IDs for copies)
+    pub restored: Vec<String>,
+    /// Requested notes left alone because they still exist
+    pub skipped: Vec<String>,
+    /// Requested IDs that aren't in the backup
+    pub not_found: Vec<String>,
+    /// Blobs copied back into the blob store
+    pub blobs_restored: usize,
+}
+
 /// Backup service
 #[derive(Clone)]
 pub struct BackupService {
@@ -40,6 +101,12 @@ pub struct BackupService {
     blob_store: BlobStore,
     app_data_dir: PathBuf,
     backups_dir: std::sync::Arc<std::sync::RwLock<PathBuf>>,
+    /// Whether `create_backup` writes incremental backups
+    incremental: std::sync::Arc<std::sync::RwLock<bool>>,
+    /// Whether scheduled backups are verified right after being created
+    verify_after_backup: std::sync::Arc<std::sync::RwLock<bool>>,
+    /// Serialises blob pack writes against sweeps
+    pack_lock: Arc<tokio::sync::Mutex<()>>,
 }
 
 impl BackupService {
@@ -50,6 +117,9 @@ impl BackupService {
             blob_store,
             app_data_dir,
             backups_dir: std::sync::Arc::new(std::sync::RwLock::new(backups_dir)),
+            incremental: std::sync::Arc::new(std::sync::RwLock::new(false)),
+            verify_after_backup: std::sync::Arc::new(std::sync::RwLock::new(false)),
+            pack_lock: Arc::new(tokio::sync::Mutex::new(())),
         }
     }
 
@@ -64,6 +134,38 @@ impl BackupService {
         Ok(())
     }
 
+    /// Set whether `create_backup` writes incremental backups
+    pub fn set_incremental(&self, incremental: bool) -> Result<()> {
+        let mut flag = self
+            .incremental
+            .write()
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))?;
+        *flag = incremental;
+        tracing::info!(
+            "Incremental backups {}",
+            if incremental { "enabled" } else { "disabled" }
+        );
+        Ok(())
+    }
+
+    /// Set whether scheduled backups are verified after creation
+    pub fn set_verify_after_backup(&self, verify: bool) -> Result<()> {
+        let mut flag = self
+            .verify_after_backup
+            .write()
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))?;
+        *flag = verify;
+        Ok(())
+    }
+
+    /// Whether scheduled backups are verified after creation
+    pub fn verify_after_backup(&self) -> bool {
+        self.verify_after_backup
+            .read()
+            .map(|flag| *flag)
+            .unwrap_or(false)
+    }
+
     /// Get current backup directory (internal)
     fn get_backup_dir(&self) -> Result<PathBuf> {
         let dir = self
@@ -78,9 +180,26 @@ impl BackupService {
         self.get_backup_dir()
     }
 
-    /// Create an encrypted backup
+    /// Create an encrypted backup using the configured mode
     pub async fn create_backup(&self, password: &str) -> Result<PathBuf> {
-        tracing::info!("Creating encrypted backup");
+        let incremental = *self
+            .incremental
+            .read()
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))?;
+        self.create_backup_with_mode(password, incremental).await
+    }
+
+    /// Create an encrypted backup. Incremental backups store blobs in the
+    /// shared blob pack; full backups put every blob in the archive.
+    pub async fn create_backup_with_mode(
+        &self,
+        password: &str,
+        incremental: bool,
+    ) -> Result<PathBuf> {
+        tracing::info!(
+            "Creating encrypted {} backup",
+            if incremental { "incremental" } else { "full" }
+        );
 
         // Check if there are any notes to backup
         let notes = self.repo.list_notes().await?;
@@ -110,13 +229,11 @@ impl BackupService {
             version: env!("CARGO_PKG_VERSION").to_string(),
             timestamp: Utc::now().to_rfc3339(),
             files: Vec::new(),
+            pack_blobs: Vec::new(),
         };
 
         // Create ZIP file
-        let temp_file = std::fs::File::create(&temp_zip_path)?;
-        let mut zip = ZipWriter::new(temp_file);
-        let options =
-            FileOptions::<()>::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut archive = ArchiveWriter::create(&temp_zip_path).await?;
 
         // Add database file
         // First, checkpoint WAL to ensure all data is flushed to the main DB file
@@ -124,67 +241,96 @@ impl BackupService {
         let db_path = self.app_data_dir.join("db.sqlite");
         if db_path.exists() {
             let db_data = fs::read(&db_path).await?;
-            let db_checksum = calculate_checksum(&db_data);
-
-            zip.start_file("db.sqlite", options)?;
-            std::io::Write::write_all(&mut zip, &db_data)?;
+            let size = db_data.len() as u64;
+            let db_checksum = archive.add("db.sqlite", db_data).await?;
 
             manifest.files.push(FileEntry {
                 path: "db.sqlite".to_string(),
-                size: db_data.len() as u64,
+                size,
                 checksum: db_checksum,
             });
 
             tracing::debug!("Added db.sqlite to backup");
         }
 
+        // Hold the pack lock from the first pack write until the refs are
+        // written, so a concurrent sweep can't drop blobs this backup is
+        // about to reference. The archive left to write and encrypt then
+        // holds little more than the database.
+        let pack_guard = if incremental {
+            Some(self.pack_lock.lock().await)
+        } else {
+            None
+        };
+
         // Add all blobs
         let blob_hashes = self.blob_store.list_all().await?;
-        for hash in &blob_hashes {
-            let blob_data = self.blob_store.read(hash).await?;
-            let blob_checksum = calculate_checksum(&blob_data);
+        if incremental {
+            let pack = BlobPack::new(&backups_dir);
+            let pack_key = pack.open_key(password).await?;
+            let mut new_blobs = 0;
+
+            for hash in &blob_hashes {
+                if !pack.contains(hash) {
+                    let blob_data = self.blob_store.read(hash).await?;
+                    pack.write(hash, &blob_data, &pack_key).await?;
+                    new_blobs += 1;
+                }
+
+                let (size, _) = self.blob_store.metadata(hash).await?;
+                manifest.pack_blobs.push(FileEntry {
+                    path: format!("blobs/{}/{}/{}", &hash[0..2], &hash[2..4], hash),
+                    size,
+                    checksum: hash.clone(),
+                });
+            }
 
-            // Store with directory structure: blobs/ab/cd/abcd123...
-            let blob_rel_path = format!("blobs/{}/{}/{}", &hash[0..2], &hash[2..4], hash);
+            // The archive carries the pack key so restore doesn't depend on
+            // the pack's password-wrapped copy
+            archive.add("pack.key", pack_key).await?;
 
-            zip.start_file(&blob_rel_path, options)?;
-            std::io::Write::write_all(&mut zip, &blob_data)?;
+            tracing::debug!(
+                "Referenced {} pack blobs ({} newly stored)",
+                blob_hashes.len(),
+                new_blobs
+            );
+        } else {
+            for hash in &blob_hashes {
+                let blob_data = self.blob_store.read(hash).await?;
+                let size = blob_data.len() as u64;
+
+                // Store with directory structure: blobs/ab/cd/abcd123...
+                let blob_rel_path = format!("blobs/{}/{}/{}", &hash[0..2], &hash[2..4], hash);
+                let blob_checksum = archive.add(&blob_rel_path, blob_data).await?;
+
+                manifest.files.push(FileEntry {
+                    path: blob_rel_path,
+                    size,
+                    checksum: blob_checksum,
+                });
+            }
 
-            manifest.files.push(FileEntry {
-                path: blob_rel_path,
-                size: blob_data.len() as u64,
-                checksum: blob_checksum,
-            });
+            tracing::debug!("Added {} blobs to backup", blob_hashes.len());
         }
 
-        tracing::debug!("Added {} blobs to backup", blob_hashes.len());
-
         // Add manifest
         let manifest_json = serde_json::to_string_pretty(&manifest)?;
-        let manifest_checksum = calculate_checksum(manifest_json.as_bytes());
-
-        zip.start_file("manifest.json", options)?;
-        std::io::Write::write_all(&mut zip, manifest_json.as_bytes())?;
+        let manifest_checksum = archive
+            .add("manifest.json", manifest_json.into_bytes())
+            .await?;
 
         // Finish ZIP
-        zip.finish()?;
+        archive.finish().await?;
 
         tracing::info!("ZIP file created, encrypting...");
 
-        // Read the ZIP file
-        let zip_data = fs::read(&temp_zip_path).await?;
-
-        // Encrypt the ZIP data
-        let encrypted = crypto::encrypt(&zip_data, password)?;
-
-        // Serialize encrypted data
-        let encrypted_json = serde_json::to_vec(&encrypted)?;
-
-        // Write encrypted data to final file
-        fs::write(&backup_path, &encrypted_json).await?;
+        // Stream the ZIP through the chunked encryptor so the archive never
+        // has to fit in memory
+        let encrypted = encrypt_backup_file(&temp_zip_path, &backup_path, password).await;
 
         // Clean up temporary ZIP file
         fs::remove_file(&temp_zip_path).await?;
+        encrypted?;
 
         // Get file size
         let metadata = fs::metadata(&backup_path).await?;
@@ -192,6 +338,13 @@ impl BackupService {
 
         tracing::info!("Backup encrypted successfully ({} bytes)", size);
 
+        if let Some(guard) = pack_guard {
+            BlobPack::new(&backups_dir)
+                .write_refs(&backup_filename, &blob_hashes)
+                .await?;
+            drop(guard);
+        }
+
         // Record backup in database
         self.repo
             .record_backup(
@@ -254,6 +407,16 @@ impl BackupService {
             }
         }
 
+        // Drop pack blobs only the deleted backups referenced
+        self.sweep_blob_pack().await?;
+
+        Ok(())
+    }
+
+    /// Remove blob pack entries no remaining backup references
+    async fn sweep_blob_pack(&self) -> Result<()> {
+        let _guard = self.pack_lock.lock().await;
+        BlobPack::new(&self.get_backup_dir()?).sweep().await?;
         Ok(())
     }
 
@@ -322,6 +485,8 @@ impl BackupService {
         // Delete database record
         self.repo.delete_backup(backup_id).await?;
 
+        self.sweep_blob_pack().await?;
+
         tracing::info!("Backup deleted successfully: {}", backup_id);
         Ok(())
     }
@@ -330,71 +495,19 @@ impl BackupService {
     pub async fn restore_backup(&self, backup_path: &Path, password: &str) -> Result<()> {
         tracing::info!("Restoring from backup: {:?}", backup_path);
 
-        // Read and decrypt backup
-        let encrypted_data = fs::read(backup_path).await?;
-        let encrypted: crypto::EncryptedData = serde_json::from_slice(&encrypted_data)
-            .map_err(|e| AppError::Restore(format!("Invalid backup file format: {}", e)))?;
-
-        let zip_data = crypto::decrypt(&encrypted, password)?;
-
         // Create temporary directory for extraction
         let backups_dir = self.get_backup_dir()?;
         let temp_restore_dir = backups_dir.join(format!("restore_temp_{}", Utc::now().timestamp()));
         fs::create_dir_all(&temp_restore_dir).await?;
 
-        // Extract and verify ZIP
-        let cursor = std::io::Cursor::new(zip_data);
-        let mut archive = zip::ZipArchive::new(cursor)?;
-
-        // Read manifest first
-        let manifest = {
-            let mut manifest_file = archive.by_name("manifest.json")?;
-            let mut manifest_data = String::new();
-            std::io::Read::read_to_string(&mut manifest_file, &mut manifest_data)?;
-            serde_json::from_str::<BackupManifest>(&manifest_data)?
-        };
-
-        tracing::info!(
-            "Backup version: {}, timestamp: {}, files: {}",
-            manifest.version,
-            manifest.timestamp,
-            manifest.files.len()
-        );
-
-        // Verify checksums and extract files
-        for file_entry in &manifest.files {
-            // Skip manifest itself
-            if file_entry.path == "manifest.json" {
-                continue;
-            }
-
-            // Read file contents (drop file before any await)
-            let contents = {
-                let mut file = archive.by_name(&file_entry.path)?;
-                let mut contents = Vec::new();
-                std::io::Read::read_to_end(&mut file, &mut contents)?;
-                contents
-            };
-
-            // Verify checksum
-            let actual_checksum = calculate_checksum(&contents);
-            if actual_checksum != file_entry.checksum {
-                // Cleanup temp dir
-                let _ = fs::remove_dir_all(&temp_restore_dir).await;
-                return Err(AppError::Restore(format!(
-                    "Checksum mismatch for {}: expected {}, got {}",
-                    file_entry.path, file_entry.checksum, actual_checksum
-                )));
-            }
-
-            // Write to temp directory
-            let temp_file_path = temp_restore_dir.join(&file_entry.path);
-            if let Some(parent) = temp_file_path.parent() {
-                fs::create_dir_all(parent).await?;
-            }
-            fs::write(&temp_file_path, &contents).await?;
-
-            tracing::debug!("Verified and extracted: {}", file_entry.path);
+        // Decrypt, verify and extract everything before touching live data.
+        // The temp dir holds decrypted data, so remove it on any failure.
+        if let Err(e) = self
+            .extract_backup(backup_path, password, &temp_restore_dir)
+            .await
+        {
+            let _ = fs::remove_dir_all(&temp_restore_dir).await;
+            return Err(e);
         }
 
         tracing::info!("All files verified successfully, performing atomic swap...");
@@ -458,6 +571,649 @@ impl BackupService {
 
         Ok(())
     }
+
+    /// Check a backup without restoring it: decrypt, verify every manifest
+    /// checksum and run `PRAGMA integrity_check` on the embedded database.
+    /// Fails only when the backup can't be decrypted or has no manifest;
+    /// other problems are listed in the report.
+    pub async fn verify_backup(
+        &self,
+        backup_path: &Path,
+        password: &str,
+    ) -> Result<BackupVerificationReport> {
+        tracing::info!("Verifying backup: {:?}", backup_path);
+
+        let backups_dir = self.get_backup_dir()?;
+        let temp_dir = backups_dir.join(format!("verify_temp_{}", Utc::now().timestamp_millis()));
+        fs::create_dir_all(&temp_dir).await?;
+
+        // The temp dir holds decrypted data, so always remove it
+        let result = verify_in_dir(backup_path, password, &temp_dir).await;
+        let _ = fs::remove_dir_all(&temp_dir).await;
+
+        if let Ok(report) = &result {
+            if report.valid {
+                tracing::info!("Backup verified: {:?}", backup_path);
+            } else {
+                tracing::warn!(
+                    "Backup verification found {} problems: {:?}",
+                    report.errors.len(),
+                    backup_path
+                );
+            }
+        }
+
+        result
+    }
+
+    /// List the notes in a backup, flagging those that still exist in the library
+    pub async fn list_backup_notes(
+        &self,
+        backup_path: &Path,
+        password: &str,
+    ) -> Result<Vec<RestorableNote>> {
+        let temp_dir = self.create_temp_dir("notes_temp").await?;
+        let result = async {
+            let backup = OpenedBackup::open(backup_path, password, &temp_dir).await?;
+            let mut notes = backup.repo.list_restorable_notes().await?;
+            backup.repo.close().await;
+
+            let ids: Vec<String> = notes.iter().map(|n| n.id.clone()).collect();
+            let existing = self.repo.existing_note_ids(&ids).await?;
+            for note in &mut notes {
+                note.in_library = existing.contains(&note.id);
+            }
+            Ok(notes)
+        }
+        .await;
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+        result
+    }
+
+    /// Restore selected notes from a backup into the live library, with their
+    /// attachments, reminders, tags and referenced blobs. Unlike
+    /// `restore_backup` this leaves all other notes untouched.
+    pub async fn restore_backup_notes(
+        &self,
+        backup_path: &Path,
+        password: &str,
+        note_ids: &[String],
+        conflict: NoteRestoreConflict,
+    ) -> Result<NoteRestoreReport> {
+        tracing::info!(
+            "Restoring {} notes from backup {:?} (conflicts: {:?})",
+            note_ids.len(),
+            backup_path,
+            conflict
+        );
+
+        let temp_dir = self.create_temp_dir("notes_temp").await?;
+        let result = async {
+            let mut backup = OpenedBackup::open(backup_path, password, &temp_dir).await?;
+            let result = self
+                .restore_notes_from(&mut backup, note_ids, conflict)
+                .await;
+            backup.repo.close().await;
+            result
+        }
+        .await;
+
+        let _ = fs::remove_dir_all(&temp_dir).await;
+
+        if let Ok(report) = &result {
+            tracing::info!(
+                "Restored {} notes ({} skipped, {} not found, {} blobs)",
+                report.restored.len(),
+                report.skipped.len(),
+                report.not_found.len(),
+                report.blobs_restored
+            );
+        }
+        result
+    }
+
+    async fn restore_notes_from(
+        &self,
+        backup: &mut OpenedBackup,
+        note_ids: &[String],
+        conflict: NoteRestoreConflict,
+    ) -> Result<NoteRestoreReport> {
+        let existing = self.repo.existing_note_ids(note_ids).await?;
+        let mut report = NoteRestoreReport::default();
+
+        for id in note_ids {
+            let Some(mut snapshot) = backup.repo.get_note_snapshot(id).await? else {
+                report.not_found.push(id.clone());
+                continue;
+            };
+
+            let as_copy = match (existing.contains(id), conflict) {
+                (false, _) => false,
+                (true, NoteRestoreConflict::Skip) => {
+                    report.skipped.push(id.clone());
+                    continue;
+                }
+                (true, NoteRestoreConflict::Overwrite) => false,
+                (true, NoteRestoreConflict::Copy) => true,
+            };
+
+            // Restore blobs first so restored rows never reference missing blobs
+            let mut hashes: HashSet<String> = snapshot
+                .attachments
+                .iter()
+                .map(|a| a.blob_hash.clone())
+                .collect();
+            hashes.extend(Repository::extract_blob_hashes_from_delta(
+                &snapshot.note.content_json,
+            ));
+
+            for hash in hashes
+                .iter()
+                .filter(|h| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()))
+            {
+                if self.blob_store.exists(hash).await? {
+                    continue;
+                }
+                match backup.read_blob(hash).await? {
+                    Some(data) => {
+                        self.blob_store.write(&data).await?;
+                        report.blobs_restored += 1;
+                    }
+                    None => tracing::warn!(
+                        "Blob {} referenced by note {} is not in the backup",
+                        hash,
+                        id
+                    ),
+                }
+            }
+
+            // Recurring reminders that came due since the backup continue
+            // from their next occurrence instead of ending
+            recurrence::catch_up_reminders(&mut snapshot.reminders, Utc::now(), &Local);
+
+            if as_copy {
+                snapshot.note.title = format!("{} (restored)", snapshot.note.title);
+            }
+            let restored_id = self.repo.import_note_snapshot(&snapshot, as_copy).await?;
+            note_links::sync_note_links(&self.repo, &restored_id, &snapshot.note.content_json)
+                .await?;
+            report.restored.push(restored_id);
+        }
+
+        Ok(report)
+    }
+
+    /// Create a uniquely named scratch directory in the backups directory
+    async fn create_temp_dir(&self, prefix: &str) -> Result<PathBuf> {
+        let temp_dir =
+            self.get_backup_dir()?
+                .join(format!("{}_{}", prefix, uuid::Uuid::new_v4().simple()));
+        fs::create_dir_all(&temp_dir).await?;
+        Ok(temp_dir)
+    }
+
+    /// Decrypt a backup into `temp_restore_dir`, verifying every checksum
+    async fn extract_backup(
+        &self,
+        backup_path: &Path,
+        password: &str,
+        temp_restore_dir: &Path,
+    ) -> Result<()> {
+        // Decrypt to a plain ZIP on disk so large archives aren't held in memory
+        let zip_path = temp_restore_dir.join("backup.zip");
+        decrypt_backup_file(backup_path, password, &zip_path).await?;
+
+        let (manifest, pack_key) = {
+            let zip_path = zip_path.clone();
+            let dir = temp_restore_dir.to_path_buf();
+            run_blocking(move || extract_archive(&zip_path, &dir)).await?
+        };
+
+        // Reassemble blobs of an incremental backup from the blob pack
+        // next to the backup file
+        if let Some(pack_key) = pack_key {
+            let pack = BlobPack::new(backup_path.parent().unwrap_or(temp_restore_dir));
+
+            for file_entry in &manifest.pack_blobs {
+                let hash = &file_entry.checksum;
+                blob_pack::check_hash(hash)?;
+                let contents = pack.read(hash, &pack_key).await?;
+
+                let temp_file_path = temp_restore_dir.join(format!(
+                    "blobs/{}/{}/{}",
+                    &hash[0..2],
+                    &hash[2..4],
+                    hash
+                ));
+                if let Some(parent) = temp_file_path.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                fs::write(&temp_file_path, &contents).await?;
+            }
+
+            tracing::debug!(
+                "Restored {} blobs from blob pack",
+                manifest.pack_blobs.len()
+            );
+        }
+
+        fs::remove_file(&zip_path).await?;
+
+        Ok(())
+    }
+}
+
+/// A backup decrypted into a scratch directory, for reading single notes
+/// and blobs without a full restore
+struct OpenedBackup {
+    archive: Arc<std::sync::Mutex<zip::ZipArchive<std::fs::File>>>,
+    manifest: BackupManifest,
+    /// Blob pack and its key (incremental backups only)
+    pack: Option<(BlobPack, Vec<u8>)>,
+    /// The backup's database, migrated to the current schema
+    repo: Repository,
+}
+
+impl OpenedBackup {
+    async fn open(backup_path: &Path, password: &str, temp_dir: &Path) -> Result<Self> {
+        let zip_path = temp_dir.join("backup.zip");
+        decrypt_backup_file(backup_path, password, &zip_path).await?;
+
+        let (archive, manifest, db_data, pack_key) = run_blocking(move || {
+            let mut archive = zip::ZipArchive::new(std::fs::File::open(&zip_path)?)?;
+            let manifest = read_archive_manifest(&mut archive)?;
+
+            let db_entry = manifest
+                .files
+                .iter()
+                .find(|f| f.path == "db.sqlite")
+                .ok_or_else(|| {
+                    AppError::Restore("Backup does not contain a database".to_string())
+                })?;
+            let db_data = read_archive_file(&mut archive, "db.sqlite")?;
+            if calculate_checksum(&db_data) != db_entry.checksum {
+                return Err(AppError::Restore(
+                    "Checksum mismatch for db.sqlite".to_string(),
+                ));
+            }
+
+            let pack_key = if manifest.pack_blobs.is_empty() {
+                None
+            } else {
+                Some(read_archive_file(&mut archive, "pack.key")?)
+            };
+            Ok((archive, manifest, db_data, pack_key))
+        })
+        .await?;
+
+        // The database is a scratch copy, so it's safe to migrate backups
+        // taken by older versions to the current schema
+        let db_path = temp_dir.join("db.sqlite");
+        fs::write(&db_path, &db_data).await?;
+        let repo = Repository::new(crate::database::create_pool(&db_path).await?);
+
+        let pack =
+            pack_key.map(|key| (BlobPack::new(backup_path.parent().unwrap_or(temp_dir)), key));
+
+        Ok(Self {
+            archive: Arc::new(std::sync::Mutex::new(archive)),
+            manifest,
+            pack,
+            repo,
+        })
+    }
+
+    /// Read a blob from the archive or the blob pack; None if the backup
+    /// doesn't contain it
+    async fn read_blob(&mut self, hash: &str) -> Result<Option<Vec<u8>>> {
+        let path = format!("blobs/{}/{}/{}", &hash[0..2], &hash[2..4], hash);
+
+        if self.manifest.files.iter().any(|f| f.path == path) {
+            let archive = Arc::clone(&self.archive);
+            let hash = hash.to_string();
+            let contents = run_blocking(move || {
+                let mut archive = archive
+                    .lock()
+                    .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))?;
+                let contents = read_archive_file(&mut archive, &path)?;
+                if calculate_checksum(&contents) != hash {
+                    return Err(AppError::Restore(format!("Checksum mismatch for {}", path)));
+                }
+                Ok(contents)
+            })
+            .await?;
+            return Ok(Some(contents));
+        }
+
+        match &self.pack {
+            Some((pack, key)) if self.manifest.pack_blobs.iter().any(|f| f.checksum == hash) => {
+                Ok(Some(pack.read(hash, key).await?))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Decrypt a backup into `temp_dir` and check it (see `verify_backup`)
+async fn verify_in_dir(
+    backup_path: &Path,
+    password: &str,
+    temp_dir: &Path,
+) -> Result<BackupVerificationReport> {
+    let zip_path = temp_dir.join("backup.zip");
+    decrypt_backup_file(backup_path, password, &zip_path).await?;
+
+    let db_path = temp_dir.join("db.sqlite");
+    let (manifest, mut report, pack_key) = {
+        let db_path = db_path.clone();
+        run_blocking(move || verify_archive(&zip_path, &db_path)).await?
+    };
+
+    if let Some(pack_key) = pack_key {
+        let pack = BlobPack::new(backup_path.parent().unwrap_or(temp_dir));
+        for file_entry in &manifest.pack_blobs {
+            match pack.read(&file_entry.checksum, &pack_key).await {
+                Ok(_) => report.pack_blobs_verified += 1,
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+    }
+
+    // The database is only written out once its checksum matched
+    if db_path.exists() {
+        match check_database(&db_path).await {
+            Ok((integrity, note_count)) => {
+                if integrity != "ok" {
+                    report
+                        .errors
+                        .push(format!("Database integrity check failed: {}", integrity));
+                }
+                report.integrity_check = Some(integrity);
+                report.note_count = Some(note_count);
+            }
+            Err(e) => report
+                .errors
+                .push(format!("Failed to open backed-up database: {}", e)),
+        }
+    } else if !manifest.files.iter().any(|f| f.path == "db.sqlite") {
+        report
+            .errors
+            .push("Backup does not contain a database".to_string());
+    }
+
+    report.valid = report.errors.is_empty();
+    Ok(report)
+}
+
+/// Check the files of a decrypted backup archive against its manifest,
+/// writing the database to `db_path` when it checks out. Returns the
+/// manifest, the report so far and the blob pack key of an incremental
+/// backup.
+fn verify_archive(
+    zip_path: &Path,
+    db_path: &Path,
+) -> Result<(BackupManifest, BackupVerificationReport, Option<Vec<u8>>)> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+    let manifest = read_archive_manifest(&mut archive)?;
+
+    let mut report = BackupVerificationReport {
+        valid: false,
+        version: manifest.version.clone(),
+        timestamp: manifest.timestamp.clone(),
+        files_verified: 0,
+        pack_blobs_verified: 0,
+        errors: Vec::new(),
+        integrity_check: None,
+        note_count: None,
+    };
+
+    for file_entry in &manifest.files {
+        let contents = match archive.by_name(&file_entry.path) {
+            Ok(mut file) => {
+                let mut contents = Vec::new();
+                std::io::Read::read_to_end(&mut file, &mut contents)?;
+                contents
+            }
+            Err(_) => {
+                report
+                    .errors
+                    .push(format!("Missing from archive: {}", file_entry.path));
+                continue;
+            }
+        };
+
+        let actual_checksum = calculate_checksum(&contents);
+        if actual_checksum != file_entry.checksum {
+            report.errors.push(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                file_entry.path, file_entry.checksum, actual_checksum
+            ));
+            continue;
+        }
+        report.files_verified += 1;
+
+        if file_entry.path == "db.sqlite" {
+            std::fs::write(db_path, &contents)?;
+        }
+    }
+
+    let mut pack_key = None;
+    if !manifest.pack_blobs.is_empty() {
+        match read_archive_file(&mut archive, "pack.key") {
+            Ok(key) => pack_key = Some(key),
+            Err(_) => report
+                .errors
+                .push("Missing from archive: pack.key".to_string()),
+        }
+    }
+
+    Ok((manifest, report, pack_key))
+}
+
+/// Verify the files of a decrypted backup archive against its manifest and
+/// extract them into `dir`. Returns the manifest and the blob pack key of an
+/// incremental backup.
+fn extract_archive(zip_path: &Path, dir: &Path) -> Result<(BackupManifest, Option<Vec<u8>>)> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(zip_path)?)?;
+
+    // Read manifest first
+    let manifest = read_archive_manifest(&mut archive)?;
+
+    tracing::info!(
+        "Backup version: {}, timestamp: {}, files: {}",
+        manifest.version,
+        manifest.timestamp,
+        manifest.files.len()
+    );
+
+    // Verify checksums and extract files
+    for file_entry in &manifest.files {
+        // Skip manifest itself
+        if file_entry.path == "manifest.json" {
+            continue;
+        }
+
+        let contents = read_archive_file(&mut archive, &file_entry.path)?;
+
+        // Verify checksum
+        let actual_checksum = calculate_checksum(&contents);
+        if actual_checksum != file_entry.checksum {
+            return Err(AppError::Restore(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                file_entry.path, file_entry.checksum, actual_checksum
+            )));
+        }
+
+        // Write to temp directory
+        let temp_file_path = dir.join(&file_entry.path);
+        if let Some(parent) = temp_file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&temp_file_path, &contents)?;
+
+        tracing::debug!("Verified and extracted: {}", file_entry.path);
+    }
+
+    let pack_key = if manifest.pack_blobs.is_empty() {
+        None
+    } else {
+        Some(read_archive_file(&mut archive, "pack.key")?)
+    };
+    Ok((manifest, pack_key))
+}
+
+/// Open a database file read-only and return its `PRAGMA integrity_check`
+/// result and note count
+async fn check_database(db_path: &Path) -> Result<(String, i64)> {
+    let options = SqliteConnectOptions::new()
+        .filename(db_path)
+        .read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+
+    let integrity: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_all(&mut conn)
+        .await?;
+    let note_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notes WHERE deleted_at IS NULL")
+        .fetch_one(&mut conn)
+        .await?;
+
+    conn.close().await?;
+    Ok((integrity.join("\n"), note_count))
+}
+
+/// Read `manifest.json` from a decrypted backup archive
+fn read_archive_manifest<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<BackupManifest> {
+    let mut manifest_file = archive.by_name("manifest.json")?;
+    let mut manifest_data = String::new();
+    std::io::Read::read_to_string(&mut manifest_file, &mut manifest_data)?;
+    Ok(serde_json::from_str(&manifest_data)?)
+}
+
+/// Read a file from a decrypted backup archive
+fn read_archive_file<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Vec<u8>> {
+    let mut file = archive.by_name(name)?;
+    let mut contents = Vec::new();
+    std::io::Read::read_to_end(&mut file, &mut contents)?;
+    Ok(contents)
+}
+
+/// ZIP archive being built for a backup. Compression and file I/O run on
+/// the blocking thread pool.
+struct ArchiveWriter {
+    /// Taken while an entry is written; None after a failed write
+    zip: Option<ZipWriter<std::fs::File>>,
+}
+
+impl ArchiveWriter {
+    async fn create(path: &Path) -> Result<Self> {
+        let path = path.to_path_buf();
+        let zip = run_blocking(move || Ok(ZipWriter::new(std::fs::File::create(&path)?))).await?;
+        Ok(Self { zip: Some(zip) })
+    }
+
+    /// Add a compressed file; returns its checksum
+    async fn add(&mut self, name: &str, data: Vec<u8>) -> Result<String> {
+        let mut zip = self.take()?;
+        let name = name.to_string();
+        let (zip, checksum) = run_blocking(move || {
+            let options =
+                FileOptions::<()>::default().compression_method(zip::CompressionMethod::Deflated);
+            zip.start_file(name, options)?;
+            std::io::Write::write_all(&mut zip, &data)?;
+            Ok((zip, calculate_checksum(&data)))
+        })
+        .await?;
+        self.zip = Some(zip);
+        Ok(checksum)
+    }
+
+    async fn finish(mut self) -> Result<()> {
+        let zip = self.take()?;
+        run_blocking(move || {
+            zip.finish()?;
+            Ok(())
+        })
+        .await
+    }
+
+    fn take(&mut self) -> Result<ZipWriter<std::fs::File>> {
+        self.zip
+            .take()
+            .ok_or_else(|| AppError::Backup("Backup archive is incomplete".to_string()))
+    }
+}
+
+/// Stream a plain ZIP through the chunked encryptor into the backup file.
+/// Key derivation and encryption run on the blocking thread pool.
+async fn encrypt_backup_file(zip_path: &Path, backup_path: &Path, password: &str) -> Result<()> {
+    let zip_path = zip_path.to_path_buf();
+    let backup_path = backup_path.to_path_buf();
+    let password = password.to_string();
+    run_blocking(move || {
+        let mut input = std::io::BufReader::new(std::fs::File::open(&zip_path)?);
+        let output = std::io::BufWriter::new(std::fs::File::create(&backup_path)?);
+
+        let mut encryptor = crypto::StreamEncryptor::new(output, &password)?;
+        std::io::copy(&mut input, &mut encryptor)?;
+        encryptor.finish()?;
+
+        Ok(())
+    })
+    .await
+}
+
+/// Decrypt a backup file into a plain ZIP on the blocking thread pool
+async fn decrypt_backup_file(backup_path: &Path, password: &str, zip_path: &Path) -> Result<()> {
+    let backup_path = backup_path.to_path_buf();
+    let password = password.to_string();
+    let zip_path = zip_path.to_path_buf();
+    run_blocking(move || decrypt_backup_file_blocking(&backup_path, &password, &zip_path)).await
+}
+
+/// Decrypt a backup file into a plain ZIP. Reads the streaming container
+/// and the legacy whole-file JSON `EncryptedData` format.
+fn decrypt_backup_file_blocking(backup_path: &Path, password: &str, zip_path: &Path) -> Result<()> {
+    let mut input = std::fs::File::open(backup_path)?;
+    let mut magic = [0u8; crypto::STREAM_MAGIC.len()];
+    let is_stream = std::io::Read::read_exact(&mut input, &mut magic).is_ok()
+        && crypto::is_stream_format(&magic);
+    std::io::Seek::rewind(&mut input)?;
+
+    let mut output = std::io::BufWriter::new(std::fs::File::create(zip_path)?);
+
+    if is_stream {
+        let mut decryptor = crypto::StreamDecryptor::new(std::io::BufReader::new(input), password)
+            .map_err(|e| AppError::Restore(format!("Invalid backup file format: {}", e)))?;
+        std::io::copy(&mut decryptor, &mut output)
+            .map_err(|e| AppError::Restore(format!("Failed to decrypt backup: {}", e)))?;
+    } else {
+        let mut encrypted_data = Vec::new();
+        std::io::Read::read_to_end(&mut input, &mut encrypted_data)?;
+        let encrypted: crypto::EncryptedData = serde_json::from_slice(&encrypted_data)
+            .map_err(|e| AppError::Restore(format!("Invalid backup file format: {}", e)))?;
+        std::io::Write::write_all(&mut output, &crypto::decrypt(&encrypted, password)?)?;
+    }
+
+    std::io::Write::flush(&mut output)?;
+    Ok(())
+}
+
+/// Run file, ZIP and key derivation work on the blocking thread pool so it
+/// doesn't stall the async runtime
+async fn run_blocking<T, F>(work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::Backup(format!("Backup task failed: {}", e)))?
 }
 
 fn calculate_checksum(data: &[u8]) -> String {
@@ -478,7 +1234,10 @@ mod tests {
         let app_data_dir = temp_dir.path().to_path_buf();
 
         let pool = SqlitePoolOptions::new()
-            .connect(&format!("sqlite://{}/db.sqlite", app_data_dir.display()))
+            .connect(&format!(
+                "sqlite://{}/db.sqlite?mode=rwc",
+                app_data_dir.display()
+            ))
             .await
             .unwrap();
 
@@ -493,6 +1252,20 @@ mod tests {
         (service, temp_dir)
     }
 
+    async fn read_manifest(backup_path: &Path, password: &str) -> BackupManifest {
+        let temp = TempDir::new().unwrap();
+        let zip_path = temp.path().join("backup.zip");
+        decrypt_backup_file(backup_path, password, &zip_path)
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&zip_path).unwrap()).unwrap();
+        let mut manifest_file = archive.by_name("manifest.json").unwrap();
+        let mut manifest_data = String::new();
+        std::io::Read::read_to_string(&mut manifest_file, &mut manifest_data).unwrap();
+        serde_json::from_str(&manifest_data).unwrap()
+    }
+
     #[tokio::test]
     async fn test_create_backup() {
         let (service, _temp) = create_test_service().await;
@@ -524,21 +1297,8 @@ mod tests {
         let password = "test_password_123";
         let backup_path = service.create_backup(password).await.unwrap();
 
-        // Read encrypted backup
-        let encrypted_data = fs::read(&backup_path).await.unwrap();
-        let encrypted: crypto::EncryptedData = serde_json::from_slice(&encrypted_data).unwrap();
-
-        // Decrypt
-        let zip_data = crypto::decrypt(&encrypted, password).unwrap();
-
-        // Read manifest from decrypted ZIP
-        let cursor = std::io::Cursor::new(zip_data);
-        let mut archive = zip::ZipArchive::new(cursor).unwrap();
-        let mut manifest_file = archive.by_name("manifest.json").unwrap();
-        let mut manifest_data = String::new();
-        std::io::Read::read_to_string(&mut manifest_file, &mut manifest_data).unwrap();
-
-        let manifest: BackupManifest = serde_json::from_str(&manifest_data).unwrap();
+        // Decrypt and read manifest
+        let manifest = read_manifest(&backup_path, password).await;
 
         assert_eq!(manifest.version, env!("CARGO_PKG_VERSION"));
         assert!(!manifest.files.is_empty());
@@ -695,6 +1455,298 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_incremental_backup_dedupes_and_restores() {
+        let (service, _temp) = create_test_service().await;
+        let password = "test_password_123";
+
+        service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Test".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let blob_data = b"incremental blob data";
+        let blob_hash = service.blob_store.write(blob_data).await.unwrap();
+
+        let first = service
+            .create_backup_with_mode(password, true)
+            .await
+            .unwrap();
+        // Backup file names have one-second resolution
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        service.set_incremental(true).unwrap();
+        let second = service.create_backup(password).await.unwrap();
+        assert_ne!(first, second);
+
+        // Blob lives in the pack once, not in either archive
+        let manifest = read_manifest(&second, password).await;
+        assert!(manifest.files.iter().all(|f| !f.path.starts_with("blobs/")));
+        assert_eq!(manifest.pack_blobs.len(), 1);
+        assert_eq!(manifest.pack_blobs[0].checksum, blob_hash);
+
+        let pack = BlobPack::new(&service.get_backup_dir().unwrap());
+        assert!(pack.contains(&blob_hash));
+
+        // A different password can't add to the existing pack
+        assert!(service
+            .create_backup_with_mode("other_password", true)
+            .await
+            .is_err());
+
+        service.blob_store.delete(&blob_hash).await.unwrap();
+        service.restore_backup(&first, password).await.unwrap();
+
+        assert_eq!(
+            service.blob_store.read(&blob_hash).await.unwrap(),
+            blob_data
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retention_sweeps_blob_pack() {
+        let (service, _temp) = create_test_service().await;
+        let password = "test_password_123";
+
+        service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Test".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        service
+            .repo
+            .set_setting("backup_retention_count", "1")
+            .await
+            .unwrap();
+
+        let old_hash = service.blob_store.write(b"old blob").await.unwrap();
+        service
+            .create_backup_with_mode(password, true)
+            .await
+            .unwrap();
+
+        service.blob_store.delete(&old_hash).await.unwrap();
+        let new_hash = service.blob_store.write(b"new blob").await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        service
+            .create_backup_with_mode(password, true)
+            .await
+            .unwrap();
+
+        // Only the surviving backup's blobs stay in the pack
+        let pack = BlobPack::new(&service.get_backup_dir().unwrap());
+        assert!(!pack.contains(&old_hash));
+        assert!(pack.contains(&new_hash));
+        assert_eq!(service.list_backups().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_backup_is_streamed_and_legacy_restores() {
+        let (service, _temp) = create_test_service().await;
+        let password = "test_password_123";
+
+        service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Test".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        let blob_hash = service.blob_store.write(b"legacy blob").await.unwrap();
+
+        let backup_path = service.create_backup(password).await.unwrap();
+        let backup_data = fs::read(&backup_path).await.unwrap();
+        assert!(crypto::is_stream_format(&backup_data));
+
+        // Rewrite the same archive in the legacy whole-file JSON format
+        let zip_path = backup_path.with_extension("zip");
+        decrypt_backup_file(&backup_path, password, &zip_path)
+            .await
+            .unwrap();
+        let zip_data = fs::read(&zip_path).await.unwrap();
+        let legacy = serde_json::to_vec(&crypto::encrypt(&zip_data, password).unwrap()).unwrap();
+        fs::write(&backup_path, legacy).await.unwrap();
+
+        service.blob_store.delete(&blob_hash).await.unwrap();
+        service
+            .restore_backup(&backup_path, password)
+            .await
+            .unwrap();
+        assert_eq!(
+            service.blob_store.read(&blob_hash).await.unwrap(),
+            b"legacy blob"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_backup() {
+        let (service, _temp) = create_test_service().await;
+        let password = "test_password_123";
+
+        service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Test".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        let blob_hash = service.blob_store.write(b"verified blob").await.unwrap();
+
+        let full = service.create_backup(password).await.unwrap();
+        let report = service.verify_backup(&full, password).await.unwrap();
+        assert!(report.valid, "{:?}", report.errors);
+        assert_eq!(report.files_verified, 2);
+        assert_eq!(report.integrity_check.as_deref(), Some("ok"));
+        assert_eq!(report.note_count, Some(1));
+
+        assert!(service
+            .verify_backup(&full, "wrong_password")
+            .await
+            .is_err());
+
+        // A missing pack blob is reported, not a hard failure
+        tokio::time::sleep(tokio::time::Duration::from_millis(1100)).await;
+        let incremental = service
+            .create_backup_with_mode(password, true)
+            .await
+            .unwrap();
+        let object = service.get_backup_dir().unwrap().join(format!(
+            "blobpack/objects/{}/{}/{}",
+            &blob_hash[0..2],
+            &blob_hash[2..4],
+            blob_hash
+        ));
+        fs::remove_file(object).await.unwrap();
+
+        let report = service.verify_backup(&incremental, password).await.unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.pack_blobs_verified, 0);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.note_count, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_restore_backup_notes() {
+        let (service, _temp) = create_test_service().await;
+        let password = "test_password_123";
+
+        let image_hash = service.blob_store.write(b"embedded image").await.unwrap();
+        let lost = service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Lost".to_string(),
+                content_json: format!(
+                    r#"{{"ops":[{{"insert":{{"image":{{"blobHash":"{}"}}}}}}]}}"#,
+                    image_hash
+                ),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        let file_hash = service.blob_store.write(b"attached file").await.unwrap();
+        service
+            .repo
+            .create_attachment(&lost.id, &file_hash, "file.txt", "text/plain", 13)
+            .await
+            .unwrap();
+        let kept = service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: "Kept".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        // Incremental, so blobs come from the blob pack
+        let backup_path = service
+            .create_backup_with_mode(password, true)
+            .await
+            .unwrap();
+
+        // Purge one note and its blobs, and edit the other
+        service.repo.delete_note(&lost.id).await.unwrap();
+        service.repo.prune_deleted_notes().await.unwrap();
+        service.blob_store.delete(&image_hash).await.unwrap();
+        service.blob_store.delete(&file_hash).await.unwrap();
+        service
+            .repo
+            .update_note(models::UpdateNoteRequest {
+                id: kept.id.clone(),
+                title: Some("Kept (edited)".to_string()),
+                content_json: None,
+                title_modified: Some(true),
+            })
+            .await
+            .unwrap();
+
+        let notes = service
+            .list_backup_notes(&backup_path, password)
+            .await
+            .unwrap();
+        assert_eq!(notes.len(), 2);
+        let listed = notes.iter().find(|n| n.id == lost.id).unwrap();
+        assert!(!listed.in_library);
+        assert_eq!(listed.attachment_count, 1);
+        assert!(notes.iter().find(|n| n.id == kept.id).unwrap().in_library);
+
+        let ids = vec![lost.id.clone(), kept.id.clone(), "missing".to_string()];
+        let report = service
+            .restore_backup_notes(&backup_path, password, &ids, NoteRestoreConflict::Skip)
+            .await
+            .unwrap();
+        assert_eq!(report.restored, vec![lost.id.clone()]);
+        assert_eq!(report.skipped, vec![kept.id.clone()]);
+        assert_eq!(report.not_found, vec!["missing".to_string()]);
+        assert_eq!(report.blobs_restored, 2);
+        assert!(service.blob_store.exists(&image_hash).await.unwrap());
+        assert!(service.blob_store.exists(&file_hash).await.unwrap());
+        assert_eq!(
+            service.repo.list_attachments(&lost.id).await.unwrap().len(),
+            1
+        );
+        assert_eq!(
+            service.repo.get_note(&kept.id).await.unwrap().title,
+            "Kept (edited)"
+        );
+
+        let ids = vec![kept.id.clone()];
+        let report = service
+            .restore_backup_notes(&backup_path, password, &ids, NoteRestoreConflict::Copy)
+            .await
+            .unwrap();
+        assert_ne!(report.restored[0], kept.id);
+        assert_eq!(
+            service
+                .repo
+                .get_note(&report.restored[0])
+                .await
+                .unwrap()
+                .title,
+            "Kept (restored)"
+        );
+
+        service
+            .restore_backup_notes(&backup_path, password, &ids, NoteRestoreConflict::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(service.repo.get_note(&kept.id).await.unwrap().title, "Kept");
+        assert_eq!(service.repo.list_notes().await.unwrap().len(), 3);
+    }
+
     #[tokio::test]
     async fn test_backup_without_notes_fails() {
         let (service, _temp) = create_test_service().await;
@@ -0,0 +1,359 @@
+//! Portable export bundles
+//!
+//! A bundle is an unencrypted ZIP holding notes as plain JSON, for moving
+//! them between machines or processing them with scripts:
+//!
+//! - `manifest.json`: a `BundleManifest` (format name and version, when it
+//!   was written and by which app version)
+//! - `notes.jsonl`: one `BundleNote` per line, a note with its tags (and
+//!   for a daily note, its date)
+//! - `collections.json`, `reminders.json`, `attachments.json`: JSON arrays
+//!   of `Collection`, `Reminder` and `Attachment` rows
+//! - `blobs/<sha256>`: the content of attachments and embedded images, named
+//!   by the hash notes and attachments refer to them by
+//!
+//! Timestamps are RFC 3339 in UTC. Readers refuse bundles with a newer
+//! `version` than `config::BUNDLE_FORMAT_VERSION`; fields added to a later
+//! version must be optional so older bundles keep reading.
+
+use crate::config;
+use crate::database::{Note, Tag};
+use crate::error::{AppError, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// `format` of every bundle manifest
+pub const BUNDLE_FORMAT: &str = "swatnotes-bundle";
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const NOTES_FILE: &str = "notes.jsonl";
+pub const COLLECTIONS_FILE: &str = "collections.json";
+pub const REMINDERS_FILE: &str = "reminders.json";
+pub const ATTACHMENTS_FILE: &str = "attachments.json";
+
+/// `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Always `BUNDLE_FORMAT`
+    pub format: String,
+    pub version: u32,
+    /// Version of the app that wrote the bundle
+    pub app_version: String,
+    pub exported_at: DateTime<Utc>,
+    pub note_count: usize,
+    pub blob_count: usize,
+}
+
+/// A line of `notes.jsonl`: the note's fields with its tags alongside
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleNote {
+    #[serde(flatten)]
+    pub note: Note,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// Date the note is the daily note of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal_date: Option<NaiveDate>,
+}
+
+/// Writes a bundle; the manifest goes last, once the counts are known
+pub struct BundleWriter {
+    zip: ZipWriter<File>,
+    notes: usize,
+    blobs: usize,
+}
+
+impl BundleWriter {
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            zip: ZipWriter::new(File::create(path)?),
+            notes: 0,
+            blobs: 0,
+        })
+    }
+
+    /// Write `value` as pretty-printed JSON to `name`
+    pub fn write_json<T: Serialize + ?Sized>(&mut self, name: &str, value: &T) -> Result<()> {
+        self.start(name)?;
+        self.zip
+            .write_all(serde_json::to_string_pretty(value)?.as_bytes())?;
+        Ok(())
+    }
+
+    /// Write `notes.jsonl`
+    pub fn write_notes(&mut self, notes: &[BundleNote]) -> Result<()> {
+        self.start(NOTES_FILE)?;
+        for note in notes {
+            self.zip
+                .write_all(serde_json::to_string(note)?.as_bytes())?;
+            self.zip.write_all(b"\n")?;
+        }
+        self.notes += notes.len();
+        Ok(())
+    }
+
+    pub fn write_blob(&mut self, hash: &str, data: &[u8]) -> Result<()> {
+        self.start(&format!("blobs/{}", hash))?;
+        self.zip.write_all(data)?;
+        self.blobs += 1;
+        Ok(())
+    }
+
+    /// Write the manifest and finish the archive
+    pub fn finish(mut self) -> Result<BundleManifest> {
+        let manifest = BundleManifest {
+            format: BUNDLE_FORMAT.to_string(),
+            version: config::BUNDLE_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: Utc::now(),
+            note_count: self.notes,
+            blob_count: self.blobs,
+        };
+        self.write_json(MANIFEST_FILE, &manifest)?;
+        self.zip.finish()?;
+        Ok(manifest)
+    }
+
+    fn start(&mut self, name: &str) -> Result<()> {
+        let options =
+            FileOptions::<()>::default().compression_method(zip::CompressionMethod::Deflated);
+        self.zip.start_file(name, options)?;
+        Ok(())
+    }
+}
+
+/// Reads a bundle, checking its manifest on open
+pub struct BundleReader {
+    archive: ZipArchive<File>,
+    pub manifest: BundleManifest,
+}
+
+impl BundleReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut archive = ZipArchive::new(File::open(path)?)
+            .map_err(|e| AppError::Import(format!("not a bundle: {}", e)))?;
+        let manifest: BundleManifest =
+            serde_json::from_slice(&read_entry(&mut archive, MANIFEST_FILE)?)
+                .map_err(|e| AppError::Import(format!("invalid {}: {}", MANIFEST_FILE, e)))?;
+
+        if manifest.format != BUNDLE_FORMAT {
+            return Err(AppError::Import(format!(
+                "not a bundle: format is {:?}",
+                manifest.format
+            )));
+        }
+        if manifest.version > config::BUNDLE_FORMAT_VERSION {
+            return Err(AppError::Import(format!(
+                "bundle version {} is newer than this app supports ({}); update the app to import it",
+                manifest.version,
+                config::BUNDLE_FORMAT_VERSION
+            )));
+        }
+
+        Ok(Self { archive, manifest })
+    }
+
+    /// Read a JSON file of the bundle
+    pub fn read_json<T: DeserializeOwned>(&mut self, name: &str) -> Result<T> {
+        serde_json::from_slice(&read_entry(&mut self.archive, name)?)
+            .map_err(|e| AppError::Import(format!("invalid {}: {}", name, e)))
+    }
+
+    /// Read `notes.jsonl`; a line that can't be read is an error for that
+    /// line only
+    pub fn read_notes(&mut self) -> Result<Vec<std::result::Result<BundleNote, String>>> {
+        let data = read_entry(&mut self.archive, NOTES_FILE)?;
+        let text = String::from_utf8_lossy(&data);
+        Ok(text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| format!("{} line {}: {}", NOTES_FILE, n + 1, e))
+            })
+            .collect())
+    }
+
+    /// Read a blob, checking it against its hash; None if the bundle
+    /// doesn't contain it
+    pub fn read_blob(&mut self, hash: &str) -> Result<Option<Vec<u8>>> {
+        let name = format!("blobs/{}", hash);
+        if self.archive.index_for_name(&name).is_none() {
+            return Ok(None);
+        }
+
+        let data = read_entry(&mut self.archive, &name)?;
+        let actual = format!("{:x}", Sha256::digest(&data));
+        if actual != hash {
+            return Err(AppError::Import(format!(
+                "checksum mismatch for {}: got {}",
+                name, actual
+            )));
+        }
+        Ok(Some(data))
+    }
+}
+
+/// Whether `hash` is a SHA-256 hex digest, as blob names are
+pub fn is_blob_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>> {
+    read_entry_limited(archive, name, config::MAX_BUNDLE_ENTRY_SIZE)
+}
+
+/// Read an entry of at most `limit` bytes; the size in the ZIP header
+/// isn't trusted
+fn read_entry_limited(archive: &mut ZipArchive<File>, name: &str, limit: u64) -> Result<Vec<u8>> {
+    let file = archive
+        .by_name(name)
+        .map_err(|_| AppError::Import(format!("bundle has no {}", name)))?;
+    let mut data = Vec::new();
+    file.take(limit + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > limit {
+        return Err(AppError::Import(format!(
+            "{} is larger than {} bytes",
+            name, limit
+        )));
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Collection;
+    use tempfile::TempDir;
+
+    fn note(id: &str) -> Note {
+        Note {
+            id: id.to_string(),
+            title: format!("Note {}", id),
+            content_json: r#"{"ops":[{"insert":"Hi\n"}]}"#.to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            deleted_at: None,
+            title_modified: true,
+            collection_id: None,
+        }
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("bundle.zip");
+
+        let mut writer = BundleWriter::create(&path).unwrap();
+        let notes = vec![
+            BundleNote {
+                note: note("a"),
+                tags: vec![Tag {
+                    id: "t".to_string(),
+                    name: "q4".to_string(),
+                    color: "#6B7280".to_string(),
+                    created_at: Utc::now(),
+                }],
+                journal_date: NaiveDate::from_ymd_opt(2026, 10, 17),
+            },
+            BundleNote {
+                note: note("b"),
+                tags: Vec::new(),
+                journal_date: None,
+            },
+        ];
+        writer.write_notes(&notes).unwrap();
+        writer
+            .write_json(COLLECTIONS_FILE, &Vec::<Collection>::new())
+            .unwrap();
+        let hash = format!("{:x}", Sha256::digest(b"blob"));
+        writer.write_blob(&hash, b"blob").unwrap();
+        let manifest = writer.finish().unwrap();
+        assert_eq!(manifest.note_count, 2);
+        assert_eq!(manifest.blob_count, 1);
+
+        let mut reader = BundleReader::open(&path).unwrap();
+        assert_eq!(reader.manifest.format, BUNDLE_FORMAT);
+        assert_eq!(reader.manifest.version, config::BUNDLE_FORMAT_VERSION);
+
+        let read: Vec<BundleNote> = reader
+            .read_notes()
+            .unwrap()
+            .into_iter()
+            .map(|note| note.unwrap())
+            .collect();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].note.title, "Note a");
+        assert_eq!(read[0].tags[0].name, "q4");
+        assert_eq!(read[0].journal_date, NaiveDate::from_ymd_opt(2026, 10, 17));
+        assert_eq!(read[1].journal_date, None);
+        let collections: Vec<Collection> = reader.read_json(COLLECTIONS_FILE).unwrap();
+        assert!(collections.is_empty());
+        assert_eq!(reader.read_blob(&hash).unwrap().unwrap(), b"blob");
+        assert!(reader.read_blob(&"0".repeat(64)).unwrap().is_none());
+        assert!(matches!(
+            reader.read_json::<Vec<Collection>>(REMINDERS_FILE),
+            Err(AppError::Import(_))
+        ));
+
+        // Notes are flat JSON objects, one per line
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let text = String::from_utf8(read_entry(&mut archive, NOTES_FILE).unwrap()).unwrap();
+        let first: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(first["id"], "a");
+        assert_eq!(first["tags"][0]["name"], "q4");
+        assert_eq!(first["journal_date"], "2026-10-17");
+
+        // Entries past the limit aren't read whole
+        let size = text.len() as u64;
+        assert_eq!(
+            read_entry_limited(&mut archive, NOTES_FILE, size)
+                .unwrap()
+                .len() as u64,
+            size
+        );
+        assert!(matches!(
+            read_entry_limited(&mut archive, NOTES_FILE, size - 1),
+            Err(AppError::Import(_))
+        ));
+    }
+
+    #[test]
+    fn test_bundle_rejects_newer_versions() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("bundle.zip");
+
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file(MANIFEST_FILE, FileOptions::<()>::default())
+            .unwrap();
+        let manifest = serde_json::json!({
+            "format": BUNDLE_FORMAT,
+            "version": config::BUNDLE_FORMAT_VERSION + 1,
+            "app_version": "9.0.0",
+            "exported_at": "2026-10-17T09:00:00Z",
+            "note_count": 0,
+            "blob_count": 0
+        });
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        assert!(matches!(
+            BundleReader::open(&path),
+            Err(AppError::Import(message)) if message.contains("newer")
+        ));
+
+        std::fs::write(&path, b"not a zip").unwrap();
+        assert!(matches!(
+            BundleReader::open(&path),
+            Err(AppError::Import(_))
+        ));
+    }
+}
@@ -0,0 +1,694 @@
+//! Evernote import
+//!
+//! Reads ENEX files, the XML Evernote exports notebooks as. Each `<note>`
+//! holds a title, created and updated times, tags, an ENML body (XHTML in a
+//! CDATA section) and `<resource>`s with attached files as base64.
+//! `enml_to_delta` converts the body to Quill Delta; the `<en-media>`
+//! elements in it refer to resources by the MD5 hash of their data, so they
+//! become `en-media` placeholder embeds for the importer to swap for the
+//! stored attachments.
+
+use crate::error::{AppError, Result};
+use crate::services::markdown::DeltaOps;
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use md5::{Digest, Md5};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// What imported Evernote notes are grouped into collections by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnexCollectionSource {
+    /// The notebook, named after the `.enex` file it was exported to
+    #[default]
+    Notebook,
+    /// The note's first tag; untagged notes stay outside any collection
+    Tag,
+}
+
+/// A note read from an ENEX file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnexNote {
+    pub title: String,
+    pub created: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    /// ENML body
+    pub content: String,
+    pub resources: Vec<EnexResource>,
+}
+
+/// A file attached to an ENEX note
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnexResource {
+    /// Base64 data as written
+    pub data: String,
+    pub mime_type: String,
+    pub filename: Option<String>,
+}
+
+impl EnexResource {
+    /// The decoded data and its MD5 hash (hex), which `<en-media>` refers to
+    pub fn decode(&self) -> Result<(Vec<u8>, String)> {
+        let base64: String = self
+            .data
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(base64)
+            .map_err(|e| AppError::Import(format!("invalid base64 resource data: {}", e)))?;
+        let hash = format!("{:x}", Md5::digest(&data));
+        Ok((data, hash))
+    }
+}
+
+/// Read the notes in an ENEX document
+pub fn parse_enex(xml: &str) -> Result<Vec<EnexNote>> {
+    let mut reader = Reader::from_str(xml);
+    let mut notes = Vec::new();
+    let mut note: Option<EnexNote> = None;
+    let mut resource: Option<EnexResource> = None;
+    // Names of the open elements
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if path.is_empty() && name != "en-export" {
+                    return Err(AppError::Import(format!(
+                        "not an ENEX file: root element is <{}>",
+                        name
+                    )));
+                }
+                match name.as_str() {
+                    "note" => note = Some(EnexNote::default()),
+                    "resource" => resource = Some(EnexResource::default()),
+                    _ => {}
+                }
+                path.push(name);
+                text.clear();
+            }
+            Ok(Event::End(_)) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str).unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                match (parent, name.as_str()) {
+                    ("en-export", "note") => notes.extend(note.take()),
+                    ("note", "resource") => {
+                        if let (Some(note), Some(resource)) = (note.as_mut(), resource.take()) {
+                            note.resources.push(resource);
+                        }
+                    }
+                    ("note", field) => {
+                        if let Some(note) = note.as_mut() {
+                            match field {
+                                "title" => note.title = value.trim().to_string(),
+                                "created" => note.created = parse_enex_time(&value),
+                                "updated" => note.updated = parse_enex_time(&value),
+                                "tag" => note.tags.push(value.trim().to_string()),
+                                "content" => note.content = value,
+                                _ => {}
+                            }
+                        }
+                    }
+                    ("resource", field) => {
+                        if let Some(resource) = resource.as_mut() {
+                            match field {
+                                "data" => resource.data = value,
+                                "mime" => resource.mime_type = value.trim().to_string(),
+                                _ => {}
+                            }
+                        }
+                    }
+                    ("resource-attributes", "file-name") => {
+                        if let Some(resource) = resource.as_mut() {
+                            let filename = value.trim();
+                            resource.filename =
+                                (!filename.is_empty()).then(|| filename.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Text(e)) => match e.unescape() {
+                Ok(unescaped) => text.push_str(&unescaped),
+                Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+            },
+            Ok(Event::CData(e)) => text.push_str(&String::from_utf8_lossy(&e.into_inner())),
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(AppError::Import(format!(
+                    "invalid ENEX XML at byte {}: {}",
+                    reader.buffer_position(),
+                    e
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    if note.is_some() || !path.is_empty() {
+        return Err(AppError::Import("ENEX file ends early".to_string()));
+    }
+    Ok(notes)
+}
+
+/// ENEX times look like `20240301T090000Z`
+fn parse_enex_time(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|at| at.and_utc())
+}
+
+/// Convert an ENML note body to Quill Delta ops. Each `<en-media>` becomes
+/// an `{"en-media": {"hash", "type"}}` embed for the caller to replace.
+pub fn enml_to_delta(enml: &str) -> Result<Vec<Value>> {
+    let mut reader = Reader::from_str(enml);
+    // Evernote bodies aren't always well-formed; every end tag closes the
+    // innermost element
+    reader.config_mut().check_end_names = false;
+    let mut converter = EnmlConverter::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => converter.open(&e),
+            Ok(Event::Empty(e)) => {
+                converter.open(&e);
+                converter.close();
+            }
+            Ok(Event::End(_)) => converter.close(),
+            Ok(Event::Text(e)) => match e.unescape_with(html_entity) {
+                Ok(text) => converter.text(&text),
+                Err(_) => converter.text(&String::from_utf8_lossy(&e)),
+            },
+            Ok(Event::CData(e)) => converter.text(&String::from_utf8_lossy(&e.into_inner())),
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(AppError::Import(format!(
+                    "invalid ENML at byte {}: {}",
+                    reader.buffer_position(),
+                    e
+                )))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(converter.finish())
+}
+
+/// Elements that start and end a line
+const BLOCK_ELEMENTS: &[&str] = &[
+    "en-note",
+    "div",
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "blockquote",
+    "pre",
+    "table",
+    "tr",
+    "hr",
+    "center",
+    "dl",
+    "dt",
+    "dd",
+    "address",
+    "section",
+    "article",
+    "header",
+    "footer",
+];
+
+struct EnmlElement {
+    name: String,
+    /// Lowercase with whitespace removed
+    style: String,
+    /// Inline formats of text inside it, inherited ones included
+    formats: Map<String, Value>,
+}
+
+struct EnmlConverter {
+    ops: DeltaOps,
+    /// Open elements
+    stack: Vec<EnmlElement>,
+    /// Whether the line being written has content
+    dirty: bool,
+    /// Whether the text so far ends in whitespace (or the line is empty)
+    space: bool,
+    /// State of a legacy `<en-todo>` checkbox on this line
+    todo: Option<bool>,
+}
+
+impl EnmlConverter {
+    fn new() -> Self {
+        Self {
+            ops: DeltaOps::default(),
+            stack: Vec::new(),
+            dirty: false,
+            space: true,
+            todo: None,
+        }
+    }
+
+    fn open(&mut self, e: &BytesStart) {
+        let name = String::from_utf8_lossy(e.name().as_ref()).to_lowercase();
+        let attr = |key: &str| {
+            e.try_get_attribute(key)
+                .ok()
+                .flatten()
+                .and_then(|attr| attr.unescape_value().ok())
+                .map(|value| value.into_owned())
+        };
+        let style = normalize_style(&attr("style").unwrap_or_default());
+
+        if BLOCK_ELEMENTS.contains(&name.as_str()) {
+            self.end_line(false);
+        }
+
+        let mut formats = self.formats();
+        apply_html_formats(&mut formats, &name, &style, attr("href"));
+        if self.in_code() {
+            formats.remove("code");
+        }
+
+        match name.as_str() {
+            "br" => self.end_line(true),
+            "en-todo" => {
+                self.todo = Some(attr("checked").as_deref() == Some("true"));
+                self.dirty = true;
+            }
+            "en-media" => {
+                let embed = json!({ "en-media": {
+                    "hash": attr("hash").unwrap_or_default(),
+                    "type": attr("type").unwrap_or_default(),
+                } });
+                self.ops.push(embed, formats.clone());
+                self.dirty = true;
+                self.space = false;
+            }
+            "img" => {
+                if let Some(src) = attr("src").filter(|src| !src.is_empty()) {
+                    self.ops.push(json!({ "image": src }), formats.clone());
+                    self.dirty = true;
+                    self.space = false;
+                }
+            }
+            "td" | "th" if self.dirty => self.text(" | "),
+            _ => {}
+        }
+
+        self.stack.push(EnmlElement {
+            name,
+            style,
+            formats,
+        });
+    }
+
+    fn close(&mut self) {
+        let block = self
+            .stack
+            .last()
+            .is_some_and(|element| BLOCK_ELEMENTS.contains(&element.name.as_str()));
+        if block {
+            self.end_line(false);
+        }
+        self.stack.pop();
+    }
+
+    fn text(&mut self, text: &str) {
+        let formats = self.formats();
+
+        if self.in_code() {
+            // Code keeps its whitespace; newlines end code lines
+            for (n, line) in text.split('\n').enumerate() {
+                if n > 0 {
+                    self.end_line(true);
+                }
+                let line = line.trim_end_matches('\r');
+                if !line.is_empty() {
+                    self.ops.push(Value::from(line), formats.clone());
+                    self.dirty = true;
+                }
+            }
+            return;
+        }
+
+        let mut collapsed = String::new();
+        for c in text.chars() {
+            if c.is_ascii_whitespace() {
+                if !self.space {
+                    collapsed.push(' ');
+                    self.space = true;
+                }
+            } else {
+                collapsed.push(c);
+                self.space = false;
+            }
+        }
+        if !collapsed.is_empty() {
+            self.ops.push(Value::from(collapsed), formats);
+            self.dirty = true;
+        }
+    }
+
+    /// End the line being written; an empty one only if `force`
+    fn end_line(&mut self, force: bool) {
+        if self.dirty || force {
+            let attrs = self.line_attrs();
+            self.ops.push(Value::from("\n"), attrs);
+        }
+        self.dirty = false;
+        self.space = true;
+        self.todo = None;
+    }
+
+    fn finish(mut self) -> Vec<Value> {
+        self.end_line(false);
+        let mut ops = self.ops.into_ops();
+        if ops.is_empty() {
+            ops.push(json!({ "insert": "\n" }));
+        }
+        ops
+    }
+
+    /// Inline formats of text written now
+    fn formats(&self) -> Map<String, Value> {
+        self.stack
+            .last()
+            .map(|element| element.formats.clone())
+            .unwrap_or_default()
+    }
+
+    /// Inside `<pre>` or an Evernote code block
+    fn in_code(&self) -> bool {
+        self.stack
+            .iter()
+            .any(|element| element.name == "pre" || element.style.contains("-en-codeblock:true"))
+    }
+
+    /// Attributes of the newline ending the current line
+    fn line_attrs(&self) -> Map<String, Value> {
+        let mut attrs = Map::new();
+        if self.in_code() {
+            attrs.insert("code-block".to_string(), Value::Bool(true));
+            return attrs;
+        }
+
+        let lists: Vec<&EnmlElement> = self
+            .stack
+            .iter()
+            .filter(|element| matches!(element.name.as_str(), "ul" | "ol"))
+            .collect();
+        let item = self.stack.iter().rev().find(|element| element.name == "li");
+
+        if let Some(list) = lists.last() {
+            // Evernote 10 writes checklists as styled lists
+            let checked = self.todo.or_else(|| {
+                list.style
+                    .contains("--en-todo:true")
+                    .then(|| item.is_some_and(|item| item.style.contains("--en-checked:true")))
+            });
+            let kind = match checked {
+                Some(true) => "checked",
+                Some(false) => "unchecked",
+                None if list.name == "ol" => "ordered",
+                None => "bullet",
+            };
+            attrs.insert("list".to_string(), Value::from(kind));
+            if lists.len() > 1 {
+                attrs.insert("indent".to_string(), Value::from(lists.len() - 1));
+            }
+        } else if let Some(checked) = self.todo {
+            let kind = if checked { "checked" } else { "unchecked" };
+            attrs.insert("list".to_string(), Value::from(kind));
+        } else if let Some(level) = self.stack.iter().rev().find_map(|e| header_level(&e.name)) {
+            attrs.insert("header".to_string(), Value::from(level));
+        } else if self
+            .stack
+            .iter()
+            .any(|element| element.name == "blockquote")
+        {
+            attrs.insert("blockquote".to_string(), Value::Bool(true));
+        }
+        attrs
+    }
+}
+
+/// Lowercase a `style` attribute and drop its whitespace
+pub fn normalize_style(style: &str) -> String {
+    style
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect()
+}
+
+/// Add the formats an inline HTML element sets, by tag name and by its
+/// (normalized) `style`, to those of its parent
+pub fn apply_html_formats(
+    formats: &mut Map<String, Value>,
+    name: &str,
+    style: &str,
+    href: Option<String>,
+) {
+    match name {
+        "b" | "strong" => set_format(formats, "bold", true),
+        "i" | "em" => set_format(formats, "italic", true),
+        "u" => set_format(formats, "underline", true),
+        "s" | "strike" | "del" => set_format(formats, "strike", true),
+        "sub" => set_format(formats, "script", "sub"),
+        "sup" => set_format(formats, "script", "super"),
+        "code" => set_format(formats, "code", true),
+        "a" => {
+            if let Some(href) = href.filter(|href| !href.is_empty()) {
+                set_format(formats, "link", href);
+            }
+        }
+        _ => {}
+    }
+    if let Some(weight) = style_value(style, "font-weight") {
+        if weight == "bold" || weight.parse::<u32>().is_ok_and(|weight| weight >= 600) {
+            set_format(formats, "bold", true);
+        }
+    }
+    if style_value(style, "font-style") == Some("italic") {
+        set_format(formats, "italic", true);
+    }
+    if let Some(decoration) = style_value(style, "text-decoration") {
+        if decoration.contains("underline") {
+            set_format(formats, "underline", true);
+        }
+        if decoration.contains("line-through") {
+            set_format(formats, "strike", true);
+        }
+    }
+}
+
+fn set_format(formats: &mut Map<String, Value>, name: &str, value: impl Into<Value>) {
+    formats.insert(name.to_string(), value.into());
+}
+
+fn header_level(name: &str) -> Option<u8> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// The value of a CSS property in a normalized `style` attribute
+fn style_value<'a>(style: &'a str, property: &str) -> Option<&'a str> {
+    style
+        .split(';')
+        .filter_map(|declaration| declaration.split_once(':'))
+        .find(|(name, _)| *name == property)
+        .map(|(_, value)| value.trim_end_matches("!important"))
+}
+
+/// The XML entities plus the HTML ones Evernote and OneNote text uses most
+pub fn html_entity(name: &str) -> Option<&'static str> {
+    quick_xml::escape::resolve_predefined_entity(name).or(match name {
+        "nbsp" => Some("\u{a0}"),
+        "ensp" | "emsp" | "thinsp" => Some(" "),
+        "ndash" => Some("–"),
+        "mdash" => Some("—"),
+        "hellip" => Some("…"),
+        "lsquo" => Some("‘"),
+        "rsquo" => Some("’"),
+        "ldquo" => Some("“"),
+        "rdquo" => Some("”"),
+        "laquo" => Some("«"),
+        "raquo" => Some("»"),
+        "bull" => Some("•"),
+        "middot" => Some("·"),
+        "copy" => Some("©"),
+        "reg" => Some("®"),
+        "trade" => Some("™"),
+        "deg" => Some("°"),
+        "times" => Some("×"),
+        "euro" => Some("€"),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_enex() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-export SYSTEM "http://xml.evernote.com/pub/evernote-export4.dtd">
+<en-export export-date="20240310T120000Z" application="Evernote">
+  <note>
+    <title>Fish &amp; chips</title>
+    <created>20240301T090000Z</created>
+    <updated>20240302T103000Z</updated>
+    <tag>food</tag>
+    <tag>recipes</tag>
+    <note-attributes><author>me</author></note-attributes>
+    <content><![CDATA[<?xml version="1.0" encoding="UTF-8"?><en-note>Hi</en-note>]]></content>
+    <resource>
+      <data encoding="base64">
+aGVsbG8g
+d29ybGQ=
+      </data>
+      <mime>text/plain</mime>
+      <resource-attributes><file-name>hello.txt</file-name></resource-attributes>
+    </resource>
+  </note>
+  <note><title>Empty</title><content><![CDATA[<en-note/>]]></content></note>
+</en-export>"#;
+        let notes = parse_enex(xml).unwrap();
+        assert_eq!(notes.len(), 2);
+
+        let note = &notes[0];
+        assert_eq!(note.title, "Fish & chips");
+        assert_eq!(
+            note.created,
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            note.updated,
+            Some(Utc.with_ymd_and_hms(2024, 3, 2, 10, 30, 0).unwrap())
+        );
+        assert_eq!(note.tags, vec!["food", "recipes"]);
+        assert!(note.content.ends_with("<en-note>Hi</en-note>"));
+        assert_eq!(note.resources.len(), 1);
+        assert_eq!(note.resources[0].mime_type, "text/plain");
+        assert_eq!(note.resources[0].filename.as_deref(), Some("hello.txt"));
+
+        let (data, hash) = note.resources[0].decode().unwrap();
+        assert_eq!(data, b"hello world");
+        assert_eq!(hash, "5eb63bbbe01eeed093cb22bb8f5acdc3");
+
+        assert_eq!(notes[1].created, None);
+        assert!(notes[1].resources.is_empty());
+
+        assert!(matches!(
+            parse_enex("<html><body/></html>"),
+            Err(AppError::Import(_))
+        ));
+        assert!(matches!(
+            parse_enex("<en-export><note><title>x</title>"),
+            Err(AppError::Import(_))
+        ));
+        let bad = EnexResource {
+            data: "not base64!".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(bad.decode(), Err(AppError::Import(_))));
+    }
+
+    #[test]
+    fn test_enml_to_delta() {
+        let enml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd">
+<en-note>
+  <h1>Trip</h1>
+  <div>Pack <b>light</b>,   <span style="font-style: italic; text-decoration: underline">really</span>&nbsp;&mdash; <a href="https://example.com">map</a><br/></div>
+  <div><br/></div>
+  <div><en-todo checked="true"/>Passport</div>
+  <div><en-todo/>Tickets</div>
+  <ul><li>Socks<ul><li>Wool</li></ul></li></ul>
+  <ol><li><div>First</div></li></ol>
+  <ul style="--en-todo:true;"><li style="--en-checked:true;">Done</li><li style="--en-checked:false;">Open</li></ul>
+  <blockquote>Quote</blockquote>
+  <div style="-en-codeblock:true;"><div>fn main() {</div><div>    run();</div><div>}</div></div>
+  <div><en-media hash="5EB63BBBE01EEED093CB22BB8F5ACDC3" type="image/png"/></div>
+  <table><tr><td>a</td><td>b</td></tr></table>
+  <p>Unclosed <i>tags</b> still work</p>
+</en-note>"#;
+        let ops = enml_to_delta(enml).unwrap();
+        assert_eq!(
+            Value::Array(ops),
+            json!([
+                { "insert": "Trip" },
+                { "insert": "\n", "attributes": { "header": 1 } },
+                { "insert": "Pack " },
+                { "insert": "light", "attributes": { "bold": true } },
+                { "insert": ", " },
+                { "insert": "really", "attributes": { "italic": true, "underline": true } },
+                { "insert": "\u{a0}— " },
+                { "insert": "map", "attributes": { "link": "https://example.com" } },
+                { "insert": "\n\nPassport" },
+                { "insert": "\n", "attributes": { "list": "checked" } },
+                { "insert": "Tickets" },
+                { "insert": "\n", "attributes": { "list": "unchecked" } },
+                { "insert": "Socks" },
+                { "insert": "\n", "attributes": { "list": "bullet" } },
+                { "insert": "Wool" },
+                { "insert": "\n", "attributes": { "list": "bullet", "indent": 1 } },
+                { "insert": "First" },
+                { "insert": "\n", "attributes": { "list": "ordered" } },
+                { "insert": "Done" },
+                { "insert": "\n", "attributes": { "list": "checked" } },
+                { "insert": "Open" },
+                { "insert": "\n", "attributes": { "list": "unchecked" } },
+                { "insert": "Quote" },
+                { "insert": "\n", "attributes": { "blockquote": true } },
+                { "insert": "fn main() {" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": "    run();" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": "}" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": { "en-media": {
+                    "hash": "5EB63BBBE01EEED093CB22BB8F5ACDC3",
+                    "type": "image/png",
+                } } },
+                { "insert": "\na | b\nUnclosed " },
+                { "insert": "tags", "attributes": { "italic": true } },
+                { "insert": " still work\n" }
+            ])
+        );
+
+        assert_eq!(
+            enml_to_delta("<en-note/>").unwrap(),
+            vec![json!({ "insert": "\n" })]
+        );
+        assert!(matches!(
+            enml_to_delta("<en-note><div"),
+            Err(AppError::Import(_))
+        ));
+    }
+}
@@ -0,0 +1,928 @@
+//! Markdown and HTML export
+//!
+//! Markdown export writes notes to a directory tree (see `markdown`): one
+//! folder per collection, with notes outside any collection at the top.
+//! Files a note embeds or has attached are copied out of the blob store into
+//! an `attachments` folder next to it and linked relatively. YAML front
+//! matter carries the note's ids, timestamps, collection and tags.
+//!
+//! HTML export writes a single self-contained page (see `html`) holding one
+//! note or every note of a collection, with embedded images inlined as data
+//! URIs. Other attachments are listed by name. The page carries print
+//! styles, one note per printed page, so it can be saved as a PDF.
+//!
+//! Bundle export writes a portable, unencrypted ZIP of the notes as JSON
+//! (see `bundle`) with the blobs they use, for `ImportService::import_bundle`
+//! or scripts to read.
+//!
+//! Existing files are never overwritten; clashing names get a " (2)" suffix.
+
+use crate::config;
+use crate::database::{Collection, Note, Repository, Tag};
+use crate::error::{AppError, Result};
+use crate::services::attachments;
+use crate::services::bundle::{self, BundleNote, BundleWriter};
+use crate::services::html;
+use crate::services::markdown::{self, EmbeddedFile};
+use crate::storage::BlobStore;
+use chrono::SecondsFormat;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Which notes to export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportScope {
+    /// One note, written straight into the export directory
+    Note(String),
+    /// The notes of one collection
+    Collection(String),
+    /// Every note
+    All,
+}
+
+/// Outcome of a Markdown export
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarkdownExportReport {
+    /// Directory the notes were written to
+    pub directory: String,
+    pub notes_exported: usize,
+    /// Files copied out of the blob store
+    pub attachments_exported: usize,
+    /// Problems that didn't stop the export (e.g. a missing blob)
+    pub errors: Vec<String>,
+}
+
+/// Outcome of an HTML export
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HtmlExportReport {
+    /// The page written
+    pub path: String,
+    pub notes_exported: usize,
+    /// Images inlined as data URIs
+    pub images_embedded: usize,
+    /// Problems that didn't stop the export (e.g. a missing blob)
+    pub errors: Vec<String>,
+}
+
+/// Outcome of a bundle export
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BundleExportReport {
+    /// The bundle written
+    pub path: String,
+    pub notes_exported: usize,
+    /// Blobs (attachment and image contents) written to `blobs/`
+    pub blobs_exported: usize,
+    /// Problems that didn't stop the export (e.g. a missing blob)
+    pub errors: Vec<String>,
+}
+
+/// Service for exporting notes
+#[derive(Clone)]
+pub struct ExportService {
+    repo: Repository,
+    blob_store: BlobStore,
+}
+
+impl ExportService {
+    pub fn new(repo: Repository, blob_store: BlobStore) -> Self {
+        Self { repo, blob_store }
+    }
+
+    /// Export notes as Markdown files under `directory` (an absolute path),
+    /// creating it if needed
+    pub async fn export_markdown(
+        &self,
+        scope: ExportScope,
+        directory: &Path,
+    ) -> Result<MarkdownExportReport> {
+        check_directory(directory)?;
+        let notes = self.notes_in(&scope).await?;
+
+        let collections: HashMap<String, Collection> = self
+            .repo
+            .list_collections()
+            .await?
+            .into_iter()
+            .map(|collection| (collection.id.clone(), collection))
+            .collect();
+
+        tracing::info!(
+            "Exporting {} notes as Markdown to {:?}",
+            notes.len(),
+            directory
+        );
+        fs::create_dir_all(directory).await?;
+
+        let mut report = MarkdownExportReport {
+            directory: directory.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let mut root = ExportFolder::new(directory.to_path_buf());
+        let mut folders: HashMap<String, ExportFolder> = HashMap::new();
+
+        for note in &notes {
+            let collection = note
+                .collection_id
+                .as_ref()
+                .and_then(|id| collections.get(id));
+
+            let folder = match collection {
+                Some(collection) if !matches!(scope, ExportScope::Note(_)) => {
+                    match folders.entry(collection.id.clone()) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => {
+                            let name = root.claim(&safe_file_name(&collection.name), "");
+                            let path = directory.join(name);
+                            fs::create_dir_all(&path).await?;
+                            entry.insert(ExportFolder::new(path))
+                        }
+                    }
+                }
+                _ => &mut root,
+            };
+
+            self.export_note(note, collection, folder, &mut report)
+                .await?;
+        }
+
+        tracing::info!(
+            "Exported {} notes and {} attachments ({} problems)",
+            report.notes_exported,
+            report.attachments_exported,
+            report.errors.len()
+        );
+        Ok(report)
+    }
+
+    /// Export notes as one self-contained HTML page under `directory` (an
+    /// absolute path), creating it if needed
+    pub async fn export_html(
+        &self,
+        scope: ExportScope,
+        directory: &Path,
+    ) -> Result<HtmlExportReport> {
+        check_directory(directory)?;
+        let notes = self.notes_in(&scope).await?;
+        let title = self.scope_title(&scope, &notes).await?;
+
+        tracing::info!("Exporting {} notes as HTML to {:?}", notes.len(), directory);
+
+        let mut report = HtmlExportReport::default();
+        let mut body = String::new();
+        if !matches!(scope, ExportScope::Note(_)) {
+            body.push_str(&format!("<h1>{}</h1>\n", html::escape(&title)));
+        }
+        for note in &notes {
+            body.push_str(&self.note_article(note, &mut report).await?);
+            report.notes_exported += 1;
+        }
+
+        fs::create_dir_all(directory).await?;
+        let name = unique_name(
+            &mut HashSet::new(),
+            directory,
+            &safe_file_name(&title),
+            "html",
+        );
+        let path = directory.join(name);
+        fs::write(&path, html::html_document(&title, &body)).await?;
+        report.path = path.to_string_lossy().to_string();
+
+        tracing::info!(
+            "Exported {} notes with {} images to {:?} ({} problems)",
+            report.notes_exported,
+            report.images_embedded,
+            path,
+            report.errors.len()
+        );
+        Ok(report)
+    }
+
+    /// Render one note as a self-contained HTML page, e.g. to send by email
+    pub async fn note_html(&self, note_id: &str) -> Result<String> {
+        let note = self.repo.get_note(note_id).await?;
+        let mut report = HtmlExportReport::default();
+        let article = self.note_article(&note, &mut report).await?;
+        for error in &report.errors {
+            tracing::warn!("Rendering note {} as HTML: {}", note_id, error);
+        }
+        Ok(html::html_document(display_title(&note.title), &article))
+    }
+
+    /// Export notes as a portable bundle (see `bundle`) under `directory`
+    /// (an absolute path), creating it if needed
+    pub async fn export_bundle(
+        &self,
+        scope: ExportScope,
+        directory: &Path,
+    ) -> Result<BundleExportReport> {
+        check_directory(directory)?;
+        let notes = self.notes_in(&scope).await?;
+        let title = self.scope_title(&scope, &notes).await?;
+
+        tracing::info!(
+            "Exporting {} notes as a bundle to {:?}",
+            notes.len(),
+            directory
+        );
+
+        let mut bundle_notes = Vec::new();
+        let mut collections: Vec<Collection> = match scope {
+            ExportScope::All => self.repo.list_collections().await?,
+            _ => Vec::new(),
+        };
+        let mut reminders = Vec::new();
+        let mut attachments = Vec::new();
+        let mut hashes = Vec::new();
+        for note in notes {
+            let Some(snapshot) = self.repo.get_note_snapshot(&note.id).await? else {
+                continue;
+            };
+            if let Some(collection) = snapshot.collection {
+                if !collections.iter().any(|c| c.id == collection.id) {
+                    collections.push(collection);
+                }
+            }
+            hashes.extend(snapshot.attachments.iter().map(|a| a.blob_hash.clone()));
+            hashes.extend(Repository::extract_blob_hashes_from_delta(
+                &snapshot.note.content_json,
+            ));
+            reminders.extend(snapshot.reminders);
+            attachments.extend(snapshot.attachments);
+            bundle_notes.push(BundleNote {
+                note: snapshot.note,
+                tags: snapshot.tags,
+                journal_date: snapshot.journal_date,
+            });
+        }
+
+        fs::create_dir_all(directory).await?;
+        let name = unique_name(
+            &mut HashSet::new(),
+            directory,
+            &safe_file_name(&title),
+            "zip",
+        );
+        let path = directory.join(name);
+        let mut report = BundleExportReport {
+            path: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let mut writer = BundleWriter::create(&path)?;
+        writer.write_notes(&bundle_notes)?;
+        writer.write_json(bundle::COLLECTIONS_FILE, &collections)?;
+        writer.write_json(bundle::REMINDERS_FILE, &reminders)?;
+        writer.write_json(bundle::ATTACHMENTS_FILE, &attachments)?;
+
+        let mut written = HashSet::new();
+        for hash in hashes.into_iter().filter(|hash| bundle::is_blob_hash(hash)) {
+            if !written.insert(hash.clone()) {
+                continue;
+            }
+            match self.blob_store.read(&hash).await {
+                Ok(data) => writer.write_blob(&hash, &data)?,
+                Err(e) => report
+                    .errors
+                    .push(format!("could not export blob {}: {}", hash, e)),
+            }
+        }
+        let manifest = writer.finish()?;
+        report.notes_exported = manifest.note_count;
+        report.blobs_exported = manifest.blob_count;
+
+        tracing::info!(
+            "Exported {} notes and {} blobs to {:?} ({} problems)",
+            report.notes_exported,
+            report.blobs_exported,
+            path,
+            report.errors.len()
+        );
+        Ok(report)
+    }
+
+    /// What an export of `scope` is called: the note's title, the
+    /// collection's name or "All notes"
+    async fn scope_title(&self, scope: &ExportScope, notes: &[Note]) -> Result<String> {
+        Ok(match scope {
+            ExportScope::Note(_) => notes
+                .first()
+                .map(|note| display_title(&note.title).to_string())
+                .unwrap_or_default(),
+            ExportScope::Collection(id) => self.repo.get_collection(id).await?.name,
+            ExportScope::All => "All notes".to_string(),
+        })
+    }
+
+    /// The notes in `scope`, oldest first so the names notes get are stable
+    /// between exports
+    async fn notes_in(&self, scope: &ExportScope) -> Result<Vec<Note>> {
+        let mut notes = match scope {
+            ExportScope::Note(id) => vec![self.repo.get_note(id).await?],
+            ExportScope::Collection(id) => {
+                self.repo.get_collection(id).await?;
+                self.repo
+                    .list_notes()
+                    .await?
+                    .into_iter()
+                    .filter(|note| note.collection_id.as_deref() == Some(id.as_str()))
+                    .collect()
+            }
+            ExportScope::All => self.repo.list_notes().await?,
+        };
+        notes.sort_by_key(|note| note.created_at);
+        Ok(notes)
+    }
+
+    /// A note as an `<article>`: its title, last update and tags, its
+    /// content with images inlined, and the attachments it doesn't embed
+    async fn note_article(&self, note: &Note, report: &mut HtmlExportReport) -> Result<String> {
+        let tags = self.repo.list_tags_for_note(&note.id).await?;
+        let attachments = self.repo.list_attachments(&note.id).await?;
+
+        // The renderer doesn't read blobs, so find the images first
+        let mut linked = HashSet::new();
+        let mut images = Vec::new();
+        html::delta_to_html(&note.content_json, &mut |file| {
+            linked.insert(file.blob_hash.to_string());
+            if attachments::is_allowed_image_mime(file.mime_type) {
+                images.push((file.blob_hash.to_string(), file.mime_type.to_string()));
+            }
+            None
+        });
+
+        let mut sources: HashMap<String, String> = HashMap::new();
+        for (blob_hash, mime_type) in images {
+            if sources.contains_key(&blob_hash) {
+                continue;
+            }
+            match self.blob_store.read(&blob_hash).await {
+                Ok(data) => {
+                    sources.insert(blob_hash, html::data_uri(&mime_type, &data));
+                    report.images_embedded += 1;
+                }
+                Err(e) => report.errors.push(format!(
+                    "\"{}\": could not embed image {}: {}",
+                    note.title, blob_hash, e
+                )),
+            }
+        }
+        let content = html::delta_to_html(&note.content_json, &mut |file| {
+            sources.get(file.blob_hash).cloned()
+        });
+
+        let mut meta = format!(
+            "<time datetime=\"{}\">{}</time>",
+            note.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            note.updated_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        if !tags.is_empty() {
+            let names: Vec<String> = tags.iter().map(|tag| html::escape(&tag.name)).collect();
+            meta.push_str(&format!(" · {}", names.join(", ")));
+        }
+
+        let mut article = format!(
+            "<article class=\"note\">\n<header>\n<h1 class=\"note-title\">{}</h1>\n\
+             <p class=\"note-meta\">{}</p>\n</header>\n{}",
+            html::escape(display_title(&note.title)),
+            meta,
+            content
+        );
+        // Attachments not embedded in the text are listed at the end
+        let unlinked: Vec<String> = attachments
+            .iter()
+            .filter(|attachment| !linked.contains(&attachment.blob_hash))
+            .map(|attachment| {
+                format!(
+                    "<li class=\"attachment\">{}</li>\n",
+                    html::escape(&attachment.filename)
+                )
+            })
+            .collect();
+        if !unlinked.is_empty() {
+            article.push_str("<h2>Attachments</h2>\n<ul>\n");
+            article.push_str(&unlinked.concat());
+            article.push_str("</ul>\n");
+        }
+        article.push_str("</article>\n");
+        Ok(article)
+    }
+
+    async fn export_note(
+        &self,
+        note: &Note,
+        collection: Option<&Collection>,
+        folder: &mut ExportFolder,
+        report: &mut MarkdownExportReport,
+    ) -> Result<()> {
+        let tags = self.repo.list_tags_for_note(&note.id).await?;
+        let attachments = self.repo.list_attachments(&note.id).await?;
+
+        let mut linked = HashSet::new();
+        let mut body = markdown::delta_to_markdown(&note.content_json, &mut |file| {
+            linked.insert(file.blob_hash.to_string());
+            folder.link(file)
+        });
+
+        // Attachments not embedded in the text are listed at the end
+        let unlinked: Vec<String> = attachments
+            .iter()
+            .filter(|attachment| !linked.contains(&attachment.blob_hash))
+            .map(|attachment| {
+                let link = folder.link(EmbeddedFile {
+                    blob_hash: &attachment.blob_hash,
+                    filename: &attachment.filename,
+                    mime_type: &attachment.mime_type,
+                });
+                format!(
+                    "- [{}]({})",
+                    markdown::escape(&attachment.filename),
+                    markdown::link_destination(&link)
+                )
+            })
+            .collect();
+        if !unlinked.is_empty() {
+            if !body.is_empty() {
+                body.push('\n');
+            }
+            body.push_str("## Attachments\n\n");
+            body.push_str(&unlinked.join("\n"));
+            body.push('\n');
+        }
+
+        let pending = std::mem::take(&mut folder.pending);
+        if !pending.is_empty() {
+            fs::create_dir_all(folder.path.join(config::EXPORT_ATTACHMENTS_DIR)).await?;
+        }
+        for (blob_hash, path) in pending {
+            let copied = match self.blob_store.read(&blob_hash).await {
+                Ok(data) => fs::write(&path, data).await.map_err(AppError::from),
+                Err(e) => Err(e),
+            };
+            match copied {
+                Ok(()) => report.attachments_exported += 1,
+                Err(e) => report.errors.push(format!(
+                    "\"{}\": could not export {}: {}",
+                    note.title,
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    e
+                )),
+            }
+        }
+
+        let name = folder.claim(&safe_file_name(&note.title), "md");
+        let contents = format!("{}{}", front_matter(note, collection, &tags), body);
+        fs::write(folder.path.join(name), contents).await?;
+        report.notes_exported += 1;
+        Ok(())
+    }
+}
+
+/// Fail unless `directory` is an absolute path
+fn check_directory(directory: &Path) -> Result<()> {
+    if directory.is_absolute() {
+        Ok(())
+    } else {
+        Err(AppError::Export(format!(
+            "export directory must be an absolute path: {:?}",
+            directory
+        )))
+    }
+}
+
+/// A note's title, or "Untitled" for a blank one
+fn display_title(title: &str) -> &str {
+    if title.trim().is_empty() {
+        "Untitled"
+    } else {
+        title
+    }
+}
+
+/// A folder being exported to: the names taken in it and the files copied
+/// to its `attachments` folder
+struct ExportFolder {
+    path: PathBuf,
+    /// Lowercased, since Windows and macOS file names ignore case
+    names: HashSet<String>,
+    attachment_names: HashSet<String>,
+    /// Relative link of each (blob, file name) already copied or pending
+    links: HashMap<(String, String), String>,
+    /// Blobs to copy, with their target paths
+    pending: Vec<(String, PathBuf)>,
+}
+
+impl ExportFolder {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            names: HashSet::new(),
+            attachment_names: HashSet::new(),
+            links: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Pick an unused name in this folder for a file or folder
+    fn claim(&mut self, stem: &str, extension: &str) -> String {
+        unique_name(&mut self.names, &self.path, stem, extension)
+    }
+
+    /// The relative link to an embedded file, queueing its copy the first
+    /// time it is seen
+    fn link(&mut self, file: EmbeddedFile) -> String {
+        let key = (file.blob_hash.to_string(), file.filename.to_string());
+        if let Some(link) = self.links.get(&key) {
+            return link.clone();
+        }
+
+        let dir = self.path.join(config::EXPORT_ATTACHMENTS_DIR);
+        let filename = safe_file_name(file.filename);
+        let path = Path::new(&filename);
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = path.extension().unwrap_or_default().to_string_lossy();
+        let name = unique_name(&mut self.attachment_names, &dir, &stem, &extension);
+
+        let link = format!("{}/{}", config::EXPORT_ATTACHMENTS_DIR, name);
+        self.pending
+            .push((file.blob_hash.to_string(), dir.join(&name)));
+        self.links.insert(key, link.clone());
+        link
+    }
+}
+
+/// `stem.extension`, or `stem (2).extension` and so on if that's taken in
+/// `taken` or on disk
+fn unique_name(taken: &mut HashSet<String>, dir: &Path, stem: &str, extension: &str) -> String {
+    let name_for = |n: usize| {
+        let stem = match n {
+            1 => stem.to_string(),
+            n => format!("{} ({})", stem, n),
+        };
+        match extension {
+            "" => stem,
+            extension => format!("{}.{}", stem, extension),
+        }
+    };
+
+    let mut n = 1;
+    loop {
+        let name = name_for(n);
+        if !taken.contains(&name.to_lowercase()) && !dir.join(&name).exists() {
+            taken.insert(name.to_lowercase());
+            return name;
+        }
+        n += 1;
+    }
+}
+
+/// Make a note title or collection name safe as a file name on every
+/// platform
+fn safe_file_name(name: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+        "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+    ];
+
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || r#"<>:"/\|?*"#.contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(config::MAX_EXPORT_FILE_NAME_LENGTH)
+        .collect();
+    // Windows drops trailing dots and spaces; leading dots hide files
+    let cleaned = cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace());
+
+    let stem = cleaned.split('.').next().unwrap_or_default();
+    if cleaned.is_empty() {
+        "Untitled".to_string()
+    } else if RESERVED.contains(&stem.to_lowercase().as_str()) {
+        format!("_{}", cleaned)
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// YAML front matter for a note. Strings are written as JSON strings, which
+/// are valid YAML.
+fn front_matter(note: &Note, collection: Option<&Collection>, tags: &[Tag]) -> String {
+    let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+    let time = |at: &chrono::DateTime<chrono::Utc>| at.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let mut yaml = String::from("---\n");
+    yaml.push_str(&format!("id: {}\n", quote(&note.id)));
+    yaml.push_str(&format!("title: {}\n", quote(&note.title)));
+    yaml.push_str(&format!("created_at: {}\n", time(&note.created_at)));
+    yaml.push_str(&format!("updated_at: {}\n", time(&note.updated_at)));
+    if let Some(collection) = collection {
+        yaml.push_str(&format!("collection: {}\n", quote(&collection.name)));
+        yaml.push_str(&format!("collection_id: {}\n", quote(&collection.id)));
+    }
+    let tag_names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+    yaml.push_str(&format!(
+        "tags: {}\n",
+        serde_json::to_string(&tag_names).unwrap_or_default()
+    ));
+    yaml.push_str("---\n\n");
+    yaml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{initialize_database, CreateCollectionRequest, CreateNoteRequest};
+    use serde_json::json;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tempfile::TempDir;
+
+    async fn create_test_service() -> (ExportService, TempDir) {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let blob_store = BlobStore::new(temp_dir.path().join("blobs"));
+        blob_store.initialize().await.unwrap();
+
+        (
+            ExportService::new(Repository::new(pool), blob_store),
+            temp_dir,
+        )
+    }
+
+    async fn create_note(
+        service: &ExportService,
+        title: &str,
+        ops: serde_json::Value,
+        collection_id: Option<String>,
+    ) -> Note {
+        service
+            .repo
+            .create_note(CreateNoteRequest {
+                title: title.to_string(),
+                content_json: json!({ "ops": ops }).to_string(),
+                collection_id,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_export_markdown_tree() {
+        let (service, temp) = create_test_service().await;
+        let out = temp.path().join("export");
+
+        let work = service
+            .repo
+            .create_collection(CreateCollectionRequest {
+                name: "Work: 2026".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+
+        let image = service.blob_store.write(b"png bytes").await.unwrap();
+        let pdf = service.blob_store.write(b"pdf bytes").await.unwrap();
+        let plan = create_note(
+            &service,
+            "Plan",
+            json!([
+                { "insert": "Plan" },
+                { "insert": "\n", "attributes": { "header": 1 } },
+                { "insert": { "attachment-image": {
+                    "blobHash": image, "mimeType": "image/png", "filename": "chart.png"
+                } } },
+                { "insert": "\n" }
+            ]),
+            Some(work.id.clone()),
+        )
+        .await;
+        service
+            .repo
+            .create_attachment(&plan.id, &image, "chart.png", "image/png", 9)
+            .await
+            .unwrap();
+        service
+            .repo
+            .create_attachment(&plan.id, &pdf, "spec sheet.pdf", "application/pdf", 9)
+            .await
+            .unwrap();
+        let tag = service.repo.create_tag("q4", None).await.unwrap();
+        service
+            .repo
+            .add_tag_to_note(&plan.id, &tag.id)
+            .await
+            .unwrap();
+
+        create_note(
+            &service,
+            "Plan",
+            json!([{ "insert": "Copy\n" }]),
+            Some(work.id.clone()),
+        )
+        .await;
+        create_note(&service, "", json!([{ "insert": "Loose\n" }]), None).await;
+
+        let report = service
+            .export_markdown(ExportScope::All, &out)
+            .await
+            .unwrap();
+        assert_eq!(report.notes_exported, 3);
+        assert_eq!(report.attachments_exported, 2);
+        assert!(report.errors.is_empty());
+
+        let folder = out.join("Work_ 2026");
+        let markdown = std::fs::read_to_string(folder.join("Plan.md")).unwrap();
+        assert_eq!(
+            markdown,
+            format!(
+                "---\nid: \"{}\"\ntitle: \"Plan\"\ncreated_at: {}\nupdated_at: {}\n\
+                 collection: \"Work: 2026\"\ncollection_id: \"{}\"\ntags: [\"q4\"]\n---\n\n\
+                 # Plan\n\n![chart.png](attachments/chart.png)\n\n\
+                 ## Attachments\n\n- [spec sheet.pdf](attachments/spec%20sheet.pdf)\n",
+                plan.id,
+                plan.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                plan.updated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                work.id
+            )
+        );
+        assert_eq!(
+            std::fs::read(folder.join("attachments/chart.png")).unwrap(),
+            b"png bytes"
+        );
+        assert!(folder.join("attachments/spec sheet.pdf").exists());
+        assert!(folder.join("Plan (2).md").exists());
+        assert!(out.join("Untitled.md").exists());
+
+        // Exporting again never overwrites; a single note goes to the top
+        let report = service
+            .export_markdown(ExportScope::Note(plan.id.clone()), &out)
+            .await
+            .unwrap();
+        assert_eq!(report.notes_exported, 1);
+        let again = std::fs::read_to_string(out.join("Plan.md")).unwrap();
+        assert!(again.contains("![chart.png](attachments/chart.png)"));
+        assert!(out.join("attachments/chart.png").exists());
+    }
+
+    #[tokio::test]
+    async fn test_export_reports_missing_blobs() {
+        let (service, temp) = create_test_service().await;
+        let note = create_note(
+            &service,
+            "Lost",
+            json!([
+                { "insert": { "attachment-file": {
+                    "blobHash": "0".repeat(64), "mimeType": "text/plain", "filename": "gone.txt"
+                } } },
+                { "insert": "\n" }
+            ]),
+            None,
+        )
+        .await;
+
+        let report = service
+            .export_markdown(ExportScope::Note(note.id), temp.path())
+            .await
+            .unwrap();
+        assert_eq!(report.notes_exported, 1);
+        assert_eq!(report.attachments_exported, 0);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("gone.txt"));
+
+        assert!(service
+            .export_markdown(ExportScope::Collection("missing".to_string()), temp.path())
+            .await
+            .is_err());
+        assert!(matches!(
+            service
+                .export_markdown(ExportScope::All, Path::new("relative/dir"))
+                .await,
+            Err(AppError::Export(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_export_html() {
+        let (service, temp) = create_test_service().await;
+        let out = temp.path().join("export");
+
+        let trip = service
+            .repo
+            .create_collection(CreateCollectionRequest {
+                name: "Trip".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+
+        let image = service.blob_store.write(b"png").await.unwrap();
+        let pdf = service.blob_store.write(b"pdf bytes").await.unwrap();
+        let plan = create_note(
+            &service,
+            "Plan & route",
+            json!([
+                { "insert": "Day 1", "attributes": { "bold": true } },
+                { "insert": "\n" },
+                { "insert": { "attachment-image": {
+                    "blobHash": image, "mimeType": "image/png", "filename": "map.png"
+                } } },
+                { "insert": { "attachment-image": {
+                    "blobHash": "0".repeat(64), "mimeType": "image/png", "filename": "lost.png"
+                } } },
+                { "insert": "\n" }
+            ]),
+            Some(trip.id.clone()),
+        )
+        .await;
+        service
+            .repo
+            .create_attachment(&plan.id, &image, "map.png", "image/png", 3)
+            .await
+            .unwrap();
+        service
+            .repo
+            .create_attachment(&plan.id, &pdf, "tickets.pdf", "application/pdf", 9)
+            .await
+            .unwrap();
+        create_note(
+            &service,
+            "",
+            json!([{ "insert": "Pack\n" }]),
+            Some(trip.id.clone()),
+        )
+        .await;
+
+        let report = service
+            .export_html(ExportScope::Collection(trip.id.clone()), &out)
+            .await
+            .unwrap();
+        assert_eq!(report.notes_exported, 2);
+        assert_eq!(report.images_embedded, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains(&"0".repeat(64)));
+        assert_eq!(Path::new(&report.path), out.join("Trip.html"));
+
+        let page = std::fs::read_to_string(&report.path).unwrap();
+        assert!(page.contains("<title>Trip</title>"));
+        assert!(page.contains("@media print"));
+        assert!(page.contains("<h1 class=\"note-title\">Plan &amp; route</h1>"));
+        assert!(page.contains("<p><strong>Day 1</strong></p>"));
+        assert!(page.contains("<img src=\"data:image/png;base64,cG5n\" alt=\"map.png\">"));
+        assert!(page.contains("<span class=\"attachment\">lost.png</span>"));
+        assert!(page.contains("<li class=\"attachment\">tickets.pdf</li>"));
+        assert!(!page.contains("<li class=\"attachment\">map.png</li>"));
+        assert!(page.contains("<h1 class=\"note-title\">Untitled</h1>"));
+        assert_eq!(page.matches("<article class=\"note\">").count(), 2);
+
+        // Exporting again never overwrites
+        let report = service
+            .export_html(ExportScope::Note(plan.id.clone()), &out)
+            .await
+            .unwrap();
+        assert_eq!(Path::new(&report.path), out.join("Plan & route.html"));
+        let report = service
+            .export_html(ExportScope::Collection(trip.id), &out)
+            .await
+            .unwrap();
+        assert_eq!(Path::new(&report.path), out.join("Trip (2).html"));
+
+        let page = service.note_html(&plan.id).await.unwrap();
+        assert!(page.contains("<title>Plan &amp; route</title>"));
+        assert!(page.contains("data:image/png;base64,cG5n"));
+        assert!(matches!(
+            service
+                .export_html(ExportScope::All, Path::new("relative"))
+                .await,
+            Err(AppError::Export(_))
+        ));
+    }
+
+    #[test]
+    fn test_safe_file_name() {
+        assert_eq!(safe_file_name("Meeting: 10/16?"), "Meeting_ 10_16_");
+        assert_eq!(safe_file_name("  ..hidden.  "), "hidden");
+        assert_eq!(safe_file_name("..."), "Untitled");
+        assert_eq!(safe_file_name("CON"), "_CON");
+        assert_eq!(safe_file_name("nul.txt"), "_nul.txt");
+        assert_eq!(safe_file_name("Résumé ☕"), "Résumé ☕");
+        assert_eq!(
+            safe_file_name(&"x".repeat(500)).len(),
+            config::MAX_EXPORT_FILE_NAME_LENGTH
+        );
+    }
+}
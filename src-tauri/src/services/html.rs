@@ -0,0 +1,536 @@
+//! HTML rendering
+//!
+//! `delta_to_html` renders note content (Quill Delta JSON) as semantic HTML:
+//! paragraphs, headers, bold, italic, underline, strikethrough, inline
+//! code, sub- and superscript, colors, links, bullet, ordered and check
+//! lists (nested by `indent`), blockquotes, code blocks, images and
+//! attachment embeds. Links and image sources with schemes other than
+//! `http`, `https`, `mailto` and `tel` are dropped, so the output is safe to
+//! open or send as it is.
+//!
+//! `html_document` wraps rendered content in a self-contained page with
+//! `STYLESHEET`, which includes print rules so the page can be printed or
+//! saved as a PDF. Nothing here touches the database or blob store: callers
+//! decide where embedded files point, e.g. to data URIs for export or
+//! sharing a note by email.
+
+use crate::services::markdown::{
+    is_set, split_lines, Block, EmbeddedFile, Insert, ListKind, Piece,
+};
+use base64::Engine;
+use serde_json::{Map, Value};
+
+/// Styles for exported pages, for screen and print
+pub const STYLESHEET: &str = "\
+body { font-family: -apple-system, BlinkMacSystemFont, \"Segoe UI\", Roboto, sans-serif; \
+line-height: 1.5; color: #1f2328; max-width: 48em; margin: 2em auto; padding: 0 1em; }
+h1, h2, h3, h4, h5, h6 { line-height: 1.25; margin: 1.2em 0 0.5em; }
+p { margin: 0 0 0.6em; }
+a { color: #0969da; }
+img { max-width: 100%; height: auto; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, Consolas, monospace; font-size: 0.9em; \
+background: #f6f8fa; padding: 0.1em 0.3em; border-radius: 3px; }
+pre { background: #f6f8fa; padding: 0.8em 1em; border-radius: 6px; overflow-x: auto; }
+pre code { background: none; padding: 0; white-space: pre-wrap; }
+blockquote { margin: 0 0 0.6em; padding: 0 1em; border-left: 4px solid #d0d7de; color: #57606a; }
+ul.checklist { list-style: none; padding-left: 1.2em; }
+ul.checklist > li > input { margin: 0 0.5em 0 -1.2em; }
+.note + .note { margin-top: 3em; padding-top: 2em; border-top: 1px solid #d0d7de; }
+.note-title { margin-top: 0; }
+.note-meta { color: #57606a; font-size: 0.9em; }
+.attachment { font-style: italic; }
+@media print {
+  @page { margin: 2cm; }
+  body { max-width: none; margin: 0; padding: 0; font-size: 11pt; color: #000; }
+  a { color: inherit; }
+  pre, code { background: none; }
+  pre { border: 1px solid #d0d7de; white-space: pre-wrap; }
+  pre, blockquote, img, tr { break-inside: avoid; }
+  h1, h2, h3, h4, h5, h6 { break-after: avoid; }
+  .note + .note { break-before: page; margin-top: 0; padding-top: 0; border-top: none; }
+}
+";
+
+/// Render Quill Delta JSON as an HTML fragment. `file_src` returns the URL
+/// an embedded file should point to, or None to show only its name.
+pub fn delta_to_html(
+    content_json: &str,
+    file_src: &mut dyn FnMut(EmbeddedFile) -> Option<String>,
+) -> String {
+    let Ok(json) = serde_json::from_str::<Value>(content_json) else {
+        return String::new();
+    };
+    let ops = json
+        .get("ops")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let lines = split_lines(ops);
+    let mut html = String::new();
+    // Open list tags, one per nesting level
+    let mut lists: Vec<&'static str> = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = &lines[i];
+        let block = Block::of(line.attrs);
+        if !matches!(block, Block::List { .. }) {
+            close_lists(&mut html, &mut lists, 0);
+        }
+
+        match block {
+            Block::Code(language) => {
+                let mut code = Vec::new();
+                while let Some(line) = lines.get(i).filter(|l| Block::of(l.attrs) == block) {
+                    code.push(line.plain_text());
+                    i += 1;
+                }
+                let class = language
+                    .map(|language| format!(" class=\"language-{}\"", escape(language)))
+                    .unwrap_or_default();
+                html.push_str(&format!(
+                    "<pre><code{}>{}</code></pre>\n",
+                    class,
+                    escape(&code.join("\n"))
+                ));
+                continue;
+            }
+            Block::Quote => {
+                html.push_str("<blockquote>\n");
+                while let Some(line) = lines.get(i).filter(|l| Block::of(l.attrs) == block) {
+                    html.push_str(&format!(
+                        "<p{}>{}</p>\n",
+                        block_style(line.attrs),
+                        render_line(&line.pieces, file_src)
+                    ));
+                    i += 1;
+                }
+                html.push_str("</blockquote>\n");
+                continue;
+            }
+            Block::Paragraph => html.push_str(&format!(
+                "<p{}>{}</p>\n",
+                block_style(line.attrs),
+                render_line(&line.pieces, file_src)
+            )),
+            Block::Header(level) => html.push_str(&format!(
+                "<h{}{}>{}</h{}>\n",
+                level,
+                block_style(line.attrs),
+                render_inline(&line.pieces, file_src),
+                level
+            )),
+            Block::List { kind, indent } => {
+                let tag = match kind {
+                    ListKind::Bullet => "<ul>",
+                    ListKind::Ordered => "<ol>",
+                    ListKind::Checked | ListKind::Unchecked => "<ul class=\"checklist\">",
+                };
+                close_lists(&mut html, &mut lists, indent + 1);
+                if lists.get(indent).is_some_and(|open| *open != tag) {
+                    close_lists(&mut html, &mut lists, indent);
+                }
+                if lists.len() == indent + 1 {
+                    html.push_str("</li>\n");
+                }
+                while lists.len() <= indent {
+                    html.push_str(tag);
+                    html.push('\n');
+                    lists.push(tag);
+                }
+
+                html.push_str("<li>");
+                match kind {
+                    ListKind::Checked => {
+                        html.push_str("<input type=\"checkbox\" checked disabled>")
+                    }
+                    ListKind::Unchecked => html.push_str("<input type=\"checkbox\" disabled>"),
+                    _ => {}
+                }
+                html.push_str(&render_inline(&line.pieces, file_src));
+            }
+        }
+        i += 1;
+    }
+
+    close_lists(&mut html, &mut lists, 0);
+    html
+}
+
+/// Wrap rendered content in a complete HTML page titled `title`
+pub fn html_document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLESHEET,
+        body
+    )
+}
+
+/// A `data:` URL holding `data`, for embedding a file in a page
+pub fn data_uri(mime_type: &str, data: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        mime_type,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    )
+}
+
+/// Escape text for use in HTML content and quoted attribute values
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Close open lists until `depth` are left
+fn close_lists(html: &mut String, lists: &mut Vec<&'static str>, depth: usize) {
+    while lists.len() > depth {
+        let closing = match lists.pop() {
+            Some("<ol>") => "</ol>",
+            _ => "</ul>",
+        };
+        html.push_str("</li>\n");
+        html.push_str(closing);
+        html.push('\n');
+    }
+}
+
+/// `style` attribute for a line's alignment and indent
+fn block_style(attrs: Option<&Map<String, Value>>) -> String {
+    let mut style = Vec::new();
+    if let Some(align) = attrs
+        .and_then(|a| a.get("align"))
+        .and_then(Value::as_str)
+        .filter(|align| matches!(*align, "center" | "right" | "justify"))
+    {
+        style.push(format!("text-align: {}", align));
+    }
+    if let Some(indent) = attrs
+        .and_then(|a| a.get("indent"))
+        .and_then(Value::as_u64)
+        .filter(|indent| *indent > 0)
+    {
+        style.push(format!("margin-left: {}em", indent * 3));
+    }
+
+    if style.is_empty() {
+        String::new()
+    } else {
+        format!(" style=\"{}\"", style.join("; "))
+    }
+}
+
+/// A paragraph's content; empty lines keep their height
+fn render_line(
+    pieces: &[Piece],
+    file_src: &mut dyn FnMut(EmbeddedFile) -> Option<String>,
+) -> String {
+    let html = render_inline(pieces, file_src);
+    if html.is_empty() {
+        "<br>".to_string()
+    } else {
+        html
+    }
+}
+
+fn render_inline(
+    pieces: &[Piece],
+    file_src: &mut dyn FnMut(EmbeddedFile) -> Option<String>,
+) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < pieces.len() {
+        match pieces[i].insert {
+            Insert::Text(_) => {
+                // Merge runs with the same formatting so tags aren't split
+                let attrs = pieces[i].attrs;
+                let mut text = String::new();
+                while let Some(Piece {
+                    insert: Insert::Text(part),
+                    ..
+                }) = pieces.get(i).filter(|p| p.attrs == attrs)
+                {
+                    text.push_str(part);
+                    i += 1;
+                }
+                out.push_str(&format_text(&text, attrs));
+                continue;
+            }
+            Insert::Embed(embed) => {
+                let rendered = render_embed(embed, file_src);
+                match pieces[i]
+                    .attrs
+                    .and_then(|a| a.get("link"))
+                    .and_then(Value::as_str)
+                    .and_then(safe_url)
+                {
+                    Some(url) if !rendered.is_empty() => {
+                        out.push_str(&format!("<a href=\"{}\">{}</a>", escape(&url), rendered))
+                    }
+                    _ => out.push_str(&rendered),
+                }
+            }
+        }
+        i += 1;
+    }
+
+    out
+}
+
+fn format_text(text: &str, attrs: Option<&Map<String, Value>>) -> String {
+    let flag = |name: &str| attrs.and_then(|a| a.get(name)).is_some_and(is_set);
+    let value = |name: &str| attrs.and_then(|a| a.get(name)).and_then(Value::as_str);
+
+    let mut html = escape(text);
+    if flag("code") {
+        html = format!("<code>{}</code>", html);
+    }
+    match value("script") {
+        Some("sub") => html = format!("<sub>{}</sub>", html),
+        Some("super") => html = format!("<sup>{}</sup>", html),
+        _ => {}
+    }
+    if flag("strike") {
+        html = format!("<s>{}</s>", html);
+    }
+    if flag("underline") {
+        html = format!("<u>{}</u>", html);
+    }
+    if flag("italic") {
+        html = format!("<em>{}</em>", html);
+    }
+    if flag("bold") {
+        html = format!("<strong>{}</strong>", html);
+    }
+
+    let mut style = Vec::new();
+    if let Some(color) = value("color").filter(|c| is_css_color(c)) {
+        style.push(format!("color: {}", color));
+    }
+    if let Some(background) = value("background").filter(|c| is_css_color(c)) {
+        style.push(format!("background-color: {}", background));
+    }
+    if !style.is_empty() {
+        html = format!("<span style=\"{}\">{}</span>", style.join("; "), html);
+    }
+
+    if let Some(url) = value("link").and_then(safe_url) {
+        html = format!("<a href=\"{}\">{}</a>", escape(&url), html);
+    }
+    html
+}
+
+fn render_embed(
+    embed: &Map<String, Value>,
+    file_src: &mut dyn FnMut(EmbeddedFile) -> Option<String>,
+) -> String {
+    for (name, value) in embed {
+        let field = |key: &str| value.get(key).and_then(Value::as_str).unwrap_or_default();
+        match name.as_str() {
+            "attachment-image" | "attachment-file" if !field("blobHash").is_empty() => {
+                let file = EmbeddedFile {
+                    blob_hash: field("blobHash"),
+                    filename: field("filename"),
+                    mime_type: field("mimeType"),
+                };
+                let filename = escape(file.filename);
+                return match file_src(file) {
+                    Some(src) if name == "attachment-image" => {
+                        format!("<img src=\"{}\" alt=\"{}\">", escape(&src), filename)
+                    }
+                    Some(href) => format!(
+                        "<a class=\"attachment\" href=\"{}\" download=\"{}\">{}</a>",
+                        escape(&href),
+                        filename,
+                        filename
+                    ),
+                    None => format!("<span class=\"attachment\">{}</span>", filename),
+                };
+            }
+            "image" => {
+                let src = value.as_str().and_then(|src| {
+                    if src.starts_with("data:image/") {
+                        Some(src.to_string())
+                    } else {
+                        safe_url(src)
+                    }
+                });
+                if let Some(src) = src {
+                    return format!("<img src=\"{}\" alt=\"\">", escape(&src));
+                }
+            }
+            "video" => {
+                if let Some(src) = value.as_str().and_then(safe_url) {
+                    return format!("<a href=\"{}\">{}</a>", escape(&src), escape(&src));
+                }
+            }
+            _ => {}
+        }
+    }
+    String::new()
+}
+
+/// The URL if it is relative or uses a scheme that is safe to follow,
+/// without the control characters (tab, newline, ...) browsers drop from
+/// URLs, so `java\tscript:` is seen as the scheme it becomes
+fn safe_url(url: &str) -> Option<String> {
+    let url: String = url.chars().filter(|c| !c.is_control()).collect();
+    let url = url.trim();
+    // Any ':' before the first '/', '?' or '#' ends a scheme
+    let scheme = url
+        .find([':', '/', '?', '#'])
+        .filter(|&end| url[end..].starts_with(':'))
+        .map(|end| &url[..end]);
+    match scheme {
+        Some(scheme) => ["http", "https", "mailto", "tel"]
+            .iter()
+            .any(|safe| scheme.eq_ignore_ascii_case(safe))
+            .then(|| url.to_string()),
+        None if url.is_empty() => None,
+        None => Some(url.to_string()),
+    }
+}
+
+/// Whether a Quill color value is a plain CSS color (`#rrggbb`, a name or
+/// `rgb(...)`) that can't break out of a style attribute
+fn is_css_color(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "#(),. %".contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(ops: Value) -> String {
+        let content = json!({ "ops": ops }).to_string();
+        delta_to_html(&content, &mut |file| {
+            (file.mime_type == "image/png")
+                .then(|| format!("data:image/png;base64,{}", file.blob_hash))
+        })
+    }
+
+    #[test]
+    fn test_delta_to_html() {
+        let html = render(json!([
+            { "insert": "Plan <A & B>" },
+            { "insert": "\n", "attributes": { "header": 1 } },
+            { "insert": "Bold", "attributes": { "bold": true, "italic": true } },
+            { "insert": " and " },
+            { "insert": "link", "attributes": { "link": "https://example.com/?a=1&b=2" } },
+            { "insert": " " },
+            { "insert": "bad", "attributes": { "link": "javascript:alert(1)" } },
+            { "insert": " H" },
+            { "insert": "2", "attributes": { "script": "sub" } },
+            { "insert": "O " },
+            { "insert": "red", "attributes": { "color": "#e60000", "background": "red\" onclick=\"x" } },
+            { "insert": "\n", "attributes": { "align": "center" } },
+            { "insert": "\nOne" },
+            { "insert": "\n", "attributes": { "list": "bullet" } },
+            { "insert": "Two" },
+            { "insert": "\n", "attributes": { "list": "ordered", "indent": 1 } },
+            { "insert": "Three" },
+            { "insert": "\n", "attributes": { "list": "ordered", "indent": 1 } },
+            { "insert": "Four" },
+            { "insert": "\n", "attributes": { "list": "bullet" } },
+            { "insert": "Done" },
+            { "insert": "\n", "attributes": { "list": "checked" } },
+            { "insert": "Todo" },
+            { "insert": "\n", "attributes": { "list": "unchecked" } },
+            { "insert": "Quoted" },
+            { "insert": "\n", "attributes": { "blockquote": true } },
+            { "insert": "Still" },
+            { "insert": "\n", "attributes": { "blockquote": true } },
+            { "insert": "fn main() {}" },
+            { "insert": "\n", "attributes": { "code-block": "rust" } },
+            { "insert": "if a < b {}" },
+            { "insert": "\n", "attributes": { "code-block": "rust" } },
+            { "insert": { "attachment-image": {
+                "blobHash": "abc", "mimeType": "image/png", "filename": "chart.png"
+            } } },
+            { "insert": { "attachment-file": {
+                "blobHash": "def", "mimeType": "application/pdf", "filename": "spec.pdf"
+            } } },
+            { "insert": { "image": "javascript:alert(1)" } },
+            { "insert": "\n" }
+        ]));
+
+        assert_eq!(
+            html,
+            "<h1>Plan &lt;A &amp; B&gt;</h1>\n\
+             <p style=\"text-align: center\"><strong><em>Bold</em></strong> and \
+             <a href=\"https://example.com/?a=1&amp;b=2\">link</a> bad H<sub>2</sub>O \
+             <span style=\"color: #e60000\">red</span></p>\n\
+             <p><br></p>\n\
+             <ul>\n<li>One<ol>\n<li>Two</li>\n<li>Three</li>\n</ol>\n</li>\n<li>Four</li>\n</ul>\n\
+             <ul class=\"checklist\">\n<li><input type=\"checkbox\" checked disabled>Done</li>\n\
+             <li><input type=\"checkbox\" disabled>Todo</li>\n</ul>\n\
+             <blockquote>\n<p>Quoted</p>\n<p>Still</p>\n</blockquote>\n\
+             <pre><code class=\"language-rust\">fn main() {}\nif a &lt; b {}</code></pre>\n\
+             <p><img src=\"data:image/png;base64,abc\" alt=\"chart.png\">\
+             <span class=\"attachment\">spec.pdf</span></p>\n"
+        );
+
+        assert_eq!(delta_to_html("not json", &mut |_| None), "");
+        assert_eq!(render(json!([{ "insert": "Tail" }])), "<p>Tail</p>\n");
+    }
+
+    #[test]
+    fn test_html_document() {
+        let page = html_document("Q4 <draft>", "<p>Hi</p>\n");
+        assert!(page.starts_with("<!DOCTYPE html>\n"));
+        assert!(page.contains("<meta charset=\"utf-8\">"));
+        assert!(page.contains("<title>Q4 &lt;draft&gt;</title>"));
+        assert!(page.contains("@media print"));
+        assert!(page.ends_with("<body>\n<p>Hi</p>\n</body>\n</html>\n"));
+
+        assert_eq!(data_uri("image/png", b"png"), "data:image/png;base64,cG5n");
+        assert_eq!(
+            safe_url("mailto:a@example.com").as_deref(),
+            Some("mailto:a@example.com")
+        );
+        assert_eq!(safe_url("#section").as_deref(), Some("#section"));
+        assert_eq!(
+            safe_url("notes/a:b.html").as_deref(),
+            Some("notes/a:b.html")
+        );
+        assert_eq!(safe_url(" JavaScript:alert(1)"), None);
+        assert_eq!(safe_url("data:text/html,x"), None);
+    }
+
+    #[test]
+    fn test_safe_url_ignores_control_characters() {
+        // Browsers drop tabs and newlines from URLs
+        assert_eq!(safe_url("java\tscript:alert(1)"), None);
+        assert_eq!(safe_url("java\nscript:alert(1)"), None);
+        assert_eq!(safe_url("\u{1} \r\njavascript:alert(1)"), None);
+        assert_eq!(safe_url("java script:alert(1)"), None);
+        assert_eq!(
+            safe_url("\thttps://exa\nmple.com/").as_deref(),
+            Some("https://example.com/")
+        );
+
+        let html = render(json!([
+            { "insert": "x", "attributes": { "link": "java\tscript:alert(1)" } },
+            { "insert": "\n" }
+        ]));
+        assert!(!html.contains("href"));
+    }
+}
@@ -0,0 +1,2162 @@
+//! Imports
+//!
+//! Folder import brings in a directory of Markdown and plain-text files as notes. Files in a
+//! subfolder go into the collection named after the top-level subfolder,
+//! matched to an existing collection or created the same way OneNote
+//! sections are; files at the top stay outside any collection. Markdown is
+//! converted with `markdown::markdown_to_delta`, YAML front matter supplies
+//! the title and dates (otherwise the file name and times), and images that
+//! point at local files are stored as attachments. Hidden files and folders
+//! and symlinks are skipped.
+//!
+//! Evernote exports (`.enex`) are read by `enex`: notes go into a collection
+//! per notebook or per first tag, their resources are stored as attachments
+//! and they keep their created and updated times.
+//!
+//! OneNote pages, read by `onenote` from OneNote itself (Windows) or from
+//! exported XML files, go into a collection per section with their images
+//! stored as attachments.
+//!
+//! Bundles written by bundle export are merged in with new IDs for every
+//! note, attachment and reminder, so they never collide with existing ones.
+
+use crate::config;
+use crate::database::{
+    Attachment, Collection, CreateCollectionRequest, CreateNoteRequest, NoteSnapshot, Reminder,
+    Repository,
+};
+use crate::error::{AppError, Result};
+use crate::services::bundle::{self, BundleNote, BundleReader};
+use crate::services::enex::{self, EnexCollectionSource, EnexNote, EnexResource};
+use crate::services::markdown::{self, DeltaOps};
+use crate::services::note_links;
+use crate::services::onenote::{self, OneNoteDocument, OneNoteImage, OneNotePage};
+use crate::services::recurrence;
+use crate::services::{attachments, AttachmentsService};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use uuid::Uuid;
+
+/// Outcome of an import (OneNote, folder or Evernote)
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub struct ImportResult {
+    pub notes_imported: usize,
+    pub collections_created: usize,
+    /// OneNote section, folder, notebook or tag name → collection ID
+    pub sections_mapped: HashMap<String, String>,
+    pub errors: Vec<String>,
+}
+
+/// The collections an import puts notes into, matched to existing
+/// collections by name (case-insensitively) and created as needed
+pub struct ImportCollections {
+    by_name: HashMap<String, String>,
+    /// Collections created so far
+    pub created: usize,
+}
+
+impl ImportCollections {
+    /// Start from the collections that already exist
+    pub async fn load(repo: &Repository) -> Result<Self> {
+        let by_name: HashMap<String, String> = repo
+            .list_collections()
+            .await?
+            .into_iter()
+            .map(|collection| (collection.name.to_lowercase(), collection.id))
+            .collect();
+        tracing::info!("Found {} existing collections", by_name.len());
+
+        Ok(Self {
+            by_name,
+            created: 0,
+        })
+    }
+
+    /// ID of the collection called `name`, creating it with the next import
+    /// color if there's none yet
+    pub async fn get_or_create(
+        &mut self,
+        repo: &Repository,
+        name: &str,
+        description: String,
+        icon: &str,
+    ) -> Result<String> {
+        let name_lower = name.to_lowercase();
+        if let Some(id) = self.by_name.get(&name_lower) {
+            tracing::info!("Using existing collection '{}'", name);
+            return Ok(id.clone());
+        }
+
+        tracing::info!("Creating new collection '{}'", name);
+        let colors = config::IMPORT_COLLECTION_COLORS;
+        let collection = repo
+            .create_collection(CreateCollectionRequest {
+                name: name.to_string(),
+                description: Some(description),
+                color: Some(colors[self.created % colors.len()].to_string()),
+                icon: Some(icon.to_string()),
+            })
+            .await?;
+
+        self.created += 1;
+        self.by_name.insert(name_lower, collection.id.clone());
+        Ok(collection.id)
+    }
+
+    /// The collection with `collection`'s name, or a new one with its
+    /// description, color and icon if there's none yet
+    pub async fn get_or_copy(
+        &mut self,
+        repo: &Repository,
+        collection: &Collection,
+    ) -> Result<Collection> {
+        let name_lower = collection.name.to_lowercase();
+        if let Some(id) = self.by_name.get(&name_lower) {
+            tracing::info!("Using existing collection '{}'", collection.name);
+            return repo.get_collection(id).await;
+        }
+
+        tracing::info!("Creating new collection '{}'", collection.name);
+        let created = repo
+            .create_collection(CreateCollectionRequest {
+                name: collection.name.clone(),
+                description: collection.description.clone(),
+                color: Some(collection.color.clone()),
+                icon: collection.icon.clone(),
+            })
+            .await?;
+
+        self.created += 1;
+        self.by_name.insert(name_lower, created.id.clone());
+        Ok(created)
+    }
+}
+
+/// Service for importing notes from files
+#[derive(Clone)]
+pub struct ImportService {
+    repo: Repository,
+    attachments: AttachmentsService,
+}
+
+impl ImportService {
+    pub fn new(repo: Repository, attachments: AttachmentsService) -> Self {
+        Self { repo, attachments }
+    }
+
+    /// Import the `.md`, `.markdown` and `.txt` files under `directory` (an
+    /// absolute path). Files that fail are reported in `errors` and skipped.
+    pub async fn import_folder(&self, directory: &Path) -> Result<ImportResult> {
+        if !directory.is_absolute() {
+            return Err(AppError::Import(format!(
+                "import directory must be an absolute path: {:?}",
+                directory
+            )));
+        }
+        if !fs::metadata(directory).await.is_ok_and(|m| m.is_dir()) {
+            return Err(AppError::Import(format!(
+                "not a directory: {}",
+                directory.display()
+            )));
+        }
+
+        let files = find_files(directory, config::IMPORT_FILE_EXTENSIONS).await?;
+        tracing::info!(
+            "Importing {} files from {}",
+            files.len(),
+            directory.display()
+        );
+
+        let mut collections = ImportCollections::load(&self.repo).await?;
+        let mut sections_mapped: HashMap<String, String> = HashMap::new();
+        let mut notes_imported = 0;
+        let mut errors = Vec::new();
+
+        for path in &files {
+            let relative = path.strip_prefix(directory).unwrap_or(path);
+            let source = relative.display().to_string();
+
+            let folder = relative
+                .parent()
+                .and_then(|parent| parent.components().next())
+                .map(|folder| folder.as_os_str().to_string_lossy().into_owned());
+            let collection_id = match folder {
+                None => None,
+                Some(folder) => match sections_mapped.get(&folder) {
+                    Some(id) => Some(id.clone()),
+                    None => {
+                        let description =
+                            format!("Imported from {}", directory.join(&folder).display());
+                        match collections
+                            .get_or_create(&self.repo, &folder, description, "folder")
+                            .await
+                        {
+                            Ok(id) => {
+                                sections_mapped.insert(folder, id.clone());
+                                Some(id)
+                            }
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to create collection for folder {}: {}",
+                                    folder,
+                                    e
+                                );
+                                errors.push(format!(
+                                    "Failed to create collection for folder '{}': {}",
+                                    folder, e
+                                ));
+                                continue;
+                            }
+                        }
+                    }
+                },
+            };
+
+            match self
+                .import_file(path, &source, collection_id, &mut errors)
+                .await
+            {
+                Ok(()) => notes_imported += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to import {}: {}", source, e);
+                    errors.push(format!("Failed to import '{}': {}", source, e));
+                }
+            }
+        }
+
+        tracing::info!(
+            "Folder import complete: {} notes imported, {} collections created, {} errors",
+            notes_imported,
+            collections.created,
+            errors.len()
+        );
+
+        Ok(ImportResult {
+            notes_imported,
+            collections_created: collections.created,
+            sections_mapped,
+            errors,
+        })
+    }
+
+    /// Import one file as a note. Images that can't be stored are reported
+    /// in `errors` without failing the note.
+    async fn import_file(
+        &self,
+        path: &Path,
+        source: &str,
+        collection_id: Option<String>,
+        errors: &mut Vec<String>,
+    ) -> Result<()> {
+        let bytes = fs::read(path).await?;
+        let metadata = fs::metadata(path).await?;
+        let text = String::from_utf8_lossy(&bytes);
+        let text = text.strip_prefix('\u{feff}').unwrap_or(&text);
+
+        let is_text = extension(path) == "txt";
+        let (front_matter, ops) = if is_text {
+            (FrontMatter::default(), plain_text_ops(text))
+        } else {
+            let (front_matter, body) = split_front_matter(text);
+            (front_matter, markdown::markdown_to_delta(body))
+        };
+
+        let title = front_matter
+            .title
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or_else(|| {
+                path.file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned()
+            });
+        let note = self
+            .repo
+            .create_note(CreateNoteRequest {
+                title,
+                content_json: json!({ "ops": [{ "insert": "\n" }] }).to_string(),
+                collection_id,
+            })
+            .await?;
+
+        let imported = async {
+            let base = path.parent().unwrap_or(Path::new(""));
+            let ops = self
+                .attach_images(&note.id, ops, base, source, errors)
+                .await;
+
+            let modified = metadata.modified().ok().map(DateTime::<Utc>::from);
+            let created_at = front_matter
+                .created_at
+                .or_else(|| metadata.created().ok().map(DateTime::<Utc>::from))
+                .or(modified)
+                .unwrap_or(note.created_at);
+            let updated_at = front_matter
+                .updated_at
+                .or(modified)
+                .unwrap_or(note.updated_at)
+                .max(created_at);
+
+            let content_json = json!({ "ops": ops }).to_string();
+            self.finish_imported_note(&note.id, &content_json, created_at, updated_at)
+                .await
+        }
+        .await;
+        if let Err(e) = imported {
+            self.discard_note(&note.id).await;
+            return Err(e);
+        }
+
+        tracing::debug!("Imported {} as note {}", source, note.id);
+        Ok(())
+    }
+
+    /// Write an imported note's content and times, and index it
+    async fn finish_imported_note(
+        &self,
+        note_id: &str,
+        content_json: &str,
+        created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let note = self
+            .repo
+            .set_imported_note(note_id, content_json, created_at, updated_at)
+            .await?;
+        self.repo
+            .insert_note_fts(&note.id, &note.title, &note.content_json)
+            .await?;
+        note_links::sync_note_links(&self.repo, &note.id, &note.content_json).await
+    }
+
+    /// Remove a note whose import failed after it was created, with the
+    /// attachments and index entries already written for it
+    async fn discard_note(&self, note_id: &str) {
+        let removed = match self.repo.delete_note_fts(note_id).await {
+            Ok(()) => self.repo.hard_delete_note(note_id).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = removed {
+            tracing::warn!("Failed to remove partly imported note {}: {}", note_id, e);
+        }
+    }
+
+    /// Store the local files `image` embeds point at as attachments of the
+    /// note and embed those instead. Remote images are kept; images that
+    /// can't be stored are replaced by their alt text.
+    async fn attach_images(
+        &self,
+        note_id: &str,
+        ops: Vec<Value>,
+        base: &Path,
+        source: &str,
+        errors: &mut Vec<String>,
+    ) -> Vec<Value> {
+        let mut stored: HashMap<PathBuf, Value> = HashMap::new();
+        let mut resolved = Vec::with_capacity(ops.len());
+
+        for mut op in ops {
+            let Some(src) = op.pointer("/insert/image").and_then(Value::as_str) else {
+                resolved.push(op);
+                continue;
+            };
+            let Some(local) = local_path(src) else {
+                resolved.push(op);
+                continue;
+            };
+            let src = src.to_string();
+            let path = base.join(local);
+
+            let embed = match stored.get(&path) {
+                Some(embed) => Ok(embed.clone()),
+                None => self.attach_image(note_id, &path).await,
+            };
+            let alt = op
+                .get_mut("attributes")
+                .and_then(Value::as_object_mut)
+                .and_then(|attrs| attrs.remove("alt"));
+            if op["attributes"]
+                .as_object()
+                .is_some_and(|attrs| attrs.is_empty())
+            {
+                if let Some(op) = op.as_object_mut() {
+                    op.remove("attributes");
+                }
+            }
+            match embed {
+                Ok(embed) => {
+                    stored.insert(path, embed.clone());
+                    op["insert"] = embed;
+                    resolved.push(op);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to attach image {} in {}: {}", src, source, e);
+                    errors.push(format!(
+                        "Failed to attach image '{}' in '{}': {}",
+                        src, source, e
+                    ));
+                    if let Some(alt) = alt.filter(|alt| *alt != "") {
+                        op["insert"] = alt;
+                        resolved.push(op);
+                    }
+                }
+            }
+        }
+
+        resolved
+    }
+
+    async fn attach_image(&self, note_id: &str, path: &Path) -> Result<Value> {
+        let mime_type = image_mime_type(path).ok_or_else(|| {
+            AppError::Import(format!("not a supported image: {}", path.display()))
+        })?;
+        let data = fs::read(path).await?;
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+
+        let attachment = self
+            .attachments
+            .create_attachment(note_id, &filename, mime_type, &data)
+            .await?;
+
+        Ok(attachment_embed(&attachment))
+    }
+
+    /// Import an Evernote export: one `.enex` file or the `.enex` files under
+    /// a directory (an absolute path). A file that can't be read or a note
+    /// that can't be imported is reported in `errors` and skipped.
+    pub async fn import_enex(
+        &self,
+        path: &Path,
+        collections_by: EnexCollectionSource,
+    ) -> Result<ImportResult> {
+        if !path.is_absolute() {
+            return Err(AppError::Import(format!(
+                "import path must be an absolute path: {:?}",
+                path
+            )));
+        }
+        let metadata = fs::metadata(path)
+            .await
+            .map_err(|_| AppError::Import(format!("not found: {}", path.display())))?;
+        let files = if metadata.is_dir() {
+            find_files(path, &["enex"]).await?
+        } else {
+            vec![path.to_path_buf()]
+        };
+        tracing::info!(
+            "Importing {} ENEX files from {}",
+            files.len(),
+            path.display()
+        );
+
+        let mut collections = ImportCollections::load(&self.repo).await?;
+        let mut sections_mapped: HashMap<String, String> = HashMap::new();
+        let mut notes_imported = 0;
+        let mut errors = Vec::new();
+
+        for file in &files {
+            let source = file.file_name().unwrap_or_default().to_string_lossy();
+            let notebook = file.file_stem().unwrap_or_default().to_string_lossy();
+
+            let notes = match fs::read(file).await {
+                Ok(bytes) => enex::parse_enex(&String::from_utf8_lossy(&bytes)),
+                Err(e) => Err(e.into()),
+            };
+            let notes = match notes {
+                Ok(notes) => notes,
+                Err(e) => {
+                    tracing::warn!("Failed to read {}: {}", source, e);
+                    errors.push(format!("Failed to read '{}': {}", source, e));
+                    continue;
+                }
+            };
+
+            for note in notes {
+                let collection = match collections_by {
+                    EnexCollectionSource::Notebook => Some(notebook.to_string()),
+                    EnexCollectionSource::Tag => note.tags.first().cloned(),
+                };
+                let collection_id = match collection {
+                    None => None,
+                    Some(name) => match sections_mapped.get(&name) {
+                        Some(id) => Some(id.clone()),
+                        None => {
+                            let description = format!("Imported from Evernote: {}", notebook);
+                            match collections
+                                .get_or_create(&self.repo, &name, description, "book")
+                                .await
+                            {
+                                Ok(id) => {
+                                    sections_mapped.insert(name, id.clone());
+                                    Some(id)
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Failed to create collection {}: {}", name, e);
+                                    errors.push(format!(
+                                        "Failed to create collection '{}': {}",
+                                        name, e
+                                    ));
+                                    continue;
+                                }
+                            }
+                        }
+                    },
+                };
+
+                let title = note.title.clone();
+                match self
+                    .import_enex_note(note, collection_id, &mut errors)
+                    .await
+                {
+                    Ok(()) => notes_imported += 1,
+                    Err(e) => {
+                        tracing::warn!("Failed to import note {} from {}: {}", title, source, e);
+                        errors.push(format!(
+                            "Failed to import note '{}' from '{}': {}",
+                            title, source, e
+                        ));
+                    }
+                }
+            }
+        }
+
+        tracing::info!(
+            "Evernote import complete: {} notes imported, {} collections created, {} errors",
+            notes_imported,
+            collections.created,
+            errors.len()
+        );
+
+        Ok(ImportResult {
+            notes_imported,
+            collections_created: collections.created,
+            sections_mapped,
+            errors,
+        })
+    }
+
+    /// Import one Evernote note. Resources that can't be stored are reported
+    /// in `errors` and left out of the note.
+    async fn import_enex_note(
+        &self,
+        enex_note: EnexNote,
+        collection_id: Option<String>,
+        errors: &mut Vec<String>,
+    ) -> Result<()> {
+        let ops = enex::enml_to_delta(&enex_note.content)?;
+        let title = if enex_note.title.is_empty() {
+            "Untitled".to_string()
+        } else {
+            enex_note.title
+        };
+        let note = self
+            .repo
+            .create_note(CreateNoteRequest {
+                title,
+                content_json: json!({ "ops": [{ "insert": "\n" }] }).to_string(),
+                collection_id,
+            })
+            .await?;
+
+        let imported = async {
+            let mut stored: HashMap<String, Value> = HashMap::new();
+            for resource in &enex_note.resources {
+                match self.attach_resource(&note.id, resource).await {
+                    Ok((hash, embed)) => {
+                        stored.insert(hash, embed);
+                    }
+                    Err(e) => {
+                        let name = resource.filename.as_deref().unwrap_or(&resource.mime_type);
+                        tracing::warn!("Failed to attach {} to {}: {}", name, note.title, e);
+                        errors.push(format!(
+                            "Failed to attach '{}' to note '{}': {}",
+                            name, note.title, e
+                        ));
+                    }
+                }
+            }
+
+            // Swap `<en-media>` placeholders for the stored resources
+            let ops = swap_embeds(ops, "en-media", |media| {
+                let hash = media["hash"].as_str()?.to_lowercase();
+                stored.get(&hash).cloned()
+            });
+
+            let created_at = enex_note.created.unwrap_or(note.created_at);
+            let updated_at = enex_note.updated.unwrap_or(note.updated_at).max(created_at);
+
+            let content_json = json!({ "ops": ops }).to_string();
+            self.finish_imported_note(&note.id, &content_json, created_at, updated_at)
+                .await
+        }
+        .await;
+        if let Err(e) = imported {
+            self.discard_note(&note.id).await;
+            return Err(e);
+        }
+
+        tracing::debug!("Imported Evernote note {}", note.id);
+        Ok(())
+    }
+
+    /// Store a resource as an attachment of the note; returns the MD5 hash
+    /// `<en-media>` refers to it by and the embed for it
+    async fn attach_resource(
+        &self,
+        note_id: &str,
+        resource: &EnexResource,
+    ) -> Result<(String, Value)> {
+        let (data, hash) = resource.decode()?;
+        let mime_type = if resource.mime_type.is_empty() {
+            "application/octet-stream"
+        } else {
+            resource.mime_type.as_str()
+        };
+        let filename = resource.filename.clone().unwrap_or_else(|| {
+            let extension = match mime_type {
+                "image/jpeg" => "jpg",
+                "image/svg+xml" => "svg",
+                "text/plain" => "txt",
+                _ => mime_type.rsplit('/').next().unwrap_or("bin"),
+            };
+            format!("attachment.{}", extension)
+        });
+
+        let attachment = self
+            .attachments
+            .create_attachment(note_id, &filename, mime_type, &data)
+            .await?;
+
+        Ok((hash, attachment_embed(&attachment)))
+    }
+
+    /// Import the pages of a OneNote hierarchy (`GetHierarchy` XML) into a
+    /// collection per section; `page_xml` fetches a page's content
+    pub async fn import_onenote_hierarchy(
+        &self,
+        hierarchy_xml: &str,
+        page_xml: impl FnMut(&OneNotePage) -> Result<String>,
+    ) -> Result<ImportResult> {
+        let mut collections = ImportCollections::load(&self.repo).await?;
+        let mut result = ImportResult::default();
+        self.import_onenote_sections(hierarchy_xml, page_xml, &mut collections, &mut result)
+            .await;
+        result.collections_created = collections.created;
+        log_onenote_result(&result);
+        Ok(result)
+    }
+
+    /// Import XML exported from OneNote (an absolute path): one page file, or
+    /// the `.xml` hierarchy and page files under a directory. Pages listed in
+    /// a hierarchy go into their section's collection, other pages into one
+    /// named after their top-level subfolder (if any).
+    pub async fn import_onenote_export(&self, path: &Path) -> Result<ImportResult> {
+        if !path.is_absolute() {
+            return Err(AppError::Import(format!(
+                "import path must be an absolute path: {:?}",
+                path
+            )));
+        }
+        let metadata = fs::metadata(path)
+            .await
+            .map_err(|_| AppError::Import(format!("not found: {}", path.display())))?;
+        let (root, files) = if metadata.is_dir() {
+            (path, find_files(path, &["xml"]).await?)
+        } else {
+            (path.parent().unwrap_or(path), vec![path.to_path_buf()])
+        };
+        tracing::info!(
+            "Importing {} OneNote XML files from {}",
+            files.len(),
+            path.display()
+        );
+
+        let mut collections = ImportCollections::load(&self.repo).await?;
+        let mut result = ImportResult::default();
+        let mut hierarchies = Vec::new();
+        // Page XML by page ID, and the pages in file order with their folder
+        let mut page_files: HashMap<String, String> = HashMap::new();
+        let mut pages: Vec<(OneNotePage, Option<String>)> = Vec::new();
+
+        for file in &files {
+            let relative = file.strip_prefix(root).unwrap_or(file);
+            let source = relative.display().to_string();
+            let xml = match fs::read(file).await {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => {
+                    result
+                        .errors
+                        .push(format!("Failed to read '{}': {}", source, e));
+                    continue;
+                }
+            };
+            let xml = xml.strip_prefix('\u{feff}').unwrap_or(&xml).to_string();
+
+            match onenote::parse_document(&xml) {
+                Ok(OneNoteDocument::Hierarchy) => hierarchies.push(xml),
+                Ok(OneNoteDocument::Page(page)) => {
+                    let folder = relative
+                        .parent()
+                        .and_then(|parent| parent.components().next())
+                        .map(|folder| folder.as_os_str().to_string_lossy().into_owned());
+                    page_files.insert(page.id.clone(), xml);
+                    pages.push((page, folder));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to read {}: {}", source, e);
+                    result
+                        .errors
+                        .push(format!("Failed to read '{}': {}", source, e));
+                }
+            }
+        }
+
+        for hierarchy_xml in &hierarchies {
+            let page_xml = |page: &OneNotePage| {
+                page_files
+                    .remove(&page.id)
+                    .ok_or_else(|| AppError::Import("the page's XML wasn't exported".to_string()))
+            };
+            self.import_onenote_sections(hierarchy_xml, page_xml, &mut collections, &mut result)
+                .await;
+        }
+
+        // Pages no hierarchy lists
+        for (page, folder) in pages {
+            let Some(page_xml) = page_files.remove(&page.id) else {
+                continue;
+            };
+            let collection_id = match folder {
+                None => None,
+                Some(folder) => {
+                    let description = format!("Imported from OneNote: {}", folder);
+                    match collections
+                        .get_or_create(&self.repo, &folder, description, "book")
+                        .await
+                    {
+                        Ok(id) => {
+                            result.sections_mapped.insert(folder, id.clone());
+                            Some(id)
+                        }
+                        Err(e) => {
+                            result.errors.push(format!(
+                                "Failed to create collection for folder '{}': {}",
+                                folder, e
+                            ));
+                            continue;
+                        }
+                    }
+                }
+            };
+            match self
+                .import_onenote_page(&page, &page_xml, collection_id, &mut result.errors)
+                .await
+            {
+                Ok(()) => result.notes_imported += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to import page '{}': {}", page.title, e);
+                    result
+                        .errors
+                        .push(format!("Failed to import page '{}': {}", page.title, e));
+                }
+            }
+        }
+
+        result.collections_created = collections.created;
+        log_onenote_result(&result);
+        Ok(result)
+    }
+
+    /// Import the sections of a hierarchy into `result`; problems are
+    /// reported in its `errors`
+    async fn import_onenote_sections(
+        &self,
+        hierarchy_xml: &str,
+        mut page_xml: impl FnMut(&OneNotePage) -> Result<String>,
+        collections: &mut ImportCollections,
+        result: &mut ImportResult,
+    ) {
+        let sections = match onenote::parse_sections(hierarchy_xml) {
+            Ok(sections) => sections,
+            Err(e) => {
+                tracing::error!("Failed to parse OneNote hierarchy: {}", e);
+                result
+                    .errors
+                    .push(format!("Failed to parse OneNote data: {}", e));
+                return;
+            }
+        };
+        tracing::info!("Found {} sections to import", sections.len());
+
+        // Parse all pages from the same hierarchy (avoids extra COM calls per section)
+        let all_pages = match onenote::parse_all_pages(hierarchy_xml) {
+            Ok(pages) => pages,
+            Err(e) => {
+                tracing::error!("Failed to parse pages from hierarchy: {}", e);
+                result.errors.push(format!("Failed to parse pages: {}", e));
+                return;
+            }
+        };
+        tracing::info!(
+            "Found {} total pages across all sections",
+            all_pages.values().map(|v| v.len()).sum::<usize>()
+        );
+
+        for section in &sections {
+            // Skip recycle bin / deleted pages section
+            if section.name == "Deleted Pages" {
+                tracing::info!("Skipping 'Deleted Pages' section");
+                continue;
+            }
+
+            tracing::info!("Processing section: {}", section.name);
+
+            let description = format!("Imported from OneNote: {}", section.notebook_name);
+            let collection_id = match collections
+                .get_or_create(&self.repo, &section.name, description, "book")
+                .await
+            {
+                Ok(id) => id,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to create collection for section {}: {}",
+                        section.name,
+                        e
+                    );
+                    result.errors.push(format!(
+                        "Failed to create collection for section '{}': {}",
+                        section.name, e
+                    ));
+                    continue;
+                }
+            };
+            result
+                .sections_mapped
+                .insert(section.name.clone(), collection_id.clone());
+
+            let Some(pages) = all_pages.get(&section.id) else {
+                tracing::info!("No pages found in section '{}'", section.name);
+                continue;
+            };
+            tracing::info!("Found {} pages in section '{}'", pages.len(), section.name);
+
+            for page in pages {
+                let imported = match page_xml(page) {
+                    Ok(xml) => {
+                        self.import_onenote_page(
+                            page,
+                            &xml,
+                            Some(collection_id.clone()),
+                            &mut result.errors,
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                };
+                match imported {
+                    Ok(()) => {
+                        result.notes_imported += 1;
+                        if result.notes_imported.is_multiple_of(10) {
+                            tracing::info!("Imported {} notes so far...", result.notes_imported);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to import page '{}': {}", page.title, e);
+                        result
+                            .errors
+                            .push(format!("Failed to import page '{}': {}", page.title, e));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Import one OneNote page with its images and times. Images that can't
+    /// be stored are reported in `errors` and left out of the note.
+    async fn import_onenote_page(
+        &self,
+        page: &OneNotePage,
+        page_xml: &str,
+        collection_id: Option<String>,
+        errors: &mut Vec<String>,
+    ) -> Result<()> {
+        let content = onenote::convert_onenote_to_quill(page_xml)?;
+        let note = self
+            .repo
+            .create_note(CreateNoteRequest {
+                title: page.title.clone(),
+                content_json: json!({ "ops": [{ "insert": "\n" }] }).to_string(),
+                collection_id,
+            })
+            .await?;
+
+        let imported = async {
+            let mut embeds = Vec::with_capacity(content.images.len());
+            for (n, image) in content.images.iter().enumerate() {
+                match self.attach_onenote_image(&note.id, image, n + 1).await {
+                    Ok(embed) => embeds.push(Some(embed)),
+                    Err(e) => {
+                        tracing::warn!("Failed to attach image {} of {}: {}", n + 1, page.title, e);
+                        errors.push(format!(
+                            "Failed to attach image {} of page '{}': {}",
+                            n + 1,
+                            page.title,
+                            e
+                        ));
+                        embeds.push(None);
+                    }
+                }
+            }
+            let ops = swap_embeds(content.ops, "onenote-image", |index| {
+                embeds.get(index.as_u64()? as usize).cloned().flatten()
+            });
+
+            let created_at =
+                onenote::parse_onenote_time(&page.created_at).unwrap_or(note.created_at);
+            let updated_at = onenote::parse_onenote_time(&page.modified_at)
+                .unwrap_or(note.updated_at)
+                .max(created_at);
+
+            let content_json = json!({ "ops": ops }).to_string();
+            self.finish_imported_note(&note.id, &content_json, created_at, updated_at)
+                .await
+        }
+        .await;
+        if let Err(e) = imported {
+            self.discard_note(&note.id).await;
+            return Err(e);
+        }
+
+        tracing::debug!("Imported OneNote page {} as note {}", page.id, note.id);
+        Ok(())
+    }
+
+    async fn attach_onenote_image(
+        &self,
+        note_id: &str,
+        image: &OneNoteImage,
+        number: usize,
+    ) -> Result<Value> {
+        let data = image.decode()?;
+        let filename = format!("image{}.{}", number, image.format);
+        let attachment = self
+            .attachments
+            .create_attachment(note_id, &filename, &image.mime_type(), &data)
+            .await?;
+        Ok(attachment_embed(&attachment))
+    }
+
+    /// Merge a bundle written by `ExportService::export_bundle` (see
+    /// `bundle`) into the library. Every note, attachment and reminder gets
+    /// a new ID, so importing never replaces a note, even when the bundle
+    /// came from this library. Collections are matched by name or created
+    /// with their color and icon; tags are matched by name. Notes that fail
+    /// are reported in `errors` and skipped.
+    pub async fn import_bundle(&self, path: &Path) -> Result<ImportResult> {
+        if !path.is_absolute() {
+            return Err(AppError::Import(format!(
+                "bundle path must be absolute: {:?}",
+                path
+            )));
+        }
+
+        let mut reader = BundleReader::open(path)?;
+        tracing::info!(
+            "Importing bundle {} (version {}, {} notes, written by {} at {})",
+            path.display(),
+            reader.manifest.version,
+            reader.manifest.note_count,
+            reader.manifest.app_version,
+            reader.manifest.exported_at
+        );
+
+        let bundle_collections: Vec<Collection> = reader.read_json(bundle::COLLECTIONS_FILE)?;
+        let mut reminders: HashMap<String, Vec<Reminder>> = HashMap::new();
+        for reminder in reader.read_json::<Vec<Reminder>>(bundle::REMINDERS_FILE)? {
+            reminders
+                .entry(reminder.note_id.clone())
+                .or_default()
+                .push(reminder);
+        }
+        let mut note_attachments: HashMap<String, Vec<Attachment>> = HashMap::new();
+        for attachment in reader.read_json::<Vec<Attachment>>(bundle::ATTACHMENTS_FILE)? {
+            note_attachments
+                .entry(attachment.note_id.clone())
+                .or_default()
+                .push(attachment);
+        }
+        let notes = reader.read_notes()?;
+
+        // New IDs are chosen up front, so links and embeds between bundle
+        // notes can be pointed at them
+        let mut state = BundleImport::default();
+        for note in notes.iter().flatten() {
+            state
+                .note_ids
+                .entry(note.note.id.clone())
+                .or_insert_with(|| Uuid::new_v4().to_string());
+        }
+        for attachment in note_attachments.values().flatten() {
+            state
+                .attachment_ids
+                .entry(attachment.id.clone())
+                .or_insert_with(|| Uuid::new_v4().to_string());
+        }
+
+        let mut result = ImportResult::default();
+        let mut collections = ImportCollections::load(&self.repo).await?;
+        let mut collection_for: HashMap<String, Collection> = HashMap::new();
+        for collection in &bundle_collections {
+            let live = collections.get_or_copy(&self.repo, collection).await?;
+            result
+                .sections_mapped
+                .insert(collection.name.clone(), live.id.clone());
+            collection_for.insert(collection.id.clone(), live);
+        }
+
+        for note in notes {
+            let note = match note {
+                Ok(note) => note,
+                Err(e) => {
+                    result.errors.push(e);
+                    continue;
+                }
+            };
+            let title = note.note.title.clone();
+            if let Err(e) = self
+                .import_bundle_note(
+                    &mut reader,
+                    note,
+                    &mut state,
+                    &collection_for,
+                    &mut reminders,
+                    &mut note_attachments,
+                )
+                .await
+            {
+                tracing::warn!("Failed to import note '{}' from bundle: {}", title, e);
+                result.errors.push(format!("\"{}\": {}", title, e));
+                continue;
+            }
+            result.notes_imported += 1;
+        }
+
+        let missing = state
+            .blobs_checked
+            .values()
+            .filter(|found| !**found)
+            .count();
+        if missing > 0 {
+            result.errors.push(format!(
+                "{} attachments or images are missing from the bundle",
+                missing
+            ));
+        }
+        result.collections_created = collections.created;
+
+        tracing::info!(
+            "Bundle import complete: {} notes imported, {} collections created, {} errors",
+            result.notes_imported,
+            result.collections_created,
+            result.errors.len()
+        );
+        Ok(result)
+    }
+
+    /// Store a bundle note's blobs, then write it with its attachments,
+    /// reminders and tags under the new IDs chosen in `state`
+    async fn import_bundle_note(
+        &self,
+        reader: &mut BundleReader,
+        note: BundleNote,
+        state: &mut BundleImport,
+        collection_for: &HashMap<String, Collection>,
+        reminders: &mut HashMap<String, Vec<Reminder>>,
+        note_attachments: &mut HashMap<String, Vec<Attachment>>,
+    ) -> Result<()> {
+        let mut attachments = note_attachments.remove(&note.note.id).unwrap_or_default();
+        let mut reminders = reminders.remove(&note.note.id).unwrap_or_default();
+
+        // Store blobs first so notes never reference missing blobs
+        let mut hashes: Vec<String> = attachments.iter().map(|a| a.blob_hash.clone()).collect();
+        hashes.extend(Repository::extract_blob_hashes_from_delta(
+            &note.note.content_json,
+        ));
+        for hash in hashes {
+            if !bundle::is_blob_hash(&hash) || state.blobs_checked.contains_key(&hash) {
+                continue;
+            }
+            let found = if self.attachments.has_blob(&hash).await? {
+                true
+            } else if let Some(data) = reader.read_blob(&hash)? {
+                self.attachments.store_blob(&data).await?;
+                true
+            } else {
+                tracing::warn!("Blob {} is not in the bundle", hash);
+                false
+            };
+            state.blobs_checked.insert(hash, found);
+        }
+
+        // Reminders that came due since the export don't all fire at once;
+        // recurring ones continue from their next occurrence
+        recurrence::catch_up_reminders(&mut reminders, Utc::now(), &Local);
+
+        let collection = note
+            .note
+            .collection_id
+            .as_ref()
+            .and_then(|id| collection_for.get(id))
+            .cloned();
+
+        let old_id = note.note.id.clone();
+        let mut row = note.note;
+        row.id = match state.note_ids.get(&old_id) {
+            // A note ID repeated in the bundle only maps to its first note
+            Some(id) if state.written_ids.insert(id.clone()) => id.clone(),
+            _ => Uuid::new_v4().to_string(),
+        };
+        if let Some(content_json) = note_links::remap_note_ids(&row.content_json, &state.note_ids) {
+            row.content_json = content_json;
+        }
+        if let Some(content_json) = remap_attachment_ids(&row.content_json, &state.attachment_ids) {
+            row.content_json = content_json;
+        }
+        for attachment in &mut attachments {
+            attachment.id = match state.attachment_ids.get(&attachment.id) {
+                Some(id) if state.written_ids.insert(id.clone()) => id.clone(),
+                _ => Uuid::new_v4().to_string(),
+            };
+        }
+        for reminder in &mut reminders {
+            reminder.id = Uuid::new_v4().to_string();
+        }
+
+        let snapshot = NoteSnapshot {
+            note: row,
+            attachments,
+            reminders,
+            tags: note.tags,
+            collection,
+            journal_date: note.journal_date,
+        };
+        // The IDs are new, so nothing is replaced
+        let id = self.repo.import_note_snapshot(&snapshot, false).await?;
+        note_links::sync_note_links(&self.repo, &id, &snapshot.note.content_json).await?;
+        tracing::debug!("Imported bundle note {} as {}", old_id, id);
+        Ok(())
+    }
+}
+
+/// Progress of a bundle import
+#[derive(Default)]
+struct BundleImport {
+    /// New note IDs by ID in the bundle
+    note_ids: HashMap<String, String>,
+    /// New attachment IDs by ID in the bundle
+    attachment_ids: HashMap<String, String>,
+    /// New IDs already written
+    written_ids: HashSet<String>,
+    /// Whether each blob seen so far was found
+    blobs_checked: HashMap<String, bool>,
+}
+
+/// Point the `attachmentId`s of attachment embeds at new attachment IDs.
+/// Returns the new content, or None if nothing changed.
+fn remap_attachment_ids(content_json: &str, ids: &HashMap<String, String>) -> Option<String> {
+    let mut json = serde_json::from_str::<Value>(content_json).ok()?;
+    let ops = json.get_mut("ops").and_then(Value::as_array_mut)?;
+
+    let mut changed = false;
+    let embeds = ops
+        .iter_mut()
+        .filter_map(|op| op.get_mut("insert"))
+        .filter_map(Value::as_object_mut);
+    for embed in embeds {
+        for value in embed.values_mut() {
+            if let Some(Value::String(id)) = value.get_mut("attachmentId") {
+                if let Some(new_id) = ids.get(id.as_str()) {
+                    *id = new_id.clone();
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    changed.then(|| json.to_string())
+}
+
+fn log_onenote_result(result: &ImportResult) {
+    tracing::info!(
+        "Import complete: {} notes imported, {} collections created, {} errors",
+        result.notes_imported,
+        result.collections_created,
+        result.errors.len()
+    );
+}
+
+/// The embed for a stored attachment: images inline, other files as a chip
+fn attachment_embed(attachment: &Attachment) -> Value {
+    if attachments::is_allowed_image_mime(&attachment.mime_type) {
+        json!({
+            "attachment-image": {
+                "attachmentId": attachment.id,
+                "blobHash": attachment.blob_hash,
+                "mimeType": attachment.mime_type,
+                "filename": attachment.filename,
+            }
+        })
+    } else {
+        json!({
+            "attachment-file": {
+                "attachmentId": attachment.id,
+                "blobHash": attachment.blob_hash,
+                "mimeType": attachment.mime_type,
+                "filename": attachment.filename,
+                "size": attachment.size,
+            }
+        })
+    }
+}
+
+/// Replace placeholder embeds (`{key: value}`) with what `embed` gives for
+/// their value, dropping those it has nothing for
+fn swap_embeds(ops: Vec<Value>, key: &str, embed: impl Fn(&Value) -> Option<Value>) -> Vec<Value> {
+    let mut resolved = DeltaOps::default();
+    for op in ops {
+        let mut insert = op["insert"].clone();
+        if let Some(value) = insert.get(key) {
+            match embed(value) {
+                Some(embed) => insert = embed,
+                None => continue,
+            }
+        }
+        let attrs = op["attributes"].as_object().cloned().unwrap_or_default();
+        resolved.push(insert, attrs);
+    }
+    resolved.into_ops()
+}
+
+/// The files under `directory` with one of `extensions`, sorted by path
+async fn find_files(directory: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut folders = vec![directory.to_path_buf()];
+
+    while let Some(folder) = folders.pop() {
+        let mut entries = fs::read_dir(&folder).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type().await?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                folders.push(path);
+            } else if file_type.is_file() && extensions.contains(&extension(&path).as_str()) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_lowercase()
+}
+
+fn plain_text_ops(text: &str) -> Vec<Value> {
+    let mut text = text.replace("\r\n", "\n");
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    vec![json!({ "insert": text })]
+}
+
+/// The title and dates read from a file's front matter
+#[derive(Debug, Default, PartialEq, Eq)]
+struct FrontMatter {
+    title: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+/// Split YAML front matter (between `---` lines at the very top) from the
+/// Markdown after it. Only top-level `key: value` lines are read.
+fn split_front_matter(text: &str) -> (FrontMatter, &str) {
+    let Some(yaml) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return (FrontMatter::default(), text);
+    };
+
+    let mut end = 0;
+    let mut body = None;
+    for line in yaml.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            body = Some(&yaml[end + line.len()..]);
+            break;
+        }
+        end += line.len();
+    }
+    let Some(body) = body else {
+        return (FrontMatter::default(), text);
+    };
+
+    let values: HashMap<String, String> = yaml[..end]
+        .lines()
+        .filter(|line| !line.starts_with([' ', '\t', '-', '#']))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_lowercase(), yaml_scalar(value.trim())))
+        .collect();
+    let first = |keys: &[&str]| keys.iter().find_map(|key| values.get(*key));
+    let date = |keys: &[&str]| first(keys).and_then(|value| parse_date(value));
+
+    let front_matter = FrontMatter {
+        title: first(&["title"]).cloned(),
+        created_at: date(&["created_at", "created", "date"]),
+        updated_at: date(&["updated_at", "updated", "modified", "last_modified"]),
+    };
+    (front_matter, body)
+}
+
+/// The value of a plain, single-quoted or double-quoted YAML scalar
+fn yaml_scalar(value: &str) -> String {
+    if value.starts_with('"') {
+        if let Ok(unquoted) = serde_json::from_str::<String>(value) {
+            return unquoted;
+        }
+        return value.trim_matches('"').to_string();
+    }
+    if let Some(quoted) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return quoted.replace("''", "'");
+    }
+    match value.find(" #") {
+        Some(comment) => value[..comment].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// An RFC 3339 timestamp, or a local date and optional time
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let local = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    recurrence::local_to_utc(&Local, local)
+}
+
+/// The file an image destination points at, or None for a URL
+fn local_path(src: &str) -> Option<PathBuf> {
+    let path = match src.split_once(':') {
+        Some(("file", rest)) => rest.trim_start_matches("//"),
+        // Anything else with a scheme is remote; one letter is a drive
+        Some((scheme, _))
+            if scheme.len() > 1
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-')) =>
+        {
+            return None
+        }
+        _ => src,
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    (!path.is_empty()).then(|| PathBuf::from(percent_decode(path)))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = text
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn image_mime_type(path: &Path) -> Option<&'static str> {
+    match extension(path).as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "bmp" => Some("image/bmp"),
+        "svg" => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{initialize_database, SearchQuery};
+    use crate::storage::BlobStore;
+    use chrono::TimeZone;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tempfile::TempDir;
+
+    async fn create_test_service() -> (ImportService, TempDir) {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let blob_store = BlobStore::new(temp_dir.path().join("blobs"));
+        blob_store.initialize().await.unwrap();
+
+        let repo = Repository::new(pool);
+        let attachments = AttachmentsService::new(repo.clone(), blob_store);
+        (ImportService::new(repo, attachments), temp_dir)
+    }
+
+    /// A service whose link index refuses links to "Broken", so notes
+    /// linking there fail to import after they are created
+    async fn create_failing_service() -> (ImportService, sqlx::SqlitePool, TempDir) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+        sqlx::query(
+            "CREATE TRIGGER refuse_broken BEFORE INSERT ON note_links \
+             WHEN NEW.target = 'Broken' BEGIN SELECT RAISE(ABORT, 'broken link'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        let blob_store = BlobStore::new(temp_dir.path().join("blobs"));
+        blob_store.initialize().await.unwrap();
+
+        let repo = Repository::new(pool.clone());
+        let attachments = AttachmentsService::new(repo.clone(), blob_store);
+        (ImportService::new(repo, attachments), pool, temp_dir)
+    }
+
+    /// Rows left in `table`, deleted notes included
+    async fn count_rows(pool: &sqlx::SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn write(path: PathBuf, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        fs::write(path, contents).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_folder() {
+        let (service, temp_dir) = create_test_service().await;
+        let work = service
+            .repo
+            .create_collection(CreateCollectionRequest {
+                name: "Work".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+
+        let root = temp_dir.path().join("notes");
+        write(
+            root.join("Welcome.md"),
+            b"---\ntitle: \"Hello: world\"\ncreated_at: 2024-03-01T09:00:00Z\n\
+              updated_at: '2024-03-02T10:30:00+01:00'\ntags: [a, b]\n---\n\
+              # Hi\n\n![chart](images/my%20chart.png) ![again](images/my%20chart.png) \
+              ![gone](missing.png) ![](https://example.com/x.png)\n",
+        )
+        .await;
+        write(root.join("images/my chart.png"), b"png bytes").await;
+        write(
+            root.join("work/Plan.txt"),
+            b"\xef\xbb\xbfStep one\r\nStep two",
+        )
+        .await;
+        write(root.join("Work/Deep/Nested.markdown"), b"- item\n").await;
+        write(root.join("Personal/idea.MD"), b"Just text").await;
+        write(root.join("Personal/.hidden.md"), b"skipped").await;
+        write(root.join(".obsidian/config.md"), b"skipped").await;
+        write(root.join("report.pdf"), b"skipped").await;
+
+        let result = service.import_folder(&root).await.unwrap();
+        assert_eq!(result.notes_imported, 4);
+        assert_eq!(result.collections_created, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].contains("missing.png"));
+
+        // Folder names match collections case-insensitively
+        let personal = &result.sections_mapped["Personal"];
+        assert_eq!(result.sections_mapped["Work"], work.id);
+        assert_eq!(result.sections_mapped["work"], work.id);
+        let collection = service.repo.get_collection(personal).await.unwrap();
+        assert_eq!(collection.color, "#EF4444");
+
+        let notes = service.repo.list_notes().await.unwrap();
+        let note = |title: &str| notes.iter().find(|n| n.title == title).unwrap();
+
+        let welcome = note("Hello: world");
+        assert_eq!(welcome.collection_id, None);
+        assert!(welcome.title_modified);
+        assert_eq!(
+            welcome.created_at,
+            Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            welcome.updated_at,
+            Utc.with_ymd_and_hms(2024, 3, 2, 9, 30, 0).unwrap()
+        );
+
+        // The image is stored once; the missing one leaves its alt text
+        let attachments = service.repo.list_attachments(&welcome.id).await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].filename, "my chart.png");
+        assert_eq!(attachments[0].mime_type, "image/png");
+        let content: Value = serde_json::from_str(&welcome.content_json).unwrap();
+        let embed = json!({ "attachment-image": {
+            "attachmentId": attachments[0].id,
+            "blobHash": attachments[0].blob_hash,
+            "mimeType": "image/png",
+            "filename": "my chart.png",
+        } });
+        assert_eq!(
+            content["ops"],
+            json!([
+                { "insert": "Hi" },
+                { "insert": "\n", "attributes": { "header": 1 } },
+                { "insert": embed },
+                { "insert": " " },
+                { "insert": embed },
+                { "insert": " " },
+                { "insert": "gone" },
+                { "insert": " " },
+                { "insert": { "image": "https://example.com/x.png" } },
+                { "insert": "\n" }
+            ])
+        );
+
+        let plan = note("Plan");
+        assert_eq!(plan.collection_id.as_deref(), Some(work.id.as_str()));
+        assert_eq!(
+            plan.content_json,
+            json!({ "ops": [{ "insert": "Step one\nStep two\n" }] }).to_string()
+        );
+        assert_eq!(
+            note("Nested").collection_id.as_deref(),
+            Some(work.id.as_str())
+        );
+        assert_eq!(
+            note("idea").collection_id.as_deref(),
+            Some(personal.as_str())
+        );
+
+        // Imported notes are searchable
+        let query = SearchQuery::parse("\"Step two\"").unwrap();
+        let found = service.repo.search_notes_query(&query).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, plan.id);
+
+        let error = service
+            .import_folder(Path::new("relative/notes"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Import(_)));
+        let error = service
+            .import_folder(&root.join("Welcome.md"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Import(_)));
+    }
+
+    #[tokio::test]
+    async fn test_import_folder_discards_failed_notes() {
+        let (service, pool, temp_dir) = create_failing_service().await;
+        let root = temp_dir.path().join("notes");
+        write(root.join("photo.png"), b"png").await;
+        write(
+            root.join("broken.md"),
+            b"![Photo](photo.png) and [[Broken]]\n",
+        )
+        .await;
+        write(root.join("fine.md"), b"![Photo](photo.png)\n").await;
+
+        let result = service.import_folder(&root).await.unwrap();
+        assert_eq!(result.notes_imported, 1);
+        assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+        assert!(result.errors[0].contains("'broken.md'"));
+
+        // Nothing of the failed note is left
+        assert_eq!(count_rows(&pool, "notes").await, 1);
+        assert_eq!(count_rows(&pool, "notes_fts").await, 1);
+        assert_eq!(count_rows(&pool, "attachments").await, 1);
+        let notes = service.repo.list_notes().await.unwrap();
+        assert_eq!(notes[0].title, "fine");
+    }
+
+    #[tokio::test]
+    async fn test_import_enex() {
+        let (service, temp_dir) = create_test_service().await;
+        let root = temp_dir.path().join("evernote");
+
+        // "hello world" as a text file and "png bytes" as an image
+        let enex = r#"<?xml version="1.0" encoding="UTF-8"?>
+<en-export>
+  <note>
+    <title>Trip</title>
+    <created>20240301T090000Z</created>
+    <updated>20240302T103000Z</updated>
+    <tag>Travel</tag>
+    <content><![CDATA[<en-note><div>Map: <en-media hash="847bee05ce221ee20516f48581e44313" type="image/png"/></div><div><en-media hash="5eb63bbbe01eeed093cb22bb8f5acdc3" type="text/plain"/></div><div><en-media hash="ffffffffffffffffffffffffffffffff" type="image/png"/></div></en-note>]]></content>
+    <resource><data encoding="base64">cG5nIGJ5dGVz</data><mime>image/png</mime>
+      <resource-attributes><file-name>map.png</file-name></resource-attributes></resource>
+    <resource><data encoding="base64">aGVsbG8gd29ybGQ=</data><mime>text/plain</mime></resource>
+    <resource><data encoding="base64">%%%</data><mime>image/png</mime></resource>
+  </note>
+  <note>
+    <title>Broken</title>
+    <content><![CDATA[<en-note><div]]></content>
+  </note>
+  <note>
+    <title>Untagged</title>
+    <content><![CDATA[<en-note>Plain</en-note>]]></content>
+  </note>
+</en-export>"#;
+        write(root.join("Holidays.enex"), enex.as_bytes()).await;
+        write(root.join("Corrupt.enex"), b"<en-export><note>").await;
+        write(root.join("notes.txt"), b"skipped").await;
+
+        let result = service
+            .import_enex(&root, EnexCollectionSource::Notebook)
+            .await
+            .unwrap();
+        assert_eq!(result.notes_imported, 2);
+        assert_eq!(result.collections_created, 1);
+        assert_eq!(result.errors.len(), 3, "{:?}", result.errors);
+        assert!(result.errors.iter().any(|e| e.contains("'Corrupt.enex'")));
+        assert!(result.errors.iter().any(|e| e.contains("'Broken'")));
+        assert!(result.errors.iter().any(|e| e.contains("base64")));
+        let holidays = &result.sections_mapped["Holidays"];
+
+        let notes = service.repo.list_notes().await.unwrap();
+        assert_eq!(notes.len(), 2);
+        let trip = notes.iter().find(|n| n.title == "Trip").unwrap();
+        assert_eq!(trip.collection_id.as_deref(), Some(holidays.as_str()));
+        assert_eq!(
+            trip.created_at,
+            Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            trip.updated_at,
+            Utc.with_ymd_and_hms(2024, 3, 2, 10, 30, 0).unwrap()
+        );
+
+        // Resources become attachments; media without one is dropped
+        let attachments = service.repo.list_attachments(&trip.id).await.unwrap();
+        assert_eq!(attachments.len(), 2);
+        let map = attachments
+            .iter()
+            .find(|a| a.filename == "map.png")
+            .unwrap();
+        let text = attachments
+            .iter()
+            .find(|a| a.mime_type == "text/plain")
+            .unwrap();
+        assert_eq!(text.size, 11);
+        let content: Value = serde_json::from_str(&trip.content_json).unwrap();
+        assert_eq!(
+            content["ops"],
+            json!([
+                { "insert": "Map: " },
+                { "insert": { "attachment-image": {
+                    "attachmentId": map.id,
+                    "blobHash": map.blob_hash,
+                    "mimeType": "image/png",
+                    "filename": "map.png",
+                } } },
+                { "insert": "\n" },
+                { "insert": { "attachment-file": {
+                    "attachmentId": text.id,
+                    "blobHash": text.blob_hash,
+                    "mimeType": "text/plain",
+                    "filename": "attachment.txt",
+                    "size": 11,
+                } } },
+                { "insert": "\n\n" }
+            ])
+        );
+
+        // Grouping by tag reuses collections by name and leaves untagged
+        // notes out
+        let file = root.join("Holidays.enex");
+        let result = service
+            .import_enex(&file, EnexCollectionSource::Tag)
+            .await
+            .unwrap();
+        assert_eq!(result.notes_imported, 2);
+        assert_eq!(result.collections_created, 1);
+        let notes = service.repo.list_notes().await.unwrap();
+        let untagged: Vec<_> = notes.iter().filter(|n| n.title == "Untagged").collect();
+        assert_eq!(untagged.len(), 2);
+        assert_eq!(
+            untagged
+                .iter()
+                .filter(|n| n.collection_id.is_none())
+                .count(),
+            1
+        );
+        let travel = &result.sections_mapped["Travel"];
+        assert!(notes
+            .iter()
+            .any(|n| n.collection_id.as_deref() == Some(travel.as_str())));
+
+        let error = service
+            .import_enex(Path::new("Holidays.enex"), EnexCollectionSource::Notebook)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Import(_)));
+        let error = service
+            .import_enex(&root.join("missing.enex"), EnexCollectionSource::Notebook)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Import(_)));
+    }
+
+    #[tokio::test]
+    async fn test_import_enex_discards_failed_notes() {
+        let (service, pool, temp_dir) = create_failing_service().await;
+        let path = temp_dir.path().join("Links.enex");
+        let enex = r#"<en-export>
+  <note>
+    <title>Links</title>
+    <content><![CDATA[<en-note><en-media hash="847bee05ce221ee20516f48581e44313" type="image/png"/> and [[Broken]]</en-note>]]></content>
+    <resource><data encoding="base64">cG5nIGJ5dGVz</data><mime>image/png</mime></resource>
+  </note>
+  <note>
+    <title>Fine</title>
+    <content><![CDATA[<en-note><en-media hash="847bee05ce221ee20516f48581e44313" type="image/png"/></en-note>]]></content>
+    <resource><data encoding="base64">cG5nIGJ5dGVz</data><mime>image/png</mime></resource>
+  </note>
+</en-export>"#;
+        write(path.clone(), enex.as_bytes()).await;
+
+        let result = service
+            .import_enex(&path, EnexCollectionSource::Tag)
+            .await
+            .unwrap();
+        assert_eq!(result.notes_imported, 1);
+        assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+        assert!(result.errors[0].contains("'Links'"));
+
+        // Nothing of the failed note is left
+        assert_eq!(count_rows(&pool, "notes").await, 1);
+        assert_eq!(count_rows(&pool, "notes_fts").await, 1);
+        assert_eq!(count_rows(&pool, "attachments").await, 1);
+        let notes = service.repo.list_notes().await.unwrap();
+        assert_eq!(notes[0].title, "Fine");
+    }
+
+    #[tokio::test]
+    async fn test_import_onenote_export() {
+        let (service, temp_dir) = create_test_service().await;
+        let root = temp_dir.path().join("onenote");
+        let hierarchy = include_str!("../../tests/fixtures/onenote/hierarchy.xml");
+        let page = include_str!("../../tests/fixtures/onenote/page.xml");
+
+        // Retro and the untitled page are in the hierarchy but weren't exported
+        write(root.join("hierarchy.xml"), hierarchy.as_bytes()).await;
+        write(root.join("pages/standup.xml"), page.as_bytes()).await;
+        write(
+            root.join("Loose/idea.xml"),
+            br#"<one:Page xmlns:one="x" ID="{PAGE-LOOSE}" name="Loose idea"><one:Outline><one:OEChildren><one:OE><one:T><![CDATA[Try <b>this</b>]]></one:T></one:OE></one:OEChildren></one:Outline></one:Page>"#,
+        )
+        .await;
+        write(root.join("notes.xml"), b"<html/>").await;
+
+        let result = service.import_onenote_export(&root).await.unwrap();
+        assert_eq!(result.notes_imported, 2);
+        assert_eq!(result.errors.len(), 3, "{:?}", result.errors);
+        assert!(result.errors.iter().any(|e| e.contains("'notes.xml'")));
+        assert!(result.errors.iter().any(|e| e.contains("'Retro'")));
+        assert!(result.errors.iter().any(|e| e.contains("'Untitled Page'")));
+        // Meetings, Old projects, Ideas and the Loose folder
+        assert_eq!(result.collections_created, 4);
+
+        let notes = service.repo.list_notes().await.unwrap();
+        let note = |title: &str| notes.iter().find(|n| n.title == title).unwrap();
+
+        let standup = note("Standup & planning");
+        assert_eq!(
+            standup.collection_id.as_ref(),
+            Some(&result.sections_mapped["Meetings"])
+        );
+        assert_eq!(
+            standup.created_at,
+            Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            standup.updated_at,
+            Utc.with_ymd_and_hms(2024, 3, 2, 10, 30, 0).unwrap()
+        );
+
+        // The page's image is stored and embedded where it was
+        let attachments = service.repo.list_attachments(&standup.id).await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].filename, "image1.png");
+        assert_eq!(attachments[0].mime_type, "image/png");
+        let content: Value = serde_json::from_str(&standup.content_json).unwrap();
+        let ops = content["ops"].as_array().unwrap();
+        let image = ops
+            .iter()
+            .position(|op| op["insert"].get("attachment-image").is_some())
+            .unwrap();
+        assert_eq!(ops[image - 1]["insert"], "Board photo: ");
+        assert_eq!(
+            ops[image]["insert"]["attachment-image"]["blobHash"],
+            json!(attachments[0].blob_hash)
+        );
+
+        let loose = note("Loose idea");
+        assert_eq!(
+            loose.collection_id.as_ref(),
+            Some(&result.sections_mapped["Loose"])
+        );
+        let content: Value = serde_json::from_str(&loose.content_json).unwrap();
+        assert_eq!(
+            content["ops"],
+            json!([
+                { "insert": "Try " },
+                { "insert": "this", "attributes": { "bold": true } },
+                { "insert": "\n" }
+            ])
+        );
+
+        // The COM path hands over page XML as it's fetched
+        let result = service
+            .import_onenote_hierarchy(hierarchy, |page_info| {
+                if page_info.id == "{PAGE-STANDUP}" {
+                    Ok(page.to_string())
+                } else {
+                    Err(AppError::Generic("OneNote isn't running".to_string()))
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.notes_imported, 1);
+        assert_eq!(result.collections_created, 0);
+        assert_eq!(result.errors.len(), 2);
+
+        let error = service
+            .import_onenote_export(Path::new("onenote"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, AppError::Import(_)));
+    }
+
+    #[tokio::test]
+    async fn test_import_bundle() {
+        use crate::services::export::{ExportScope, ExportService};
+
+        // Another machine's library
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+        let source_dir = TempDir::new().unwrap();
+        let source_blobs = BlobStore::new(source_dir.path().join("blobs"));
+        source_blobs.initialize().await.unwrap();
+        let source = Repository::new(pool);
+
+        let trip = source
+            .create_collection(CreateCollectionRequest {
+                name: "Trip".to_string(),
+                description: None,
+                color: Some("#10B981".to_string()),
+                icon: Some("map".to_string()),
+            })
+            .await
+            .unwrap();
+        let books = source
+            .create_collection(CreateCollectionRequest {
+                name: "Books".to_string(),
+                description: Some("Reading list".to_string()),
+                color: Some("#8B5CF6".to_string()),
+                icon: None,
+            })
+            .await
+            .unwrap();
+        let image = source_blobs.write(b"png").await.unwrap();
+        let plan = source
+            .create_note(CreateNoteRequest {
+                title: "Plan".to_string(),
+                content_json: json!({ "ops": [
+                    { "insert": "Pack, see [[Loose]]\n" },
+                    { "insert": { "attachment-image": {
+                        "blobHash": image, "mimeType": "image/png", "filename": "map.png"
+                    } } },
+                    { "insert": "\n" }
+                ] })
+                .to_string(),
+                collection_id: Some(trip.id.clone()),
+            })
+            .await
+            .unwrap();
+        let map = source
+            .create_attachment(&plan.id, &image, "map.png", "image/png", 3)
+            .await
+            .unwrap();
+        let tag = source.create_tag("q4", Some("#EF4444")).await.unwrap();
+        source.add_tag_to_note(&plan.id, &tag.id).await.unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        source.set_journal_note(day, &plan.id).await.unwrap();
+        source
+            .create_reminder(
+                &plan.id,
+                Utc::now() + chrono::Duration::days(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        source
+            .create_reminder(
+                &plan.id,
+                Utc::now() - chrono::Duration::days(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let loose = source
+            .create_note(CreateNoteRequest {
+                title: "Loose".to_string(),
+                content_json: json!({ "ops": [{ "insert": "Loose\n" }] }).to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        // Links and embeds by ID
+        source
+            .update_note(crate::database::UpdateNoteRequest {
+                id: plan.id.clone(),
+                title: None,
+                content_json: Some(
+                    json!({ "ops": [
+                        { "insert": format!("Pack, see [[{}]] and ", loose.id) },
+                        { "insert": "this", "attributes": { "link": format!("note://{}", loose.id) } },
+                        { "insert": "\n" },
+                        { "insert": { "attachment-image": {
+                            "blobHash": image, "mimeType": "image/png", "filename": "map.png",
+                            "attachmentId": map.id
+                        } } },
+                        { "insert": "\n" }
+                    ] })
+                    .to_string(),
+                ),
+                title_modified: None,
+            })
+            .await
+            .unwrap();
+
+        let report = ExportService::new(source, source_blobs)
+            .export_bundle(ExportScope::All, &source_dir.path().join("out"))
+            .await
+            .unwrap();
+        assert_eq!(report.notes_exported, 2);
+        assert_eq!(report.blobs_exported, 1);
+        assert!(report.errors.is_empty());
+        let bundle_path = PathBuf::from(&report.path);
+        assert_eq!(bundle_path.file_name().unwrap(), "All notes.zip");
+
+        // This library already has a "trip" collection
+        let (service, _temp) = create_test_service().await;
+        let existing_trip = service
+            .repo
+            .create_collection(CreateCollectionRequest {
+                name: "trip".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+
+        let result = service.import_bundle(&bundle_path).await.unwrap();
+        assert_eq!(result.notes_imported, 2);
+        assert_eq!(result.collections_created, 1);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+        assert_eq!(result.sections_mapped["Trip"], existing_trip.id);
+
+        let books = service
+            .repo
+            .get_collection(&result.sections_mapped[&books.name])
+            .await
+            .unwrap();
+        assert_eq!(books.color, "#8B5CF6");
+        assert_eq!(books.description.as_deref(), Some("Reading list"));
+
+        let notes = service.repo.list_notes().await.unwrap();
+        let imported = notes.iter().find(|n| n.title == "Plan").unwrap();
+        assert_ne!(imported.id, plan.id);
+        assert_eq!(
+            imported.collection_id.as_deref(),
+            Some(existing_trip.id.as_str())
+        );
+        assert_eq!(imported.created_at, plan.created_at);
+
+        let attachments = service.repo.list_attachments(&imported.id).await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(
+            service
+                .attachments
+                .get_attachment_by_hash(&attachments[0].blob_hash)
+                .await
+                .unwrap(),
+            b"png"
+        );
+        let imported_loose = notes.iter().find(|n| n.title == "Loose").unwrap();
+        let links = service
+            .repo
+            .list_outgoing_links(&imported.id)
+            .await
+            .unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(
+            links[0].target_note_id.as_deref(),
+            Some(imported_loose.id.as_str())
+        );
+
+        // The content points at the imported note and attachment
+        let content: Value = serde_json::from_str(&imported.content_json).unwrap();
+        let ops = content["ops"].as_array().unwrap();
+        assert_eq!(
+            ops[0]["insert"],
+            json!(format!("Pack, see [[{}]] and ", imported_loose.id))
+        );
+        assert_eq!(
+            ops[1]["attributes"]["link"],
+            json!(format!("note://{}", imported_loose.id))
+        );
+        assert_eq!(
+            ops[3]["insert"]["attachment-image"]["attachmentId"],
+            json!(attachments[0].id)
+        );
+        assert_ne!(attachments[0].id, map.id);
+        assert!(!imported.content_json.contains(&loose.id));
+        let tags = service.repo.list_tags_for_note(&imported.id).await.unwrap();
+        assert_eq!(tags[0].name, "q4");
+        assert_eq!(tags[0].color, "#EF4444");
+        // The reminder that came due since the export doesn't fire
+        let active = service.repo.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].note_id, imported.id);
+
+        // The daily note of the source is the daily note here too
+        assert_eq!(
+            service.repo.get_journal_note_id(day).await.unwrap(),
+            Some(imported.id.clone())
+        );
+
+        // Importing again adds copies instead of replacing anything
+        let result = service.import_bundle(&bundle_path).await.unwrap();
+        assert_eq!(result.notes_imported, 2);
+        assert_eq!(result.collections_created, 0);
+        assert_eq!(service.repo.list_notes().await.unwrap().len(), 4);
+        assert_eq!(
+            service.repo.get_journal_note_id(day).await.unwrap(),
+            Some(imported.id.clone())
+        );
+
+        assert!(matches!(
+            service.import_bundle(Path::new("relative.zip")).await,
+            Err(AppError::Import(_))
+        ));
+    }
+
+    #[test]
+    fn test_split_front_matter() {
+        let (front_matter, body) = split_front_matter(
+            "---\ntitle: It's 'here' # a comment\ndate: 2024-05-06\n\
+             modified: \"2024-05-07 08:09\"\nnested:\n  title: ignored\n...\nBody\n",
+        );
+        let local = |text: &str| {
+            let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+            recurrence::local_to_utc(&Local, naive)
+        };
+        assert_eq!(
+            front_matter,
+            FrontMatter {
+                title: Some("It's 'here'".to_string()),
+                created_at: local("2024-05-06 00:00"),
+                updated_at: local("2024-05-07 08:09"),
+            }
+        );
+        assert_eq!(body, "Body\n");
+
+        // Unclosed front matter is just Markdown
+        let text = "---\ntitle: x\n";
+        assert_eq!(split_front_matter(text), (FrontMatter::default(), text));
+        assert_eq!(
+            split_front_matter("# No front matter").1,
+            "# No front matter"
+        );
+
+        assert_eq!(
+            local_path("../a%20b.png?raw=1"),
+            Some(PathBuf::from("../a b.png"))
+        );
+        assert_eq!(
+            local_path("file:///tmp/x.png"),
+            Some(PathBuf::from("/tmp/x.png"))
+        );
+        assert_eq!(
+            local_path("C:/pics/x.png"),
+            Some(PathBuf::from("C:/pics/x.png"))
+        );
+        assert_eq!(local_path("https://example.com/x.png"), None);
+        assert_eq!(local_path("data:image/png;base64,AAAA"), None);
+    }
+}
@@ -0,0 +1,295 @@
+//! Inline reminders
+//!
+//! Finds reminder markers written in note text, such as
+//! `@remind 2026-11-03 14:00 call supplier`, and lines them up with the
+//! reminders already created from the note's markers. A marker runs from
+//! `@remind` to the end of its line (or the next marker): a local date, an
+//! optional 24-hour time (`DEFAULT_REMINDER_HOUR` without one) and a label.
+//!
+//! Markers are found in the note's plain text as
+//! `Repository::extract_text_from_delta` produces it. Spans are offsets into
+//! that text in UTF-16 code units, like JavaScript string indices.
+
+use crate::config;
+use crate::database::Reminder;
+use crate::services::recurrence;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// An `@remind` marker found in note text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineMarker {
+    /// The marker as written, without trailing whitespace
+    pub text: String,
+    /// Start of the marker (UTF-16 offset)
+    pub start: u32,
+    /// End of the marker (UTF-16 offset, exclusive)
+    pub end: u32,
+    /// Local date and time as written; None while they can't be read
+    pub local_time: Option<NaiveDateTime>,
+    /// When it fires
+    pub trigger_time: Option<DateTime<Utc>>,
+    /// What follows the date and time
+    pub label: String,
+}
+
+/// How a note's markers line up with its inline reminders
+#[derive(Debug, Default)]
+pub struct InlineMatch<'a> {
+    /// Markers and the reminder created from them
+    pub linked: Vec<(&'a Reminder, &'a InlineMarker)>,
+    /// Markers with no reminder yet
+    pub added: Vec<&'a InlineMarker>,
+    /// Reminders whose marker is gone
+    pub removed: Vec<&'a Reminder>,
+}
+
+/// Find the `@remind` markers in plain note text, in order, reading their
+/// dates and times in `tz`
+pub fn find_markers<Tz: TimeZone>(text: &str, tz: &Tz) -> Vec<InlineMarker> {
+    let keyword = config::INLINE_REMINDER_MARKER;
+    let mut markers = Vec::new();
+    let mut line_start = 0;
+
+    for line in text.split('\n') {
+        let starts: Vec<usize> = line
+            .match_indices('@')
+            .map(|(i, _)| i)
+            .filter(|&i| {
+                let after = i + keyword.len();
+                line.get(i..after)
+                    .is_some_and(|word| word.eq_ignore_ascii_case(keyword))
+                    && line[..i]
+                        .chars()
+                        .next_back()
+                        .is_none_or(char::is_whitespace)
+                    && line[after..].chars().next().is_none_or(char::is_whitespace)
+            })
+            .collect();
+
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(line.len());
+            let marker = line[start..end].trim_end();
+            let (local_time, label) = split_marker(&marker[keyword.len()..]);
+            let offset = utf16_len(&text[..line_start + start]);
+            markers.push(InlineMarker {
+                text: marker.to_string(),
+                start: offset,
+                end: offset + utf16_len(marker),
+                local_time,
+                trigger_time: local_time.and_then(|local| recurrence::local_to_utc(tz, local)),
+                label: label.to_string(),
+            });
+        }
+
+        line_start += line.len() + 1;
+    }
+
+    markers
+}
+
+/// Pair markers with the reminders created from them (`reminders` in text
+/// order): first unchanged markers, then markers that kept their label or
+/// their date and time, then whatever is left in text order, so an edited
+/// marker keeps its reminder
+pub fn match_markers<'a>(
+    reminders: &'a [Reminder],
+    markers: &'a [InlineMarker],
+) -> InlineMatch<'a> {
+    let mut reminder_for: Vec<Option<usize>> = vec![None; markers.len()];
+    let mut used = vec![false; reminders.len()];
+
+    let mut pair = |same: &dyn Fn(&Reminder, &InlineMarker) -> bool| {
+        for (m, marker) in markers.iter().enumerate() {
+            if reminder_for[m].is_some() {
+                continue;
+            }
+            if let Some(r) = (0..reminders.len()).find(|&r| !used[r] && same(&reminders[r], marker))
+            {
+                reminder_for[m] = Some(r);
+                used[r] = true;
+            }
+        }
+    };
+    pair(&|reminder, marker| reminder.inline_text.as_deref() == Some(marker.text.as_str()));
+    pair(&|reminder, marker| !marker.label.is_empty() && linked_marker(reminder).1 == marker.label);
+    pair(&|reminder, marker| {
+        marker.local_time.is_some() && linked_marker(reminder).0 == marker.local_time
+    });
+    pair(&|_, _| true);
+
+    let mut matched = InlineMatch::default();
+    for (marker, reminder) in markers.iter().zip(reminder_for) {
+        match reminder {
+            Some(r) => matched.linked.push((&reminders[r], marker)),
+            None => matched.added.push(marker),
+        }
+    }
+    matched.removed = reminders
+        .iter()
+        .zip(used)
+        .filter(|(_, used)| !used)
+        .map(|(reminder, _)| reminder)
+        .collect();
+    matched
+}
+
+/// The local date and time and the label of the marker a reminder was last
+/// linked to
+pub fn linked_marker(reminder: &Reminder) -> (Option<NaiveDateTime>, &str) {
+    reminder
+        .inline_text
+        .as_deref()
+        .and_then(|text| text.get(config::INLINE_REMINDER_MARKER.len()..))
+        .map_or((None, ""), split_marker)
+}
+
+/// Split the text after `@remind` into its local date and time and its label
+fn split_marker(body: &str) -> (Option<NaiveDateTime>, &str) {
+    let (first, rest) = next_word(body);
+    let Ok(date) = NaiveDate::parse_from_str(first, "%Y-%m-%d") else {
+        return (None, body.trim());
+    };
+
+    let (second, after_time) = next_word(rest);
+    let (time, label) = match NaiveTime::parse_from_str(second, "%H:%M") {
+        Ok(time) => (time, after_time),
+        Err(_) => (
+            NaiveTime::from_hms_opt(config::DEFAULT_REMINDER_HOUR, 0, 0).unwrap_or(NaiveTime::MIN),
+            rest,
+        ),
+    };
+
+    (Some(date.and_time(time)), label.trim())
+}
+
+/// Split off the first whitespace-separated word
+fn next_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    text.split_at(end)
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    fn at(local: &str) -> DateTime<Utc> {
+        let naive = chrono::NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap();
+        New_York
+            .from_local_datetime(&naive)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn linked(id: &str, text: &str) -> Reminder {
+        let marker = find_markers(text, &New_York).remove(0);
+        Reminder {
+            id: id.to_string(),
+            note_id: "note".to_string(),
+            trigger_time: marker.trigger_time.unwrap_or_else(Utc::now),
+            triggered: false,
+            created_at: Utc::now(),
+            sound_enabled: None,
+            sound_type: None,
+            shake_enabled: None,
+            glow_enabled: None,
+            recurrence_rule: None,
+            recurrence_start: None,
+            snoozed_until: None,
+            missed_policy: None,
+            missed_skip_after_hours: None,
+            inline_text: Some(marker.text),
+            inline_start: Some(marker.start),
+            inline_end: Some(marker.end),
+        }
+    }
+
+    #[test]
+    fn test_find_markers() {
+        let text = "Todo\n@remind 2026-11-03 14:00 call supplier  \n\
+                    ok @REMIND 2026-11-04 order ☕ @remind 2026-11-05 09:30\n\
+                    me@remind 2026-11-06, @reminder 2026-11-07, @remind soon";
+        let markers = find_markers(text, &New_York);
+        assert_eq!(markers.len(), 4);
+
+        assert_eq!(markers[0].text, "@remind 2026-11-03 14:00 call supplier");
+        assert_eq!(markers[0].trigger_time, Some(at("2026-11-03 14:00")));
+        assert_eq!(markers[0].label, "call supplier");
+        assert_eq!((markers[0].start, markers[0].end), (5, 43));
+
+        // No time means the default hour; a marker ends at the next one
+        assert_eq!(markers[1].text, "@REMIND 2026-11-04 order ☕");
+        assert_eq!(markers[1].trigger_time, Some(at("2026-11-04 09:00")));
+        assert_eq!(markers[1].label, "order ☕");
+        assert_eq!(markers[2].trigger_time, Some(at("2026-11-05 09:30")));
+        assert_eq!(markers[2].label, "");
+
+        // Spans count UTF-16 code units
+        let start = text.find("@remind 2026-11-05").unwrap();
+        assert_eq!(markers[2].start, utf16_len(&text[..start]));
+
+        // Unreadable dates still make a marker, so its reminder is kept
+        assert_eq!(markers[3].text, "@remind soon");
+        assert_eq!(markers[3].trigger_time, None);
+        assert_eq!(markers[3].label, "soon");
+
+        assert!(find_markers("no markers here", &New_York).is_empty());
+    }
+
+    #[test]
+    fn test_match_markers() {
+        let reminders = vec![
+            linked("call", "@remind 2026-11-03 14:00 call supplier"),
+            linked("order", "@remind 2026-11-04 order coffee"),
+            linked("gone", "@remind 2026-11-05 pay rent"),
+        ];
+
+        // The call moved to another time and the order was relabelled; the
+        // rent marker was rewritten into a new one above the others
+        let markers = find_markers(
+            "@remind 2026-11-01 book flights\n\
+             @remind 2026-11-03 16:00 call supplier\n\
+             @remind 2026-11-04 order tea",
+            &New_York,
+        );
+        let matched = match_markers(&reminders, &markers);
+
+        let pairs: Vec<(&str, &str)> = matched
+            .linked
+            .iter()
+            .map(|(reminder, marker)| (reminder.id.as_str(), marker.label.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("gone", "book flights"),
+                ("call", "call supplier"),
+                ("order", "order tea"),
+            ]
+        );
+        assert!(matched.added.is_empty());
+        assert!(matched.removed.is_empty());
+
+        // Unchanged markers keep their reminders whatever else changes
+        let markers = find_markers("@remind 2026-11-04 order coffee", &New_York);
+        let matched = match_markers(&reminders, &markers);
+        assert_eq!(matched.linked[0].0.id, "order");
+        let removed: Vec<&str> = matched.removed.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(removed, vec!["call", "gone"]);
+
+        let matched = match_markers(&[], &markers);
+        assert_eq!(matched.added.len(), 1);
+
+        let (local_time, label) = linked_marker(&reminders[0]);
+        assert_eq!(
+            local_time.map(|t| t.to_string()).as_deref(),
+            Some("2026-11-03 14:00:00")
+        );
+        assert_eq!(label, "call supplier");
+    }
+}
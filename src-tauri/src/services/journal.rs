@@ -0,0 +1,349 @@
+//! Daily notes (journal)
+//!
+//! Each local date can have one journal note. Notes are found again through
+//! the `journal_entries` table rather than by title, so renaming a daily
+//! note or changing the title format doesn't lose track of it. A deleted
+//! daily note frees its date for a new one.
+
+use crate::config;
+use crate::database::{CreateCollectionRequest, JournalEntry, Note, Repository};
+use crate::error::{AppError, Result};
+use crate::services::notes::NotesService;
+use crate::services::settings::JournalSettings;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone};
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Content of daily notes created without a template
+const EMPTY_CONTENT: &str = r#"{"ops":[{"insert":"\n"}]}"#;
+
+/// Service for daily notes
+#[derive(Clone)]
+pub struct JournalService {
+    repo: Repository,
+    notes: NotesService,
+    /// Held while looking up or creating a daily note, so a double-pressed
+    /// hotkey doesn't create two notes for the same day
+    create_lock: Arc<Mutex<()>>,
+}
+
+impl JournalService {
+    pub fn new(repo: Repository, notes: NotesService) -> Self {
+        Self {
+            repo,
+            notes,
+            create_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Today's local date
+    pub fn today() -> NaiveDate {
+        Local::now().date_naive()
+    }
+
+    /// Get a date's daily note, creating it if the date has none: titled
+    /// by the title format, in the journal collection, and from the journal
+    /// template if one is set
+    pub async fn get_or_create_daily_note(
+        &self,
+        date: NaiveDate,
+        settings: &JournalSettings,
+    ) -> Result<Note> {
+        let _guard = self.create_lock.lock().await;
+
+        if let Some(note_id) = self.repo.get_journal_note_id(date).await? {
+            return self.notes.get_note(&note_id).await;
+        }
+
+        let title = format_title(&settings.title_format, date)?;
+        let collection_id = self.journal_collection(settings).await?;
+        tracing::info!("Creating daily note for {}", date);
+
+        let template_id = match &settings.template_id {
+            Some(id) => match self.repo.get_template(id).await {
+                Ok(template) => Some(template.id),
+                Err(e) => {
+                    tracing::warn!("Journal template {} not found: {}", id, e);
+                    // Create a blank daily note rather than none
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let note = match template_id {
+            Some(template_id) => {
+                self.notes
+                    .create_note_from_template_at(
+                        &template_id,
+                        Some(collection_id),
+                        None,
+                        &at_current_time(date),
+                        Some(title),
+                    )
+                    .await?
+            }
+            None => {
+                let note = self
+                    .notes
+                    .create_note(title, EMPTY_CONTENT.to_string(), Some(collection_id))
+                    .await?;
+                self.notes
+                    .update_note(note.id, None, None, Some(true))
+                    .await?
+            }
+        };
+
+        self.repo.set_journal_note(date, &note.id).await?;
+        Ok(note)
+    }
+
+    /// The nearest earlier (or with `forward`, later) date with a daily note
+    pub async fn adjacent_daily_note(
+        &self,
+        date: NaiveDate,
+        forward: bool,
+    ) -> Result<Option<JournalEntry>> {
+        self.repo.adjacent_journal_entry(date, forward).await
+    }
+
+    /// The dates from `from` to `to` (inclusive) that have daily notes
+    pub async fn list_daily_notes(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<JournalEntry>> {
+        if from > to {
+            return Err(AppError::Generic(format!(
+                "Invalid date range: {} is after {}",
+                from, to
+            )));
+        }
+        self.repo.list_journal_entries(from, to).await
+    }
+
+    /// ID of the collection daily notes go into: the chosen one, or else
+    /// one named `config::JOURNAL_COLLECTION_NAME`, created if missing
+    async fn journal_collection(&self, settings: &JournalSettings) -> Result<String> {
+        if let Some(id) = &settings.collection_id {
+            match self.repo.get_collection(id).await {
+                Ok(collection) => return Ok(collection.id),
+                Err(e) => tracing::warn!("Journal collection {} not found: {}", id, e),
+            }
+        }
+
+        if let Some(collection) = self
+            .repo
+            .get_collection_by_name(config::JOURNAL_COLLECTION_NAME)
+            .await?
+        {
+            return Ok(collection.id);
+        }
+
+        let collection = self
+            .repo
+            .create_collection(CreateCollectionRequest {
+                name: config::JOURNAL_COLLECTION_NAME.to_string(),
+                description: Some("Daily notes".to_string()),
+                color: None,
+                icon: None,
+            })
+            .await?;
+        Ok(collection.id)
+    }
+}
+
+/// Title of a date's daily note, from a chrono strftime format
+pub fn format_title(format: &str, date: NaiveDate) -> Result<String> {
+    let format = format.trim();
+    if format.is_empty() {
+        return Err(AppError::Generic(
+            "Daily note title format must not be empty".to_string(),
+        ));
+    }
+    if format.chars().count() > config::MAX_JOURNAL_TITLE_FORMAT_LENGTH {
+        return Err(AppError::Generic(format!(
+            "Daily note title format exceeds maximum length of {} characters",
+            config::MAX_JOURNAL_TITLE_FORMAT_LENGTH
+        )));
+    }
+
+    // Unknown specifiers and time fields make formatting fail
+    let mut title = String::new();
+    write!(title, "{}", date.format(format))
+        .map_err(|_| AppError::Generic(format!("Invalid daily note title format: {}", format)))?;
+    Ok(title)
+}
+
+/// `date` at the current local time, for template time variables
+fn at_current_time(date: NaiveDate) -> DateTime<Local> {
+    let now = Local::now();
+    at_time(date, now.time(), &Local).unwrap_or(now)
+}
+
+/// `date` at `time` in `tz`, an hour later or earlier on the same date when
+/// `time` falls in a DST gap
+fn at_time<Tz: TimeZone>(date: NaiveDate, time: NaiveTime, tz: &Tz) -> Option<DateTime<Tz>> {
+    let at = date.and_time(time);
+    [at, at + Duration::hours(1), at - Duration::hours(1)]
+        .into_iter()
+        .filter(|at| at.date() == date)
+        .find_map(|at| tz.from_local_datetime(&at).earliest())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{initialize_database, SaveTemplateRequest};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn create_test_service() -> (JournalService, NotesService) {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+
+        let repo = Repository::new(pool);
+        let notes = NotesService::new(repo.clone());
+        (JournalService::new(repo, notes.clone()), notes)
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_format_title() {
+        assert_eq!(format_title("%Y-%m-%d", date(17)).unwrap(), "2026-10-17");
+        assert_eq!(
+            format_title(" %A, %B %-d ", date(7)).unwrap(),
+            "Wednesday, October 7"
+        );
+        assert!(format_title("  ", date(7)).is_err());
+        assert!(format_title("%Y %H:%M", date(7)).is_err());
+        assert!(format_title("%Q", date(7)).is_err());
+        assert!(format_title(&"%Y".repeat(60), date(7)).is_err());
+    }
+
+    #[test]
+    fn test_at_time_in_dst_gap() {
+        use chrono_tz::America::New_York;
+
+        let day = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        // 02:30 doesn't exist that day in New York
+        let at = at_time(day, time(2, 30), &New_York).unwrap();
+        assert_eq!(at.date_naive(), day);
+        assert_eq!(at.time(), time(3, 30));
+        let at = at_time(day, time(23, 30), &New_York).unwrap();
+        assert_eq!(at.time(), time(23, 30));
+    }
+
+    #[tokio::test]
+    async fn test_get_or_create_daily_note() {
+        let (journal, notes) = create_test_service().await;
+        let settings = JournalSettings {
+            title_format: "%a %-d %b %Y".to_string(),
+            ..JournalSettings::default()
+        };
+
+        let note = journal
+            .get_or_create_daily_note(date(17), &settings)
+            .await
+            .unwrap();
+        assert_eq!(note.title, "Sat 17 Oct 2026");
+        assert!(note.title_modified);
+
+        // The same note comes back, even after a rename
+        notes
+            .rename_note(&note.id, "Busy Saturday", false)
+            .await
+            .unwrap();
+        let again = journal
+            .get_or_create_daily_note(date(17), &settings)
+            .await
+            .unwrap();
+        assert_eq!(again.id, note.id);
+
+        // Daily notes share the journal collection
+        let other = journal
+            .get_or_create_daily_note(date(18), &settings)
+            .await
+            .unwrap();
+        assert_ne!(other.id, note.id);
+        assert!(note.collection_id.is_some());
+        assert_eq!(other.collection_id, note.collection_id);
+
+        // A deleted daily note frees its date
+        notes.delete_note(&other.id).await.unwrap();
+        let replaced = journal
+            .get_or_create_daily_note(date(18), &settings)
+            .await
+            .unwrap();
+        assert_ne!(replaced.id, other.id);
+    }
+
+    #[tokio::test]
+    async fn test_daily_note_from_template() {
+        let (journal, notes) = create_test_service().await;
+        let template = notes
+            .create_template(SaveTemplateRequest {
+                name: "Journal".to_string(),
+                title: "Ignored {{date}}".to_string(),
+                content_json: r#"{"ops":[{"insert":"{{weekday}} {{date}} in {{collection}}\n"}]}"#
+                    .to_string(),
+                collection_id: None,
+                reminder_offset_minutes: None,
+            })
+            .await
+            .unwrap();
+        let mut settings = JournalSettings {
+            template_id: Some(template.id.clone()),
+            ..JournalSettings::default()
+        };
+
+        let note = journal
+            .get_or_create_daily_note(date(5), &settings)
+            .await
+            .unwrap();
+        assert_eq!(note.title, "2026-10-05");
+        assert!(note.content_json.contains("Monday 2026-10-05 in Journal"));
+
+        // A missing template makes a blank note
+        settings.template_id = Some("missing".to_string());
+        let blank = journal
+            .get_or_create_daily_note(date(6), &settings)
+            .await
+            .unwrap();
+        assert_eq!(blank.content_json, EMPTY_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_daily_note_navigation_and_calendar() {
+        let (journal, _notes) = create_test_service().await;
+        let settings = JournalSettings::default();
+        for day in [3, 10, 12] {
+            journal
+                .get_or_create_daily_note(date(day), &settings)
+                .await
+                .unwrap();
+        }
+
+        let previous = journal.adjacent_daily_note(date(10), false).await.unwrap();
+        assert_eq!(previous.unwrap().date, date(3));
+        let next = journal.adjacent_daily_note(date(10), true).await.unwrap();
+        assert_eq!(next.unwrap().title, "2026-10-12");
+        assert!(journal
+            .adjacent_daily_note(date(3), false)
+            .await
+            .unwrap()
+            .is_none());
+
+        let month = journal.list_daily_notes(date(1), date(31)).await.unwrap();
+        let dates: Vec<NaiveDate> = month.iter().map(|e| e.date).collect();
+        assert_eq!(dates, vec![date(3), date(10), date(12)]);
+        assert!(journal.list_daily_notes(date(12), date(1)).await.is_err());
+    }
+}
@@ -0,0 +1,1412 @@
+//! Markdown conversion
+//!
+//! `delta_to_markdown` converts note content (Quill Delta JSON) to
+//! CommonMark: headers, bold, italic, inline code, links, bullet, ordered
+//! and check lists (nested by `indent`), blockquotes, code blocks, images
+//! and attachment embeds. Underline and strikethrough have no CommonMark
+//! syntax and are written as raw `<u>` and `<s>` HTML; colors are dropped.
+//! Each Quill line becomes its own block, so empty lines between paragraphs
+//! collapse.
+//!
+//! `markdown_to_delta` reads the same constructs back, plus setext headers,
+//! `~~strikethrough~~` and autolinks.
+
+use serde_json::{Map, Value};
+
+/// A file embedded in a note (`attachment-image` / `attachment-file`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddedFile<'a> {
+    pub blob_hash: &'a str,
+    pub filename: &'a str,
+    pub mime_type: &'a str,
+}
+
+/// Convert Quill Delta JSON to CommonMark. `link_file` decides where each
+/// embedded file goes and returns the link to it.
+pub fn delta_to_markdown(
+    content_json: &str,
+    link_file: &mut dyn FnMut(EmbeddedFile) -> String,
+) -> String {
+    let Ok(json) = serde_json::from_str::<Value>(content_json) else {
+        return String::new();
+    };
+    let ops = json
+        .get("ops")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let lines = split_lines(ops);
+    let mut blocks: Vec<String> = Vec::new();
+    let mut lists = ListState::default();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = &lines[i];
+        let block = Block::of(line.attrs);
+        if !matches!(block, Block::List { .. }) {
+            lists = ListState::default();
+        }
+
+        match block {
+            Block::Code(language) => {
+                let mut code = Vec::new();
+                while let Some(line) = lines.get(i).filter(|l| Block::of(l.attrs) == block) {
+                    code.push(line.plain_text());
+                    i += 1;
+                }
+                blocks.push(fenced_code(&code.join("\n"), language));
+                continue;
+            }
+            Block::Paragraph => {
+                let text = render_inline(&line.pieces, link_file);
+                if !text.trim().is_empty() {
+                    blocks.push(text.trim_end().to_string());
+                }
+            }
+            Block::Header(level) => {
+                let text = render_inline(&line.pieces, link_file);
+                blocks.push(
+                    format!("{} {}", "#".repeat(level), text)
+                        .trim_end()
+                        .to_string(),
+                );
+            }
+            Block::Quote => {
+                let text = render_inline(&line.pieces, link_file);
+                let quote = format!("> {}", text).trim_end().to_string();
+                match blocks.last_mut() {
+                    Some(last) if i > 0 && Block::of(lines[i - 1].attrs) == Block::Quote => {
+                        last.push_str("\n>\n");
+                        last.push_str(&quote);
+                    }
+                    _ => blocks.push(quote),
+                }
+            }
+            Block::List { kind, indent } => {
+                let item = format!(
+                    "{}{}",
+                    lists.marker(kind, indent),
+                    render_inline(&line.pieces, link_file)
+                );
+                let item = item.trim_end().to_string();
+                match blocks.last_mut() {
+                    Some(last) if lists.items > 1 => {
+                        last.push('\n');
+                        last.push_str(&item);
+                    }
+                    _ => blocks.push(item),
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if blocks.is_empty() {
+        return String::new();
+    }
+    let mut markdown = blocks.join("\n\n");
+    markdown.push('\n');
+    markdown
+}
+
+/// One Quill line: its inline pieces and the attributes on its newline
+pub(crate) struct Line<'a> {
+    pub(crate) pieces: Vec<Piece<'a>>,
+    pub(crate) attrs: Option<&'a Map<String, Value>>,
+}
+
+impl Line<'_> {
+    pub(crate) fn plain_text(&self) -> String {
+        self.pieces
+            .iter()
+            .filter_map(|piece| match piece.insert {
+                Insert::Text(text) => Some(text),
+                Insert::Embed(_) => None,
+            })
+            .collect()
+    }
+}
+
+pub(crate) struct Piece<'a> {
+    pub(crate) insert: Insert<'a>,
+    pub(crate) attrs: Option<&'a Map<String, Value>>,
+}
+
+pub(crate) enum Insert<'a> {
+    Text(&'a str),
+    Embed(&'a Map<String, Value>),
+}
+
+/// Split Delta ops into Quill lines
+pub(crate) fn split_lines(ops: &[Value]) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut pieces = Vec::new();
+
+    for op in ops {
+        let attrs = op.get("attributes").and_then(Value::as_object);
+        match op.get("insert") {
+            Some(Value::String(text)) => {
+                for (n, part) in text.split('\n').enumerate() {
+                    if n > 0 {
+                        lines.push(Line {
+                            pieces: std::mem::take(&mut pieces),
+                            attrs,
+                        });
+                    }
+                    if !part.is_empty() {
+                        pieces.push(Piece {
+                            insert: Insert::Text(part),
+                            attrs,
+                        });
+                    }
+                }
+            }
+            Some(Value::Object(embed)) => pieces.push(Piece {
+                insert: Insert::Embed(embed),
+                attrs,
+            }),
+            _ => {}
+        }
+    }
+
+    if !pieces.is_empty() {
+        lines.push(Line {
+            pieces,
+            attrs: None,
+        });
+    }
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Block<'a> {
+    Paragraph,
+    Header(usize),
+    Quote,
+    Code(Option<&'a str>),
+    List { kind: ListKind, indent: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    Bullet,
+    Ordered,
+    Checked,
+    Unchecked,
+}
+
+impl<'a> Block<'a> {
+    pub(crate) fn of(attrs: Option<&'a Map<String, Value>>) -> Self {
+        let Some(attrs) = attrs else {
+            return Block::Paragraph;
+        };
+
+        if let Some(level) = attrs.get("header").and_then(Value::as_u64) {
+            return Block::Header(level.clamp(1, 6) as usize);
+        }
+        if let Some(code) = attrs.get("code-block") {
+            let language = code.as_str().filter(|l| *l != "plain" && !l.is_empty());
+            return Block::Code(language);
+        }
+        let kind = match attrs.get("list").and_then(Value::as_str) {
+            Some("bullet") => Some(ListKind::Bullet),
+            Some("ordered") => Some(ListKind::Ordered),
+            Some("checked") => Some(ListKind::Checked),
+            Some("unchecked") => Some(ListKind::Unchecked),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            // Quill nests lists at most 8 levels deep
+            let indent = attrs.get("indent").and_then(Value::as_u64).unwrap_or(0);
+            return Block::List {
+                kind,
+                indent: indent.min(8) as usize,
+            };
+        }
+        if attrs.get("blockquote").is_some_and(is_set) {
+            return Block::Quote;
+        }
+        Block::Paragraph
+    }
+}
+
+/// Numbering and nesting of the list being written
+#[derive(Default)]
+struct ListState {
+    /// Per nesting level: marker width, last number used and whether it
+    /// is ordered
+    levels: Vec<(usize, u32, bool)>,
+    items: usize,
+}
+
+impl ListState {
+    fn marker(&mut self, kind: ListKind, indent: usize) -> String {
+        self.items += 1;
+        self.levels.truncate(indent + 1);
+        while self.levels.len() <= indent {
+            self.levels.push((2, 0, false));
+        }
+
+        let ordered = kind == ListKind::Ordered;
+        let padding = " ".repeat(self.levels[..indent].iter().map(|l| l.0).sum());
+        let level = &mut self.levels[indent];
+        if level.2 != ordered {
+            level.1 = 0;
+        }
+        level.1 += 1;
+        level.2 = ordered;
+
+        let marker = match kind {
+            ListKind::Ordered => format!("{}. ", level.1),
+            ListKind::Bullet => "- ".to_string(),
+            ListKind::Checked => "- [x] ".to_string(),
+            ListKind::Unchecked => "- [ ] ".to_string(),
+        };
+        level.0 = if ordered { marker.len() } else { 2 };
+        format!("{}{}", padding, marker)
+    }
+}
+
+fn render_inline(pieces: &[Piece], link_file: &mut dyn FnMut(EmbeddedFile) -> String) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < pieces.len() {
+        match pieces[i].insert {
+            Insert::Text(_) => {
+                // Merge runs with the same formatting so markers aren't split
+                let attrs = pieces[i].attrs;
+                let mut text = String::new();
+                while let Some(Piece {
+                    insert: Insert::Text(part),
+                    ..
+                }) = pieces.get(i).filter(|p| p.attrs == attrs)
+                {
+                    text.push_str(part);
+                    i += 1;
+                }
+                out.push_str(&format_text(&text, attrs));
+                continue;
+            }
+            Insert::Embed(embed) => {
+                let rendered = render_embed(embed, link_file);
+                match pieces[i]
+                    .attrs
+                    .and_then(|a| a.get("link"))
+                    .and_then(Value::as_str)
+                {
+                    Some(url) if !rendered.is_empty() => {
+                        out.push_str(&format!("[{}]({})", rendered, link_destination(url)))
+                    }
+                    _ => out.push_str(&rendered),
+                }
+            }
+        }
+        i += 1;
+    }
+
+    escape_line_start(&out)
+}
+
+fn format_text(text: &str, attrs: Option<&Map<String, Value>>) -> String {
+    let flag = |name: &str| attrs.and_then(|a| a.get(name)).is_some_and(is_set);
+
+    // Emphasis can't start or end with whitespace, so keep it outside
+    let core = text.trim();
+    if core.is_empty() {
+        return text.to_string();
+    }
+    let start = text.len() - text.trim_start().len();
+    let (lead, trail) = (&text[..start], &text[start + core.len()..]);
+
+    let mut formatted = if flag("code") {
+        code_span(core)
+    } else {
+        escape(core)
+    };
+    if flag("strike") {
+        formatted = format!("<s>{}</s>", formatted);
+    }
+    if flag("underline") {
+        formatted = format!("<u>{}</u>", formatted);
+    }
+    if flag("italic") {
+        formatted = format!("*{}*", formatted);
+    }
+    if flag("bold") {
+        formatted = format!("**{}**", formatted);
+    }
+    if let Some(url) = attrs.and_then(|a| a.get("link")).and_then(Value::as_str) {
+        formatted = format!("[{}]({})", formatted, link_destination(url));
+    }
+
+    format!("{}{}{}", lead, formatted, trail)
+}
+
+fn render_embed(
+    embed: &Map<String, Value>,
+    link_file: &mut dyn FnMut(EmbeddedFile) -> String,
+) -> String {
+    for (name, value) in embed {
+        let field = |key: &str| value.get(key).and_then(Value::as_str).unwrap_or_default();
+        match name.as_str() {
+            "attachment-image" | "attachment-file" if !field("blobHash").is_empty() => {
+                let file = EmbeddedFile {
+                    blob_hash: field("blobHash"),
+                    filename: field("filename"),
+                    mime_type: field("mimeType"),
+                };
+                let link = link_destination(&link_file(file));
+                return if name == "attachment-image" {
+                    format!("![{}]({})", escape(file.filename), link)
+                } else {
+                    format!("[{}]({})", escape(file.filename), link)
+                };
+            }
+            "image" => {
+                if let Some(src) = value.as_str() {
+                    return format!("![]({})", link_destination(src));
+                }
+            }
+            "video" => {
+                if let Some(src) = value.as_str() {
+                    return format!("[{}]({})", escape(src), link_destination(src));
+                }
+            }
+            _ => {}
+        }
+    }
+    String::new()
+}
+
+/// Quill attribute values are `true`, a string or a number when set
+pub(crate) fn is_set(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+/// Backslash-escape characters that would otherwise start Markdown syntax
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let needs_escape = match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' => true,
+            '&' => chars
+                .peek()
+                .is_some_and(|n| n.is_alphanumeric() || *n == '#'),
+            _ => false,
+        };
+        if needs_escape {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape a line that would otherwise read as a header, list item, quote or
+/// thematic break
+fn escape_line_start(line: &str) -> String {
+    let line = line.trim_start();
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let after_digits = line[digits..].chars().next();
+
+    if (1..=9).contains(&digits) && matches!(after_digits, Some('.') | Some(')')) {
+        return format!("{}\\{}", &line[..digits], &line[digits..]);
+    }
+    match line.chars().next() {
+        Some('#' | '-' | '+' | '=' | '|' | '~') => format!("\\{}", line),
+        _ => line.to_string(),
+    }
+}
+
+fn code_span(code: &str) -> String {
+    let fence = "`".repeat(longest_run(code, '`') + 1);
+    if code.starts_with('`') || code.ends_with('`') {
+        format!("{} {} {}", fence, code, fence)
+    } else {
+        format!("{}{}{}", fence, code, fence)
+    }
+}
+
+fn fenced_code(code: &str, language: Option<&str>) -> String {
+    let fence = "`".repeat(longest_run(code, '`').max(2) + 1);
+    format!(
+        "{}{}\n{}\n{}",
+        fence,
+        language.unwrap_or_default(),
+        code,
+        fence
+    )
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|ch| ch != c).map(str::len).max().unwrap_or(0)
+}
+
+/// Percent-encode the characters that would end a link destination
+pub fn link_destination(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            ' ' => encoded.push_str("%20"),
+            '(' => encoded.push_str("%28"),
+            ')' => encoded.push_str("%29"),
+            '<' => encoded.push_str("%3C"),
+            '>' => encoded.push_str("%3E"),
+            c if c.is_control() => {}
+            c => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+/// Convert CommonMark to Quill Delta ops. Each paragraph, header, list item
+/// and code line becomes one Quill line. Images become `image` embeds
+/// holding the destination as written, with the alt text as an `alt`
+/// attribute, for the caller to swap local files for attachments. Raw HTML
+/// other than simple formatting tags, tables and indented code read as
+/// plain paragraphs.
+pub fn markdown_to_delta(markdown: &str) -> Vec<Value> {
+    let mut ops = DeltaOps::default();
+    // Text of the line being read and the attributes of its newline
+    let mut pending: Option<(String, Map<String, Value>)> = None;
+    // Closing fence, its indentation and the `code-block` value
+    let mut fence: Option<(String, usize, Value)> = None;
+    // Indentation of each open list level
+    let mut list_indents: Vec<usize> = Vec::new();
+
+    for raw in markdown.lines() {
+        let line = raw.replace('\t', "    ");
+        let rest = line.trim_start_matches(' ');
+        let indent = line.len() - rest.len();
+
+        if let Some((close, fence_indent, language)) = &fence {
+            if closes_fence(rest.trim_end(), close) {
+                fence = None;
+            } else {
+                let code = &line[indent.min(*fence_indent)..];
+                if !code.is_empty() {
+                    ops.push(Value::from(code), Map::new());
+                }
+                ops.push(
+                    Value::from("\n"),
+                    with_attr(&Map::new(), "code-block", language.clone()),
+                );
+            }
+            continue;
+        }
+
+        if rest.trim_end().is_empty() {
+            flush_line(&mut ops, &mut pending);
+            continue;
+        }
+
+        if let Some((close, info)) = fence_start(rest) {
+            flush_line(&mut ops, &mut pending);
+            list_indents.clear();
+            let language = info
+                .split_whitespace()
+                .next()
+                .map_or(Value::Bool(true), Value::from);
+            fence = Some((close.to_string(), indent, language));
+            continue;
+        }
+
+        if pending.as_ref().is_some_and(|(_, attrs)| attrs.is_empty()) {
+            if let Some(level) = setext_level(rest.trim_end()) {
+                if let Some((text, _)) = pending.take() {
+                    push_line(
+                        &mut ops,
+                        &text,
+                        with_attr(&Map::new(), "header", level.into()),
+                    );
+                }
+                continue;
+            }
+        }
+
+        if is_thematic_break(rest) {
+            flush_line(&mut ops, &mut pending);
+            list_indents.clear();
+            continue;
+        }
+
+        if let Some((level, text)) = atx_header(rest.trim_end()) {
+            flush_line(&mut ops, &mut pending);
+            list_indents.clear();
+            push_line(
+                &mut ops,
+                text,
+                with_attr(&Map::new(), "header", level.into()),
+            );
+            continue;
+        }
+
+        if let Some(quoted) = rest.strip_prefix('>') {
+            let quoted = quoted.strip_prefix(' ').unwrap_or(quoted);
+            list_indents.clear();
+            let in_quote = pending
+                .as_ref()
+                .is_some_and(|(_, attrs)| attrs.contains_key("blockquote"));
+            if in_quote && !quoted.trim().is_empty() {
+                continue_line(&mut ops, &mut pending, quoted);
+            } else {
+                flush_line(&mut ops, &mut pending);
+                if !quoted.trim().is_empty() {
+                    let attrs = with_attr(&Map::new(), "blockquote", Value::Bool(true));
+                    pending = Some((quoted.to_string(), attrs));
+                }
+            }
+            continue;
+        }
+
+        if let Some((kind, content)) = list_item(rest) {
+            flush_line(&mut ops, &mut pending);
+            while list_indents.last().is_some_and(|&last| last > indent) {
+                list_indents.pop();
+            }
+            if list_indents.last().is_none_or(|&last| last < indent) {
+                list_indents.push(indent);
+            }
+            let mut attrs = with_attr(&Map::new(), "list", Value::from(kind));
+            if list_indents.len() > 1 {
+                attrs.insert("indent".to_string(), (list_indents.len() - 1).into());
+            }
+            pending = Some((content.to_string(), attrs));
+            continue;
+        }
+
+        if pending.is_some() {
+            continue_line(&mut ops, &mut pending, rest);
+        } else {
+            if indent == 0 {
+                list_indents.clear();
+            }
+            pending = Some((rest.to_string(), Map::new()));
+        }
+    }
+    flush_line(&mut ops, &mut pending);
+
+    if ops.0.is_empty() {
+        ops.push(Value::from("\n"), Map::new());
+    }
+    ops.into_ops()
+}
+
+/// Delta ops being built; neighbouring text with the same attributes is
+/// merged into one op
+#[derive(Default)]
+pub struct DeltaOps(Vec<Value>);
+
+impl DeltaOps {
+    pub fn push(&mut self, insert: Value, attrs: Map<String, Value>) {
+        if let (Value::String(text), Some(Value::Object(last))) = (&insert, self.0.last_mut()) {
+            let same_attrs = last
+                .get("attributes")
+                .and_then(Value::as_object)
+                .map_or(attrs.is_empty(), |last_attrs| *last_attrs == attrs);
+            if let (true, Some(Value::String(previous))) = (same_attrs, last.get_mut("insert")) {
+                previous.push_str(text);
+                return;
+            }
+        }
+
+        let mut op = Map::new();
+        op.insert("insert".to_string(), insert);
+        if !attrs.is_empty() {
+            op.insert("attributes".to_string(), Value::Object(attrs));
+        }
+        self.0.push(Value::Object(op));
+    }
+
+    pub fn into_ops(self) -> Vec<Value> {
+        self.0
+    }
+}
+
+/// Write out the line being read, if any
+fn flush_line(ops: &mut DeltaOps, pending: &mut Option<(String, Map<String, Value>)>) {
+    if let Some((text, attrs)) = pending.take() {
+        push_line(ops, &text, attrs);
+    }
+}
+
+fn push_line(ops: &mut DeltaOps, text: &str, attrs: Map<String, Value>) {
+    let text: Vec<char> = text.trim().chars().collect();
+    push_inline(ops, &text, &Map::new());
+    ops.push(Value::from("\n"), attrs);
+}
+
+/// Add the next line of a paragraph. A hard line break (two trailing spaces
+/// or a backslash) starts a new Quill line; a soft one reads as a space.
+fn continue_line(
+    ops: &mut DeltaOps,
+    pending: &mut Option<(String, Map<String, Value>)>,
+    next: &str,
+) {
+    let Some((text, attrs)) = pending else {
+        return;
+    };
+
+    let hard_break = if text.ends_with("  ") {
+        Some(text.trim_end().len())
+    } else if text.ends_with('\\') {
+        Some(text.len() - 1)
+    } else {
+        None
+    };
+    match hard_break {
+        Some(end) => {
+            text.truncate(end);
+            // A list item can't continue on a second line, so the rest of
+            // it becomes a paragraph
+            let next_attrs = if attrs.contains_key("list") {
+                Map::new()
+            } else {
+                attrs.clone()
+            };
+            flush_line(ops, pending);
+            *pending = Some((next.to_string(), next_attrs));
+        }
+        None => {
+            text.truncate(text.trim_end().len());
+            text.push(' ');
+            text.push_str(next);
+        }
+    }
+}
+
+/// An opening code fence (```` ``` ```` or `~~~`) and its info string
+fn fence_start(line: &str) -> Option<(&str, &str)> {
+    let c = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let run = line.len() - line.trim_start_matches(c).len();
+    let info = line[run..].trim();
+    (run >= 3 && !(c == '`' && info.contains('`'))).then_some((&line[..run], info))
+}
+
+fn closes_fence(line: &str, fence: &str) -> bool {
+    let c = fence.chars().next();
+    line.len() >= fence.len() && line.chars().all(|ch| Some(ch) == c)
+}
+
+/// Level of a setext header underline (`===` or `---`)
+fn setext_level(line: &str) -> Option<u64> {
+    if line.is_empty() {
+        None
+    } else if line.chars().all(|c| c == '=') {
+        Some(1)
+    } else if line.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn is_thematic_break(line: &str) -> bool {
+    let mut chars = line.chars().filter(|c| !c.is_whitespace());
+    let Some(first) = chars.next().filter(|c| matches!(c, '*' | '-' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in chars {
+        if c != first {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+/// An ATX header (`## Title`, optionally closed by `#`s): its level and text
+fn atx_header(line: &str) -> Option<(u64, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    let text = rest.trim();
+    let unclosed = text.trim_end_matches('#');
+    if unclosed.is_empty() || unclosed.ends_with(' ') {
+        Some((level as u64, unclosed.trim_end()))
+    } else {
+        Some((level as u64, text))
+    }
+}
+
+/// A list item: its Quill `list` value and its text
+fn list_item(line: &str) -> Option<(&'static str, &str)> {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let (kind, after) = if line.starts_with(['-', '*', '+']) {
+        ("bullet", &line[1..])
+    } else if (1..=9).contains(&digits) && line[digits..].starts_with(['.', ')']) {
+        ("ordered", &line[digits + 1..])
+    } else {
+        return None;
+    };
+    if !(after.is_empty() || after.starts_with(' ')) {
+        return None;
+    }
+
+    let content = after.trim_start();
+    if kind == "bullet" {
+        for (mark, task) in [("[ ]", "unchecked"), ("[x]", "checked"), ("[X]", "checked")] {
+            match content.strip_prefix(mark) {
+                Some(text) if text.is_empty() || text.starts_with(' ') => {
+                    return Some((task, text.trim_start()))
+                }
+                _ => {}
+            }
+        }
+    }
+    Some((kind, content))
+}
+
+/// Parse inline Markdown into ops, formatted with `attrs` as well as its
+/// own syntax
+fn push_inline(ops: &mut DeltaOps, text: &[char], attrs: &Map<String, Value>) {
+    let mut plain = String::new();
+    let mut i = 0;
+
+    while i < text.len() {
+        let c = text[i];
+
+        if c == '\\' && text.get(i + 1).is_some_and(char::is_ascii_punctuation) {
+            plain.push(text[i + 1]);
+            i += 2;
+            continue;
+        }
+
+        if c == '`' {
+            let run = run_length(text, i);
+            if let Some(end) = find_run(text, i + run, '`', run) {
+                flush_text(ops, &mut plain, attrs);
+                let code: String = text[i + run..end].iter().collect();
+                let padded = code.len() > 2 && code.starts_with(' ') && code.ends_with(' ');
+                let code = if padded && !code.trim().is_empty() {
+                    code[1..code.len() - 1].to_string()
+                } else {
+                    code
+                };
+                ops.push(
+                    Value::from(code),
+                    with_attr(attrs, "code", Value::Bool(true)),
+                );
+                i = end + run;
+            } else {
+                plain.extend(&text[i..i + run]);
+                i += run;
+            }
+            continue;
+        }
+
+        if c == '!' && text.get(i + 1) == Some(&'[') {
+            if let Some(link) = parse_link(text, i + 1) {
+                flush_text(ops, &mut plain, attrs);
+                let alt = unescape(&text[link.label]);
+                let mut embed_attrs = attrs.clone();
+                if !alt.is_empty() {
+                    embed_attrs.insert("alt".to_string(), Value::from(alt));
+                }
+                let mut embed = Map::new();
+                embed.insert("image".to_string(), Value::from(link.destination));
+                ops.push(Value::Object(embed), embed_attrs);
+                i = link.end;
+                continue;
+            }
+        }
+
+        if c == '[' {
+            if let Some(link) = parse_link(text, i) {
+                flush_text(ops, &mut plain, attrs);
+                let link_attrs = with_attr(attrs, "link", Value::from(link.destination));
+                push_inline(ops, &text[link.label], &link_attrs);
+                i = link.end;
+                continue;
+            }
+        }
+
+        if c == '<' {
+            if let Some((url, end)) = autolink(text, i) {
+                flush_text(ops, &mut plain, attrs);
+                ops.push(
+                    Value::from(url.as_str()),
+                    with_attr(attrs, "link", Value::from(url)),
+                );
+                i = end;
+                continue;
+            }
+            if let Some((format, inner, end)) = html_format(text, i) {
+                flush_text(ops, &mut plain, attrs);
+                push_inline(
+                    ops,
+                    &text[inner],
+                    &with_attr(attrs, format, Value::Bool(true)),
+                );
+                i = end;
+                continue;
+            }
+        }
+
+        if matches!(c, '*' | '_' | '~') {
+            let run = run_length(text, i);
+            if let Some((formats, end)) = emphasis(text, i, run) {
+                flush_text(ops, &mut plain, attrs);
+                let mut inner_attrs = attrs.clone();
+                for format in formats {
+                    inner_attrs.insert(format.to_string(), Value::Bool(true));
+                }
+                push_inline(ops, &text[i + run..end], &inner_attrs);
+                i = end + run;
+            } else {
+                plain.extend(&text[i..i + run]);
+                i += run;
+            }
+            continue;
+        }
+
+        if c == '&' {
+            if let Some((decoded, end)) = entity(text, i) {
+                plain.push(decoded);
+                i = end;
+                continue;
+            }
+        }
+
+        plain.push(c);
+        i += 1;
+    }
+
+    flush_text(ops, &mut plain, attrs);
+}
+
+fn flush_text(ops: &mut DeltaOps, plain: &mut String, attrs: &Map<String, Value>) {
+    if !plain.is_empty() {
+        ops.push(Value::from(std::mem::take(plain)), attrs.clone());
+    }
+}
+
+fn with_attr(attrs: &Map<String, Value>, name: &str, value: Value) -> Map<String, Value> {
+    let mut attrs = attrs.clone();
+    attrs.insert(name.to_string(), value);
+    attrs
+}
+
+/// The formats of emphasis opened by the `run` delimiters at `start`, and
+/// where its closing delimiters are
+fn emphasis(text: &[char], start: usize, run: usize) -> Option<(&'static [&'static str], usize)> {
+    let delimiter = text[start];
+    let formats: &'static [&'static str] = match (delimiter, run) {
+        ('~', 2) => &["strike"],
+        ('~', _) => return None,
+        (_, 1) => &["italic"],
+        (_, 2) => &["bold"],
+        (_, 3) => &["bold", "italic"],
+        _ => return None,
+    };
+
+    // Underscores don't make emphasis inside words
+    let in_word = |at: Option<&char>| at.is_some_and(|c| c.is_alphanumeric());
+    let opens = text.get(start + run).is_some_and(|c| !c.is_whitespace());
+    if !opens || (delimiter == '_' && start > 0 && in_word(text.get(start - 1))) {
+        return None;
+    }
+
+    // Delimiters left open by emphasis nested inside this one
+    let mut nested: Vec<usize> = Vec::new();
+    let mut j = start + run;
+    while j < text.len() {
+        match text[j] {
+            '\\' => j += 2,
+            '`' => {
+                let ticks = run_length(text, j);
+                j = find_run(text, j + ticks, '`', ticks).map_or(j + ticks, |end| end + ticks);
+            }
+            c if c == delimiter => {
+                let length = run_length(text, j);
+                let after = text.get(j + length);
+                let can_open = after.is_some_and(|c| !c.is_whitespace())
+                    && (delimiter != '_' || !in_word(text.get(j - 1)));
+                let can_close =
+                    !text[j - 1].is_whitespace() && (delimiter != '_' || !in_word(after));
+
+                let mut remaining = length;
+                if can_close {
+                    while let Some(open) = nested.last_mut() {
+                        let used = (*open).min(remaining);
+                        *open -= used;
+                        remaining -= used;
+                        if *open == 0 {
+                            nested.pop();
+                        }
+                        if remaining == 0 {
+                            break;
+                        }
+                    }
+                    if nested.is_empty() && remaining >= run {
+                        return Some((formats, j + length - remaining));
+                    }
+                }
+                if can_open && remaining > 0 {
+                    nested.push(remaining);
+                }
+                j += length;
+            }
+            _ => j += 1,
+        }
+    }
+    None
+}
+
+/// Number of repeats of the character at `start`
+fn run_length(text: &[char], start: usize) -> usize {
+    text[start..]
+        .iter()
+        .take_while(|&&c| c == text[start])
+        .count()
+}
+
+/// Start of the next run of exactly `length` `c`s from `from`
+fn find_run(text: &[char], from: usize, c: char, length: usize) -> Option<usize> {
+    let mut j = from;
+    while j < text.len() {
+        if text[j] == c {
+            let run = run_length(text, j);
+            if run == length {
+                return Some(j);
+            }
+            j += run;
+        } else {
+            j += 1;
+        }
+    }
+    None
+}
+
+struct ParsedLink {
+    label: std::ops::Range<usize>,
+    destination: String,
+    end: usize,
+}
+
+/// An inline link `[label](destination "title")` starting at the `[`
+fn parse_link(text: &[char], open: usize) -> Option<ParsedLink> {
+    let mut depth = 0;
+    let mut j = open;
+    let close = loop {
+        match *text.get(j)? {
+            '\\' => j += 1,
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    break j;
+                }
+            }
+            _ => {}
+        }
+        j += 1;
+    };
+    if text.get(close + 1) != Some(&'(') {
+        return None;
+    }
+
+    let skip_spaces = |mut j: usize| {
+        while text.get(j).is_some_and(|c| c.is_whitespace()) {
+            j += 1;
+        }
+        j
+    };
+    let mut j = skip_spaces(close + 2);
+    let mut destination = String::new();
+    if text.get(j) == Some(&'<') {
+        j += 1;
+        loop {
+            match *text.get(j)? {
+                '>' => break,
+                '\\' if text.get(j + 1).is_some_and(char::is_ascii_punctuation) => {
+                    destination.push(text[j + 1]);
+                    j += 1;
+                }
+                c => destination.push(c),
+            }
+            j += 1;
+        }
+        j += 1;
+    } else {
+        let mut parens = 0;
+        while let Some(&c) = text.get(j) {
+            match c {
+                '\\' if text.get(j + 1).is_some_and(char::is_ascii_punctuation) => {
+                    destination.push(text[j + 1]);
+                    j += 2;
+                    continue;
+                }
+                ')' if parens == 0 => break,
+                c if c.is_whitespace() => break,
+                '(' => parens += 1,
+                ')' => parens -= 1,
+                _ => {}
+            }
+            destination.push(c);
+            j += 1;
+        }
+    }
+
+    j = skip_spaces(j);
+    if let Some(&quote) = text.get(j).filter(|c| matches!(c, '"' | '\'' | '(')) {
+        let end_quote = if quote == '(' { ')' } else { quote };
+        j += 1;
+        while *text.get(j)? != end_quote {
+            if text[j] == '\\' {
+                j += 1;
+            }
+            j += 1;
+        }
+        j = skip_spaces(j + 1);
+    }
+
+    (text.get(j) == Some(&')')).then(|| ParsedLink {
+        label: open + 1..close,
+        destination,
+        end: j + 1,
+    })
+}
+
+/// An autolink (`<https://example.com>`) starting at the `<`: its URL and
+/// where it ends
+fn autolink(text: &[char], open: usize) -> Option<(String, usize)> {
+    let close = open + text[open..].iter().position(|&c| c == '>')?;
+    let url: String = text[open + 1..close].iter().collect();
+    let (scheme, _) = url.split_once(':')?;
+    let valid = scheme.len() >= 2
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'))
+        && !url.contains(|c: char| c.is_whitespace() || c == '<');
+    valid.then_some((url, close + 1))
+}
+
+/// An HTML formatting tag pair (`<u>…</u>`) starting at the `<`: its Quill
+/// format, the range inside it and where it ends
+fn html_format(
+    text: &[char],
+    open: usize,
+) -> Option<(&'static str, std::ops::Range<usize>, usize)> {
+    const TAGS: &[(&str, &str)] = &[
+        ("u", "underline"),
+        ("s", "strike"),
+        ("del", "strike"),
+        ("strike", "strike"),
+        ("b", "bold"),
+        ("strong", "bold"),
+        ("i", "italic"),
+        ("em", "italic"),
+    ];
+    let matches_at = |at: usize, tag: &[char]| {
+        text.get(at..at + tag.len()).is_some_and(|found| {
+            found
+                .iter()
+                .zip(tag)
+                .all(|(a, b)| a.to_ascii_lowercase() == *b)
+        })
+    };
+
+    TAGS.iter().find_map(|&(tag, format)| {
+        let opening: Vec<char> = format!("<{}>", tag).chars().collect();
+        let closing: Vec<char> = format!("</{}>", tag).chars().collect();
+        if !matches_at(open, &opening) {
+            return None;
+        }
+        let inner = open + opening.len();
+        let close = (inner..text.len()).find(|&j| matches_at(j, &closing))?;
+        Some((format, inner..close, close + closing.len()))
+    })
+}
+
+/// An HTML entity (`&amp;`, `&#39;`) starting at the `&`: the character and
+/// where it ends
+fn entity(text: &[char], start: usize) -> Option<(char, usize)> {
+    let semicolon = start + text[start..].iter().take(10).position(|&c| c == ';')?;
+    let name: String = text[start + 1..semicolon].iter().collect();
+    let decoded = match name.as_str() {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let number = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(number)?
+        }
+    };
+    Some((decoded, semicolon + 1))
+}
+
+/// Drop the backslashes of escaped punctuation
+fn unescape(text: &[char]) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < text.len() {
+        if text[i] == '\\' && text.get(i + 1).is_some_and(char::is_ascii_punctuation) {
+            i += 1;
+        }
+        unescaped.push(text[i]);
+        i += 1;
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn convert(ops: Value) -> String {
+        delta_to_markdown(&json!({ "ops": ops }).to_string(), &mut |file| {
+            format!("attachments/{}", file.filename)
+        })
+    }
+
+    #[test]
+    fn test_blocks() {
+        let markdown = convert(json!([
+            { "insert": "Title" },
+            { "insert": "\n", "attributes": { "header": 1 } },
+            { "insert": "Intro with " },
+            { "insert": "bold", "attributes": { "bold": true } },
+            { "insert": ", " },
+            { "insert": "both ", "attributes": { "bold": true, "italic": true } },
+            { "insert": "and " },
+            { "insert": "a link", "attributes": { "link": "https://example.com/a b" } },
+            { "insert": "\n\n" },
+            { "insert": "Quoted" },
+            { "insert": "\n", "attributes": { "blockquote": true } },
+            { "insert": "Still quoted" },
+            { "insert": "\n", "attributes": { "blockquote": true } },
+            { "insert": "let x = 1;" },
+            { "insert": "\n", "attributes": { "code-block": "rust" } },
+            { "insert": "// ```" },
+            { "insert": "\n", "attributes": { "code-block": "rust" } },
+            { "insert": "Use " },
+            { "insert": "cargo build", "attributes": { "code": true } },
+            { "insert": " and " },
+            { "insert": "strike", "attributes": { "strike": true, "underline": true } },
+            { "insert": "\n" }
+        ]));
+
+        assert_eq!(
+            markdown,
+            "# Title\n\n\
+             Intro with **bold**, ***both*** and [a link](https://example.com/a%20b)\n\n\
+             > Quoted\n>\n> Still quoted\n\n\
+             ````rust\nlet x = 1;\n// ```\n````\n\n\
+             Use `cargo build` and <u><s>strike</s></u>\n"
+        );
+    }
+
+    #[test]
+    fn test_lists() {
+        let item = |text: &str, list: &str, indent: u64| {
+            vec![
+                json!({ "insert": text }),
+                json!({ "insert": "\n", "attributes": { "list": list, "indent": indent } }),
+            ]
+        };
+        let mut ops: Vec<Value> = Vec::new();
+        ops.extend(item("One", "ordered", 0));
+        ops.extend(item("Nested", "bullet", 1));
+        ops.extend(item("Deeper", "ordered", 2));
+        ops.extend(item("Two", "ordered", 0));
+        ops.extend(item("Done", "checked", 0));
+        ops.extend(item("Todo", "unchecked", 0));
+        ops.push(json!({ "insert": "After\n" }));
+        ops.extend(item("Fresh", "ordered", 0));
+
+        assert_eq!(
+            convert(Value::Array(ops)),
+            "1. One\n   - Nested\n     1. Deeper\n2. Two\n- [x] Done\n- [ ] Todo\n\n\
+             After\n\n\
+             1. Fresh\n"
+        );
+
+        // An out-of-range indent nests as deep as Quill allows
+        assert_eq!(
+            convert(Value::Array(item("Deep", "bullet", u64::MAX))),
+            format!("{}- Deep\n", " ".repeat(16))
+        );
+    }
+
+    #[test]
+    fn test_embeds() {
+        let mut linked = Vec::new();
+        let markdown = delta_to_markdown(
+            &json!({ "ops": [
+                { "insert": "See " },
+                { "insert": { "attachment-image": {
+                    "blobHash": "abc", "mimeType": "image/png", "filename": "my photo.png"
+                } } },
+                { "insert": " and " },
+                { "insert": { "attachment-file": {
+                    "blobHash": "def", "mimeType": "application/pdf", "filename": "spec.pdf"
+                } } },
+                { "insert": { "image": "https://example.com/x.png" } },
+                { "insert": { "formula": "e=mc^2" } },
+                { "insert": "\n" }
+            ] })
+            .to_string(),
+            &mut |file| {
+                linked.push((file.blob_hash.to_string(), file.mime_type.to_string()));
+                format!("attachments/{}", file.filename)
+            },
+        );
+
+        assert_eq!(
+            markdown,
+            "See ![my photo.png](attachments/my%20photo.png) and \
+             [spec.pdf](attachments/spec.pdf)![](https://example.com/x.png)\n"
+        );
+        assert_eq!(
+            linked,
+            vec![
+                ("abc".to_string(), "image/png".to_string()),
+                ("def".to_string(), "application/pdf".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        let markdown = convert(json!([
+            { "insert": "# not a header\n1. not a list\n- not a bullet\n" },
+            { "insert": "a*b*_c_ [x] <tag> Tom & Jerry &amp; `tick`\n" },
+            { "insert": "  padded  ", "attributes": { "bold": true } },
+            { "insert": "\n" }
+        ]));
+
+        assert_eq!(
+            markdown,
+            "\\# not a header\n\n\
+             1\\. not a list\n\n\
+             \\- not a bullet\n\n\
+             a\\*b\\*\\_c\\_ \\[x\\] \\<tag\\> Tom & Jerry \\&amp; \\`tick\\`\n\n\
+             **padded**\n"
+        );
+
+        assert_eq!(convert(json!([])), "");
+        assert_eq!(delta_to_markdown("not json", &mut |_| String::new()), "");
+    }
+
+    fn delta(markdown: &str) -> Value {
+        Value::Array(markdown_to_delta(markdown))
+    }
+
+    #[test]
+    fn test_markdown_to_delta_blocks() {
+        let ops = delta(
+            "---\n\
+             Title\n=====\n\n\
+             ## Sub ##\n\
+             First line\nsame paragraph  \nnew line\n\n\
+             > quoted\n> on\n>\n> again\n\n\
+             ~~~ python extra\n  indented\n\n~~~\n\
+             * a\n  lazy\n    * b\n      1) c\n* [x] done\n- [ ] todo\n\n\
+             ***\n\
+             Plain\n",
+        );
+
+        assert_eq!(
+            ops,
+            json!([
+                { "insert": "Title" },
+                { "insert": "\n", "attributes": { "header": 1 } },
+                { "insert": "Sub" },
+                { "insert": "\n", "attributes": { "header": 2 } },
+                { "insert": "First line same paragraph\nnew line\nquoted on" },
+                { "insert": "\n", "attributes": { "blockquote": true } },
+                { "insert": "again" },
+                { "insert": "\n", "attributes": { "blockquote": true } },
+                { "insert": "  indented" },
+                { "insert": "\n\n", "attributes": { "code-block": "python" } },
+                { "insert": "a lazy" },
+                { "insert": "\n", "attributes": { "list": "bullet" } },
+                { "insert": "b" },
+                { "insert": "\n", "attributes": { "list": "bullet", "indent": 1 } },
+                { "insert": "c" },
+                { "insert": "\n", "attributes": { "list": "ordered", "indent": 2 } },
+                { "insert": "done" },
+                { "insert": "\n", "attributes": { "list": "checked" } },
+                { "insert": "todo" },
+                { "insert": "\n", "attributes": { "list": "unchecked" } },
+                { "insert": "Plain\n" }
+            ])
+        );
+
+        assert_eq!(delta(""), json!([{ "insert": "\n" }]));
+    }
+
+    #[test]
+    fn test_markdown_to_delta_inline() {
+        let ops = delta(
+            "**bold *both*** _it_ snake_case ~~gone~~ `a*b` <u>under</u> \
+             [**link**](<a b.md> \"title\") ![a \\*chart](img/chart%201.png) \
+             <https://example.com> \\*no\\* 2 * 3 &amp; &#x263A; [no link]",
+        );
+
+        assert_eq!(
+            ops,
+            json!([
+                { "insert": "bold ", "attributes": { "bold": true } },
+                { "insert": "both", "attributes": { "bold": true, "italic": true } },
+                { "insert": " " },
+                { "insert": "it", "attributes": { "italic": true } },
+                { "insert": " snake_case " },
+                { "insert": "gone", "attributes": { "strike": true } },
+                { "insert": " " },
+                { "insert": "a*b", "attributes": { "code": true } },
+                { "insert": " " },
+                { "insert": "under", "attributes": { "underline": true } },
+                { "insert": " " },
+                { "insert": "link", "attributes": { "bold": true, "link": "a b.md" } },
+                { "insert": " " },
+                { "insert": { "image": "img/chart%201.png" }, "attributes": { "alt": "a *chart" } },
+                { "insert": " " },
+                { "insert": "https://example.com", "attributes": { "link": "https://example.com" } },
+                { "insert": " *no* 2 * 3 & ☺ [no link]\n" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let ops = json!([
+            { "insert": "Plan" },
+            { "insert": "\n", "attributes": { "header": 1 } },
+            { "insert": "Ship " },
+            { "insert": "v2", "attributes": { "bold": true } },
+            { "insert": " with " },
+            { "insert": "care", "attributes": { "italic": true, "underline": true } },
+            { "insert": ", see " },
+            { "insert": "the docs", "attributes": { "link": "https://example.com/docs?v=2" } },
+            { "insert": "\n# not a header *really*\nStep" },
+            { "insert": "\n", "attributes": { "list": "ordered" } },
+            { "insert": "Detail" },
+            { "insert": "\n", "attributes": { "list": "bullet", "indent": 1 } },
+            { "insert": "fn main() {}" },
+            { "insert": "\n", "attributes": { "code-block": "rust" } },
+            { "insert": "Quote" },
+            { "insert": "\n", "attributes": { "blockquote": true } }
+        ]);
+
+        let markdown = convert(ops.clone());
+        assert_eq!(delta(&markdown), ops);
+    }
+}
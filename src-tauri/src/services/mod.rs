@@ -4,18 +4,41 @@
 
 pub mod attachments;
 pub mod backup;
+pub mod bundle;
 pub mod credentials;
+pub mod enex;
+pub mod export;
+pub mod html;
+pub mod import;
+pub mod inline_reminders;
+pub mod journal;
+pub mod markdown;
+pub mod note_links;
 pub mod notes;
+pub mod onenote;
+pub mod recurrence;
+pub mod reminder_queue;
+pub mod reminder_time;
 pub mod reminders;
+pub mod revisions;
 pub mod scheduler;
 pub mod settings;
+pub mod templates;
 
-pub use attachments::AttachmentsService;
-pub use backup::BackupService;
+pub use attachments::{AttachmentsService, BlobGcReport};
+pub use backup::{BackupService, BackupVerificationReport, NoteRestoreConflict, NoteRestoreReport};
 pub use credentials::CredentialManager;
+pub use enex::EnexCollectionSource;
+pub use export::{
+    BundleExportReport, ExportScope, ExportService, HtmlExportReport, MarkdownExportReport,
+};
+pub use import::{ImportCollections, ImportResult, ImportService};
+pub use journal::JournalService;
 pub use notes::NotesService;
-pub use reminders::RemindersService;
+pub use reminder_time::ParsedReminderTime;
+pub use reminders::{MissedReminderSummary, RemindersService, SnoozeDuration};
 pub use scheduler::SchedulerService;
 pub use settings::{
-    AutoBackupSettings, BehaviorSettings, HotkeySettings, ReminderSettings, SettingsService,
+    AutoBackupSettings, BehaviorSettings, BlobGcSettings, HotkeySettings, JournalSettings,
+    ReminderSettings, RevisionSettings, SettingsService,
 };
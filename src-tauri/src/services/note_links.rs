@@ -0,0 +1,269 @@
+//! Links between notes
+//!
+//! Notes link to each other with `[[Note Title]]` (or `[[Note Title|label]]`)
+//! written in their text, or with `[[note-id]]` and Quill links to
+//! `note://<note-id>`. `find_links` lists a note's link targets as written;
+//! the repository resolves them against note IDs first and titles second
+//! (case-insensitively) whenever links are read, so a link starts resolving
+//! as soon as a note with that title exists.
+//!
+//! `rewrite_links` renames the target of `[[...]]` links for note renames.
+//! It works on each text insert separately, so a link whose brackets are
+//! formatted differently from its title is found but not rewritten.
+//!
+//! Every path that writes note content (editing, imports, restores) calls
+//! `sync_note_links` so the stored links stay current.
+
+use crate::config;
+use crate::database::Repository;
+use crate::error::Result;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Store the links in a note's content, replacing those stored before
+pub async fn sync_note_links(repo: &Repository, note_id: &str, content_json: &str) -> Result<()> {
+    repo.replace_note_links(note_id, &find_links(content_json))
+        .await
+}
+
+/// Link targets in note content (Quill Delta JSON), in order of first
+/// appearance and without case-insensitive duplicates
+pub fn find_links(content_json: &str) -> Vec<String> {
+    let Ok(json) = serde_json::from_str::<Value>(content_json) else {
+        return Vec::new();
+    };
+    let ops = json
+        .get("ops")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    let mut text = String::new();
+    let mut targets: Vec<String> = Vec::new();
+    for op in ops {
+        if let Some(insert) = op.get("insert").and_then(Value::as_str) {
+            text.push_str(insert);
+        }
+        let note_id = op
+            .get("attributes")
+            .and_then(|a| a.get("link"))
+            .and_then(Value::as_str)
+            .and_then(|link| link.strip_prefix(config::NOTE_LINK_SCHEME));
+        if let Some(note_id) = note_id.map(str::trim).filter(|id| !id.is_empty()) {
+            targets.push(note_id.to_string());
+        }
+    }
+    let mut links: Vec<String> = wiki_links(&text)
+        .into_iter()
+        .map(|link| link.target.to_string())
+        .collect();
+    links.extend(targets);
+
+    let mut seen = std::collections::HashSet::new();
+    links.retain(|target| seen.insert(target.to_lowercase()));
+    links
+}
+
+/// Rewrite `[[old_target]]` links (any case, keeping their labels) to point
+/// at `new_target`. Returns the new content, or None if nothing changed.
+pub fn rewrite_links(content_json: &str, old_target: &str, new_target: &str) -> Option<String> {
+    let mut json = serde_json::from_str::<Value>(content_json).ok()?;
+    let ops = json.get_mut("ops").and_then(Value::as_array_mut)?;
+    let old_lower = old_target.trim().to_lowercase();
+
+    let mut changed = false;
+    for op in ops {
+        let Some(Value::String(insert)) = op.get_mut("insert") else {
+            continue;
+        };
+        let mut rewritten = String::with_capacity(insert.len());
+        let mut last = 0;
+        for link in wiki_links(insert) {
+            if link.target.to_lowercase() != old_lower {
+                continue;
+            }
+            rewritten.push_str(&insert[last..link.target_start]);
+            rewritten.push_str(new_target);
+            last = link.target_end;
+        }
+        if last > 0 {
+            rewritten.push_str(&insert[last..]);
+            *insert = rewritten;
+            changed = true;
+        }
+    }
+
+    changed.then(|| json.to_string())
+}
+
+/// Point `[[note-id]]` and `note://` links at new note IDs, for notes copied
+/// under new IDs (`ids` maps old IDs to new ones). Returns the new content,
+/// or None if nothing changed.
+pub fn remap_note_ids(content_json: &str, ids: &HashMap<String, String>) -> Option<String> {
+    let mut json = serde_json::from_str::<Value>(content_json).ok()?;
+    let ops = json.get_mut("ops").and_then(Value::as_array_mut)?;
+
+    let mut changed = false;
+    for op in ops {
+        if let Some(Value::String(link)) = op.get_mut("attributes").and_then(|a| a.get_mut("link"))
+        {
+            let new_id = link
+                .strip_prefix(config::NOTE_LINK_SCHEME)
+                .and_then(|id| ids.get(id.trim()));
+            if let Some(new_id) = new_id {
+                *link = format!("{}{}", config::NOTE_LINK_SCHEME, new_id);
+                changed = true;
+            }
+        }
+
+        let Some(Value::String(insert)) = op.get_mut("insert") else {
+            continue;
+        };
+        let mut rewritten = String::with_capacity(insert.len());
+        let mut last = 0;
+        for link in wiki_links(insert) {
+            let Some(new_id) = ids.get(link.target) else {
+                continue;
+            };
+            rewritten.push_str(&insert[last..link.target_start]);
+            rewritten.push_str(new_id);
+            last = link.target_end;
+        }
+        if last > 0 {
+            rewritten.push_str(&insert[last..]);
+            *insert = rewritten;
+            changed = true;
+        }
+    }
+
+    changed.then(|| json.to_string())
+}
+
+/// Whether a title can be written inside `[[...]]`
+pub fn is_linkable_title(title: &str) -> bool {
+    !title.trim().is_empty() && !title.contains(['[', ']', '|', '\n'])
+}
+
+/// A `[[...]]` link in text
+struct WikiLink<'a> {
+    /// The target, trimmed
+    target: &'a str,
+    /// Byte span of the target in the text
+    target_start: usize,
+    target_end: usize,
+}
+
+fn wiki_links(text: &str) -> Vec<WikiLink<'_>> {
+    let mut links = Vec::new();
+    let mut from = 0;
+
+    while let Some(open) = text[from..].find("[[").map(|i| from + i) {
+        let inner_start = open + 2;
+        let Some(close) = text[inner_start..].find("]]").map(|i| inner_start + i) else {
+            break;
+        };
+        let inner = &text[inner_start..close];
+        if inner.contains(['[', ']', '\n']) {
+            // Not a link; look again from the next bracket
+            from = open + 1;
+            continue;
+        }
+
+        let raw_target = inner.split('|').next().unwrap_or_default();
+        let target = raw_target.trim();
+        if !target.is_empty() {
+            let target_start = inner_start + (raw_target.len() - raw_target.trim_start().len());
+            links.push(WikiLink {
+                target,
+                target_start,
+                target_end: target_start + target.len(),
+            });
+        }
+        from = close + 2;
+    }
+
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_links() {
+        let content = json!({ "ops": [
+            { "insert": "See [[Trip " },
+            { "insert": "plan", "attributes": { "bold": true } },
+            { "insert": "]] and [[ trip PLAN | the plan]], [[Budget]]\n[[not\na link]] [[]] [x]] [[[Nested]]" },
+            { "insert": "here", "attributes": { "link": "note://4f0e-id" } },
+            { "insert": " and [[4f0e-id]] [[Unclosed\n" },
+            { "insert": "web", "attributes": { "link": "https://example.com" } }
+        ] })
+        .to_string();
+
+        assert_eq!(
+            find_links(&content),
+            vec!["Trip plan", "Budget", "Nested", "4f0e-id"]
+        );
+        assert!(find_links("not json").is_empty());
+        assert!(find_links(r#"{"ops":[{"insert":"No links\n"}]}"#).is_empty());
+    }
+
+    #[test]
+    fn test_rewrite_links() {
+        let content = json!({ "ops": [
+            { "insert": "See [[Trip plan]], [[ TRIP PLAN |the plan]] and [[Trip plans]]\n" },
+            { "insert": "[[trip plan]]", "attributes": { "bold": true } },
+            { "insert": { "image": "x.png" } },
+            { "insert": "\n" }
+        ] })
+        .to_string();
+
+        let rewritten = rewrite_links(&content, "Trip Plan", "Itinerary").unwrap();
+        let json: Value = serde_json::from_str(&rewritten).unwrap();
+        assert_eq!(
+            json["ops"][0]["insert"],
+            "See [[Itinerary]], [[ Itinerary |the plan]] and [[Trip plans]]\n"
+        );
+        assert_eq!(json["ops"][1]["insert"], "[[Itinerary]]");
+        assert_eq!(json["ops"][1]["attributes"]["bold"], true);
+        assert_eq!(json["ops"][2]["insert"]["image"], "x.png");
+
+        assert!(rewrite_links(&content, "Budget", "Costs").is_none());
+        assert!(rewrite_links("not json", "a", "b").is_none());
+
+        assert!(is_linkable_title("Trip plan"));
+        assert!(!is_linkable_title("a|b"));
+        assert!(!is_linkable_title("[draft]"));
+        assert!(!is_linkable_title("  "));
+    }
+
+    #[test]
+    fn test_remap_note_ids() {
+        let content = json!({ "ops": [
+            { "insert": "See [[old-a]], [[ old-a |A]] and [[Old-A]]\n" },
+            { "insert": "here", "attributes": { "link": "note://old-b", "bold": true } },
+            { "insert": " [[other]]", "attributes": { "link": "https://example.com" } },
+            { "insert": "\n" }
+        ] })
+        .to_string();
+        let ids = HashMap::from([
+            ("old-a".to_string(), "new-a".to_string()),
+            ("old-b".to_string(), "new-b".to_string()),
+        ]);
+
+        let remapped = remap_note_ids(&content, &ids).unwrap();
+        let json: Value = serde_json::from_str(&remapped).unwrap();
+        // IDs are matched exactly, unlike titles
+        assert_eq!(
+            json["ops"][0]["insert"],
+            "See [[new-a]], [[ new-a |A]] and [[Old-A]]\n"
+        );
+        assert_eq!(json["ops"][1]["attributes"]["link"], "note://new-b");
+        assert_eq!(json["ops"][1]["attributes"]["bold"], true);
+        assert_eq!(json["ops"][2]["attributes"]["link"], "https://example.com");
+
+        assert!(remap_note_ids(&content, &HashMap::new()).is_none());
+    }
+}
@@ -3,18 +3,64 @@
 //! High-level business logic for notes operations.
 //! Handles autosave coordination and note lifecycle.
 
-use crate::database::{CreateNoteRequest, Note, Repository, UpdateNoteRequest};
-use crate::error::Result;
+use crate::config;
+use crate::database::{
+    search_query, CreateNoteRequest, LinkRewrite, Note, NoteLink, NoteListRequest, NotePage,
+    NoteRevision, NoteRevisionSummary, NoteTemplate, Repository, SaveTemplateRequest, SearchQuery,
+    SearchResult, Tag, TagWithCount, UpdateNoteRequest, UpdateTagRequest,
+};
+use crate::error::{AppError, Result};
+use crate::services::note_links;
+use crate::services::reminders::RemindersService;
+use crate::services::revisions::RevisionDiff;
+use crate::services::settings::RevisionSettings;
+use crate::services::templates::{self, TemplateVariables};
+use chrono::{DateTime, Duration, Local, Utc};
+use std::sync::{Arc, RwLock};
+
+/// Settings key recording that links of older notes have been read
+const NOTE_LINKS_BACKFILLED_KEY: &str = "note_links_backfilled";
 
 /// Service for managing notes
 #[derive(Clone)]
 pub struct NotesService {
     repo: Repository,
+    revision_settings: Arc<RwLock<RevisionSettings>>,
+    /// Keeps reminders in sync with `@remind` markers in note content
+    reminders: Option<RemindersService>,
 }
 
 impl NotesService {
     pub fn new(repo: Repository) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            revision_settings: Arc::new(RwLock::new(RevisionSettings::default())),
+            reminders: None,
+        }
+    }
+
+    /// Create and update reminders from `@remind` markers in note content
+    /// when notes are saved
+    pub fn with_reminders(mut self, reminders: RemindersService) -> Self {
+        self.reminders = Some(reminders);
+        self
+    }
+
+    /// Apply revision history settings (throttle and retention)
+    pub fn set_revision_settings(&self, settings: RevisionSettings) -> Result<()> {
+        let mut current = self
+            .revision_settings
+            .write()
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))?;
+        *current = settings;
+        Ok(())
+    }
+
+    fn revision_settings(&self) -> Result<RevisionSettings> {
+        self.revision_settings
+            .read()
+            .map(|s| s.clone())
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))
     }
 
     /// Create a new note
@@ -48,6 +94,8 @@ impl NotesService {
             // Don't fail the whole operation if FTS fails
         }
 
+        self.sync_note_links(&note.id, &content_json).await;
+
         tracing::info!("Note created successfully: {}", note.id);
 
         Ok(note)
@@ -63,6 +111,13 @@ impl NotesService {
         self.repo.list_notes().await
     }
 
+    /// List one page of note summaries, optionally filtered by a search
+    /// query and collection
+    pub async fn list_note_summaries(&self, req: &NoteListRequest) -> Result<NotePage> {
+        let query = SearchQuery::parse(req.query.as_deref().unwrap_or(""))?;
+        self.repo.list_note_summaries(&query, req).await
+    }
+
     /// Update a note
     pub async fn update_note(
         &self,
@@ -73,6 +128,17 @@ impl NotesService {
     ) -> Result<Note> {
         tracing::debug!("Updating note: {}", id);
 
+        // Snapshot the current state before it is overwritten
+        if title.is_some() || content_json.is_some() {
+            if let Err(e) = self
+                .record_revision_if_due(&id, title.as_deref(), content_json.as_deref())
+                .await
+            {
+                tracing::warn!("Failed to record revision for note {}: {}", id, e);
+                // Don't fail the whole operation if revision history fails
+            }
+        }
+
         let req = UpdateNoteRequest {
             id: id.clone(),
             title: title.clone(),
@@ -94,6 +160,11 @@ impl NotesService {
             }
         }
 
+        if let Some(content_json) = &content_json {
+            self.sync_inline_reminders(&id, content_json).await;
+            self.sync_note_links(&id, content_json).await;
+        }
+
         tracing::debug!("Note updated successfully: {}", note.id);
 
         Ok(note)
@@ -117,51 +188,590 @@ impl NotesService {
     }
 
     /// Search notes by title, content, or attachment filenames
-    /// Uses FTS5 full-text search for fast server-side searching
+    /// using the query language in `database::search_query`.
+    /// Queries made only of plain terms also match attachment filenames.
     pub async fn search_notes(&self, query: &str) -> Result<Vec<Note>> {
-        use std::collections::HashSet;
-
         if query.trim().is_empty() {
             return self.list_notes().await;
         }
 
+        let results = self.search_notes_with_snippets(query).await?;
+        Ok(results.into_iter().map(|r| r.note).collect())
+    }
+
+    /// Like `search_notes`, but with highlighted titles, content snippets,
+    /// relevance scores and attachment-match markers for result display
+    pub async fn search_notes_with_snippets(&self, query: &str) -> Result<Vec<SearchResult>> {
+        use std::collections::HashSet;
+
+        let query = SearchQuery::parse(query)?;
+        if query.is_empty() {
+            let notes = self.list_notes().await?;
+            return Ok(notes
+                .into_iter()
+                .map(|note| search_query::preview_result(note, false))
+                .collect());
+        }
+
         // Search using FTS5 for title and content
-        let mut results = self.repo.search_notes_fts(query).await?;
-        let seen_ids: HashSet<String> = results.iter().map(|n| n.id.clone()).collect();
+        let mut results = self.repo.search_notes_with_snippets(&query).await?;
+
+        let Some(text) = query.plain_text() else {
+            return Ok(results);
+        };
 
         // Also search attachment filenames (not in FTS index)
-        match self.repo.search_notes_by_attachment(query).await {
-            Ok(attachment_note_ids) => {
-                // Filter to only IDs we haven't seen yet
-                let new_ids: Vec<String> = attachment_note_ids
-                    .into_iter()
-                    .filter(|id| !seen_ids.contains(id))
-                    .collect();
-
-                // Batch fetch all new notes in a single query (avoids N+1)
-                if !new_ids.is_empty() {
-                    match self.repo.get_notes_by_ids(&new_ids).await {
-                        Ok(notes) => {
-                            results.extend(notes);
-                        }
-                        Err(e) => {
-                            tracing::warn!(
-                                "Failed to batch fetch notes for attachment search: {}",
-                                e
-                            );
-                        }
-                    }
+        let attachment_note_ids: HashSet<String> =
+            match self.repo.search_notes_by_attachment(&text).await {
+                Ok(ids) => ids.into_iter().collect(),
+                Err(e) => {
+                    tracing::warn!("Failed to search attachments: {}", e);
+                    // Continue with FTS results even if attachment search fails
+                    return Ok(results);
+                }
+            };
+
+        for result in &mut results {
+            result.attachment_match = attachment_note_ids.contains(&result.note.id);
+        }
+
+        // Filter to only IDs we haven't seen yet
+        let seen_ids: HashSet<&str> = results.iter().map(|r| r.note.id.as_str()).collect();
+        let new_ids: Vec<String> = attachment_note_ids
+            .iter()
+            .filter(|id| !seen_ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+
+        // Batch fetch all new notes in a single query (avoids N+1)
+        if !new_ids.is_empty() {
+            match self.repo.get_notes_by_ids(&new_ids).await {
+                Ok(notes) => {
+                    results.extend(
+                        notes
+                            .into_iter()
+                            .map(|note| search_query::preview_result(note, true)),
+                    );
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to batch fetch notes for attachment search: {}", e);
                 }
-            }
-            Err(e) => {
-                tracing::warn!("Failed to search attachments: {}", e);
-                // Continue with FTS results even if attachment search fails
             }
         }
 
         Ok(results)
     }
 
+    // ===== Revision History =====
+
+    /// Snapshot a note before an update, if the revision throttle allows it.
+    ///
+    /// Snapshots are skipped when nothing changes or when the last snapshot is
+    /// younger than `min_interval_secs`. A change that removes most of the
+    /// note's text is always snapshotted, so an accidental select-all-delete
+    /// can be undone even in the middle of an editing session.
+    async fn record_revision_if_due(
+        &self,
+        id: &str,
+        new_title: Option<&str>,
+        new_content: Option<&str>,
+    ) -> Result<()> {
+        let settings = self.revision_settings()?;
+        if !settings.enabled {
+            return Ok(());
+        }
+
+        let current = self.repo.get_note(id).await?;
+        let title_changed = new_title.is_some_and(|t| t != current.title);
+        let content_changed = new_content.is_some_and(|c| c != current.content_json);
+        if !title_changed && !content_changed {
+            return Ok(());
+        }
+
+        let large_deletion = new_content.is_some_and(|c| {
+            is_large_deletion(
+                &Repository::extract_text_from_delta(&current.content_json),
+                &Repository::extract_text_from_delta(c),
+            )
+        });
+
+        if !large_deletion {
+            if let Some(latest) = self.repo.latest_note_revision_time(id).await? {
+                let min_interval = Duration::seconds(i64::from(settings.min_interval_secs));
+                if Utc::now() - latest < min_interval {
+                    return Ok(());
+                }
+            }
+        }
+
+        self.snapshot(&current, &settings).await
+    }
+
+    /// Record a revision of the note's current state and apply retention
+    async fn snapshot(&self, note: &Note, settings: &RevisionSettings) -> Result<()> {
+        self.repo
+            .create_note_revision(&note.id, &note.title, &note.content_json)
+            .await?;
+
+        let thin_before = (settings.thin_after_days > 0)
+            .then(|| Utc::now() - Duration::days(i64::from(settings.thin_after_days)));
+        self.repo
+            .prune_note_revisions(&note.id, settings.max_per_note, thin_before)
+            .await?;
+
+        Ok(())
+    }
+
+    /// List a note's revisions, newest first
+    pub async fn list_revisions(&self, note_id: &str) -> Result<Vec<NoteRevisionSummary>> {
+        self.repo.list_note_revisions(note_id).await
+    }
+
+    /// Get a single revision with its full content
+    pub async fn get_revision(&self, revision_id: &str) -> Result<NoteRevision> {
+        self.repo.get_note_revision(revision_id).await
+    }
+
+    /// Diff two revisions of the same note.
+    /// When `to_revision_id` is None, diffs against the note's current content.
+    pub async fn diff_revisions(
+        &self,
+        from_revision_id: &str,
+        to_revision_id: Option<&str>,
+    ) -> Result<RevisionDiff> {
+        let from = self.repo.get_note_revision(from_revision_id).await?;
+
+        let (to_title, to_content) = match to_revision_id {
+            Some(to_id) => {
+                let to = self.repo.get_note_revision(to_id).await?;
+                if to.note_id != from.note_id {
+                    return Err(AppError::Generic(
+                        "Cannot diff revisions of different notes".to_string(),
+                    ));
+                }
+                (to.title, to.content_json)
+            }
+            None => {
+                let note = self.repo.get_note(&from.note_id).await?;
+                (note.title, note.content_json)
+            }
+        };
+
+        Ok(RevisionDiff::new(
+            from.id,
+            to_revision_id.map(str::to_string),
+            from.title,
+            to_title,
+            &Repository::extract_text_from_delta(&from.content_json),
+            &Repository::extract_text_from_delta(&to_content),
+        ))
+    }
+
+    /// Restore a revision as the note's new head.
+    /// The current state is snapshotted first, so a restore can itself be undone.
+    pub async fn restore_revision(&self, revision_id: &str) -> Result<Note> {
+        let revision = self.repo.get_note_revision(revision_id).await?;
+        let current = self.repo.get_note(&revision.note_id).await?;
+
+        tracing::info!(
+            "Restoring note {} to revision {}",
+            revision.note_id,
+            revision.id
+        );
+
+        let settings = self.revision_settings()?;
+        self.snapshot(&current, &settings).await?;
+
+        let note = self
+            .repo
+            .update_note(UpdateNoteRequest {
+                id: revision.note_id.clone(),
+                title: Some(revision.title.clone()),
+                content_json: Some(revision.content_json.clone()),
+                title_modified: None,
+            })
+            .await?;
+
+        if let Err(e) = self
+            .repo
+            .update_note_fts(
+                &note.id,
+                Some(&revision.title),
+                Some(&revision.content_json),
+            )
+            .await
+        {
+            tracing::warn!("Failed to update note in FTS index: {}", e);
+            // Don't fail the whole operation if FTS fails
+        }
+
+        self.sync_inline_reminders(&note.id, &revision.content_json)
+            .await;
+        self.sync_note_links(&note.id, &revision.content_json).await;
+
+        Ok(note)
+    }
+
+    /// Create, move or delete the note's reminders to match the `@remind`
+    /// markers in its content
+    async fn sync_inline_reminders(&self, note_id: &str, content_json: &str) {
+        let Some(reminders) = &self.reminders else {
+            return;
+        };
+        if let Err(e) = reminders.sync_inline_reminders(note_id, content_json).await {
+            tracing::warn!("Failed to sync inline reminders of note {}: {}", note_id, e);
+            // Don't fail the whole operation if reminder sync fails
+        }
+    }
+
+    // ===== Links =====
+
+    /// Store the links in a note's content
+    async fn sync_note_links(&self, note_id: &str, content_json: &str) {
+        if let Err(e) = note_links::sync_note_links(&self.repo, note_id, content_json).await {
+            tracing::warn!("Failed to store links of note {}: {}", note_id, e);
+            // Don't fail the whole operation if link indexing fails
+        }
+    }
+
+    /// Read the links of notes written before link tracking existed.
+    /// Runs once per database; since then every write path stores links.
+    pub async fn backfill_note_links(&self) -> Result<()> {
+        if self
+            .repo
+            .get_setting(NOTE_LINKS_BACKFILLED_KEY)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let notes = self.repo.list_notes().await?;
+        for note in &notes {
+            note_links::sync_note_links(&self.repo, &note.id, &note.content_json).await?;
+        }
+        self.repo
+            .set_setting(NOTE_LINKS_BACKFILLED_KEY, "1")
+            .await?;
+
+        tracing::info!("Note links backfilled for {} notes", notes.len());
+        Ok(())
+    }
+
+    /// List the links in a note
+    pub async fn list_outgoing_links(&self, note_id: &str) -> Result<Vec<NoteLink>> {
+        self.repo.list_outgoing_links(note_id).await
+    }
+
+    /// List the links from other notes to a note
+    pub async fn list_backlinks(&self, note_id: &str) -> Result<Vec<NoteLink>> {
+        self.repo.list_backlinks(note_id).await
+    }
+
+    /// List links that don't resolve to any note
+    pub async fn list_dangling_links(&self) -> Result<Vec<NoteLink>> {
+        self.repo.list_dangling_links().await
+    }
+
+    /// Rename a note.
+    /// With `rewrite_links`, `[[...]]` links to it by title in other notes
+    /// are rewritten to the new title in the same transaction; links by
+    /// note ID keep working either way.
+    pub async fn rename_note(&self, id: &str, title: &str, rewrite_links: bool) -> Result<Note> {
+        let title = title.trim();
+        if !rewrite_links {
+            return self
+                .update_note(id.to_string(), Some(title.to_string()), None, Some(true))
+                .await;
+        }
+        if !note_links::is_linkable_title(title) {
+            return Err(AppError::Generic(
+                "Title must not be empty or contain '[', ']', '|' or line breaks for links to be rewritten"
+                    .to_string(),
+            ));
+        }
+
+        let mut rewrites = Vec::new();
+        for link in self.repo.list_backlinks(id).await? {
+            if link.target.eq_ignore_ascii_case(id) {
+                continue;
+            }
+            let source = self.repo.get_note(&link.source_note_id).await?;
+            let Some(content_json) =
+                note_links::rewrite_links(&source.content_json, &link.target, title)
+            else {
+                continue;
+            };
+            rewrites.push(LinkRewrite {
+                note_id: source.id,
+                links: note_links::find_links(&content_json),
+                content_json,
+            });
+        }
+
+        tracing::info!(
+            "Renaming note {} and rewriting links in {} notes",
+            id,
+            rewrites.len()
+        );
+
+        for rewrite in &rewrites {
+            if let Err(e) = self
+                .record_revision_if_due(&rewrite.note_id, None, Some(&rewrite.content_json))
+                .await
+            {
+                tracing::warn!(
+                    "Failed to record revision for note {}: {}",
+                    rewrite.note_id,
+                    e
+                );
+                // Don't fail the whole operation if revision history fails
+            }
+        }
+        if let Err(e) = self.record_revision_if_due(id, Some(title), None).await {
+            tracing::warn!("Failed to record revision for note {}: {}", id, e);
+            // Don't fail the whole operation if revision history fails
+        }
+
+        let note = self
+            .repo
+            .rename_note_with_links(id, title, &rewrites)
+            .await?;
+
+        // Inline reminder spans move with the rewritten text
+        for rewrite in &rewrites {
+            self.sync_inline_reminders(&rewrite.note_id, &rewrite.content_json)
+                .await;
+        }
+
+        Ok(note)
+    }
+
+    // ===== Tags =====
+
+    /// Create a new tag
+    pub async fn create_tag(&self, name: &str, color: Option<&str>) -> Result<Tag> {
+        let name = validate_tag_name(name)?;
+        self.repo.create_tag(&name, color).await
+    }
+
+    /// List all tags with note counts
+    pub async fn list_tags(&self) -> Result<Vec<TagWithCount>> {
+        self.repo.list_tags().await
+    }
+
+    /// Rename and/or recolor a tag
+    pub async fn update_tag(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        color: Option<&str>,
+    ) -> Result<Tag> {
+        let name = name.map(validate_tag_name).transpose()?;
+        self.repo
+            .update_tag(UpdateTagRequest {
+                id: id.to_string(),
+                name,
+                color: color.map(str::to_string),
+            })
+            .await
+    }
+
+    /// Delete a tag and remove it from all notes
+    pub async fn delete_tag(&self, id: &str) -> Result<()> {
+        tracing::info!("Deleting tag: {}", id);
+        self.repo.delete_tag(id).await
+    }
+
+    /// Merge source tags into a target tag
+    pub async fn merge_tags(&self, source_ids: &[String], target_id: &str) -> Result<Tag> {
+        tracing::info!("Merging tags {:?} into {}", source_ids, target_id);
+        self.repo.merge_tags(source_ids, target_id).await
+    }
+
+    /// Tag a note by tag name, creating the tag if it doesn't exist yet
+    pub async fn add_tag_to_note(&self, note_id: &str, tag_name: &str) -> Result<Tag> {
+        let name = validate_tag_name(tag_name)?;
+        let tag = match self.repo.get_tag_by_name(&name).await? {
+            Some(tag) => tag,
+            None => self.repo.create_tag(&name, None).await?,
+        };
+
+        self.repo.add_tag_to_note(note_id, &tag.id).await?;
+        Ok(tag)
+    }
+
+    /// Remove a tag from a note
+    pub async fn remove_tag_from_note(&self, note_id: &str, tag_id: &str) -> Result<()> {
+        self.repo.remove_tag_from_note(note_id, tag_id).await
+    }
+
+    /// List the tags applied to a note
+    pub async fn list_note_tags(&self, note_id: &str) -> Result<Vec<Tag>> {
+        self.repo.list_tags_for_note(note_id).await
+    }
+
+    /// List notes carrying a tag
+    pub async fn list_notes_by_tag(&self, tag_id: &str) -> Result<Vec<Note>> {
+        self.repo.list_notes_with_tag(tag_id).await
+    }
+
+    // ===== Templates =====
+
+    /// Create a note template
+    pub async fn create_template(&self, req: SaveTemplateRequest) -> Result<NoteTemplate> {
+        let req = self.validate_template(req).await?;
+        self.repo.create_template(&req).await
+    }
+
+    /// Get a note template
+    pub async fn get_template(&self, id: &str) -> Result<NoteTemplate> {
+        self.repo.get_template(id).await
+    }
+
+    /// List all note templates by name
+    pub async fn list_templates(&self) -> Result<Vec<NoteTemplate>> {
+        self.repo.list_templates().await
+    }
+
+    /// Replace a note template's fields
+    pub async fn update_template(
+        &self,
+        id: &str,
+        req: SaveTemplateRequest,
+    ) -> Result<NoteTemplate> {
+        let req = self.validate_template(req).await?;
+        self.repo.update_template(id, &req).await
+    }
+
+    /// Delete a note template
+    pub async fn delete_template(&self, id: &str) -> Result<()> {
+        tracing::info!("Deleting template: {}", id);
+        self.repo.delete_template(id).await
+    }
+
+    /// Create a note from a template, filling in its `{{variables}}`.
+    /// The note goes into `collection_id`, or else the template's
+    /// collection, and gets a reminder if the template has an offset.
+    pub async fn create_note_from_template(
+        &self,
+        template_id: &str,
+        collection_id: Option<String>,
+        clipboard: Option<String>,
+    ) -> Result<Note> {
+        self.create_note_from_template_at(
+            template_id,
+            collection_id,
+            clipboard,
+            &Local::now(),
+            None,
+        )
+        .await
+    }
+
+    /// Like `create_note_from_template`, with the date and time variables
+    /// taken from `now`, and titled `title` instead of the template's title
+    /// when one is given
+    pub async fn create_note_from_template_at(
+        &self,
+        template_id: &str,
+        collection_id: Option<String>,
+        clipboard: Option<String>,
+        now: &DateTime<Local>,
+        title: Option<String>,
+    ) -> Result<Note> {
+        let template = self.repo.get_template(template_id).await?;
+        let collection_id = collection_id.or(template.collection_id);
+        let collection_name = match &collection_id {
+            Some(id) => Some(self.repo.get_collection(id).await?.name),
+            None => None,
+        };
+
+        let variables = TemplateVariables::new(now, clipboard, collection_name);
+        let title = title.unwrap_or_else(|| templates::substitute(&template.title, &variables));
+        let content_json = templates::substitute_in_delta(&template.content_json, &variables)?;
+
+        tracing::info!("Creating note from template {}", template.id);
+        let mut note = self
+            .create_note(title.clone(), content_json.clone(), collection_id)
+            .await?;
+
+        // A template title is chosen, not generated from the content
+        if !title.trim().is_empty() {
+            note = self
+                .repo
+                .update_note(UpdateNoteRequest {
+                    id: note.id.clone(),
+                    title: None,
+                    content_json: None,
+                    title_modified: Some(true),
+                })
+                .await?;
+        }
+
+        self.sync_inline_reminders(&note.id, &content_json).await;
+
+        if let (Some(minutes), Some(reminders)) =
+            (template.reminder_offset_minutes, &self.reminders)
+        {
+            // Relative to the creation time the variables show, which for
+            // daily notes of other days isn't the current time. A past
+            // day's reminder would fire as soon as the note opens.
+            let trigger_time = now.with_timezone(&Utc) + Duration::minutes(minutes);
+            if trigger_time <= Utc::now() {
+                tracing::debug!(
+                    "Skipping template reminder for note {}, already due",
+                    note.id
+                );
+            } else if let Err(e) = reminders
+                .create_reminder(&note.id, trigger_time, None, None, None, None)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to create template reminder for note {}: {}",
+                    note.id,
+                    e
+                );
+                // Don't fail the whole operation if the reminder fails
+            }
+        }
+
+        Ok(note)
+    }
+
+    /// Normalize and validate a template before saving it
+    async fn validate_template(&self, mut req: SaveTemplateRequest) -> Result<SaveTemplateRequest> {
+        req.name = req.name.trim().to_string();
+        if req.name.is_empty() {
+            return Err(AppError::Generic(
+                "Template name must not be empty".to_string(),
+            ));
+        }
+        if req.name.chars().count() > config::MAX_TEMPLATE_NAME_LENGTH {
+            return Err(AppError::Generic(format!(
+                "Template name exceeds maximum length of {} characters",
+                config::MAX_TEMPLATE_NAME_LENGTH
+            )));
+        }
+
+        serde_json::from_str::<serde_json::Value>(&req.content_json)?;
+
+        if let Some(minutes) = req.reminder_offset_minutes {
+            if !(0..=config::MAX_TEMPLATE_REMINDER_OFFSET_MINUTES).contains(&minutes) {
+                return Err(AppError::Generic(format!(
+                    "Template reminder offset must be between 0 and {} minutes",
+                    config::MAX_TEMPLATE_REMINDER_OFFSET_MINUTES
+                )));
+            }
+        }
+
+        if let Some(collection_id) = &req.collection_id {
+            self.repo.get_collection(collection_id).await?;
+        }
+
+        Ok(req)
+    }
+
     /// Count soft-deleted notes
     pub async fn count_deleted_notes(&self) -> Result<i64> {
         self.repo.count_deleted_notes().await
@@ -177,6 +787,37 @@ impl NotesService {
     }
 }
 
+/// Normalize and validate a tag name
+fn validate_tag_name(name: &str) -> Result<String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(AppError::Generic("Tag name must not be empty".to_string()));
+    }
+    if name.chars().count() > config::MAX_TAG_NAME_LENGTH {
+        return Err(AppError::Generic(format!(
+            "Tag name exceeds maximum length of {} characters",
+            config::MAX_TAG_NAME_LENGTH
+        )));
+    }
+    if name.chars().any(char::is_control) {
+        return Err(AppError::Generic(
+            "Tag name must not contain control characters".to_string(),
+        ));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Whether an edit removed most of a note's text (e.g. select-all + delete)
+fn is_large_deletion(old_text: &str, new_text: &str) -> bool {
+    const MIN_CHARS: usize = 20;
+
+    let old_len = old_text.trim().chars().count();
+    let new_len = new_text.trim().chars().count();
+    old_len >= MIN_CHARS && new_len * 2 < old_len
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,4 +1004,552 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].title, "UPPERCASE");
     }
+
+    // ===== Revision History Tests =====
+
+    fn delta(text: &str) -> String {
+        serde_json::json!({ "ops": [{ "insert": format!("{}\n", text) }] }).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_update_records_throttled_revision() {
+        let service = create_test_service().await;
+
+        let note = service
+            .create_note("Draft".to_string(), delta("v1"), None)
+            .await
+            .unwrap();
+
+        // First edit snapshots the original, later edits inside the throttle window don't
+        for text in ["v2", "v3", "v4"] {
+            service
+                .update_note(note.id.clone(), None, Some(delta(text)), None)
+                .await
+                .unwrap();
+        }
+
+        let revisions = service.list_revisions(&note.id).await.unwrap();
+        assert_eq!(revisions.len(), 1);
+
+        let revision = service.get_revision(&revisions[0].id).await.unwrap();
+        assert_eq!(revision.content_json, delta("v1"));
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_update_records_no_revision() {
+        let service = create_test_service().await;
+
+        let note = service
+            .create_note("Same".to_string(), delta("text"), None)
+            .await
+            .unwrap();
+
+        service
+            .update_note(
+                note.id.clone(),
+                Some("Same".to_string()),
+                Some(delta("text")),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert!(service.list_revisions(&note.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_large_deletion_bypasses_throttle() {
+        let service = create_test_service().await;
+
+        let long_text = "A long paragraph of important meeting notes";
+        let note = service
+            .create_note("Meeting".to_string(), delta("short"), None)
+            .await
+            .unwrap();
+
+        service
+            .update_note(note.id.clone(), None, Some(delta(long_text)), None)
+            .await
+            .unwrap();
+        // Select-all + delete inside the throttle window
+        service
+            .update_note(note.id.clone(), None, Some(delta("")), None)
+            .await
+            .unwrap();
+
+        let revisions = service.list_revisions(&note.id).await.unwrap();
+        assert_eq!(revisions.len(), 2);
+
+        let newest = service.get_revision(&revisions[0].id).await.unwrap();
+        assert_eq!(newest.content_json, delta(long_text));
+    }
+
+    #[tokio::test]
+    async fn test_revisions_disabled() {
+        let service = create_test_service().await;
+        service
+            .set_revision_settings(RevisionSettings {
+                enabled: false,
+                ..RevisionSettings::default()
+            })
+            .unwrap();
+
+        let note = service
+            .create_note("Note".to_string(), delta("one"), None)
+            .await
+            .unwrap();
+        service
+            .update_note(note.id.clone(), None, Some(delta("two")), None)
+            .await
+            .unwrap();
+
+        assert!(service.list_revisions(&note.id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restore_revision_and_diff() {
+        let service = create_test_service().await;
+
+        let note = service
+            .create_note("Original".to_string(), delta("line one\nline two"), None)
+            .await
+            .unwrap();
+        service
+            .update_note(
+                note.id.clone(),
+                Some("Edited".to_string()),
+                Some(delta("line one\nline three")),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let revisions = service.list_revisions(&note.id).await.unwrap();
+        let original_id = revisions[0].id.clone();
+
+        // Diff the snapshot against the current head
+        let diff = service.diff_revisions(&original_id, None).await.unwrap();
+        assert_eq!(diff.from_title, "Original");
+        assert_eq!(diff.to_title, "Edited");
+        assert_eq!(diff.additions, 1);
+        assert_eq!(diff.deletions, 1);
+
+        // Restore makes the snapshot the head and keeps the edited state as a revision
+        let restored = service.restore_revision(&original_id).await.unwrap();
+        assert_eq!(restored.title, "Original");
+        assert_eq!(restored.content_json, delta("line one\nline two"));
+
+        let revisions = service.list_revisions(&note.id).await.unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].title, "Edited");
+
+        // Restored content is searchable again
+        let results = service.search_notes("two").await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_tag_by_name_and_search_filter() {
+        let service = create_test_service().await;
+
+        let tagged = service
+            .create_note("Meeting notes".to_string(), "{}".to_string(), None)
+            .await
+            .unwrap();
+        service
+            .create_note("Meeting agenda".to_string(), "{}".to_string(), None)
+            .await
+            .unwrap();
+
+        let tag = service
+            .add_tag_to_note(&tagged.id, "  Project X ")
+            .await
+            .unwrap();
+        assert_eq!(tag.name, "Project X");
+
+        // Re-adding by a differently cased name reuses the existing tag
+        let again = service
+            .add_tag_to_note(&tagged.id, "project x")
+            .await
+            .unwrap();
+        assert_eq!(again.id, tag.id);
+
+        let results = service
+            .search_notes(r#"meeting tag:"project x""#)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, tagged.id);
+
+        // A tag filter alone lists every tagged note, matching whole names only
+        let results = service.search_notes(r#"tag:"PROJECT X""#).await.unwrap();
+        assert_eq!(results.len(), 1);
+        let results = service.search_notes("tag:Project").await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_tag_name_validation() {
+        assert!(validate_tag_name("   ").is_err());
+        assert!(validate_tag_name(&"a".repeat(config::MAX_TAG_NAME_LENGTH + 1)).is_err());
+        assert!(validate_tag_name("bad\nname").is_err());
+        assert_eq!(validate_tag_name(" ok ").unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_search_with_snippets_marks_attachment_matches() {
+        let service = create_test_service().await;
+
+        let by_text = service
+            .create_note("Quarterly report".to_string(), "{}".to_string(), None)
+            .await
+            .unwrap();
+        let by_file = service
+            .create_note("Scans".to_string(), "{}".to_string(), None)
+            .await
+            .unwrap();
+        service
+            .repo
+            .create_attachment(&by_file.id, "hash", "report.pdf", "application/pdf", 10)
+            .await
+            .unwrap();
+
+        let results = service.search_notes_with_snippets("report").await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].note.id, by_text.id);
+        assert!(!results[0].attachment_match);
+        assert_eq!(results[0].title_highlight, "Quarterly <mark>report</mark>");
+        assert_eq!(results[1].note.id, by_file.id);
+        assert!(results[1].attachment_match);
+        assert!(results[1].score.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_syncs_inline_reminders() {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+        let repo = Repository::new(pool);
+        let reminders = RemindersService::new(repo.clone());
+        let service = NotesService::new(repo).with_reminders(reminders.clone());
+
+        let note = service
+            .create_note("Orders".to_string(), delta("Todo"), None)
+            .await
+            .unwrap();
+        let save = |text: &str| service.update_note(note.id.clone(), None, Some(delta(text)), None);
+
+        // Markers whose time has passed don't make reminders
+        save("Todo\n@remind 2099-11-03 14:00 call supplier\n@remind 2000-01-01 too late")
+            .await
+            .unwrap();
+        let active = reminders.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        let created = &active[0];
+        assert_eq!(
+            created.inline_text.as_deref(),
+            Some("@remind 2099-11-03 14:00 call supplier")
+        );
+        assert_eq!(
+            (created.inline_start, created.inline_end),
+            (Some(5), Some(43))
+        );
+
+        // Moving the marker and changing its time keeps the same reminder
+        save("Todo\nfirst this\n@remind 2099-11-03 16:30 call supplier")
+            .await
+            .unwrap();
+        let active = reminders.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, created.id);
+        assert_eq!(active[0].inline_start, Some(16));
+        assert_eq!(
+            active[0].trigger_time - created.trigger_time,
+            Duration::minutes(150)
+        );
+
+        // Deleting the marker deletes the reminder; other reminders stay
+        reminders
+            .create_reminder(
+                &note.id,
+                Utc::now() + Duration::days(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        save("Todo").await.unwrap();
+        let active = reminders.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_ne!(active[0].id, created.id);
+        assert_eq!(active[0].inline_text, None);
+    }
+
+    #[tokio::test]
+    async fn test_links_and_rename_rewrites_them() {
+        let service = create_test_service().await;
+
+        let trip = service
+            .create_note("Trip plan".to_string(), delta("Day one"), None)
+            .await
+            .unwrap();
+        let source = service
+            .create_note(
+                "Source".to_string(),
+                delta("See [[trip plan|the plan]] and [[Packing]]"),
+                None,
+            )
+            .await
+            .unwrap();
+        let by_id = service
+            .create_note("By ID".to_string(), delta("Nothing yet"), None)
+            .await
+            .unwrap();
+        service
+            .update_note(
+                by_id.id.clone(),
+                None,
+                Some(delta(&format!("Link [[{}]]", trip.id))),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let backlinks = service.list_backlinks(&trip.id).await.unwrap();
+        assert_eq!(backlinks.len(), 2);
+        let dangling = service.list_dangling_links().await.unwrap();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].target, "Packing");
+
+        assert!(service
+            .rename_note(&trip.id, "Trip [draft]", true)
+            .await
+            .is_err());
+
+        let renamed = service
+            .rename_note(&trip.id, " Itinerary ", true)
+            .await
+            .unwrap();
+        assert_eq!(renamed.title, "Itinerary");
+
+        // Title links are rewritten with their labels; ID links stay as they are
+        let source = service.get_note(&source.id).await.unwrap();
+        assert_eq!(
+            source.content_json,
+            delta("See [[Itinerary|the plan]] and [[Packing]]")
+        );
+        let outgoing = service.list_outgoing_links(&source.id).await.unwrap();
+        assert!(outgoing
+            .iter()
+            .any(|l| l.target == "Itinerary" && l.target_note_id.as_deref() == Some(&*trip.id)));
+        assert_eq!(service.list_backlinks(&trip.id).await.unwrap().len(), 2);
+        assert_eq!(service.search_notes("itinerary").await.unwrap().len(), 2);
+
+        // Without rewriting, title links stop resolving
+        service
+            .rename_note(&trip.id, "Journey", false)
+            .await
+            .unwrap();
+        let backlinks = service.list_backlinks(&trip.id).await.unwrap();
+        assert_eq!(backlinks.len(), 1);
+        assert_eq!(backlinks[0].source_note_id, by_id.id);
+        assert_eq!(service.list_dangling_links().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_note_links_runs_once() {
+        let service = create_test_service().await;
+        let note = service
+            .create_note("Source".to_string(), delta("See [[Packing]]"), None)
+            .await
+            .unwrap();
+
+        // As if written before link tracking
+        service
+            .repo
+            .replace_note_links(&note.id, &[])
+            .await
+            .unwrap();
+        service.backfill_note_links().await.unwrap();
+        let outgoing = service.list_outgoing_links(&note.id).await.unwrap();
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].target, "Packing");
+
+        // Later startups don't read every note again
+        service
+            .repo
+            .replace_note_links(&note.id, &[])
+            .await
+            .unwrap();
+        service.backfill_note_links().await.unwrap();
+        assert!(service
+            .list_outgoing_links(&note.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_note_from_template() {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+        let repo = Repository::new(pool);
+        let reminders = RemindersService::new(repo.clone());
+        let service = NotesService::new(repo.clone()).with_reminders(reminders.clone());
+
+        let meetings = repo
+            .create_collection(crate::database::CreateCollectionRequest {
+                name: "Meetings".to_string(),
+                description: None,
+                color: None,
+                icon: None,
+            })
+            .await
+            .unwrap();
+
+        let template = service
+            .create_template(SaveTemplateRequest {
+                name: "  Standup ".to_string(),
+                title: "Standup {{date}}".to_string(),
+                content_json: delta("{{weekday}} in {{collection}}: {{clipboard}}"),
+                collection_id: Some(meetings.id.clone()),
+                reminder_offset_minutes: Some(15),
+            })
+            .await
+            .unwrap();
+        assert_eq!(template.name, "Standup");
+
+        let note = service
+            .create_note_from_template(&template.id, None, Some("blocked on review".to_string()))
+            .await
+            .unwrap();
+        assert!(note.title.starts_with("Standup 20"));
+        assert!(note.title_modified);
+        assert_eq!(note.collection_id.as_deref(), Some(meetings.id.as_str()));
+        assert!(note.content_json.contains("in Meetings: blocked on review"));
+        assert!(!note.content_json.contains("{{"));
+        // Created notes are searchable like any other
+        assert_eq!(service.search_notes("blocked").await.unwrap().len(), 1);
+
+        let active = reminders.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].note_id, note.id);
+        let offset = active[0].trigger_time - Utc::now();
+        assert!(offset > Duration::minutes(14) && offset <= Duration::minutes(15));
+
+        // A chosen collection wins over the template's; no clipboard is empty
+        let plain = service
+            .update_template(
+                &template.id,
+                SaveTemplateRequest {
+                    name: "Plain".to_string(),
+                    title: String::new(),
+                    content_json: delta("[{{clipboard}}] {{date}}"),
+                    collection_id: None,
+                    reminder_offset_minutes: None,
+                },
+            )
+            .await
+            .unwrap();
+        let note = service
+            .create_note_from_template(&plain.id, Some(meetings.id.clone()), None)
+            .await
+            .unwrap();
+        assert!(!note.title_modified);
+        assert_eq!(note.collection_id.as_deref(), Some(meetings.id.as_str()));
+        assert!(note.content_json.contains("[] 20"));
+        assert_eq!(reminders.list_active_reminders().await.unwrap().len(), 1);
+
+        // Invalid templates are refused
+        let invalid = |name: &str, content_json: &str, offset: Option<i64>| SaveTemplateRequest {
+            name: name.to_string(),
+            title: String::new(),
+            content_json: content_json.to_string(),
+            collection_id: None,
+            reminder_offset_minutes: offset,
+        };
+        assert!(service
+            .create_template(invalid(" ", &delta("x"), None))
+            .await
+            .is_err());
+        assert!(service
+            .create_template(invalid("Broken", "not json", None))
+            .await
+            .is_err());
+        assert!(service
+            .create_template(invalid("Early", &delta("x"), Some(-5)))
+            .await
+            .is_err());
+        assert!(service
+            .create_note_from_template("missing", None, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_template_reminder_follows_creation_time() {
+        let pool = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        initialize_database(&pool).await.unwrap();
+        let repo = Repository::new(pool);
+        let reminders = RemindersService::new(repo.clone());
+        let service = NotesService::new(repo).with_reminders(reminders.clone());
+
+        let template = service
+            .create_template(SaveTemplateRequest {
+                name: "Review".to_string(),
+                title: String::new(),
+                content_json: delta("Review of {{date}}"),
+                collection_id: None,
+                reminder_offset_minutes: Some(90),
+            })
+            .await
+            .unwrap();
+
+        let at = chrono::NaiveDate::from_ymd_opt(2030, 3, 12)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap();
+        let note = service
+            .create_note_from_template_at(&template.id, None, None, &at, None)
+            .await
+            .unwrap();
+        assert!(note.content_json.contains("Review of 2030-03-12"));
+
+        let active = reminders.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(
+            active[0].trigger_time,
+            at.with_timezone(&Utc) + Duration::minutes(90)
+        );
+
+        // A past day's note gets no reminder that's already due
+        let past = chrono::NaiveDate::from_ymd_opt(2020, 3, 12)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap();
+        let note = service
+            .create_note_from_template_at(&template.id, None, None, &past, None)
+            .await
+            .unwrap();
+        assert!(note.content_json.contains("Review of 2020-03-12"));
+        let active = reminders.list_active_reminders().await.unwrap();
+        assert_eq!(active.len(), 1);
+        assert!(active.iter().all(|r| r.note_id != note.id));
+    }
 }
@@ -0,0 +1,835 @@
+//! OneNote page conversion
+//!
+//! Parses the XML OneNote's COM API returns (`GetHierarchy` for notebooks,
+//! section groups, sections and pages; `GetPageContent` for one page) on
+//! every platform, so pages can be imported straight from OneNote on Windows
+//! or from XML files exported there. `convert_onenote_to_quill` turns a page
+//! into Quill Delta; its images are returned separately, with `onenote-image`
+//! placeholder embeds for the importer to swap for stored attachments.
+
+use crate::error::{AppError, Result};
+use crate::services::enex::{apply_html_formats, html_entity, normalize_style};
+use crate::services::markdown::DeltaOps;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+#[allow(dead_code)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OneNoteSection {
+    pub id: String,
+    pub name: String,
+    pub notebook_name: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OneNotePage {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub section_id: String,
+    pub section_name: String,
+    pub created_at: String,
+    pub modified_at: String,
+}
+
+// XML Parsing Functions
+// OneNote hierarchy XML uses the namespace prefix "one:" on all elements
+// (e.g., one:Notebook, one:Section, one:Page). We use local_name() to
+// match element names without the namespace prefix.
+
+/// Parse sections from the full OneNote hierarchy XML.
+/// Tracks the current notebook name to associate with each section.
+pub fn parse_sections(hierarchy_xml: &str) -> Result<Vec<OneNoteSection>> {
+    let mut sections = Vec::new();
+    let mut reader = Reader::from_str(hierarchy_xml);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut current_notebook = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let local = e.name().local_name();
+                match local.as_ref() {
+                    b"Notebook" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.local_name().as_ref() == b"name" {
+                                current_notebook = attr_value(&attr);
+                            }
+                        }
+                    }
+                    b"Section" => {
+                        let mut section_id = String::new();
+                        let mut section_name = String::new();
+                        let mut is_recycle_bin = false;
+
+                        for attr in e.attributes().flatten() {
+                            match attr.key.local_name().as_ref() {
+                                b"ID" => {
+                                    section_id = attr_value(&attr);
+                                }
+                                b"name" => {
+                                    section_name = attr_value(&attr);
+                                }
+                                b"isInRecycleBin" => {
+                                    is_recycle_bin = attr_value(&attr) == "true";
+                                }
+                                _ => {}
+                            }
+                        }
+
+                        if !section_id.is_empty() && !section_name.is_empty() && !is_recycle_bin {
+                            sections.push(OneNoteSection {
+                                id: section_id,
+                                name: section_name,
+                                notebook_name: current_notebook.clone(),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(AppError::Import(format!("XML parse error: {}", e)));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(sections)
+}
+
+/// Parse all pages from the full hierarchy XML, grouped by section ID.
+/// Returns a map of section_id -> Vec<OneNotePage>.
+/// This avoids needing separate COM calls per section.
+pub fn parse_all_pages(hierarchy_xml: &str) -> Result<HashMap<String, Vec<OneNotePage>>> {
+    let mut pages_by_section: HashMap<String, Vec<OneNotePage>> = HashMap::new();
+    let mut reader = Reader::from_str(hierarchy_xml);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut current_section_id = String::new();
+    let mut current_section_name = String::new();
+    let mut in_recycle_bin = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                let local = e.name().local_name();
+                match local.as_ref() {
+                    b"SectionGroup" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.local_name().as_ref() == b"isRecycleBin"
+                                && attr_value(&attr) == "true"
+                            {
+                                in_recycle_bin = true;
+                            }
+                        }
+                    }
+                    b"Section" => {
+                        if in_recycle_bin {
+                            // Skip sections inside recycle bin
+                        } else {
+                            current_section_id.clear();
+                            current_section_name.clear();
+
+                            for attr in e.attributes().flatten() {
+                                match attr.key.local_name().as_ref() {
+                                    b"ID" => {
+                                        current_section_id = attr_value(&attr);
+                                    }
+                                    b"name" => {
+                                        current_section_name = attr_value(&attr);
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                    b"Page" => {
+                        if in_recycle_bin || current_section_id.is_empty() {
+                            // Skip pages in recycle bin or without a section
+                        } else {
+                            let mut page_id = String::new();
+                            let mut page_title = String::new();
+                            let mut created_at = String::new();
+                            let mut modified_at = String::new();
+                            let mut is_in_recycle_bin = false;
+
+                            for attr in e.attributes().flatten() {
+                                match attr.key.local_name().as_ref() {
+                                    b"ID" => {
+                                        page_id = attr_value(&attr);
+                                    }
+                                    b"name" => {
+                                        page_title = attr_value(&attr);
+                                    }
+                                    b"dateTime" => {
+                                        created_at = attr_value(&attr);
+                                    }
+                                    b"lastModifiedTime" => {
+                                        modified_at = attr_value(&attr);
+                                    }
+                                    b"isInRecycleBin" => {
+                                        is_in_recycle_bin = attr_value(&attr) == "true";
+                                    }
+                                    _ => {}
+                                }
+                            }
+
+                            if !page_id.is_empty() && !is_in_recycle_bin {
+                                let page = OneNotePage {
+                                    id: page_id,
+                                    title: if page_title.is_empty() {
+                                        "Untitled Page".to_string()
+                                    } else {
+                                        page_title
+                                    },
+                                    content: String::new(),
+                                    section_id: current_section_id.clone(),
+                                    section_name: current_section_name.clone(),
+                                    created_at,
+                                    modified_at,
+                                };
+                                pages_by_section
+                                    .entry(current_section_id.clone())
+                                    .or_default()
+                                    .push(page);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(e)) => {
+                let local = e.name().local_name();
+                if local.as_ref() == b"SectionGroup" {
+                    in_recycle_bin = false;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(AppError::Import(format!("XML parse error: {}", e)));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(pages_by_section)
+}
+
+/// What an exported OneNote XML file holds
+#[derive(Debug)]
+pub enum OneNoteDocument {
+    /// Notebooks, section groups, sections and pages (`GetHierarchy`)
+    Hierarchy,
+    /// One page's content (`GetPageContent`), with the page's ID, title and
+    /// times from its root element
+    Page(OneNotePage),
+}
+
+/// Tell a hierarchy from a page by its root element
+pub fn parse_document(xml: &str) -> Result<OneNoteDocument> {
+    let mut reader = Reader::from_str(xml);
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                return match e.name().local_name().as_ref() {
+                    b"Notebooks" | b"Notebook" | b"SectionGroup" | b"Section" => {
+                        Ok(OneNoteDocument::Hierarchy)
+                    }
+                    b"Page" => {
+                        let attrs = attributes(&e);
+                        let attr = |key: &str| attrs.get(key).cloned().unwrap_or_default();
+                        let title = attr("name");
+                        Ok(OneNoteDocument::Page(OneNotePage {
+                            id: attr("ID"),
+                            title: if title.is_empty() {
+                                "Untitled Page".to_string()
+                            } else {
+                                title
+                            },
+                            content: String::new(),
+                            section_id: String::new(),
+                            section_name: String::new(),
+                            created_at: attr("dateTime"),
+                            modified_at: attr("lastModifiedTime"),
+                        }))
+                    }
+                    other => Err(AppError::Import(format!(
+                        "not OneNote XML: root element is <{}>",
+                        String::from_utf8_lossy(other)
+                    ))),
+                };
+            }
+            Ok(Event::Eof) => return Err(AppError::Import("empty XML document".to_string())),
+            Err(e) => return Err(AppError::Import(format!("XML parse error: {}", e))),
+            _ => {}
+        }
+    }
+}
+
+/// OneNote writes times as RFC 3339 (`2024-03-01T09:00:00.000Z`)
+pub fn parse_onenote_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// An image on a OneNote page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OneNoteImage {
+    /// Base64 data as written
+    pub data: String,
+    /// The `format` attribute: `png`, `jpg`, ...
+    pub format: String,
+}
+
+impl OneNoteImage {
+    pub fn decode(&self) -> Result<Vec<u8>> {
+        let base64: String = self
+            .data
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        base64::engine::general_purpose::STANDARD
+            .decode(base64)
+            .map_err(|e| AppError::Import(format!("invalid base64 image data: {}", e)))
+    }
+
+    pub fn mime_type(&self) -> String {
+        match self.format.as_str() {
+            "jpg" | "jpeg" => "image/jpeg".to_string(),
+            "svg" => "image/svg+xml".to_string(),
+            format => format!("image/{}", format),
+        }
+    }
+}
+
+/// A page converted to Quill Delta
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OneNotePageContent {
+    /// Delta ops; each image is an `{"onenote-image": n}` embed, `n` being
+    /// its index in `images`
+    pub ops: Vec<Value>,
+    pub images: Vec<OneNoteImage>,
+}
+
+/// Convert OneNote page XML to Quill Delta.
+///
+/// OneNote page XML structure:
+/// - one:Title > one:OE > one:T holds the title (skipped, the title is stored separately)
+/// - one:Outline > one:OEChildren > one:OE is a paragraph; each nested
+///   one:OEChildren indents its OEs one level deeper
+/// - one:OE > one:List > one:Bullet or one:Number makes it a list item
+/// - one:T holds text, as HTML in CDATA (`<span style='font-weight:bold'>`)
+/// - one:Table > one:Row > one:Cell holds OEs of its own
+/// - one:Image > one:Data holds base64 image data
+/// - one:QuickStyleDef names the styles OEs refer to by `quickStyleIndex`
+///   (`h1`–`h6`, `code`, ...)
+pub fn convert_onenote_to_quill(page_xml: &str) -> Result<OneNotePageContent> {
+    let page = parse_tree(page_xml)?;
+    let mut converter = PageConverter {
+        quick_styles: page
+            .children
+            .iter()
+            .filter(|child| child.name == "QuickStyleDef")
+            .filter_map(|style| Some((style.attr("index")?, style.attr("name")?)))
+            .map(|(index, name)| (index.to_string(), name.to_lowercase()))
+            .collect(),
+        ..Default::default()
+    };
+
+    for child in &page.children {
+        match child.name.as_str() {
+            "Outline" => {
+                for children in child.children.iter().filter(|c| c.name == "OEChildren") {
+                    converter.oe_children(children, 0);
+                }
+            }
+            // Images placed on the page outside any outline
+            "Image" => {
+                converter.image(child);
+                converter.end_line(&Map::new(), false);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(converter.finish())
+}
+
+/// Quill's deepest indent
+const MAX_INDENT: usize = 8;
+
+#[derive(Default)]
+struct PageConverter {
+    ops: DeltaOps,
+    images: Vec<OneNoteImage>,
+    /// Quick style index → lowercase name
+    quick_styles: HashMap<String, String>,
+    /// Whether the line being written has content
+    dirty: bool,
+}
+
+impl PageConverter {
+    fn oe_children(&mut self, children: &XmlElement, depth: usize) {
+        for oe in children.children.iter().filter(|c| c.name == "OE") {
+            self.oe(oe, depth);
+        }
+    }
+
+    fn oe(&mut self, oe: &XmlElement, depth: usize) {
+        let line = self.line_attrs(oe, depth);
+        // Whether the OE holds more than its own line
+        let mut nested = false;
+
+        for child in &oe.children {
+            match child.name.as_str() {
+                "T" => self.text(child, Some(&line)),
+                "Image" => self.image(child),
+                "Table" => {
+                    self.end_line(&line, false);
+                    self.table(child, depth);
+                    nested = true;
+                }
+                "OEChildren" => {
+                    self.end_line(&line, false);
+                    self.oe_children(child, depth + 1);
+                    nested = true;
+                }
+                _ => {}
+            }
+        }
+
+        // An OE without text is an empty paragraph
+        self.end_line(&line, !nested);
+    }
+
+    /// Attributes of the newline ending an OE's line
+    fn line_attrs(&self, oe: &XmlElement, depth: usize) -> Map<String, Value> {
+        let mut attrs = Map::new();
+        let style = oe
+            .attr("quickStyleIndex")
+            .and_then(|index| self.quick_styles.get(index))
+            .map(String::as_str)
+            .unwrap_or_default();
+
+        if let Some(list) = oe.child("List") {
+            let kind = if list.child("Number").is_some() {
+                "ordered"
+            } else {
+                "bullet"
+            };
+            attrs.insert("list".to_string(), Value::from(kind));
+        } else if let Some(level) = header_level(style) {
+            attrs.insert("header".to_string(), Value::from(level));
+        } else if style == "code" {
+            attrs.insert("code-block".to_string(), Value::Bool(true));
+            return attrs;
+        }
+        if depth > 0 {
+            attrs.insert("indent".to_string(), Value::from(depth.min(MAX_INDENT)));
+        }
+        attrs
+    }
+
+    /// Write a `one:T`; `line` is None in a table cell, where line breaks
+    /// become spaces
+    fn text(&mut self, t: &XmlElement, line: Option<&Map<String, Value>>) {
+        let mut base = Map::new();
+        let style = normalize_style(t.attr("style").unwrap_or_default());
+        apply_html_formats(&mut base, "", &style, None);
+
+        for (text, formats) in html_runs(&t.text, base) {
+            for (n, part) in text.split('\n').enumerate() {
+                if n > 0 {
+                    match line {
+                        Some(line) => self.end_line(line, true),
+                        None => self.ops.push(Value::from(" "), formats.clone()),
+                    }
+                }
+                if !part.is_empty() {
+                    self.ops.push(Value::from(part), formats.clone());
+                    self.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Embed an image with its data; one without (only a callback ID) is
+    /// left out, or written as its alt text
+    fn image(&mut self, image: &XmlElement) {
+        let data = image
+            .child("Data")
+            .map(|data| data.text.trim())
+            .filter(|data| !data.is_empty());
+        match data {
+            Some(data) => {
+                self.ops
+                    .push(json!({ "onenote-image": self.images.len() }), Map::new());
+                self.images.push(OneNoteImage {
+                    data: data.to_string(),
+                    format: image.attr("format").unwrap_or("png").to_lowercase(),
+                });
+                self.dirty = true;
+            }
+            None => {
+                if let Some(alt) = image.attr("alt").filter(|alt| !alt.is_empty()) {
+                    self.ops.push(Value::from(alt), Map::new());
+                    self.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// A line per row, cells separated by ` | `
+    fn table(&mut self, table: &XmlElement, depth: usize) {
+        let mut line = Map::new();
+        if depth > 0 {
+            line.insert("indent".to_string(), Value::from(depth.min(MAX_INDENT)));
+        }
+
+        for row in table.children.iter().filter(|c| c.name == "Row") {
+            for (n, cell) in row.children.iter().filter(|c| c.name == "Cell").enumerate() {
+                if n > 0 {
+                    self.ops.push(Value::from(" | "), Map::new());
+                }
+                let mut content = Vec::new();
+                cell_content(cell, &mut content);
+                for (n, element) in content.into_iter().enumerate() {
+                    if n > 0 {
+                        self.ops.push(Value::from(" "), Map::new());
+                    }
+                    match element.name.as_str() {
+                        "T" => self.text(element, None),
+                        _ => self.image(element),
+                    }
+                }
+            }
+            self.end_line(&line, true);
+        }
+    }
+
+    /// End the line being written; an empty one only if `force`
+    fn end_line(&mut self, line: &Map<String, Value>, force: bool) {
+        if self.dirty || force {
+            self.ops.push(Value::from("\n"), line.clone());
+        }
+        self.dirty = false;
+    }
+
+    fn finish(mut self) -> OneNotePageContent {
+        self.end_line(&Map::new(), false);
+        let mut ops = self.ops.into_ops();
+        if ops.is_empty() {
+            ops.push(json!({ "insert": "\n" }));
+        }
+        OneNotePageContent {
+            ops,
+            images: self.images,
+        }
+    }
+}
+
+/// The texts and images in a table cell, in order
+fn cell_content<'a>(element: &'a XmlElement, found: &mut Vec<&'a XmlElement>) {
+    for child in &element.children {
+        match child.name.as_str() {
+            "T" | "Image" => found.push(child),
+            _ => cell_content(child, found),
+        }
+    }
+}
+
+fn header_level(style: &str) -> Option<u8> {
+    style
+        .strip_prefix('h')
+        .and_then(|level| level.parse().ok())
+        .filter(|level| (1..=6).contains(level))
+}
+
+/// Split the HTML in a `one:T` into runs of text with their formats; `<br>`
+/// becomes a newline. Text that isn't well-formed is kept as it is.
+fn html_runs(html: &str, base: Map<String, Value>) -> Vec<(String, Map<String, Value>)> {
+    let mut reader = Reader::from_str(html);
+    reader.config_mut().check_end_names = false;
+    let mut stack = vec![base.clone()];
+    let mut runs = Vec::new();
+
+    loop {
+        let formats = stack.last().cloned().unwrap_or_default();
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_lowercase();
+                // HTML's <br> has no end tag
+                if name == "br" {
+                    runs.push(("\n".to_string(), formats));
+                    continue;
+                }
+                let attrs: HashMap<String, String> = e
+                    .html_attributes()
+                    .flatten()
+                    .map(|attr| {
+                        let key = String::from_utf8_lossy(attr.key.as_ref()).to_lowercase();
+                        let value = attr
+                            .unescape_value_with(html_entity)
+                            .map(|value| value.into_owned())
+                            .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).into_owned());
+                        (key, value)
+                    })
+                    .collect();
+                let mut formats = formats;
+                let style = normalize_style(attrs.get("style").map_or("", String::as_str));
+                apply_html_formats(&mut formats, &name, &style, attrs.get("href").cloned());
+                stack.push(formats);
+            }
+            Ok(Event::Empty(e)) if e.local_name().as_ref().eq_ignore_ascii_case(b"br") => {
+                runs.push(("\n".to_string(), formats));
+            }
+            Ok(Event::End(_)) if stack.len() > 1 => {
+                stack.pop();
+            }
+            Ok(Event::Text(e)) => {
+                let text = match e.unescape_with(html_entity) {
+                    Ok(text) => text.into_owned(),
+                    Err(_) => String::from_utf8_lossy(&e).into_owned(),
+                };
+                // Line breaks in HTML source are just whitespace
+                runs.push((text.replace(['\r', '\n'], " "), formats));
+            }
+            Ok(Event::CData(e)) => runs.push((
+                String::from_utf8_lossy(&e.into_inner()).into_owned(),
+                formats,
+            )),
+            Ok(Event::Eof) => break,
+            Err(_) => return vec![(html.to_string(), base)],
+            _ => {}
+        }
+    }
+
+    runs
+}
+
+/// An element of page XML by local name, with its text (CDATA included)
+#[derive(Debug, Default)]
+struct XmlElement {
+    name: String,
+    attrs: HashMap<String, String>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(String::as_str)
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.name == name)
+    }
+}
+
+/// Attributes by local name
+fn attributes(e: &BytesStart) -> HashMap<String, String> {
+    e.attributes()
+        .flatten()
+        .map(|attr| {
+            let key = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+            (key, attr_value(&attr))
+        })
+        .collect()
+}
+
+/// An attribute's value with entities resolved
+fn attr_value(attr: &Attribute) -> String {
+    attr.unescape_value()
+        .map(|value| value.into_owned())
+        .unwrap_or_else(|_| String::from_utf8_lossy(&attr.value).into_owned())
+}
+
+/// Read page XML into a tree and return its root element
+fn parse_tree(xml: &str) -> Result<XmlElement> {
+    let mut reader = Reader::from_str(xml);
+    let element = |e: &BytesStart| XmlElement {
+        name: String::from_utf8_lossy(e.local_name().as_ref()).into_owned(),
+        attrs: attributes(e),
+        ..Default::default()
+    };
+    // The document, then the open elements
+    let mut stack = vec![XmlElement::default()];
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => stack.push(element(&e)),
+            Ok(Event::Empty(e)) => {
+                let child = element(&e);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(child);
+                }
+            }
+            Ok(Event::End(_)) => {
+                let child = stack.pop().unwrap_or_default();
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(child),
+                    None => return Err(AppError::Import("unbalanced page XML".to_string())),
+                }
+            }
+            Ok(Event::Text(e)) => {
+                if let Some(current) = stack.last_mut() {
+                    match e.unescape() {
+                        Ok(text) => current.text.push_str(&text),
+                        Err(_) => current.text.push_str(&String::from_utf8_lossy(&e)),
+                    }
+                }
+            }
+            Ok(Event::CData(e)) => {
+                if let Some(current) = stack.last_mut() {
+                    current
+                        .text
+                        .push_str(&String::from_utf8_lossy(&e.into_inner()));
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(AppError::Import(format!("XML parse error: {}", e))),
+            _ => {}
+        }
+    }
+
+    let mut document = stack.pop().unwrap_or_default();
+    if !stack.is_empty() {
+        return Err(AppError::Import("page XML ends early".to_string()));
+    }
+    document
+        .children
+        .pop()
+        .ok_or_else(|| AppError::Import("empty page XML".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HIERARCHY: &str = include_str!("../../tests/fixtures/onenote/hierarchy.xml");
+    const PAGE: &str = include_str!("../../tests/fixtures/onenote/page.xml");
+
+    #[test]
+    fn test_parse_hierarchy() {
+        let sections = parse_sections(HIERARCHY).unwrap();
+        let names: Vec<(&str, &str)> = sections
+            .iter()
+            .map(|s| (s.notebook_name.as_str(), s.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Work", "Meetings"),
+                ("Work", "Old projects"),
+                ("Personal", "Ideas")
+            ]
+        );
+
+        let pages = parse_all_pages(HIERARCHY).unwrap();
+        let titles = |section: &str| -> Vec<&str> {
+            pages[section].iter().map(|p| p.title.as_str()).collect()
+        };
+        assert_eq!(
+            titles("{SEC-MEETINGS}"),
+            vec!["Standup & planning", "Retro"]
+        );
+        // Untitled pages get a title; recycled pages are left out
+        assert_eq!(titles("{SEC-OLD}"), vec!["Untitled Page"]);
+        assert!(!pages.contains_key("{SEC-DELETED}"));
+        assert!(!pages.contains_key("{SEC-IDEAS}"));
+
+        let standup = &pages["{SEC-MEETINGS}"][0];
+        assert_eq!(standup.id, "{PAGE-STANDUP}");
+        assert_eq!(standup.section_name, "Meetings");
+        assert_eq!(
+            parse_onenote_time(&standup.created_at).map(|t| t.to_rfc3339()),
+            Some("2024-03-01T09:00:00+00:00".to_string())
+        );
+
+        assert!(matches!(
+            parse_document(HIERARCHY).unwrap(),
+            OneNoteDocument::Hierarchy
+        ));
+        match parse_document(PAGE).unwrap() {
+            OneNoteDocument::Page(page) => {
+                assert_eq!(page.id, "{PAGE-STANDUP}");
+                assert_eq!(page.title, "Standup & planning");
+                assert_eq!(page.modified_at, "2024-03-02T10:30:00.000Z");
+            }
+            other => panic!("expected a page, got {:?}", other),
+        }
+        assert!(matches!(
+            parse_document("<html/>"),
+            Err(AppError::Import(_))
+        ));
+    }
+
+    #[test]
+    fn test_convert_onenote_to_quill() {
+        let content = convert_onenote_to_quill(PAGE).unwrap();
+        assert_eq!(
+            Value::Array(content.ops),
+            json!([
+                { "insert": "Agenda" },
+                { "insert": "\n", "attributes": { "header": 2 } },
+                { "insert": "Ship " },
+                { "insert": "v2", "attributes": { "bold": true } },
+                { "insert": " by " },
+                { "insert": "Friday", "attributes": { "italic": true, "underline": true } },
+                { "insert": "\u{a0}& " },
+                { "insert": "plan", "attributes": { "link": "https://example.com/plan" } },
+                { "insert": "\nnext line\nRisks" },
+                { "insert": "\n", "attributes": { "list": "bullet" } },
+                { "insert": "Hiring", "attributes": { "strike": true } },
+                { "insert": "\n", "attributes": { "list": "ordered", "indent": 1 } },
+                { "insert": "Budget" },
+                { "insert": "\n", "attributes": { "list": "ordered", "indent": 1 } },
+                { "insert": "Ask finance" },
+                { "insert": "\n", "attributes": { "indent": 2 } },
+                { "insert": "\n" },
+                { "insert": "Owner", "attributes": { "bold": true } },
+                { "insert": " | Task\nSam | Demo deck\ncargo test" },
+                { "insert": "\n", "attributes": { "code-block": true } },
+                { "insert": "Board photo: " },
+                { "insert": { "onenote-image": 0 } },
+                { "insert": "\nWhiteboard sketch\n" }
+            ])
+        );
+
+        assert_eq!(
+            content.images,
+            vec![OneNoteImage {
+                data: "cG5nIGJ5\ndGVz".to_string(),
+                format: "png".to_string(),
+            }]
+        );
+        assert_eq!(content.images[0].decode().unwrap(), b"png bytes");
+        assert_eq!(content.images[0].mime_type(), "image/png");
+
+        // A page with no outline is one empty line
+        let empty =
+            convert_onenote_to_quill(r#"<one:Page xmlns:one="x" ID="{P}"><one:Title/></one:Page>"#)
+                .unwrap();
+        assert_eq!(empty.ops, vec![json!({ "insert": "\n" })]);
+        assert!(matches!(
+            convert_onenote_to_quill("<one:Page><one:Outline>"),
+            Err(AppError::Import(_))
+        ));
+    }
+}
@@ -0,0 +1,743 @@
+//! Reminder recurrence rules
+//!
+//! Parses and evaluates a subset of iCalendar (RFC 5545) RRULEs:
+//! - `FREQ` — `DAILY`, `WEEKLY`, `MONTHLY` or `YEARLY` (required)
+//! - `INTERVAL` — every N periods (default 1)
+//! - `BYDAY` — weekdays (`MO,WE,FR`); with `FREQ=MONTHLY` also ordinals
+//!   such as `1MO` (first Monday) or `-1FR` (last Friday)
+//! - `BYMONTHDAY` — days of the month, negative counting from the end
+//!   (`-1` is the last day); not allowed with `WEEKLY` or `YEARLY`
+//! - `COUNT` — total number of occurrences
+//! - `UNTIL` — last possible occurrence, as `YYYYMMDD`, local
+//!   `YYYYMMDDTHHMMSS` or UTC `YYYYMMDDTHHMMSSZ`
+//!
+//! Occurrences are computed in local wall-clock time from the series start
+//! (like a DTSTART without TZID) and only then converted to UTC, so
+//! "every day at 09:00" stays at 09:00 across DST changes. A time skipped
+//! by a spring-forward change is shifted forward by the gap (02:30 becomes
+//! 03:30); a time repeated by a fall-back change fires on its first pass.
+
+use crate::database::Reminder;
+use crate::error::{AppError, Result};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, Offset, TimeZone,
+    Utc, Weekday,
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// Years past the search point after which a rule is treated as exhausted.
+/// Every supported rule that has further occurrences has one well within
+/// this (the sparsest is a yearly Feb 29).
+const HORIZON_YEARS: i32 = 10;
+
+/// Recurrence frequency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A `BYDAY` entry, optionally with an ordinal within the month
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ByDay {
+    ordinal: Option<i32>,
+    weekday: Weekday,
+}
+
+/// Rule end given by `UNTIL`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Until {
+    /// Whole local day, inclusive
+    Date(NaiveDate),
+    /// Local wall-clock time
+    Local(NaiveDateTime),
+    Utc(DateTime<Utc>),
+}
+
+/// A parsed recurrence rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    freq: Frequency,
+    interval: u32,
+    by_day: Vec<ByDay>,
+    by_month_day: Vec<i32>,
+    count: Option<u32>,
+    until: Option<Until>,
+}
+
+impl RecurrenceRule {
+    /// Parse an RRULE such as `FREQ=WEEKLY;BYDAY=MO,WE`.
+    /// A leading `RRULE:` is accepted and parts are case-insensitive.
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        let body = match input.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &input[6..],
+            _ => input,
+        };
+
+        let mut freq = None;
+        let mut interval = None;
+        let mut by_day = None;
+        let mut by_month_day = None;
+        let mut count = None;
+        let mut until = None;
+
+        for part in body.split(';').filter(|p| !p.trim().is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected NAME=VALUE, got '{}'", part)))?;
+            let name = name.trim().to_ascii_uppercase();
+            let value = value.trim().to_ascii_uppercase();
+
+            let duplicate = match name.as_str() {
+                "FREQ" => freq.replace(parse_freq(&value)?).is_some(),
+                "INTERVAL" => interval.replace(parse_positive(&name, &value)?).is_some(),
+                "BYDAY" => by_day.replace(parse_by_day(&value)?).is_some(),
+                "BYMONTHDAY" => by_month_day.replace(parse_by_month_day(&value)?).is_some(),
+                "COUNT" => count.replace(parse_positive(&name, &value)?).is_some(),
+                "UNTIL" => until.replace(parse_until(&value)?).is_some(),
+                _ => return Err(invalid(format!("unsupported rule part '{}'", name))),
+            };
+            if duplicate {
+                return Err(invalid(format!("{} is given more than once", name)));
+            }
+        }
+
+        let rule = Self {
+            freq: freq.ok_or_else(|| invalid("FREQ is required".to_string()))?,
+            interval: interval.unwrap_or(1),
+            by_day: by_day.unwrap_or_default(),
+            by_month_day: by_month_day.unwrap_or_default(),
+            count,
+            until,
+        };
+
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err(invalid("COUNT and UNTIL can't be combined".to_string()));
+        }
+        if rule.freq != Frequency::Monthly && rule.by_day.iter().any(|d| d.ordinal.is_some()) {
+            return Err(invalid(
+                "BYDAY ordinals like 1MO are only supported with FREQ=MONTHLY".to_string(),
+            ));
+        }
+        if rule.freq == Frequency::Yearly && !rule.by_day.is_empty() {
+            return Err(invalid(
+                "BYDAY is not supported with FREQ=YEARLY".to_string(),
+            ));
+        }
+        if matches!(rule.freq, Frequency::Weekly | Frequency::Yearly)
+            && !rule.by_month_day.is_empty()
+        {
+            return Err(invalid(
+                "BYMONTHDAY is only supported with FREQ=DAILY or FREQ=MONTHLY".to_string(),
+            ));
+        }
+
+        Ok(rule)
+    }
+
+    /// First occurrence of the series starting at `start` (local wall-clock)
+    pub fn first_occurrence<Tz: TimeZone>(
+        &self,
+        start: NaiveDateTime,
+        tz: &Tz,
+    ) -> Option<DateTime<Utc>> {
+        let horizon = start.date() + Months::new(12 * HORIZON_YEARS as u32);
+        self.find(start, tz, horizon, |_| true)
+    }
+
+    /// First occurrence strictly after `after` of the series starting at
+    /// `start` (local wall-clock), or None once the rule is exhausted
+    pub fn next_after<Tz: TimeZone>(
+        &self,
+        start: NaiveDateTime,
+        after: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Option<DateTime<Utc>> {
+        let after_local = after.with_timezone(tz).naive_local().date();
+        let horizon = after_local.max(start.date()) + Months::new(12 * HORIZON_YEARS as u32);
+        self.find(start, tz, horizon, |t| t > after)
+    }
+
+    /// Walk the series in order, counting every occurrence for `COUNT`,
+    /// until one matches or a period begins past `horizon`
+    fn find<Tz: TimeZone>(
+        &self,
+        start: NaiveDateTime,
+        tz: &Tz,
+        horizon: NaiveDate,
+        matches: impl Fn(DateTime<Utc>) -> bool,
+    ) -> Option<DateTime<Utc>> {
+        let mut seen = 0u32;
+
+        for period in 0u32.. {
+            let (period_start, dates) = self.period_dates(start.date(), period)?;
+            if period_start > horizon {
+                return None;
+            }
+
+            for date in dates.into_iter().filter(|d| *d >= start.date()) {
+                seen += 1;
+                if self.count.is_some_and(|count| seen > count) {
+                    return None;
+                }
+
+                let local = date.and_time(start.time());
+                let utc = local_to_utc(tz, local)?;
+                let past_until = match self.until {
+                    Some(Until::Date(until)) => date > until,
+                    Some(Until::Local(until)) => local > until,
+                    Some(Until::Utc(until)) => utc > until,
+                    None => false,
+                };
+                if past_until {
+                    return None;
+                }
+
+                if matches(utc) {
+                    return Some(utc);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Candidate dates of the `period`-th period (in steps of INTERVAL),
+    /// sorted, together with the first day of that period
+    fn period_dates(&self, start: NaiveDate, period: u32) -> Option<(NaiveDate, Vec<NaiveDate>)> {
+        let step = period.checked_mul(self.interval)?;
+
+        match self.freq {
+            Frequency::Daily => {
+                let date = start.checked_add_signed(Duration::days(step as i64))?;
+                let keep = (self.by_day.is_empty()
+                    || self.by_day.iter().any(|d| d.weekday == date.weekday()))
+                    && (self.by_month_day.is_empty()
+                        || month_days(date.year(), date.month(), &self.by_month_day)
+                            .contains(&date.day()));
+                Some((date, if keep { vec![date] } else { Vec::new() }))
+            }
+            Frequency::Weekly => {
+                let monday = start
+                    .checked_sub_signed(Duration::days(
+                        start.weekday().num_days_from_monday() as i64
+                    ))?
+                    .checked_add_signed(Duration::weeks(step as i64))?;
+                let mut dates: Vec<NaiveDate> = if self.by_day.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.by_day.iter().map(|d| d.weekday).collect()
+                }
+                .into_iter()
+                .map(|weekday| monday + Duration::days(weekday.num_days_from_monday() as i64))
+                .collect();
+                dates.sort();
+                dates.dedup();
+                Some((monday, dates))
+            }
+            Frequency::Monthly => {
+                let first = start.with_day(1)?.checked_add_months(Months::new(step))?;
+                let (year, month) = (first.year(), first.month());
+
+                let mut days = match (self.by_month_day.is_empty(), self.by_day.is_empty()) {
+                    (true, true) => month_days(year, month, &[start.day() as i32]),
+                    (false, true) => month_days(year, month, &self.by_month_day),
+                    (true, false) => weekday_days(year, month, &self.by_day),
+                    (false, false) => {
+                        let weekdays = weekday_days(year, month, &self.by_day);
+                        month_days(year, month, &self.by_month_day)
+                            .into_iter()
+                            .filter(|d| weekdays.contains(d))
+                            .collect()
+                    }
+                };
+                days.sort();
+                days.dedup();
+
+                let dates = days
+                    .into_iter()
+                    .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+                    .collect();
+                Some((first, dates))
+            }
+            Frequency::Yearly => {
+                let year = start.year().checked_add(i32::try_from(step).ok()?)?;
+                let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+                // Years without the start date (Feb 29) are skipped
+                let dates = NaiveDate::from_ymd_opt(year, start.month(), start.day())
+                    .into_iter()
+                    .collect();
+                Some((first, dates))
+            }
+        }
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// Canonical form, e.g. `FREQ=MONTHLY;INTERVAL=2;BYDAY=1MO`
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let freq = match self.freq {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", freq)?;
+
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<String> = self
+                .by_day
+                .iter()
+                .map(|d| match d.ordinal {
+                    Some(n) => format!("{}{}", n, weekday_code(d.weekday)),
+                    None => weekday_code(d.weekday).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|d| d.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        match self.until {
+            Some(Until::Date(date)) => write!(f, ";UNTIL={}", date.format("%Y%m%d"))?,
+            Some(Until::Local(local)) => write!(f, ";UNTIL={}", local.format("%Y%m%dT%H%M%S"))?,
+            Some(Until::Utc(utc)) => write!(f, ";UNTIL={}", utc.format("%Y%m%dT%H%M%SZ"))?,
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Next trigger time of a recurring reminder after `after`, evaluated in
+/// `tz`. Returns None for one-off reminders and exhausted rules.
+pub fn next_reminder_occurrence<Tz: TimeZone>(
+    reminder: &Reminder,
+    after: DateTime<Utc>,
+    tz: &Tz,
+) -> Result<Option<DateTime<Utc>>> {
+    let Some(rule) = &reminder.recurrence_rule else {
+        return Ok(None);
+    };
+    let rule = RecurrenceRule::parse(rule)?;
+    let start = reminder
+        .recurrence_start
+        .unwrap_or_else(|| reminder.trigger_time.with_timezone(tz).naive_local());
+
+    Ok(rule.next_after(start, after.max(reminder.trigger_time), tz))
+}
+
+/// Move recurring reminders that came due before `now` while they were
+/// out of the library (in a backup or export) on to their next occurrence,
+/// so the series continues instead of ending
+pub fn catch_up_reminders<Tz: TimeZone>(reminders: &mut [Reminder], now: DateTime<Utc>, tz: &Tz) {
+    for reminder in reminders {
+        if reminder.triggered || reminder.trigger_time > now {
+            continue;
+        }
+        if let Ok(Some(next)) = next_reminder_occurrence(reminder, now, tz) {
+            reminder.trigger_time = next;
+        }
+    }
+}
+
+/// Convert a local wall-clock time to UTC (see the module docs for DST gaps
+/// and overlaps)
+pub fn local_to_utc<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest.with_timezone(&Utc)),
+        LocalResult::None => {
+            // Use the offset in effect before the gap
+            let before = tz
+                .from_local_datetime(&(local - Duration::days(1)))
+                .earliest()?;
+            let offset = before.offset().fix().local_minus_utc() as i64;
+            Some(Utc.from_utc_datetime(&(local - Duration::seconds(offset))))
+        }
+    }
+}
+
+/// Resolve BYMONTHDAY values (negative from the end) to days of the month
+fn month_days(year: i32, month: u32, by_month_day: &[i32]) -> Vec<u32> {
+    let len = days_in_month(year, month) as i32;
+    by_month_day
+        .iter()
+        .map(|&d| if d < 0 { len + 1 + d } else { d })
+        .filter(|d| (1..=len).contains(d))
+        .map(|d| d as u32)
+        .collect()
+}
+
+/// Resolve BYDAY entries to days of the month
+fn weekday_days(year: i32, month: u32, by_day: &[ByDay]) -> Vec<u32> {
+    let len = days_in_month(year, month);
+    let first_weekday = match NaiveDate::from_ymd_opt(year, month, 1) {
+        Some(date) => date.weekday(),
+        None => return Vec::new(),
+    };
+
+    let mut days = Vec::new();
+    for entry in by_day {
+        // Day of the month of the first such weekday
+        let first = 1
+            + (entry.weekday.num_days_from_monday() + 7 - first_weekday.num_days_from_monday()) % 7;
+        let all = (first..=len).step_by(7);
+
+        match entry.ordinal {
+            None => days.extend(all),
+            Some(n) if n > 0 => days.extend(all.clone().nth(n as usize - 1)),
+            Some(n) => {
+                let all: Vec<u32> = all.collect();
+                days.extend(
+                    all.len()
+                        .checked_sub(n.unsigned_abs() as usize)
+                        .map(|i| all[i]),
+                );
+            }
+        }
+    }
+    days
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+fn parse_freq(value: &str) -> Result<Frequency> {
+    match value {
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        "SECONDLY" | "MINUTELY" | "HOURLY" => Err(invalid(format!(
+            "FREQ={} is not supported; use DAILY, WEEKLY, MONTHLY or YEARLY",
+            value
+        ))),
+        _ => Err(invalid(format!("unknown FREQ '{}'", value))),
+    }
+}
+
+fn parse_positive(name: &str, value: &str) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(invalid(format!(
+            "{} must be a positive integer, got '{}'",
+            name, value
+        ))),
+    }
+}
+
+fn parse_by_day(value: &str) -> Result<Vec<ByDay>> {
+    value
+        .split(',')
+        .map(|item| {
+            let item = item.trim();
+            // Split by byte offset below, so only ASCII is accepted
+            if !item.is_ascii() {
+                return Err(invalid(format!("invalid BYDAY value '{}'", item)));
+            }
+            let split = item.len().saturating_sub(2);
+            let (ordinal, code) = item.split_at(split);
+            let weekday = parse_weekday(code)
+                .ok_or_else(|| invalid(format!("invalid BYDAY value '{}'", item)))?;
+
+            let ordinal = if ordinal.is_empty() {
+                None
+            } else {
+                match ordinal.parse::<i32>() {
+                    Ok(n) if n != 0 && n.abs() <= 5 => Some(n),
+                    _ => {
+                        return Err(invalid(format!(
+                            "BYDAY ordinal must be 1 to 5 or -1 to -5, got '{}'",
+                            item
+                        )))
+                    }
+                }
+            };
+
+            Ok(ByDay { ordinal, weekday })
+        })
+        .collect()
+}
+
+fn parse_by_month_day(value: &str) -> Result<Vec<i32>> {
+    value
+        .split(',')
+        .map(|item| match item.trim().parse::<i32>() {
+            Ok(d) if d != 0 && d.abs() <= 31 => Ok(d),
+            _ => Err(invalid(format!(
+                "BYMONTHDAY must be 1 to 31 or -1 to -31, got '{}'",
+                item
+            ))),
+        })
+        .collect()
+}
+
+fn parse_until(value: &str) -> Result<Until> {
+    let error = || {
+        invalid(format!(
+            "UNTIL must be YYYYMMDD, YYYYMMDDTHHMMSS or YYYYMMDDTHHMMSSZ, got '{}'",
+            value
+        ))
+    };
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let local = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| error())?;
+        return Ok(Until::Utc(Utc.from_utc_datetime(&local)));
+    }
+    if value.contains('T') {
+        return NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+            .map(Until::Local)
+            .map_err(|_| error());
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d")
+        .map(Until::Date)
+        .map_err(|_| error())
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+    Some(match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// RRULE code of a weekday (`MO`, `TU`, ...)
+pub fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn invalid(message: String) -> AppError {
+    AppError::InvalidRecurrence(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveTime;
+    use chrono_tz::America::New_York;
+
+    fn local(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    /// All occurrences (as New York wall-clock times) up to `limit`
+    fn occurrences(rule: &str, start: &str, limit: usize) -> Vec<String> {
+        let rule = RecurrenceRule::parse(rule).unwrap();
+        let start = local(start);
+        let mut result = Vec::new();
+        let mut next = rule.first_occurrence(start, &New_York);
+        while let Some(t) = next {
+            if result.len() == limit {
+                break;
+            }
+            result.push(
+                t.with_timezone(&New_York)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+            );
+            next = rule.next_after(start, t, &New_York);
+        }
+        result
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let rule = RecurrenceRule::parse("rrule:freq=monthly;interval=2;byday=1mo,-1fr").unwrap();
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;INTERVAL=2;BYDAY=1MO,-1FR");
+
+        let rule: RecurrenceRule = "FREQ=DAILY;UNTIL=20261231T235959Z".parse().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=DAILY;UNTIL=20261231T235959Z");
+
+        for bad in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=3;UNTIL=20260101",
+            "FREQ=WEEKLY;BYDAY=1MO",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYDAY=XX",
+            "FREQ=MONTHLY;BYDAY=éA",
+            "FREQ=WEEKLY;BYDAY=X€",
+            "FREQ=WEEKLY;BYDAY=€",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=DAILY;FREQ=WEEKLY",
+            "FREQ=DAILY;BYHOUR=9",
+        ] {
+            assert!(
+                matches!(
+                    RecurrenceRule::parse(bad),
+                    Err(AppError::InvalidRecurrence(_))
+                ),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_weekdays_and_count() {
+        // Starting on a Saturday, the first occurrence is Monday
+        assert_eq!(
+            occurrences(
+                "FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;COUNT=6",
+                "2026-10-17 09:00",
+                10
+            ),
+            vec![
+                "2026-10-19 09:00",
+                "2026-10-20 09:00",
+                "2026-10-21 09:00",
+                "2026-10-22 09:00",
+                "2026-10-23 09:00",
+                "2026-10-26 09:00",
+            ]
+        );
+        assert_eq!(
+            occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,FR", "2026-10-14 18:30", 4),
+            vec![
+                "2026-10-16 18:30",
+                "2026-10-27 18:30",
+                "2026-10-30 18:30",
+                "2026-11-10 18:30",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monthly_and_yearly() {
+        assert_eq!(
+            occurrences("FREQ=MONTHLY;BYDAY=1MO", "2026-10-16 08:00", 3),
+            vec!["2026-11-02 08:00", "2026-12-07 08:00", "2027-01-04 08:00"]
+        );
+        assert_eq!(
+            occurrences(
+                "FREQ=MONTHLY;BYMONTHDAY=-1;UNTIL=20270228",
+                "2026-12-01 12:00",
+                10
+            ),
+            vec!["2026-12-31 12:00", "2027-01-31 12:00", "2027-02-28 12:00"]
+        );
+        // Months without a 31st are skipped
+        assert_eq!(
+            occurrences("FREQ=MONTHLY", "2026-10-31 12:00", 3),
+            vec!["2026-10-31 12:00", "2026-12-31 12:00", "2027-01-31 12:00"]
+        );
+        assert_eq!(
+            occurrences("FREQ=YEARLY", "2028-02-29 07:00", 2),
+            vec!["2028-02-29 07:00", "2032-02-29 07:00"]
+        );
+        assert!(occurrences("FREQ=DAILY;UNTIL=20260101", "2026-10-16 08:00", 1).is_empty());
+    }
+
+    #[test]
+    fn test_dst_keeps_wall_clock_time() {
+        let rule = RecurrenceRule::parse("FREQ=DAILY").unwrap();
+        let start = local("2026-10-31 09:00");
+        let first = rule.first_occurrence(start, &New_York).unwrap();
+        let second = rule.next_after(start, first, &New_York).unwrap();
+        // Clocks went back overnight, so the day was 25 hours long
+        assert_eq!(second - first, Duration::hours(25));
+        assert_eq!(
+            second.with_timezone(&New_York).time(),
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+        );
+
+        // 02:30 doesn't exist on 2026-03-08 and becomes 03:30, then goes back to 02:30
+        assert_eq!(
+            occurrences("FREQ=DAILY", "2026-03-07 02:30", 3),
+            vec!["2026-03-07 02:30", "2026-03-08 03:30", "2026-03-09 02:30"]
+        );
+        // 01:30 happens twice on 2026-11-01 and fires on the first (EDT) pass
+        let repeated = rule_at("FREQ=DAILY", "2026-11-01 01:30");
+        assert_eq!(repeated.to_rfc3339(), "2026-11-01T05:30:00+00:00");
+    }
+
+    #[test]
+    fn test_next_reminder_occurrence() {
+        let trigger_time = Utc.with_ymd_and_hms(2026, 10, 26, 13, 0, 0).unwrap();
+        let mut reminder = Reminder {
+            id: "r".to_string(),
+            note_id: "n".to_string(),
+            trigger_time,
+            triggered: false,
+            created_at: trigger_time,
+            sound_enabled: None,
+            sound_type: None,
+            shake_enabled: None,
+            glow_enabled: None,
+            recurrence_rule: None,
+            recurrence_start: None,
+            snoozed_until: None,
+            missed_policy: None,
+            missed_skip_after_hours: None,
+            inline_text: None,
+            inline_start: None,
+            inline_end: None,
+        };
+        assert_eq!(
+            next_reminder_occurrence(&reminder, trigger_time, &New_York).unwrap(),
+            None
+        );
+
+        reminder.recurrence_rule = Some("FREQ=WEEKLY;COUNT=2".to_string());
+        reminder.recurrence_start = Some(local("2026-10-26 09:00"));
+        let next = next_reminder_occurrence(&reminder, trigger_time, &New_York)
+            .unwrap()
+            .unwrap();
+        // A week later, in EST after the clocks went back
+        assert_eq!(next.to_rfc3339(), "2026-11-02T14:00:00+00:00");
+
+        // Missed occurrences are skipped rather than replayed, and COUNT ends the series
+        let later = trigger_time + Duration::weeks(3);
+        assert_eq!(
+            next_reminder_occurrence(&reminder, later, &New_York).unwrap(),
+            None
+        );
+    }
+
+    fn rule_at(rule: &str, start: &str) -> DateTime<Utc> {
+        RecurrenceRule::parse(rule)
+            .unwrap()
+            .first_occurrence(local(start), &New_York)
+            .unwrap()
+    }
+}
@@ -0,0 +1,189 @@
+//! In-memory reminder schedule
+//!
+//! Keeps every active reminder ordered by the time it next needs the
+//! scheduler, so the scheduler can sleep until the earliest one instead of
+//! polling the database. The database stays the source of truth: the queue
+//! only says when to look, and is rebuilt from it on resync.
+
+use crate::database::Reminder;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+/// Change to the scheduled reminders, sent to the scheduler
+#[derive(Debug, Clone)]
+pub enum ScheduleChange {
+    /// A reminder was created or changed; carries its new state
+    Upsert(Reminder),
+    /// A reminder was deleted
+    Remove(String),
+    /// Reminders changed behind the service's back (e.g. a restore);
+    /// reload them all from the database
+    Resync,
+}
+
+/// Active reminders ordered by when they are next due
+#[derive(Debug, Default)]
+pub struct ReminderQueue {
+    by_time: BTreeSet<(DateTime<Utc>, String)>,
+    by_id: HashMap<String, DateTime<Utc>>,
+}
+
+impl ReminderQueue {
+    /// Replace the queue's contents
+    pub fn reset(&mut self, reminders: impl IntoIterator<Item = Reminder>) {
+        self.by_time.clear();
+        self.by_id.clear();
+        for reminder in reminders {
+            self.upsert(&reminder);
+        }
+    }
+
+    /// Add or move a reminder (or drop it once it has triggered)
+    pub fn upsert(&mut self, reminder: &Reminder) {
+        self.remove(&reminder.id);
+        if let Some(due) = next_due(reminder) {
+            self.by_time.insert((due, reminder.id.clone()));
+            self.by_id.insert(reminder.id.clone(), due);
+        }
+    }
+
+    /// Drop a reminder; returns whether it was queued
+    pub fn remove(&mut self, id: &str) -> bool {
+        match self.by_id.remove(id) {
+            Some(due) => self.by_time.remove(&(due, id.to_string())),
+            None => false,
+        }
+    }
+
+    /// Apply a change from the service; returns false for `Resync`,
+    /// which needs the database
+    pub fn apply(&mut self, change: ScheduleChange) -> bool {
+        match change {
+            ScheduleChange::Upsert(reminder) => self.upsert(&reminder),
+            ScheduleChange::Remove(id) => {
+                self.remove(&id);
+            }
+            ScheduleChange::Resync => return false,
+        }
+        true
+    }
+
+    /// When the earliest queued reminder is due
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.by_time.first().map(|(due, _)| *due)
+    }
+
+    /// Remove and return the IDs of the reminders due at `now`, earliest first
+    pub fn pop_due(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut due = Vec::new();
+        while let Some((time, _)) = self.by_time.first() {
+            if *time > now {
+                break;
+            }
+            if let Some((_, id)) = self.by_time.pop_first() {
+                self.by_id.remove(&id);
+                due.push(id);
+            }
+        }
+        due
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_id.is_empty()
+    }
+}
+
+/// When a reminder next needs the scheduler: its trigger time or an
+/// earlier snooze (None once it has triggered)
+pub fn next_due(reminder: &Reminder) -> Option<DateTime<Utc>> {
+    if reminder.triggered {
+        return None;
+    }
+    Some(match reminder.snoozed_until {
+        Some(until) => until.min(reminder.trigger_time),
+        None => reminder.trigger_time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn reminder(id: &str, trigger_time: DateTime<Utc>) -> Reminder {
+        Reminder {
+            id: id.to_string(),
+            note_id: "note".to_string(),
+            trigger_time,
+            triggered: false,
+            created_at: trigger_time,
+            sound_enabled: None,
+            sound_type: None,
+            shake_enabled: None,
+            glow_enabled: None,
+            recurrence_rule: None,
+            recurrence_start: None,
+            snoozed_until: None,
+            missed_policy: None,
+            missed_skip_after_hours: None,
+            inline_text: None,
+            inline_start: None,
+            inline_end: None,
+        }
+    }
+
+    #[test]
+    fn test_queue_orders_by_due_time() {
+        let now = Utc::now();
+        let mut queue = ReminderQueue::default();
+        queue.reset([
+            reminder("later", now + Duration::minutes(10)),
+            reminder("soon", now + Duration::minutes(1)),
+            reminder("past", now - Duration::minutes(1)),
+        ]);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.next_due(), Some(now - Duration::minutes(1)));
+
+        assert_eq!(queue.pop_due(now), vec!["past".to_string()]);
+        assert_eq!(queue.next_due(), Some(now + Duration::minutes(1)));
+        assert_eq!(
+            queue.pop_due(now + Duration::minutes(10)),
+            vec!["soon".to_string(), "later".to_string()]
+        );
+        assert!(queue.is_empty());
+        assert_eq!(queue.next_due(), None);
+    }
+
+    #[test]
+    fn test_queue_applies_changes() {
+        let now = Utc::now();
+        let mut queue = ReminderQueue::default();
+        let mut moved = reminder("a", now + Duration::hours(1));
+        assert!(queue.apply(ScheduleChange::Upsert(moved.clone())));
+        queue.upsert(&reminder("b", now + Duration::hours(2)));
+
+        // Moving a reminder replaces its old entry
+        moved.trigger_time = now + Duration::hours(3);
+        queue.upsert(&moved);
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.next_due(), Some(now + Duration::hours(2)));
+
+        // A snooze before the next occurrence is what the scheduler waits for
+        moved.snoozed_until = Some(now + Duration::minutes(5));
+        queue.upsert(&moved);
+        assert_eq!(queue.next_due(), Some(now + Duration::minutes(5)));
+
+        // Triggered and deleted reminders leave the queue
+        moved.triggered = true;
+        queue.upsert(&moved);
+        assert!(queue.apply(ScheduleChange::Remove("b".to_string())));
+        assert!(queue.is_empty());
+        assert!(!queue.remove("b"));
+
+        assert!(!queue.apply(ScheduleChange::Resync));
+    }
+}
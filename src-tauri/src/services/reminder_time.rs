@@ -0,0 +1,670 @@
+//! Natural-language reminder times
+//!
+//! Turns phrases such as "tomorrow 9am", "in 2 hours", "next friday at
+//! noon" or "every monday 8:30" into a trigger time and, for repeating
+//! phrases, a recurrence rule (see `recurrence`). Phrases are read in the
+//! local timezone and are made of:
+//! - offsets — `in 2 hours`, `in 90m`, `in an hour and 30 minutes`,
+//!   `in half an hour`, `in 3 days`; they can't be combined with the rest
+//! - days — `today`, `tonight`, `tomorrow`, `2026-12-25`, `dec 25`,
+//!   `25th december`, and weekdays: `friday` is the coming Friday (today
+//!   while the time is still ahead), `next friday` never means today
+//! - times — `9am`, `9:30 pm`, `21:00`, `at 7`, `noon`, `midnight`, and
+//!   `morning` / `afternoon` / `evening` / `night`
+//! - repeats — `every day` (`daily`), `every weekday`, `every weekend`,
+//!   `every monday and thursday`, `every week` (`weekly`), `every other
+//!   week`, `every 3 days`, `every month` (`monthly`), `every year`
+//!   (`yearly`), `every morning`
+//!
+//! A day without a time means `DEFAULT_REMINDER_HOUR`; a time without a day
+//! means its next occurrence. Filler words (`at`, `on`, `the`) are ignored.
+
+use crate::config;
+use crate::error::{AppError, Result};
+use crate::services::recurrence::{self, RecurrenceRule};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// A reminder time resolved from a phrase
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedReminderTime {
+    /// When the reminder (first) fires
+    pub trigger_time: DateTime<Utc>,
+    /// Recurrence rule for repeating phrases, in canonical form
+    pub recurrence_rule: Option<String>,
+}
+
+/// Resolve a reminder phrase relative to `now`, in `tz`
+pub fn parse_reminder_time<Tz: TimeZone>(
+    input: &str,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Result<ParsedReminderTime> {
+    if input.chars().count() > config::MAX_REMINDER_PHRASE_LENGTH {
+        return Err(AppError::InvalidReminderTime(format!(
+            "phrase is longer than {} characters",
+            config::MAX_REMINDER_PHRASE_LENGTH
+        )));
+    }
+
+    let normalized = input.to_lowercase().replace(',', " ");
+    let tokens: Vec<&str> = normalized.split_whitespace().collect();
+    if tokens.is_empty() {
+        return Err(AppError::InvalidReminderTime("phrase is empty".to_string()));
+    }
+
+    Parser { tokens, pos: 0 }
+        .parse()?
+        .resolve(input.trim(), now, tz)
+}
+
+/// Day part of a phrase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Day {
+    Today,
+    Tomorrow,
+    /// `next` skips today
+    Weekday {
+        weekday: Weekday,
+        next: bool,
+    },
+    Date(NaiveDate),
+    /// A date without a year: the next one to come
+    Yearless {
+        month: u32,
+        day: u32,
+    },
+}
+
+/// What a phrase says, before it is resolved against the clock
+#[derive(Debug, Default)]
+struct Phrase {
+    offset: Option<Duration>,
+    day: Option<Day>,
+    time: Option<NaiveTime>,
+    /// Time implied by words like "tonight"; an explicit time wins
+    implied_time: Option<NaiveTime>,
+    /// RRULE for repeating phrases
+    recurrence: Option<String>,
+}
+
+impl Phrase {
+    fn set_day(&mut self, day: Day) -> Result<()> {
+        if self.day.replace(day).is_some() {
+            return Err(invalid("more than one day"));
+        }
+        Ok(())
+    }
+
+    fn set_time(&mut self, time: NaiveTime) -> Result<()> {
+        if self.time.replace(time).is_some() {
+            return Err(invalid("more than one time"));
+        }
+        Ok(())
+    }
+
+    fn set_recurrence(&mut self, rule: String) -> Result<()> {
+        if self.recurrence.replace(rule).is_some() {
+            return Err(invalid("more than one repeat"));
+        }
+        Ok(())
+    }
+
+    fn resolve<Tz: TimeZone>(
+        self,
+        input: &str,
+        now: DateTime<Utc>,
+        tz: &Tz,
+    ) -> Result<ParsedReminderTime> {
+        if let Some(offset) = self.offset {
+            if self.day.is_some() || self.time.is_some() || self.recurrence.is_some() {
+                return Err(invalid(
+                    "\"in ...\" can't be combined with a day, time or repeat",
+                ));
+            }
+            let trigger_time = now
+                .checked_add_signed(offset)
+                .ok_or_else(|| invalid("offset is too large"))?;
+            return Ok(ParsedReminderTime {
+                trigger_time,
+                recurrence_rule: None,
+            });
+        }
+
+        if self.day.is_none()
+            && self.time.is_none()
+            && self.implied_time.is_none()
+            && self.recurrence.is_none()
+        {
+            return Err(invalid(&format!("no day or time in \"{}\"", input)));
+        }
+
+        let now_local = now.with_timezone(tz).naive_local();
+        let today = now_local.date();
+        let time = self
+            .time
+            .or(self.implied_time)
+            .unwrap_or_else(|| hour(config::DEFAULT_REMINDER_HOUR));
+        let ahead_today = today.and_time(time) > now_local;
+
+        let date = match self.day {
+            None if ahead_today => today,
+            None | Some(Day::Tomorrow) => today + Duration::days(1),
+            Some(Day::Today) => today,
+            Some(Day::Weekday { weekday, next }) => {
+                let mut days_ahead = (7 + weekday.num_days_from_monday()
+                    - today.weekday().num_days_from_monday())
+                    % 7;
+                if days_ahead == 0 && (next || !ahead_today) {
+                    days_ahead = 7;
+                }
+                today + Duration::days(days_ahead as i64)
+            }
+            Some(Day::Date(date)) => date,
+            Some(Day::Yearless { month, day }) => (today.year()..=today.year() + 8)
+                .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+                .find(|date| date.and_time(time) > now_local)
+                .ok_or_else(|| invalid(&format!("{}/{} is not a date", month, day)))?,
+        };
+        let start = date.and_time(time);
+
+        let Some(rule) = self.recurrence else {
+            let trigger_time = recurrence::local_to_utc(tz, start)
+                .ok_or_else(|| invalid(&format!("\"{}\" is not a valid local time", input)))?;
+            if trigger_time <= now {
+                return Err(invalid(&format!("\"{}\" is in the past", input)));
+            }
+            return Ok(ParsedReminderTime {
+                trigger_time,
+                recurrence_rule: None,
+            });
+        };
+
+        let rule = RecurrenceRule::parse(&rule)?;
+        let trigger_time = rule
+            .next_after(start, now, tz)
+            .ok_or_else(|| invalid(&format!("\"{}\" never happens", input)))?;
+        Ok(ParsedReminderTime {
+            trigger_time,
+            recurrence_rule: Some(rule.to_string()),
+        })
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, what: &str) -> Result<&'a str> {
+        self.next()
+            .ok_or_else(|| invalid(&format!("expected {} at the end", what)))
+    }
+
+    fn parse(mut self) -> Result<Phrase> {
+        let mut phrase = Phrase::default();
+
+        while let Some(token) = self.next() {
+            match token {
+                "at" | "on" | "the" | "@" => {}
+                "in" => {
+                    if phrase.offset.is_some() {
+                        return Err(invalid("more than one \"in ...\""));
+                    }
+                    phrase.offset = Some(self.parse_offset()?);
+                }
+                "today" => phrase.set_day(Day::Today)?,
+                "tonight" => {
+                    phrase.set_day(Day::Today)?;
+                    phrase.implied_time = part_of_day("night");
+                }
+                "tomorrow" | "tmrw" => phrase.set_day(Day::Tomorrow)?,
+                "next" | "this" => {
+                    let name = self.expect("a weekday")?;
+                    let weekday = weekday(name).ok_or_else(|| {
+                        invalid(&format!("expected a weekday after \"{}\"", token))
+                    })?;
+                    phrase.set_day(Day::Weekday {
+                        weekday,
+                        next: token == "next",
+                    })?;
+                }
+                "every" => {
+                    let (rule, implied_time) = self.parse_every()?;
+                    phrase.set_recurrence(rule)?;
+                    if implied_time.is_some() {
+                        phrase.implied_time = implied_time;
+                    }
+                }
+                "daily" => phrase.set_recurrence("FREQ=DAILY".to_string())?,
+                "weekly" => phrase.set_recurrence("FREQ=WEEKLY".to_string())?,
+                "monthly" => phrase.set_recurrence("FREQ=MONTHLY".to_string())?,
+                "yearly" | "annually" => phrase.set_recurrence("FREQ=YEARLY".to_string())?,
+                "noon" | "midday" => phrase.set_time(hour(12))?,
+                "midnight" => phrase.set_time(hour(0))?,
+                _ => {
+                    if let Some(time) = part_of_day(token) {
+                        phrase.implied_time = Some(time);
+                    } else if let Some(weekday) = weekday(token) {
+                        phrase.set_day(Day::Weekday {
+                            weekday,
+                            next: false,
+                        })?;
+                    } else if let Some(month) = month(token) {
+                        let day = self.expect("a day of the month")?;
+                        let day = day_of_month(day)
+                            .ok_or_else(|| invalid(&format!("\"{}\" is not a day", day)))?;
+                        phrase.set_day(Day::Yearless { month, day })?;
+                    } else if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+                        phrase.set_day(Day::Date(date))?;
+                    } else if let Some(date) = day_of_month(token)
+                        .and_then(|day| Some((day, self.peek().and_then(month)?)))
+                        .map(|(day, month)| Day::Yearless { month, day })
+                    {
+                        // "25th december"
+                        self.pos += 1;
+                        phrase.set_day(date)?;
+                    } else {
+                        let time = self.parse_time(token)?;
+                        phrase.set_time(time)?;
+                    }
+                }
+            }
+        }
+
+        Ok(phrase)
+    }
+
+    /// `2 hours`, `90m`, `an hour and 30 minutes`, `half an hour`
+    fn parse_offset(&mut self) -> Result<Duration> {
+        let mut total = Duration::zero();
+
+        loop {
+            let token = self.expect("an amount")?;
+            let part = if token == "half" {
+                let article = self.expect("\"an hour\"")?;
+                let unit = self.expect("\"an hour\"")?;
+                if !matches!(article, "a" | "an") || unit_minutes(unit) != Some(60) {
+                    return Err(invalid("expected \"half an hour\""));
+                }
+                Duration::minutes(30)
+            } else {
+                let (amount, unit) = match token {
+                    "a" | "an" => (1, None),
+                    _ => {
+                        let split = token
+                            .find(|c: char| !c.is_ascii_digit())
+                            .unwrap_or(token.len());
+                        let amount = token[..split].parse::<u32>().map_err(|_| {
+                            invalid(&format!("\"{}\" is not an amount of time", token))
+                        })?;
+                        (amount, Some(&token[split..]).filter(|u| !u.is_empty()))
+                    }
+                };
+                let unit = match unit {
+                    Some(unit) => unit,
+                    None => self.expect("a unit")?,
+                };
+                let minutes = unit_minutes(unit)
+                    .ok_or_else(|| invalid(&format!("\"{}\" is not a unit of time", unit)))?;
+                Duration::try_minutes(amount as i64 * minutes)
+                    .ok_or_else(|| invalid("offset is too large"))?
+            };
+            total = total
+                .checked_add(&part)
+                .ok_or_else(|| invalid("offset is too large"))?;
+
+            // More parts: "1 hour 30 minutes", "1 hour and 30 minutes"
+            match self.peek() {
+                Some("and") => self.pos += 1,
+                Some(next) if next.starts_with(|c: char| c.is_ascii_digit()) => {}
+                _ => return Ok(total),
+            }
+        }
+    }
+
+    /// What follows "every"; returns the RRULE and an implied time of day
+    fn parse_every(&mut self) -> Result<(String, Option<NaiveTime>)> {
+        let token = self.expect("what to repeat")?;
+
+        let (interval, unit) = match token {
+            "other" => (2, self.expect("a unit")?),
+            _ => match token.parse::<u32>() {
+                Ok(0) => return Err(invalid("can't repeat every 0")),
+                Ok(interval) => (interval, self.expect("a unit")?),
+                Err(_) => (1, token),
+            },
+        };
+        let freq = match unit {
+            "day" | "days" => Some("DAILY"),
+            "week" | "weeks" => Some("WEEKLY"),
+            "month" | "months" => Some("MONTHLY"),
+            "year" | "years" => Some("YEARLY"),
+            _ => None,
+        };
+        if let Some(freq) = freq {
+            let rule = match interval {
+                1 => format!("FREQ={}", freq),
+                n => format!("FREQ={};INTERVAL={}", freq, n),
+            };
+            return Ok((rule, None));
+        }
+        if interval != 1 {
+            return Err(invalid(&format!("\"{}\" is not a unit of time", unit)));
+        }
+
+        let days = match unit {
+            "weekday" | "weekdays" => "MO,TU,WE,TH,FR".to_string(),
+            "weekend" | "weekends" => "SA,SU".to_string(),
+            _ => {
+                if let Some(time) = part_of_day(unit) {
+                    return Ok(("FREQ=DAILY".to_string(), Some(time)));
+                }
+                let first = weekday(unit)
+                    .ok_or_else(|| invalid(&format!("can't repeat every \"{}\"", unit)))?;
+                let mut weekdays = vec![first];
+                // "monday and thursday", "mon wed fri"
+                loop {
+                    match self.peek() {
+                        Some("and") => self.pos += 1,
+                        Some(next) => match weekday(next) {
+                            Some(day) => {
+                                weekdays.push(day);
+                                self.pos += 1;
+                            }
+                            None => break,
+                        },
+                        None => break,
+                    }
+                }
+                weekdays.sort_by_key(|day| day.num_days_from_monday());
+                weekdays.dedup();
+                weekdays
+                    .iter()
+                    .map(|day| recurrence::weekday_code(*day))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+        };
+        Ok((format!("FREQ=WEEKLY;BYDAY={}", days), None))
+    }
+
+    /// `9am`, `9 am`, `9:30`, `9:30pm`, `21:00`, `7`
+    fn parse_time(&mut self, token: &str) -> Result<NaiveTime> {
+        let not_understood = || invalid(&format!("didn't understand \"{}\"", token));
+
+        let (clock, mut meridiem) = match token
+            .strip_suffix("am")
+            .map(|clock| (clock, Some(false)))
+            .or_else(|| token.strip_suffix("pm").map(|clock| (clock, Some(true))))
+        {
+            Some(split) => split,
+            None => (token, None),
+        };
+        if meridiem.is_none() {
+            meridiem = match self.peek() {
+                Some("am") => Some(false),
+                Some("pm") => Some(true),
+                _ => None,
+            };
+            if meridiem.is_some() {
+                self.pos += 1;
+            }
+        }
+
+        let (hour, minute) = match clock.split_once(':') {
+            Some((hour, minute)) if minute.len() == 2 => (hour, minute),
+            Some(_) => return Err(not_understood()),
+            None => (clock, "0"),
+        };
+        let hour: u32 = hour.parse().map_err(|_| not_understood())?;
+        let minute: u32 = minute.parse().map_err(|_| not_understood())?;
+
+        let hour = match meridiem {
+            Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+            Some(_) => return Err(invalid(&format!("\"{}\" is not a time", token))),
+            None => hour,
+        };
+        NaiveTime::from_hms_opt(hour, minute, 0)
+            .ok_or_else(|| invalid(&format!("\"{}\" is not a time", token)))
+    }
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::InvalidReminderTime(message.to_string())
+}
+
+fn hour(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, 0, 0).unwrap_or(NaiveTime::MIN)
+}
+
+fn part_of_day(token: &str) -> Option<NaiveTime> {
+    match token {
+        "morning" | "mornings" => Some(hour(config::DEFAULT_REMINDER_HOUR)),
+        "afternoon" | "afternoons" => Some(hour(15)),
+        "evening" | "evenings" => Some(hour(18)),
+        "night" | "nights" => Some(hour(20)),
+        _ => None,
+    }
+}
+
+fn unit_minutes(unit: &str) -> Option<i64> {
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(1),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(60),
+        "d" | "day" | "days" => Some(60 * 24),
+        "w" | "wk" | "wks" | "week" | "weeks" => Some(60 * 24 * 7),
+        _ => None,
+    }
+}
+
+fn weekday(token: &str) -> Option<Weekday> {
+    match token {
+        "monday" | "mondays" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tuesdays" | "tue" | "tues" => Some(Weekday::Tue),
+        "wednesday" | "wednesdays" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thursdays" | "thu" | "thur" | "thurs" => Some(Weekday::Thu),
+        "friday" | "fridays" | "fri" => Some(Weekday::Fri),
+        "saturday" | "saturdays" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sundays" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn month(token: &str) -> Option<u32> {
+    let month = match token {
+        "january" | "jan" => 1,
+        "february" | "feb" => 2,
+        "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "may" => 5,
+        "june" | "jun" => 6,
+        "july" | "jul" => 7,
+        "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "october" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+/// `25`, `25th`, `1st`
+fn day_of_month(token: &str) -> Option<u32> {
+    let digits = ["st", "nd", "rd", "th"]
+        .iter()
+        .find_map(|suffix| token.strip_suffix(suffix))
+        .unwrap_or(token);
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use chrono_tz::America::New_York;
+
+    /// Friday 2026-10-16 14:00 in New York
+    fn now() -> DateTime<Utc> {
+        at("2026-10-16 14:00")
+    }
+
+    fn at(local: &str) -> DateTime<Utc> {
+        let local = NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M").unwrap();
+        recurrence::local_to_utc(&New_York, local).unwrap()
+    }
+
+    fn parse(input: &str) -> ParsedReminderTime {
+        parse_reminder_time(input, now(), &New_York).unwrap()
+    }
+
+    fn parse_at(input: &str) -> DateTime<Utc> {
+        let parsed = parse(input);
+        assert_eq!(parsed.recurrence_rule, None, "{}", input);
+        parsed.trigger_time
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(parse_at("in 2 hours"), now() + Duration::hours(2));
+        assert_eq!(parse_at("in 90m"), now() + Duration::minutes(90));
+        assert_eq!(parse_at("In an hour"), now() + Duration::hours(1));
+        assert_eq!(
+            parse_at("in 1 hour and 30 minutes"),
+            now() + Duration::minutes(90)
+        );
+        assert_eq!(parse_at("in 1h 15min"), now() + Duration::minutes(75));
+        assert_eq!(parse_at("in half an hour"), now() + Duration::minutes(30));
+        assert_eq!(parse_at("in 3 days"), now() + Duration::days(3));
+    }
+
+    #[test]
+    fn test_days_and_times() {
+        assert_eq!(parse_at("tomorrow 9am"), at("2026-10-17 09:00"));
+        assert_eq!(parse_at("tomorrow"), at("2026-10-17 09:00"));
+        assert_eq!(parse_at("Tomorrow at 9:30 pm"), at("2026-10-17 21:30"));
+        assert_eq!(parse_at("today 17:45"), at("2026-10-16 17:45"));
+        assert_eq!(parse_at("tonight"), at("2026-10-16 20:00"));
+        assert_eq!(parse_at("tomorrow evening"), at("2026-10-17 18:00"));
+        assert_eq!(parse_at("at midnight"), at("2026-10-17 00:00"));
+
+        // A time alone is its next occurrence
+        assert_eq!(parse_at("3pm"), at("2026-10-16 15:00"));
+        assert_eq!(parse_at("at 1 pm"), at("2026-10-17 13:00"));
+        assert_eq!(parse_at("12am"), at("2026-10-17 00:00"));
+
+        // Weekdays: today while the time is ahead, "next" never today
+        assert_eq!(parse_at("friday 5pm"), at("2026-10-16 17:00"));
+        assert_eq!(parse_at("friday 9am"), at("2026-10-23 09:00"));
+        assert_eq!(parse_at("next friday at noon"), at("2026-10-23 12:00"));
+        assert_eq!(parse_at("on monday"), at("2026-10-19 09:00"));
+
+        // Dates
+        assert_eq!(parse_at("dec 25"), at("2026-12-25 09:00"));
+        assert_eq!(parse_at("25th December 8am"), at("2026-12-25 08:00"));
+        assert_eq!(parse_at("october 1"), at("2027-10-01 09:00"));
+        assert_eq!(parse_at("2027-01-05 7:15am"), at("2027-01-05 07:15"));
+    }
+
+    #[test]
+    fn test_across_dst() {
+        // New York falls back on 2026-11-01: 09:00 EDT today, 09:00 EST tomorrow
+        let now = at("2026-10-31 14:00");
+        let parsed = parse_reminder_time("tomorrow 9am", now, &New_York).unwrap();
+        assert_eq!(parsed.trigger_time, now + Duration::hours(20));
+    }
+
+    #[test]
+    fn test_repeats() {
+        let cases = [
+            (
+                "every monday 8:30",
+                "2026-10-19 08:30",
+                "FREQ=WEEKLY;BYDAY=MO",
+            ),
+            (
+                "every weekday at 7:45am",
+                "2026-10-19 07:45",
+                "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR",
+            ),
+            (
+                "every Thursday and mon, 6pm",
+                "2026-10-19 18:00",
+                "FREQ=WEEKLY;BYDAY=MO,TH",
+            ),
+            (
+                "every weekend",
+                "2026-10-17 09:00",
+                "FREQ=WEEKLY;BYDAY=SA,SU",
+            ),
+            ("every day at 3pm", "2026-10-16 15:00", "FREQ=DAILY"),
+            ("daily 8am", "2026-10-17 08:00", "FREQ=DAILY"),
+            ("every morning", "2026-10-17 09:00", "FREQ=DAILY"),
+            (
+                "every other week",
+                "2026-10-17 09:00",
+                "FREQ=WEEKLY;INTERVAL=2",
+            ),
+            (
+                "every 3 days at noon",
+                "2026-10-17 12:00",
+                "FREQ=DAILY;INTERVAL=3",
+            ),
+            ("every month on dec 1", "2026-12-01 09:00", "FREQ=MONTHLY"),
+            ("every year", "2026-10-17 09:00", "FREQ=YEARLY"),
+        ];
+        for (input, first, rule) in cases {
+            let parsed = parse(input);
+            assert_eq!(parsed.trigger_time, at(first), "{}", input);
+            assert_eq!(parsed.recurrence_rule.as_deref(), Some(rule), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_rejects() {
+        for input in [
+            "",
+            "   ",
+            "yesterday",
+            "today 8am",
+            "2026-01-01",
+            "feb 30",
+            "13pm",
+            "25:00",
+            "9:5",
+            "in",
+            "in 2",
+            "in 2 fortnights",
+            "in 2 hours tomorrow",
+            "tomorrow today",
+            "next week",
+            "every 0 days",
+            "every 2 mondays",
+            "every blue moon",
+            "at",
+        ] {
+            let result = parse_reminder_time(input, now(), &New_York);
+            assert!(
+                matches!(result, Err(AppError::InvalidReminderTime(_))),
+                "{:?} gave {:?}",
+                input,
+                result
+            );
+        }
+
+        let long = "tomorrow ".repeat(50);
+        assert!(parse_reminder_time(&long, now(), &New_York).is_err());
+    }
+}
@@ -1,30 +1,157 @@
 //! Reminders service
 //!
 //! Manages reminder scheduling and notifications.
-//! Runs background task that checks for due reminders every minute.
-
-use crate::database::{Reminder, Repository};
-use crate::error::Result;
-use chrono::{DateTime, Utc};
-use std::sync::Arc;
+//! A background task sleeps until the next reminder is due (see
+//! `reminder_queue`); reminder changes wake it through a channel.
+//! Recurring reminders move to their next occurrence when they fire
+//! (see `recurrence`). Fired reminders can be snoozed, dismissed or
+//! acknowledged, and every step is recorded in the reminder history.
+//! On startup, reminders that came due while the app was closed are
+//! caught up per their missed policy before the first check.
+
+use crate::config;
+use crate::database::{
+    MissedReminderPolicy, Reminder, ReminderAction, ReminderHistoryEntry, Repository,
+};
+use crate::error::{AppError, Result};
+use crate::services::inline_reminders;
+use crate::services::recurrence::{self, RecurrenceRule};
+use crate::services::reminder_queue::{ReminderQueue, ScheduleChange};
+use crate::services::reminder_time::{self, ParsedReminderTime};
+use crate::services::settings::ReminderSettings;
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter};
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
+
+/// How long to snooze a reminder: a preset, or `{"minutes": n}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnoozeDuration {
+    FiveMinutes,
+    TenMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    OneHour,
+    /// Tomorrow at `SNOOZE_MORNING_HOUR`, local time
+    TomorrowMorning,
+    Minutes(u32),
+}
+
+impl SnoozeDuration {
+    /// When a reminder snoozed at `now` fires again
+    pub fn until<Tz: TimeZone>(self, now: DateTime<Utc>, tz: &Tz) -> Result<DateTime<Utc>> {
+        let minutes = match self {
+            Self::FiveMinutes => 5,
+            Self::TenMinutes => 10,
+            Self::FifteenMinutes => 15,
+            Self::ThirtyMinutes => 30,
+            Self::OneHour => 60,
+            Self::Minutes(minutes) => {
+                if !(1..=config::MAX_SNOOZE_MINUTES).contains(&minutes) {
+                    return Err(AppError::Generic(format!(
+                        "Snooze must be between 1 and {} minutes",
+                        config::MAX_SNOOZE_MINUTES
+                    )));
+                }
+                minutes
+            }
+            Self::TomorrowMorning => {
+                let tomorrow = now
+                    .with_timezone(tz)
+                    .date_naive()
+                    .succ_opt()
+                    .and_then(|date| date.and_hms_opt(config::SNOOZE_MORNING_HOUR, 0, 0))
+                    .and_then(|local| recurrence::local_to_utc(tz, local));
+                return tomorrow.ok_or_else(|| {
+                    AppError::Generic("Could not compute tomorrow morning".to_string())
+                });
+            }
+        };
+
+        Ok(now + Duration::minutes(minutes as i64))
+    }
+}
+
+/// A reminder that came due while the app was closed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedReminder {
+    pub reminder_id: String,
+    pub note_id: String,
+    /// Title of the note (None if it could not be loaded)
+    pub note_title: Option<String>,
+    /// The trigger or snooze time that was missed
+    pub due_at: DateTime<Utc>,
+}
+
+/// Result of the startup catch-up of missed reminders
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MissedReminderSummary {
+    /// Batched into this summary instead of one popup each
+    pub reminders: Vec<MissedReminder>,
+    /// Older than their skip age and dropped
+    pub skipped: Vec<MissedReminder>,
+    /// Left to pop up on their own (`fire` policy)
+    pub fired: usize,
+}
 
 /// Reminders service with background scheduler
 #[derive(Clone)]
 pub struct RemindersService {
     repo: Repository,
     app_handle: Arc<Mutex<Option<AppHandle>>>,
+    reminder_settings: Arc<RwLock<ReminderSettings>>,
+    missed_summary: Arc<Mutex<Option<MissedReminderSummary>>>,
+    schedule_tx: mpsc::UnboundedSender<ScheduleChange>,
+    /// Taken by the scheduler when it starts
+    schedule_rx: Arc<Mutex<Option<mpsc::UnboundedReceiver<ScheduleChange>>>>,
 }
 
 impl RemindersService {
     pub fn new(repo: Repository) -> Self {
+        let (schedule_tx, schedule_rx) = mpsc::unbounded_channel();
         Self {
             repo,
             app_handle: Arc::new(Mutex::new(None)),
+            reminder_settings: Arc::new(RwLock::new(ReminderSettings::default())),
+            missed_summary: Arc::new(Mutex::new(None)),
+            schedule_tx,
+            schedule_rx: Arc::new(Mutex::new(Some(schedule_rx))),
+        }
+    }
+
+    /// Tell the scheduler about a reminder change
+    fn notify_scheduler(&self, change: ScheduleChange) {
+        if self.schedule_tx.send(change).is_err() {
+            tracing::warn!("Reminders scheduler is not running");
         }
     }
 
+    /// Make the scheduler reload all reminders, after they were changed
+    /// outside this service (e.g. by a backup restore)
+    pub fn resync_scheduler(&self) {
+        self.notify_scheduler(ScheduleChange::Resync);
+    }
+
+    /// Apply reminder settings (the global missed reminder policy)
+    pub fn set_reminder_settings(&self, settings: ReminderSettings) -> Result<()> {
+        let mut current = self
+            .reminder_settings
+            .write()
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))?;
+        *current = settings;
+        Ok(())
+    }
+
+    fn reminder_settings(&self) -> Result<ReminderSettings> {
+        self.reminder_settings
+            .read()
+            .map(|s| s.clone())
+            .map_err(|e| AppError::Generic(format!("Failed to acquire lock: {}", e)))
+    }
+
     /// Set the app handle for notifications
     pub async fn set_app_handle(&self, handle: AppHandle) {
         tracing::info!("Setting app handle for reminders service");
@@ -44,7 +171,8 @@ impl RemindersService {
         glow_enabled: Option<bool>,
     ) -> Result<Reminder> {
         tracing::info!("Creating reminder for note {} at {}", note_id, trigger_time);
-        self.repo
+        let reminder = self
+            .repo
             .create_reminder(
                 note_id,
                 trigger_time,
@@ -53,7 +181,16 @@ impl RemindersService {
                 shake_enabled,
                 glow_enabled,
             )
-            .await
+            .await?;
+
+        self.notify_scheduler(ScheduleChange::Upsert(reminder.clone()));
+        Ok(reminder)
+    }
+
+    /// Resolve a natural-language reminder time ("tomorrow 9am", "every
+    /// monday 8:30") in local time, without saving anything
+    pub fn preview_reminder_time(&self, text: &str) -> Result<ParsedReminderTime> {
+        reminder_time::parse_reminder_time(text, Utc::now(), &Local)
     }
 
     /// List all active (not yet triggered) reminders
@@ -64,7 +201,9 @@ impl RemindersService {
     /// Delete a reminder
     pub async fn delete_reminder(&self, id: &str) -> Result<()> {
         tracing::info!("Deleting reminder: {}", id);
-        self.repo.delete_reminder(id).await
+        self.repo.delete_reminder(id).await?;
+        self.notify_scheduler(ScheduleChange::Remove(id.to_string()));
+        Ok(())
     }
 
     /// Update an existing reminder
@@ -78,7 +217,8 @@ impl RemindersService {
         glow_enabled: Option<bool>,
     ) -> Result<Reminder> {
         tracing::info!("Updating reminder {} to trigger at {}", id, trigger_time);
-        self.repo
+        let reminder = self
+            .repo
             .update_reminder(
                 id,
                 trigger_time,
@@ -87,64 +227,459 @@ impl RemindersService {
                 shake_enabled,
                 glow_enabled,
             )
+            .await?;
+
+        // A new time restarts the series from that time
+        match reminder.recurrence_rule.clone() {
+            Some(rule) => self.set_reminder_recurrence(id, Some(rule.as_str())).await,
+            None => {
+                self.notify_scheduler(ScheduleChange::Upsert(reminder.clone()));
+                Ok(reminder)
+            }
+        }
+    }
+
+    /// Make a reminder recur (or stop recurring with None).
+    /// The series starts at the reminder's current trigger time, in local
+    /// time, and the reminder moves to the rule's first occurrence.
+    pub async fn set_reminder_recurrence(&self, id: &str, rule: Option<&str>) -> Result<Reminder> {
+        let reminder = self.repo.get_reminder(id).await?;
+
+        let Some(rule) = rule.filter(|r| !r.trim().is_empty()) else {
+            tracing::info!("Clearing recurrence of reminder {}", id);
+            let cleared = self
+                .repo
+                .set_reminder_recurrence(id, reminder.trigger_time, None, None)
+                .await?;
+            self.notify_scheduler(ScheduleChange::Upsert(cleared.clone()));
+            return Ok(cleared);
+        };
+
+        let rule = RecurrenceRule::parse(rule)?;
+        let start = reminder.trigger_time.with_timezone(&Local).naive_local();
+        let first = rule.first_occurrence(start, &Local).ok_or_else(|| {
+            AppError::InvalidRecurrence(
+                "the rule has no occurrences after the reminder time".to_string(),
+            )
+        })?;
+
+        tracing::info!(
+            "Setting recurrence of reminder {} to {} (first at {})",
+            id,
+            rule,
+            first
+        );
+        let recurring = self
+            .repo
+            .set_reminder_recurrence(id, first, Some(&rule.to_string()), Some(start))
+            .await?;
+
+        self.notify_scheduler(ScheduleChange::Upsert(recurring.clone()));
+        Ok(recurring)
+    }
+
+    /// Set how a reminder is handled if it comes due while the app is
+    /// closed (None = use the global reminder settings)
+    pub async fn set_reminder_missed_policy(
+        &self,
+        id: &str,
+        policy: Option<MissedReminderPolicy>,
+        skip_after_hours: Option<u32>,
+    ) -> Result<Reminder> {
+        if let Some(hours) = skip_after_hours {
+            if !(1..=config::MAX_MISSED_SKIP_AFTER_HOURS).contains(&hours) {
+                return Err(AppError::Generic(format!(
+                    "Missed reminder skip age must be between 1 and {} hours",
+                    config::MAX_MISSED_SKIP_AFTER_HOURS
+                )));
+            }
+        }
+
+        tracing::info!(
+            "Setting missed policy of reminder {} to {:?} ({:?} hours)",
+            id,
+            policy,
+            skip_after_hours
+        );
+        self.repo
+            .set_reminder_missed_policy(id, policy, skip_after_hours)
+            .await
+    }
+
+    /// Bring a note's inline reminders in line with the `@remind` markers in
+    /// its content: new markers get a reminder (unless their time has
+    /// passed), edited markers move or relink theirs, and reminders whose
+    /// marker is gone are deleted
+    pub async fn sync_inline_reminders(&self, note_id: &str, content_json: &str) -> Result<()> {
+        let text = Repository::extract_text_from_delta(content_json);
+        let markers = inline_reminders::find_markers(&text, &Local);
+        let reminders = self.repo.list_inline_reminders(note_id).await?;
+        if markers.is_empty() && reminders.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let matched = inline_reminders::match_markers(&reminders, &markers);
+
+        for reminder in matched.removed {
+            tracing::info!("Marker of inline reminder {} was removed", reminder.id);
+            self.delete_reminder(&reminder.id).await?;
+        }
+
+        for marker in matched.added {
+            let Some(trigger_time) = marker.trigger_time.filter(|t| *t > now) else {
+                tracing::debug!("Not scheduling inline reminder \"{}\"", marker.text);
+                continue;
+            };
+            let reminder = self
+                .create_reminder(note_id, trigger_time, None, None, None, None)
+                .await?;
+            self.repo
+                .set_reminder_inline_source(&reminder.id, &marker.text, marker.start, marker.end)
+                .await?;
+        }
+
+        for (reminder, marker) in matched.linked {
+            if reminder.inline_text.as_deref() == Some(marker.text.as_str())
+                && reminder.inline_start == Some(marker.start)
+                && reminder.inline_end == Some(marker.end)
+            {
+                continue;
+            }
+
+            let (previous_time, _) = inline_reminders::linked_marker(reminder);
+            self.repo
+                .set_reminder_inline_source(&reminder.id, &marker.text, marker.start, marker.end)
+                .await?;
+
+            // Only a new, readable, future time moves the reminder, so half
+            // typed edits don't drag it around
+            match marker.trigger_time {
+                Some(trigger_time)
+                    if trigger_time > now
+                        && trigger_time != reminder.trigger_time
+                        && marker.local_time != previous_time =>
+                {
+                    self.reschedule_inline_reminder(reminder, trigger_time)
+                        .await?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move an inline reminder to its marker's new time, re-arming it if it
+    /// already fired
+    async fn reschedule_inline_reminder(
+        &self,
+        reminder: &Reminder,
+        trigger_time: DateTime<Utc>,
+    ) -> Result<()> {
+        tracing::info!("Moving inline reminder {} to {}", reminder.id, trigger_time);
+        let moved = self
+            .repo
+            .update_reminder_state(&reminder.id, trigger_time, false, None)
+            .await?;
+
+        // A new time restarts the series from that time
+        match moved.recurrence_rule.clone() {
+            Some(rule) => {
+                self.set_reminder_recurrence(&reminder.id, Some(rule.as_str()))
+                    .await?;
+            }
+            None => self.notify_scheduler(ScheduleChange::Upsert(moved)),
+        }
+        Ok(())
+    }
+
+    /// Snooze a reminder. One-off and finished reminders come back at the
+    /// snooze time; recurring ones fire once more then and keep their next
+    /// occurrence (which drops the snooze if it comes first).
+    pub async fn snooze_reminder(&self, id: &str, duration: SnoozeDuration) -> Result<Reminder> {
+        let reminder = self.repo.get_reminder(id).await?;
+        let until = duration.until(Utc::now(), &Local)?;
+
+        let trigger_time = if reminder.recurrence_rule.is_none() || reminder.triggered {
+            until
+        } else {
+            reminder.trigger_time
+        };
+
+        tracing::info!("Snoozing reminder {} until {}", id, until);
+        let snoozed = self
+            .repo
+            .update_reminder_state(id, trigger_time, false, Some(until))
+            .await?;
+        self.repo
+            .record_reminder_event(&reminder, ReminderAction::Snoozed, None, Some(until))
+            .await?;
+
+        self.notify_scheduler(ScheduleChange::Upsert(snoozed.clone()));
+        Ok(snoozed)
+    }
+
+    /// Dismiss a fired reminder, cancelling any pending snooze
+    pub async fn dismiss_reminder(&self, id: &str) -> Result<Reminder> {
+        self.close_reminder(id, ReminderAction::Dismissed).await
+    }
+
+    /// Acknowledge a fired reminder, cancelling any pending snooze
+    pub async fn acknowledge_reminder(&self, id: &str) -> Result<Reminder> {
+        self.close_reminder(id, ReminderAction::Acknowledged).await
+    }
+
+    async fn close_reminder(&self, id: &str, action: ReminderAction) -> Result<Reminder> {
+        let reminder = self.repo.get_reminder(id).await?;
+        tracing::info!("Reminder {}: {:?}", id, action);
+
+        let closed = match reminder.snoozed_until {
+            // A reminder that is only active because of the snooze is done
+            Some(until) => {
+                let done = reminder.triggered || reminder.trigger_time == until;
+                let closed = self
+                    .repo
+                    .update_reminder_state(id, reminder.trigger_time, done, None)
+                    .await?;
+                self.notify_scheduler(ScheduleChange::Upsert(closed.clone()));
+                closed
+            }
+            None => reminder.clone(),
+        };
+        self.repo
+            .record_reminder_event(&reminder, action, None, None)
+            .await?;
+
+        Ok(closed)
+    }
+
+    /// Reminder history, newest first, optionally for one reminder
+    pub async fn list_reminder_history(
+        &self,
+        reminder_id: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<Vec<ReminderHistoryEntry>> {
+        self.repo
+            .list_reminder_history(reminder_id, history_limit(limit))
+            .await
+    }
+
+    /// Fired reminders that were never snoozed, dismissed or acknowledged
+    pub async fn list_missed_reminders(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<ReminderHistoryEntry>> {
+        let before = Utc::now() - Duration::minutes(config::MISSED_REMINDER_AFTER_MINUTES);
+        self.repo
+            .list_missed_reminders(before, history_limit(limit))
             .await
     }
 
+    /// Summary of the reminders missed while the app was closed, if any
+    /// were batched on this startup
+    pub async fn get_missed_reminder_summary(&self) -> Option<MissedReminderSummary> {
+        self.missed_summary.lock().await.clone()
+    }
+
+    /// Catch up on reminders that came due while the app was closed.
+    /// Depending on each reminder's missed policy (or the global one) it is
+    /// left to pop up on its own, batched into one summary, or dropped when
+    /// older than the skip age (and batched otherwise).
+    pub async fn catch_up_missed_reminders(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<MissedReminderSummary> {
+        let settings = self.reminder_settings()?;
+        let mut summary = MissedReminderSummary::default();
+
+        for reminder in self.list_active_reminders().await? {
+            let Some(due) = due_at(&reminder, now) else {
+                continue;
+            };
+            let policy = reminder.missed_policy.unwrap_or(settings.missed_policy);
+            let skip_after = reminder
+                .missed_skip_after_hours
+                .unwrap_or(settings.missed_skip_after_hours);
+
+            let action = match policy {
+                MissedReminderPolicy::Fire => {
+                    summary.fired += 1;
+                    continue;
+                }
+                MissedReminderPolicy::Skip if now - due > Duration::hours(skip_after as i64) => {
+                    ReminderAction::Skipped
+                }
+                _ => ReminderAction::Fired,
+            };
+
+            self.advance_due_reminder(&reminder, now).await?;
+            self.record_scheduler_event(&reminder, action, due).await;
+
+            let missed = MissedReminder {
+                reminder_id: reminder.id.clone(),
+                note_id: reminder.note_id.clone(),
+                note_title: self
+                    .repo
+                    .get_note(&reminder.note_id)
+                    .await
+                    .ok()
+                    .map(|note| note.title),
+                due_at: due,
+            };
+            if action == ReminderAction::Skipped {
+                summary.skipped.push(missed);
+            } else {
+                summary.reminders.push(missed);
+            }
+        }
+
+        tracing::info!(
+            "Missed reminders: {} batched, {} skipped, {} left to fire",
+            summary.reminders.len(),
+            summary.skipped.len(),
+            summary.fired
+        );
+        Ok(summary)
+    }
+
+    /// Keep the batched missed reminders for `get_missed_reminder_summary`
+    /// and announce them with one `missed-reminders` event
+    async fn publish_missed_summary(&self, summary: MissedReminderSummary) {
+        *self.missed_summary.lock().await = Some(summary.clone());
+
+        let handle = self.app_handle.lock().await.clone();
+        match handle {
+            Some(handle) => {
+                if let Err(e) = handle.emit("missed-reminders", summary) {
+                    tracing::error!("Failed to emit missed-reminders event: {}", e);
+                }
+            }
+            None => tracing::error!("publish_missed_summary: App handle not set"),
+        }
+    }
+
     /// Start the background scheduler
     pub fn start_scheduler(self) {
         tokio::spawn(async move {
-            tracing::info!("Starting reminders scheduler loop");
+            // Deal with reminders missed while the app was closed first,
+            // so they don't all pop up at once on the first check
+            match self.catch_up_missed_reminders(Utc::now()).await {
+                Ok(summary) if summary.reminders.is_empty() => {}
+                Ok(summary) => self.publish_missed_summary(summary).await,
+                Err(e) => tracing::error!("Failed to catch up on missed reminders: {}", e),
+            }
 
-            // Check every 5 seconds for reminders (ensures accurate timing within a few seconds)
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
+            let Some(changes) = self.schedule_rx.lock().await.take() else {
+                tracing::error!("Reminders scheduler is already running");
+                return;
+            };
 
-            // First tick happens immediately, so consume it
-            interval.tick().await;
-            tracing::info!("Reminders scheduler: initial tick consumed, starting loop");
+            tracing::info!("Starting reminders scheduler loop");
+            self.run_scheduler(changes).await;
+            tracing::info!("Reminders scheduler loop stopped");
+        });
 
-            let mut iteration = 0u64;
-            loop {
-                iteration += 1;
-                tracing::debug!(
-                    "Reminders scheduler: iteration {} - waiting for tick",
-                    iteration
-                );
+        tracing::info!("Reminders scheduler task spawned successfully");
+    }
 
-                interval.tick().await;
+    /// Scheduler loop: sleep until the earliest queued reminder is due or a
+    /// change arrives, then fire whatever is due. Sleeps are capped at
+    /// `REMINDER_SCHEDULER_MAX_SLEEP_SECS` because tokio timers follow the
+    /// monotonic clock; waking up regularly is how wall-clock changes and
+    /// resume from sleep get noticed, after which the queue is reloaded.
+    async fn run_scheduler(&self, mut changes: mpsc::UnboundedReceiver<ScheduleChange>) {
+        let mut queue = ReminderQueue::default();
+        let mut clock = ClockWatch::new();
+        let mut resync = true;
+
+        loop {
+            if clock.jumped() {
+                tracing::info!("System clock changed or system resumed; resyncing reminders");
+                resync = true;
+            }
 
-                tracing::debug!(
-                    "Reminders scheduler: iteration {} - tick received, checking reminders",
-                    iteration
-                );
+            if resync {
+                match self.resync_queue(&mut queue).await {
+                    Ok(()) => resync = false,
+                    Err(e) => tracing::error!("Failed to load reminders for scheduling: {}", e),
+                }
+            }
 
-                match self.check_and_trigger_reminders().await {
-                    Ok(_) => {
-                        tracing::debug!(
-                            "Reminders scheduler: iteration {} - check complete successfully",
-                            iteration
-                        );
-                    }
+            let now = Utc::now();
+            let mut failed = false;
+            for id in queue.pop_due(now) {
+                match self.trigger_queued_reminder(&id, now).await {
+                    Ok(Some(reminder)) => queue.upsert(&reminder),
+                    Ok(None) => {}
                     Err(e) => {
-                        tracing::error!(
-                            "Reminders scheduler: iteration {} - error during check: {}",
-                            iteration,
-                            e
-                        );
-                        // Continue loop even on error
+                        tracing::error!("Failed to trigger reminder {}: {}", id, e);
+                        failed = true;
                     }
                 }
+            }
 
-                tracing::debug!(
-                    "Reminders scheduler: iteration {} - continuing to next tick",
-                    iteration
-                );
+            let max_sleep =
+                tokio::time::Duration::from_secs(config::REMINDER_SCHEDULER_MAX_SLEEP_SECS);
+            let sleep = queue
+                .next_due()
+                .map(|due| (due - Utc::now()).to_std().unwrap_or_default())
+                .map_or(max_sleep, |until_due| until_due.min(max_sleep));
+            tracing::debug!(
+                "Reminders scheduler: {} queued, sleeping for {:?}",
+                queue.len(),
+                sleep
+            );
+
+            tokio::select! {
+                change = changes.recv() => {
+                    let Some(change) = change else {
+                        break;
+                    };
+                    // Apply everything that arrived together before rescheduling
+                    resync |= !queue.apply(change);
+                    while let Ok(change) = changes.try_recv() {
+                        resync |= !queue.apply(change);
+                    }
+                }
+                _ = tokio::time::sleep(sleep) => {}
             }
-        });
 
-        tracing::info!("Reminders scheduler task spawned successfully");
+            // Failed reminders left the queue; the reload brings them back
+            resync |= failed;
+        }
+    }
+
+    /// Fire everything that is due, then reload the queue from the database
+    async fn resync_queue(&self, queue: &mut ReminderQueue) -> Result<()> {
+        self.check_and_trigger_reminders().await?;
+        queue.reset(self.list_active_reminders().await?);
+        tracing::debug!("Reminders scheduler: resynced {} reminders", queue.len());
+        Ok(())
     }
 
-    /// Check for due reminders and trigger them
+    /// Fire a reminder the queue says is due, after re-reading it (it may
+    /// have changed or gone with its note). Returns its new state to queue.
+    /// Errors leave it to the next resync.
+    async fn trigger_queued_reminder(
+        &self,
+        id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Reminder>> {
+        let Some(reminder) = self.repo.find_reminder(id).await? else {
+            tracing::debug!("Queued reminder {} is gone", id);
+            return Ok(None);
+        };
+
+        if due_at(&reminder, now).is_none() {
+            return Ok(Some(reminder));
+        }
+
+        self.trigger_reminder(&reminder, now).await?;
+        self.repo.get_reminder(id).await.map(Some)
+    }
+
+    /// Check all active reminders and trigger the due ones
     async fn check_and_trigger_reminders(&self) -> Result<()> {
         let reminders = self.list_active_reminders().await?;
         let now = Utc::now();
@@ -154,32 +689,80 @@ impl RemindersService {
         }
 
         for reminder in reminders {
-            tracing::debug!(
-                "Reminder {} scheduled for {} (now: {})",
-                reminder.id,
-                reminder.trigger_time,
-                now
-            );
+            if due_at(&reminder, now).is_some() {
+                self.trigger_reminder(&reminder, now).await?;
+            }
+        }
 
-            // trigger_time is already DateTime<Utc>
-            if reminder.trigger_time <= now {
-                tracing::info!(
-                    "Triggering reminder {} for note {}",
-                    reminder.id,
-                    reminder.note_id
-                );
+        Ok(())
+    }
+
+    /// Fire a due reminder: move it on, record it and show it
+    async fn trigger_reminder(&self, reminder: &Reminder, now: DateTime<Utc>) -> Result<()> {
+        // trigger_time is already DateTime<Utc>
+        let Some(scheduled_for) = due_at(reminder, now) else {
+            return Ok(());
+        };
 
-                // Mark as triggered in database
-                self.repo.mark_reminder_triggered(&reminder.id).await?;
+        tracing::info!(
+            "Triggering reminder {} for note {}",
+            reminder.id,
+            reminder.note_id
+        );
 
-                // Send notification
-                self.send_notification(&reminder).await;
-            }
-        }
+        self.advance_due_reminder(reminder, now).await?;
+        self.record_scheduler_event(reminder, ReminderAction::Fired, scheduled_for)
+            .await;
 
+        // Send notification
+        self.send_notification(reminder).await;
         Ok(())
     }
 
+    /// Move a due reminder on: recurring reminders go to their next
+    /// occurrence, one-off and finished ones are marked as triggered, and
+    /// a due snooze of a recurring reminder is cleared
+    async fn advance_due_reminder(&self, reminder: &Reminder, now: DateTime<Utc>) -> Result<()> {
+        if reminder.trigger_time > now {
+            // Snooze of a recurring reminder; its next occurrence stays
+            return self
+                .repo
+                .update_reminder_state(&reminder.id, reminder.trigger_time, false, None)
+                .await
+                .map(|_| ());
+        }
+
+        let next =
+            recurrence::next_reminder_occurrence(reminder, now, &Local).unwrap_or_else(|e| {
+                tracing::warn!("Reminder {} has an unusable rule: {}", reminder.id, e);
+                None
+            });
+        match next {
+            Some(next) => self.repo.reschedule_reminder(&reminder.id, next).await,
+            None => self.repo.mark_reminder_triggered(&reminder.id).await,
+        }
+    }
+
+    /// Record a scheduler event in the reminder history; failures are only logged
+    async fn record_scheduler_event(
+        &self,
+        reminder: &Reminder,
+        action: ReminderAction,
+        scheduled_for: DateTime<Utc>,
+    ) {
+        if let Err(e) = self
+            .repo
+            .record_reminder_event(reminder, action, Some(scheduled_for), None)
+            .await
+        {
+            tracing::warn!(
+                "Failed to record reminder {} in history: {}",
+                reminder.id,
+                e
+            );
+        }
+    }
+
     /// Send notification for a reminder
     /// Shows the note window centered on screen with visual effects (no system notification)
     async fn send_notification(&self, reminder: &Reminder) {
@@ -373,6 +956,50 @@ impl RemindersService {
     }
 }
 
+/// Notices wall-clock jumps (manual changes, time sync, resume from sleep)
+/// by comparing the wall clock against the monotonic clock
+struct ClockWatch {
+    wall: DateTime<Utc>,
+    monotonic: Instant,
+}
+
+impl ClockWatch {
+    fn new() -> Self {
+        Self {
+            wall: Utc::now(),
+            monotonic: Instant::now(),
+        }
+    }
+
+    /// Whether the two clocks drifted apart since the last call
+    fn jumped(&mut self) -> bool {
+        let (wall, monotonic) = (Utc::now(), Instant::now());
+        let wall_elapsed = wall - self.wall;
+        let monotonic_elapsed =
+            Duration::from_std(monotonic - self.monotonic).unwrap_or_else(|_| Duration::zero());
+        self.wall = wall;
+        self.monotonic = monotonic;
+
+        (wall_elapsed - monotonic_elapsed).num_seconds().abs() >= config::CLOCK_JUMP_TOLERANCE_SECS
+    }
+}
+
+/// When a due reminder became due: its trigger time, or else its snooze
+fn due_at(reminder: &Reminder, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if reminder.trigger_time <= now {
+        Some(reminder.trigger_time)
+    } else {
+        reminder.snoozed_until.filter(|until| *until <= now)
+    }
+}
+
+/// Clamp a requested history length to `1..=MAX_REMINDER_HISTORY_LIMIT`
+fn history_limit(limit: Option<u32>) -> i64 {
+    limit
+        .unwrap_or(config::DEFAULT_REMINDER_HISTORY_LIMIT)
+        .clamp(1, config::MAX_REMINDER_HISTORY_LIMIT) as i64
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 struct ReminderEvent {
     reminder_id: String,
@@ -392,7 +1019,6 @@ struct ReminderEvent {
 mod tests {
     use super::*;
     use crate::database::{initialize_database, CreateNoteRequest, Repository};
-    use chrono::Duration;
     use sqlx::sqlite::SqlitePoolOptions;
     use tempfile::TempDir;
 
@@ -401,7 +1027,10 @@ mod tests {
         let app_data_dir = temp_dir.path().to_path_buf();
 
         let pool = SqlitePoolOptions::new()
-            .connect(&format!("sqlite://{}/db.sqlite", app_data_dir.display()))
+            .connect(&format!(
+                "sqlite://{}/db.sqlite?mode=rwc",
+                app_data_dir.display()
+            ))
             .await
             .unwrap();
 
@@ -495,4 +1124,409 @@ mod tests {
         let reminders = service.list_active_reminders().await.unwrap();
         assert_eq!(reminders.len(), 0); // Should be empty now
     }
+
+    #[tokio::test]
+    async fn test_trigger_queued_reminder() {
+        let (service, repo, _temp) = create_test_service().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Test Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+        let reminder = service
+            .create_reminder(
+                &note.id,
+                Utc::now() + Duration::hours(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Not due yet: queued again as it is
+        let now = Utc::now();
+        let queued = service.trigger_queued_reminder(&reminder.id, now).await;
+        assert_eq!(queued.unwrap().unwrap().id, reminder.id);
+
+        // Deleted: dropped from the queue
+        service.delete_reminder(&reminder.id).await.unwrap();
+        let queued = service.trigger_queued_reminder(&reminder.id, now).await;
+        assert!(queued.unwrap().is_none());
+
+        // A database error isn't taken for a deletion
+        repo.close().await;
+        let queued = service.trigger_queued_reminder(&reminder.id, now).await;
+        assert!(queued.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recurring_reminder_reschedules() {
+        let (service, repo, _temp) = create_test_service().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Test Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let trigger_time = Utc::now() - Duration::minutes(5);
+        let reminder = service
+            .create_reminder(&note.id, trigger_time, None, None, None, None)
+            .await
+            .unwrap();
+
+        assert!(service
+            .set_reminder_recurrence(&reminder.id, Some("FREQ=SOMETIMES"))
+            .await
+            .is_err());
+
+        let reminder = service
+            .set_reminder_recurrence(&reminder.id, Some("freq=daily;count=2"))
+            .await
+            .unwrap();
+        assert_eq!(
+            reminder.recurrence_rule.as_deref(),
+            Some("FREQ=DAILY;COUNT=2")
+        );
+
+        // Fires and moves on to tomorrow instead of being marked as triggered
+        service.check_and_trigger_reminders().await.unwrap();
+        let reminders = service.list_active_reminders().await.unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert!(reminders[0].trigger_time > Utc::now() + Duration::hours(22));
+
+        // Clearing the rule keeps the reminder as a one-off
+        let cleared = service
+            .set_reminder_recurrence(&reminder.id, None)
+            .await
+            .unwrap();
+        assert!(cleared.recurrence_rule.is_none());
+        assert!(!cleared.triggered);
+    }
+
+    #[test]
+    fn test_snooze_duration() {
+        use chrono_tz::America::New_York;
+
+        let now = Utc.with_ymd_and_hms(2026, 10, 31, 20, 0, 0).unwrap();
+        assert_eq!(
+            SnoozeDuration::OneHour.until(now, &New_York).unwrap(),
+            now + Duration::hours(1)
+        );
+        assert_eq!(
+            SnoozeDuration::Minutes(45).until(now, &New_York).unwrap(),
+            now + Duration::minutes(45)
+        );
+        assert!(SnoozeDuration::Minutes(0).until(now, &New_York).is_err());
+        assert!(SnoozeDuration::Minutes(config::MAX_SNOOZE_MINUTES + 1)
+            .until(now, &New_York)
+            .is_err());
+
+        // 09:00 the next day, which is in EST after the clocks went back
+        assert_eq!(
+            SnoozeDuration::TomorrowMorning
+                .until(now, &New_York)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-11-01T14:00:00+00:00"
+        );
+
+        assert_eq!(
+            serde_json::from_str::<SnoozeDuration>(r#""tomorrow_morning""#).unwrap(),
+            SnoozeDuration::TomorrowMorning
+        );
+        assert_eq!(
+            serde_json::from_str::<SnoozeDuration>(r#"{"minutes":20}"#).unwrap(),
+            SnoozeDuration::Minutes(20)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snooze_and_dismiss() {
+        let (service, repo, _temp) = create_test_service().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Test Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let reminder = service
+            .create_reminder(
+                &note.id,
+                Utc::now() - Duration::minutes(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service.check_and_trigger_reminders().await.unwrap();
+        assert!(service.list_active_reminders().await.unwrap().is_empty());
+
+        // A fired one-off reminder comes back at the snooze time
+        let snoozed = service
+            .snooze_reminder(&reminder.id, SnoozeDuration::FiveMinutes)
+            .await
+            .unwrap();
+        assert!(!snoozed.triggered);
+        assert_eq!(snoozed.snoozed_until, Some(snoozed.trigger_time));
+        assert!(snoozed.trigger_time > Utc::now() + Duration::minutes(4));
+
+        // Dismissing cancels the snooze
+        let dismissed = service.dismiss_reminder(&reminder.id).await.unwrap();
+        assert!(dismissed.triggered);
+        assert!(dismissed.snoozed_until.is_none());
+
+        let actions: Vec<ReminderAction> = service
+            .list_reminder_history(Some(&reminder.id), None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.action)
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ReminderAction::Dismissed,
+                ReminderAction::Snoozed,
+                ReminderAction::Fired
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snoozed_recurring_reminder() {
+        let (service, repo, _temp) = create_test_service().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Test Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let reminder = service
+            .create_reminder(
+                &note.id,
+                Utc::now() - Duration::minutes(1),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service
+            .set_reminder_recurrence(&reminder.id, Some("FREQ=DAILY"))
+            .await
+            .unwrap();
+        service.check_and_trigger_reminders().await.unwrap();
+
+        // Snoozing keeps tomorrow's occurrence
+        let next = repo.get_reminder(&reminder.id).await.unwrap().trigger_time;
+        let snoozed = service
+            .snooze_reminder(&reminder.id, SnoozeDuration::Minutes(10))
+            .await
+            .unwrap();
+        assert_eq!(snoozed.trigger_time, next);
+        assert!(snoozed.snoozed_until.is_some());
+
+        // When the snooze comes due it fires without touching the series
+        let due = Utc::now() - Duration::seconds(1);
+        repo.update_reminder_state(&reminder.id, next, false, Some(due))
+            .await
+            .unwrap();
+        service.check_and_trigger_reminders().await.unwrap();
+        let after = repo.get_reminder(&reminder.id).await.unwrap();
+        assert_eq!(after.trigger_time, next);
+        assert!(after.snoozed_until.is_none());
+        assert!(!after.triggered);
+
+        let history = service
+            .list_reminder_history(Some(&reminder.id), None)
+            .await
+            .unwrap();
+        assert_eq!(history[0].action, ReminderAction::Fired);
+        assert_eq!(history[0].scheduled_for, Some(due));
+
+        // Acknowledging a recurring reminder leaves the series running
+        let acknowledged = service.acknowledge_reminder(&reminder.id).await.unwrap();
+        assert!(!acknowledged.triggered);
+    }
+
+    #[tokio::test]
+    async fn test_catch_up_missed_reminders() {
+        let (service, repo, _temp) = create_test_service().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Test Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let mut ids = Vec::new();
+        for (hours_ago, policy, skip_after) in [
+            (2, None, None),                                 // global default: batch
+            (3, Some(MissedReminderPolicy::Skip), Some(1)),  // too old: skipped
+            (3, Some(MissedReminderPolicy::Skip), None),     // within 24h: batched
+            (1, Some(MissedReminderPolicy::Fire), None),     // left to fire
+            (-1, Some(MissedReminderPolicy::Skip), Some(1)), // not due yet
+        ] {
+            let reminder = service
+                .create_reminder(
+                    &note.id,
+                    now - Duration::hours(hours_ago),
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            service
+                .set_reminder_missed_policy(&reminder.id, policy, skip_after)
+                .await
+                .unwrap();
+            ids.push(reminder.id);
+        }
+
+        let summary = service.catch_up_missed_reminders(now).await.unwrap();
+        let batched: Vec<&str> = summary
+            .reminders
+            .iter()
+            .map(|m| m.reminder_id.as_str())
+            .collect();
+        assert_eq!(batched.len(), 2);
+        assert!(batched.contains(&ids[0].as_str()));
+        assert!(batched.contains(&ids[2].as_str()));
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].reminder_id, ids[1]);
+        assert_eq!(summary.skipped[0].note_title.as_deref(), Some("Test Note"));
+        assert_eq!(summary.fired, 1);
+
+        // Only the fire-policy and future reminders are left for the scheduler
+        let active: Vec<String> = service
+            .list_active_reminders()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(active.len(), 2);
+        assert!(active.contains(&ids[3]) && active.contains(&ids[4]));
+
+        // Batched reminders count as missed until answered; skipped ones don't
+        let history = service
+            .list_reminder_history(Some(&ids[1]), None)
+            .await
+            .unwrap();
+        assert_eq!(history[0].action, ReminderAction::Skipped);
+        let missed = repo
+            .list_missed_reminders(Utc::now() + Duration::minutes(1), 10)
+            .await
+            .unwrap();
+        assert_eq!(missed.len(), 2);
+
+        // The global default applies to reminders without their own policy
+        service
+            .set_reminder_settings(ReminderSettings {
+                missed_policy: MissedReminderPolicy::Fire,
+                ..ReminderSettings::default()
+            })
+            .unwrap();
+        service
+            .create_reminder(&note.id, now - Duration::hours(1), None, None, None, None)
+            .await
+            .unwrap();
+        let summary = service.catch_up_missed_reminders(now).await.unwrap();
+        assert!(summary.reminders.is_empty());
+        assert_eq!(summary.fired, 2);
+
+        // Out-of-range skip ages are rejected
+        assert!(service
+            .set_reminder_missed_policy(&ids[0], Some(MissedReminderPolicy::Skip), Some(0))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_wakes_for_new_reminder() {
+        let (service, repo, _temp) = create_test_service().await;
+
+        let note = repo
+            .create_note(CreateNoteRequest {
+                title: "Test Note".to_string(),
+                content_json: "{}".to_string(),
+                collection_id: None,
+            })
+            .await
+            .unwrap();
+
+        service.clone().start_scheduler();
+        tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+
+        // The idle scheduler sleeps far longer than this; the new reminder
+        // has to wake it up
+        let reminder = service
+            .create_reminder(
+                &note.id,
+                Utc::now() + Duration::milliseconds(300),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let deleted = service
+            .create_reminder(
+                &note.id,
+                Utc::now() + Duration::milliseconds(300),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        service.delete_reminder(&deleted.id).await.unwrap();
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(1500)).await;
+        assert!(service.list_active_reminders().await.unwrap().is_empty());
+        let history = service.list_reminder_history(None, None).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].reminder_id, reminder.id);
+        assert_eq!(history[0].action, ReminderAction::Fired);
+    }
+
+    #[test]
+    fn test_clock_watch_detects_jumps() {
+        let mut clock = ClockWatch::new();
+        assert!(!clock.jumped());
+
+        // As if the wall clock had been set back an hour
+        clock.wall += Duration::hours(1);
+        assert!(clock.jumped());
+        assert!(!clock.jumped());
+    }
 }
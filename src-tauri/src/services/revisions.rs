@@ -0,0 +1,206 @@
+//! Revision diffing
+//!
+//! Line-based text diff between two note revisions.
+//! Quill Delta content is flattened to plain text with
+//! `Repository::extract_text_from_delta` before diffing, so formatting-only
+//! changes do not show up as line changes.
+
+use serde::Serialize;
+
+/// Above this many LCS table cells the diff falls back to replacing the
+/// whole changed region instead of computing a minimal diff.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Kind of change for a single diff line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A single line in a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Diff between two revisions of a note
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDiff {
+    pub from_revision_id: String,
+    /// Target revision ID, or None when diffing against the current note
+    pub to_revision_id: Option<String>,
+    pub from_title: String,
+    pub to_title: String,
+    pub lines: Vec<DiffLine>,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+impl RevisionDiff {
+    pub fn new(
+        from_revision_id: String,
+        to_revision_id: Option<String>,
+        from_title: String,
+        to_title: String,
+        from_text: &str,
+        to_text: &str,
+    ) -> Self {
+        let lines = diff_lines(from_text, to_text);
+        let additions = lines.iter().filter(|l| l.op == DiffOp::Insert).count();
+        let deletions = lines.iter().filter(|l| l.op == DiffOp::Delete).count();
+
+        Self {
+            from_revision_id,
+            to_revision_id,
+            from_title,
+            to_title,
+            lines,
+            additions,
+            deletions,
+        }
+    }
+}
+
+/// Compute a line-based diff between two texts using longest common subsequence
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    // Strip the common prefix and suffix so the LCS table only covers the changed region
+    let prefix = old_lines
+        .iter()
+        .zip(new_lines.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut result: Vec<DiffLine> = old_lines[..prefix]
+        .iter()
+        .map(|l| line(DiffOp::Equal, l))
+        .collect();
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_DIFF_CELLS {
+        result.extend(old_mid.iter().map(|l| line(DiffOp::Delete, l)));
+        result.extend(new_mid.iter().map(|l| line(DiffOp::Insert, l)));
+    } else {
+        result.extend(lcs_diff(old_mid, new_mid));
+    }
+
+    result.extend(
+        old_lines[old_lines.len() - suffix..]
+            .iter()
+            .map(|l| line(DiffOp::Equal, l)),
+    );
+
+    result
+}
+
+fn line(op: DiffOp, text: &str) -> DiffLine {
+    DiffLine {
+        op,
+        text: text.to_string(),
+    }
+}
+
+fn lcs_diff(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let n = old.len();
+    let m = new.len();
+
+    // table[i][j] = LCS length of old[i..] and new[j..]
+    let width = m + 1;
+    let mut table = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * width + j] = if old[i] == new[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            result.push(line(DiffOp::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            result.push(line(DiffOp::Delete, old[i]));
+            i += 1;
+        } else {
+            result.push(line(DiffOp::Insert, new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|l| line(DiffOp::Delete, l)));
+    result.extend(new[j..].iter().map(|l| line(DiffOp::Insert, l)));
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(lines: &[DiffLine]) -> Vec<(DiffOp, &str)> {
+        lines.iter().map(|l| (l.op, l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn test_identical_texts() {
+        let diff = diff_lines("a\nb\nc", "a\nb\nc");
+        assert!(diff.iter().all(|l| l.op == DiffOp::Equal));
+        assert_eq!(diff.len(), 3);
+    }
+
+    #[test]
+    fn test_insert_and_delete() {
+        let diff = diff_lines("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(
+            ops(&diff),
+            vec![
+                (DiffOp::Equal, "a"),
+                (DiffOp::Delete, "b"),
+                (DiffOp::Equal, "c"),
+                (DiffOp::Insert, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_everything_deleted() {
+        let diff = diff_lines("first\nsecond\n", "");
+        assert_eq!(
+            ops(&diff),
+            vec![(DiffOp::Delete, "first"), (DiffOp::Delete, "second")]
+        );
+    }
+
+    #[test]
+    fn test_revision_diff_counts() {
+        let diff = RevisionDiff::new(
+            "r1".to_string(),
+            None,
+            "Old".to_string(),
+            "New".to_string(),
+            "keep\nold line\n",
+            "keep\nnew line\nextra\n",
+        );
+        assert_eq!(diff.additions, 2);
+        assert_eq!(diff.deletions, 1);
+    }
+}
@@ -176,6 +176,26 @@ impl SchedulerService {
                         ) {
                             tracing::error!("Failed to send notification: {}", e);
                         }
+
+                        if backup_service.verify_after_backup() {
+                            let problem = match backup_service.verify_backup(&path, &password).await
+                            {
+                                Ok(report) if report.valid => None,
+                                Ok(report) => Some(report.errors.join("; ")),
+                                Err(e) => Some(e.to_string()),
+                            };
+                            if let Some(problem) = problem {
+                                tracing::error!(
+                                    "Automatic backup failed verification: {}",
+                                    problem
+                                );
+                                if let Err(ne) =
+                                    send_notification("Backup Verification Failed", &problem)
+                                {
+                                    tracing::error!("Failed to send notification: {}", ne);
+                                }
+                            }
+                        }
                     }
                     Err(e) => {
                         tracing::error!("Automatic backup failed: {}", e);
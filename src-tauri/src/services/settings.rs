@@ -2,6 +2,7 @@
 //!
 //! Manages application settings persistence using JSON file storage.
 
+use crate::database::MissedReminderPolicy;
 use crate::error::{AppError, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -19,6 +20,8 @@ pub struct HotkeySettings {
     pub toggle_all_notes: String,
     #[serde(default = "default_quick_capture_hotkey")]
     pub quick_capture: String,
+    #[serde(default = "default_daily_note_hotkey")]
+    pub daily_note: String,
 }
 
 fn default_open_settings_hotkey() -> String {
@@ -33,6 +36,10 @@ fn default_quick_capture_hotkey() -> String {
     "Ctrl+Shift+V".to_string()
 }
 
+fn default_daily_note_hotkey() -> String {
+    "Ctrl+Shift+D".to_string()
+}
+
 impl Default for HotkeySettings {
     fn default() -> Self {
         Self {
@@ -42,6 +49,7 @@ impl Default for HotkeySettings {
             open_settings: "Ctrl+Shift+,".to_string(),
             toggle_all_notes: "Ctrl+Shift+A".to_string(),
             quick_capture: "Ctrl+Shift+V".to_string(),
+            daily_note: "Ctrl+Shift+D".to_string(),
         }
     }
 }
@@ -58,6 +66,13 @@ pub struct AutoBackupSettings {
     /// Custom backup location (if None, uses default app_data_dir/backups)
     #[serde(default)]
     pub backup_location: Option<String>,
+    /// Store blobs once in a shared encrypted blob pack instead of
+    /// copying them into every backup
+    #[serde(default)]
+    pub incremental: bool,
+    /// Verify each automatic backup right after it is created
+    #[serde(default)]
+    pub verify_after_backup: bool,
 }
 
 fn default_backup_frequency() -> String {
@@ -75,6 +90,8 @@ impl Default for AutoBackupSettings {
             frequency: default_backup_frequency(),
             retention_days: default_backup_retention(),
             backup_location: None,
+            incremental: false,
+            verify_after_backup: false,
         }
     }
 }
@@ -103,6 +120,12 @@ pub struct ReminderSettings {
     /// Sound preset type: "whoosh", "chime", "bell", "gentle", "alert"
     #[serde(default = "default_reminder_sound_type")]
     pub sound_type: String,
+    /// What to do with reminders that came due while the app was closed
+    #[serde(default)]
+    pub missed_policy: MissedReminderPolicy,
+    /// Age in hours after which the `skip` policy drops a missed reminder
+    #[serde(default = "default_missed_skip_after_hours")]
+    pub missed_skip_after_hours: u32,
 }
 
 fn default_reminder_sound_enabled() -> bool {
@@ -133,6 +156,10 @@ fn default_reminder_sound_type() -> String {
     "whoosh".to_string()
 }
 
+fn default_missed_skip_after_hours() -> u32 {
+    24 // 1 day
+}
+
 impl Default for ReminderSettings {
     fn default() -> Self {
         Self {
@@ -143,6 +170,8 @@ impl Default for ReminderSettings {
             shake_duration: default_reminder_shake_duration(),
             glow_enabled: default_reminder_glow_enabled(),
             sound_type: default_reminder_sound_type(),
+            missed_policy: MissedReminderPolicy::default(),
+            missed_skip_after_hours: default_missed_skip_after_hours(),
         }
     }
 }
@@ -187,6 +216,100 @@ impl Default for BehaviorSettings {
     }
 }
 
+/// Note revision history settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionSettings {
+    /// Whether note updates record revision snapshots
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Minimum seconds between snapshots of the same note, so autosave
+    /// doesn't create a revision on every keystroke
+    #[serde(default = "default_revision_min_interval")]
+    pub min_interval_secs: u32,
+    /// Maximum revisions kept per note (0 = unlimited)
+    #[serde(default = "default_revision_max_per_note")]
+    pub max_per_note: u32,
+    /// Revisions older than this many days are thinned to one per day (0 = never thin)
+    #[serde(default = "default_revision_thin_after_days")]
+    pub thin_after_days: u32,
+}
+
+fn default_revision_min_interval() -> u32 {
+    300 // 5 minutes
+}
+
+fn default_revision_max_per_note() -> u32 {
+    50
+}
+
+fn default_revision_thin_after_days() -> u32 {
+    7
+}
+
+impl Default for RevisionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_secs: default_revision_min_interval(),
+            max_per_note: default_revision_max_per_note(),
+            thin_after_days: default_revision_thin_after_days(),
+        }
+    }
+}
+
+/// Scheduled blob garbage collection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobGcSettings {
+    /// Whether unreferenced blobs are collected automatically
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours between automatic collection runs
+    #[serde(default = "default_blob_gc_interval_hours")]
+    pub interval_hours: u32,
+}
+
+fn default_blob_gc_interval_hours() -> u32 {
+    24
+}
+
+impl Default for BlobGcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_blob_gc_interval_hours(),
+        }
+    }
+}
+
+/// Daily note (journal) settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalSettings {
+    /// chrono strftime format of daily note titles, e.g. "%A, %B %-d, %Y"
+    #[serde(default = "default_journal_title_format")]
+    pub title_format: String,
+    /// Collection daily notes go into; None for a collection named
+    /// `config::JOURNAL_COLLECTION_NAME`, created when first needed
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    /// Template daily notes are created from
+    #[serde(default)]
+    pub template_id: Option<String>,
+}
+
+fn default_journal_title_format() -> String {
+    "%Y-%m-%d".to_string()
+}
+
+impl Default for JournalSettings {
+    fn default() -> Self {
+        Self {
+            title_format: default_journal_title_format(),
+            collection_id: None,
+            template_id: None,
+        }
+    }
+}
+
 /// Application settings
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppSettings {
@@ -200,6 +323,12 @@ pub struct AppSettings {
     pub reminders: ReminderSettings,
     #[serde(default)]
     pub behavior: BehaviorSettings,
+    #[serde(default)]
+    pub revisions: RevisionSettings,
+    #[serde(default)]
+    pub blob_gc: BlobGcSettings,
+    #[serde(default)]
+    pub journal: JournalSettings,
 }
 
 /// Service for managing application settings
@@ -297,6 +426,48 @@ impl SettingsService {
         self.save(&settings).await?;
         Ok(())
     }
+
+    /// Get note revision history settings
+    pub async fn get_revisions(&self) -> Result<RevisionSettings> {
+        let settings = self.load().await?;
+        Ok(settings.revisions)
+    }
+
+    /// Update note revision history settings
+    pub async fn update_revisions(&self, revisions: RevisionSettings) -> Result<()> {
+        let mut settings = self.load().await?;
+        settings.revisions = revisions;
+        self.save(&settings).await?;
+        Ok(())
+    }
+
+    /// Get scheduled blob garbage collection settings
+    pub async fn get_blob_gc(&self) -> Result<BlobGcSettings> {
+        let settings = self.load().await?;
+        Ok(settings.blob_gc)
+    }
+
+    /// Update scheduled blob garbage collection settings
+    pub async fn update_blob_gc(&self, blob_gc: BlobGcSettings) -> Result<()> {
+        let mut settings = self.load().await?;
+        settings.blob_gc = blob_gc;
+        self.save(&settings).await?;
+        Ok(())
+    }
+
+    /// Get daily note settings
+    pub async fn get_journal(&self) -> Result<JournalSettings> {
+        let settings = self.load().await?;
+        Ok(settings.journal)
+    }
+
+    /// Update daily note settings
+    pub async fn update_journal(&self, journal: JournalSettings) -> Result<()> {
+        let mut settings = self.load().await?;
+        settings.journal = journal;
+        self.save(&settings).await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -331,6 +502,16 @@ mod tests {
 
         // Verify default behavior settings
         assert!(!settings.behavior.start_hidden_to_tray);
+
+        // Verify default revision settings
+        assert!(settings.revisions.enabled);
+        assert_eq!(settings.revisions.min_interval_secs, 300);
+        assert_eq!(settings.revisions.max_per_note, 50);
+        assert_eq!(settings.revisions.thin_after_days, 7);
+
+        // Verify default blob GC settings
+        assert!(!settings.blob_gc.enabled);
+        assert_eq!(settings.blob_gc.interval_hours, 24);
     }
 
     #[tokio::test]
@@ -351,6 +532,8 @@ mod tests {
             shake_duration: 800,
             glow_enabled: true,
             sound_type: "chime".to_string(),
+            missed_policy: MissedReminderPolicy::Skip,
+            missed_skip_after_hours: 6,
         };
 
         service.update_reminders(updated.clone()).await.unwrap();
@@ -364,6 +547,8 @@ mod tests {
         assert_eq!(loaded.shake_duration, 800);
         assert!(loaded.glow_enabled);
         assert_eq!(loaded.sound_type, "chime");
+        assert_eq!(loaded.missed_policy, MissedReminderPolicy::Skip);
+        assert_eq!(loaded.missed_skip_after_hours, 6);
     }
 
     #[tokio::test]
@@ -382,6 +567,8 @@ mod tests {
                 shake_duration: 400,
                 glow_enabled: false,
                 sound_type: "bell".to_string(),
+                missed_policy: MissedReminderPolicy::Fire,
+                missed_skip_after_hours: 24,
             };
             service.update_reminders(updated).await.unwrap();
         }
@@ -411,6 +598,8 @@ mod tests {
         assert_eq!(settings.shake_duration, 600);
         assert!(settings.glow_enabled);
         assert_eq!(settings.sound_type, "whoosh");
+        assert_eq!(settings.missed_policy, MissedReminderPolicy::Batch);
+        assert_eq!(settings.missed_skip_after_hours, 24);
     }
 
     #[tokio::test]
@@ -432,4 +621,52 @@ mod tests {
         let settings = service.load().await.unwrap();
         assert_eq!(settings.hotkeys.new_note, initial_new_note);
     }
+
+    #[tokio::test]
+    async fn test_revision_settings_missing_from_file_uses_defaults() {
+        let (service, temp) = create_test_service();
+
+        // Settings written by an older version have no "revisions" section
+        tokio::fs::write(
+            temp.path().join("settings.json"),
+            r#"{"start_with_windows":false}"#,
+        )
+        .await
+        .unwrap();
+
+        let revisions = service.get_revisions().await.unwrap();
+        assert!(revisions.enabled);
+        assert_eq!(revisions.max_per_note, 50);
+    }
+
+    #[tokio::test]
+    async fn test_journal_settings_and_daily_note_hotkey_defaults() {
+        let (service, temp) = create_test_service();
+
+        // Settings written by an older version have no journal section or
+        // daily note hotkey
+        tokio::fs::write(
+            temp.path().join("settings.json"),
+            r#"{"hotkeys":{"new_note":"Ctrl+Alt+N","toggle_note":"Ctrl+Shift+H","open_search":"Ctrl+Shift+F"}}"#,
+        )
+        .await
+        .unwrap();
+
+        let hotkeys = service.get_hotkeys().await.unwrap();
+        assert_eq!(hotkeys.new_note, "Ctrl+Alt+N");
+        assert_eq!(hotkeys.daily_note, "Ctrl+Shift+D");
+
+        let mut journal = service.get_journal().await.unwrap();
+        assert_eq!(journal.title_format, "%Y-%m-%d");
+        assert!(journal.collection_id.is_none());
+
+        journal.title_format = "%A %-d %B".to_string();
+        journal.template_id = Some("template-1".to_string());
+        service.update_journal(journal).await.unwrap();
+
+        let journal = service.get_journal().await.unwrap();
+        assert_eq!(journal.title_format, "%A %-d %B");
+        assert_eq!(journal.template_id.as_deref(), Some("template-1"));
+        assert_eq!(service.get_hotkeys().await.unwrap().new_note, "Ctrl+Alt+N");
+    }
 }
@@ -0,0 +1,170 @@
+//! Note template variables
+//!
+//! Templates hold ordinary note content (Quill Delta) and a title with
+//! `{{variable}}` placeholders that are filled in when a note is created
+//! from the template:
+//!
+//! - `{{date}}`: the local date, e.g. `2026-10-17`
+//! - `{{time}}`: the local time, e.g. `09:30`
+//! - `{{weekday}}`: the local day of the week, e.g. `Saturday`
+//! - `{{clipboard}}`: the text on the clipboard
+//! - `{{collection}}`: the name of the collection the note is created in
+//!
+//! Names are case-insensitive and may be padded with spaces. Unknown
+//! variables are left as written. Variables are replaced within each text
+//! insert, so a placeholder formatted differently part-way through is not
+//! replaced.
+
+use crate::error::Result;
+use chrono::{DateTime, TimeZone};
+use serde_json::Value;
+use std::fmt::Display;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+const WEEKDAY_FORMAT: &str = "%A";
+
+/// Values of the template variables for one note
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVariables {
+    pub date: String,
+    pub time: String,
+    pub weekday: String,
+    pub clipboard: String,
+    pub collection: String,
+}
+
+impl TemplateVariables {
+    /// Variables for a note created at `now` (in the user's time zone)
+    pub fn new<Tz: TimeZone>(
+        now: &DateTime<Tz>,
+        clipboard: Option<String>,
+        collection: Option<String>,
+    ) -> Self
+    where
+        Tz::Offset: Display,
+    {
+        Self {
+            date: now.format(DATE_FORMAT).to_string(),
+            time: now.format(TIME_FORMAT).to_string(),
+            weekday: now.format(WEEKDAY_FORMAT).to_string(),
+            clipboard: clipboard.unwrap_or_default(),
+            collection: collection.unwrap_or_default(),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        let value = match name.to_lowercase().as_str() {
+            "date" => &self.date,
+            "time" => &self.time,
+            "weekday" => &self.weekday,
+            "clipboard" => &self.clipboard,
+            "collection" => &self.collection,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Replace the known `{{variables}}` in text
+pub fn substitute(text: &str, variables: &TemplateVariables) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}").map(|i| open + 2 + i) else {
+            break;
+        };
+        let name = rest[open + 2..close].trim();
+        match variables.get(name) {
+            Some(value) => {
+                result.push_str(&rest[..open]);
+                result.push_str(value);
+                rest = &rest[close + 2..];
+            }
+            None => {
+                // Not a variable; look again from the next brace
+                result.push_str(&rest[..open + 1]);
+                rest = &rest[open + 1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Replace the known `{{variables}}` in the text inserts of note content
+pub fn substitute_in_delta(content_json: &str, variables: &TemplateVariables) -> Result<String> {
+    let mut json: Value = serde_json::from_str(content_json)?;
+
+    if let Some(ops) = json.get_mut("ops").and_then(Value::as_array_mut) {
+        for op in ops {
+            if let Some(Value::String(insert)) = op.get_mut("insert") {
+                if insert.contains("{{") {
+                    *insert = substitute(insert, variables);
+                }
+            }
+        }
+    }
+
+    Ok(json.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+    use serde_json::json;
+
+    fn variables() -> TemplateVariables {
+        let now = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2026, 10, 17, 9, 5, 0)
+            .unwrap();
+        TemplateVariables::new(&now, Some("pasted".to_string()), Some("Work".to_string()))
+    }
+
+    #[test]
+    fn test_substitute() {
+        let vars = variables();
+        assert_eq!(
+            substitute("Standup {{date}} ({{ Weekday }}, {{time}})", &vars),
+            "Standup 2026-10-17 (Saturday, 09:05)"
+        );
+        assert_eq!(
+            substitute("{{collection}}: {{clipboard}}", &vars),
+            "Work: pasted"
+        );
+        assert_eq!(
+            substitute("{{unknown}} {{date}} {{ }} {{date", &vars),
+            "{{unknown}} 2026-10-17 {{ }} {{date"
+        );
+        assert_eq!(substitute("{{{date}}}", &vars), "{2026-10-17}");
+
+        let empty = TemplateVariables::new(&chrono::Utc::now(), None, None);
+        assert_eq!(substitute("[{{clipboard}}{{collection}}]", &empty), "[]");
+    }
+
+    #[test]
+    fn test_substitute_in_delta() {
+        let content = json!({ "ops": [
+            { "insert": "Standup {{date}}" },
+            { "insert": "\n", "attributes": { "header": 1 } },
+            { "insert": "{{weekday}}", "attributes": { "bold": true } },
+            { "insert": { "image": "{{date}}.png" } },
+            { "insert": "\n" }
+        ] })
+        .to_string();
+
+        let result = substitute_in_delta(&content, &variables()).unwrap();
+        let json: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["ops"][0]["insert"], "Standup 2026-10-17");
+        assert_eq!(json["ops"][1]["attributes"]["header"], 1);
+        assert_eq!(json["ops"][2]["insert"], "Saturday");
+        assert_eq!(json["ops"][2]["attributes"]["bold"], true);
+        assert_eq!(json["ops"][3]["insert"]["image"], "{{date}}.png");
+
+        assert!(substitute_in_delta("not json", &variables()).is_err());
+    }
+}
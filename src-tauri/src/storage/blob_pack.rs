@@ -0,0 +1,322 @@
+//! Encrypted blob pack for incremental backups
+//!
+//! Incremental backups store each blob once in a pack shared by all backups
+//! in the same directory, instead of copying every blob into every archive.
+//!
+//! Layout (inside the backups directory):
+//! - `blobpack/pack.key` — random pack key, encrypted with the backup password
+//! - `blobpack/objects/ab/cd/abcd1234...` — blob encrypted with the pack key
+//! - `blobpack/refs/<backup file name>.json` — blob hashes a backup references
+//!
+//! Objects are named by the blob's SHA-256 hash, so identical blobs are
+//! stored once, and the hash is re-checked after decryption. A blob is only
+//! removed by `sweep` once no remaining backup references it.
+
+use crate::crypto;
+use crate::error::{AppError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+/// Name of the pack directory inside a backups directory
+pub const BLOB_PACK_DIR: &str = "blobpack";
+
+/// Shared encrypted blob store for incremental backups
+#[derive(Clone)]
+pub struct BlobPack {
+    root: PathBuf,
+}
+
+impl BlobPack {
+    /// Pack belonging to the given backups directory
+    pub fn new(backups_dir: &Path) -> Self {
+        Self {
+            root: backups_dir.join(BLOB_PACK_DIR),
+        }
+    }
+
+    /// Load the pack key, creating the pack on first use.
+    /// Fails if the pack was created with a different password.
+    pub async fn open_key(&self, password: &str) -> Result<Vec<u8>> {
+        let key_path = self.root.join("pack.key");
+        let password = password.to_string();
+
+        if key_path.exists() {
+            let wrapped = fs::read(&key_path).await?;
+            let encrypted: crypto::EncryptedData = serde_json::from_slice(&wrapped)
+                .map_err(|e| AppError::Backup(format!("Invalid blob pack key file: {}", e)))?;
+            // Key derivation is slow by design, so keep it off async threads
+            return run_blocking(move || crypto::decrypt(&encrypted, &password))
+                .await?
+                .map_err(|_| {
+                    AppError::Backup(
+                        "The incremental backup blob pack was created with a different password. \
+                        Use the original password or create a full backup."
+                            .to_string(),
+                    )
+                });
+        }
+
+        fs::create_dir_all(&self.root).await?;
+        let key = crypto::generate_key();
+        let wrapped = {
+            let key = key.clone();
+            run_blocking(move || crypto::encrypt(&key, &password)).await??
+        };
+        fs::write(&key_path, serde_json::to_vec(&wrapped)?).await?;
+
+        tracing::info!("Created blob pack at {:?}", self.root);
+        Ok(key)
+    }
+
+    /// Check whether a blob is already stored
+    pub fn contains(&self, hash: &str) -> bool {
+        self.object_path(hash).is_ok_and(|path| path.exists())
+    }
+
+    /// Encrypt and store a blob under its hash
+    pub async fn write(&self, hash: &str, data: &[u8], key: &[u8]) -> Result<()> {
+        let path = self.object_path(hash)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Write to temp file first (atomic write)
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, crypto::encrypt_with_key(data, key)?).await?;
+        fs::rename(&temp_path, &path).await?;
+
+        Ok(())
+    }
+
+    /// Read and decrypt a blob, verifying its content hash
+    pub async fn read(&self, hash: &str, key: &[u8]) -> Result<Vec<u8>> {
+        let path = self.object_path(hash)?;
+        if !path.exists() {
+            return Err(AppError::Restore(format!(
+                "Blob {} is missing from the backup blob pack",
+                hash
+            )));
+        }
+
+        let data = crypto::decrypt_with_key(&fs::read(&path).await?, key)
+            .map_err(|e| AppError::Restore(format!("Blob {}: {}", hash, e)))?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(&data);
+        let actual = format!("{:x}", hasher.finalize());
+        if actual != hash {
+            return Err(AppError::Restore(format!(
+                "Checksum mismatch for pack blob {}: got {}",
+                hash, actual
+            )));
+        }
+
+        Ok(data)
+    }
+
+    /// Record the blobs referenced by a backup file
+    pub async fn write_refs(&self, backup_file_name: &str, hashes: &[String]) -> Result<()> {
+        let refs_dir = self.root.join("refs");
+        fs::create_dir_all(&refs_dir).await?;
+        fs::write(
+            refs_dir.join(format!("{}.json", backup_file_name)),
+            serde_json::to_vec(hashes)?,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Delete reference lists of backups that no longer exist, then every
+    /// blob no remaining reference list mentions.
+    /// Returns the number of blobs and bytes removed.
+    pub async fn sweep(&self) -> Result<(usize, u64)> {
+        let refs_dir = self.root.join("refs");
+        let Some(backups_dir) = self.root.parent() else {
+            return Ok((0, 0));
+        };
+        if !refs_dir.exists() {
+            return Ok((0, 0));
+        }
+
+        let mut referenced = HashSet::new();
+        let mut entries = fs::read_dir(&refs_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let Some(backup_file_name) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+            else {
+                continue;
+            };
+
+            if !backups_dir.join(backup_file_name).exists() {
+                tracing::debug!(
+                    "Removing blob pack refs of deleted backup {}",
+                    backup_file_name
+                );
+                fs::remove_file(&path).await?;
+                continue;
+            }
+
+            let hashes: Vec<String> = serde_json::from_slice(&fs::read(&path).await?)?;
+            referenced.extend(hashes);
+        }
+
+        let mut removed = 0;
+        let mut freed = 0;
+        for (hash, path) in self.list_objects().await? {
+            if referenced.contains(&hash) {
+                continue;
+            }
+            let size = fs::metadata(&path).await?.len();
+            fs::remove_file(&path).await?;
+            removed += 1;
+            freed += size;
+        }
+
+        if removed > 0 {
+            tracing::info!(
+                "Removed {} unreferenced blobs ({} bytes) from blob pack",
+                removed,
+                freed
+            );
+        }
+
+        Ok((removed, freed))
+    }
+
+    /// All stored objects as (hash, path)
+    async fn list_objects(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut objects = Vec::new();
+        let mut dirs = vec![self.root.join("objects")];
+
+        while let Some(dir) = dirs.pop() {
+            if !dir.exists() {
+                continue;
+            }
+            let mut entries = fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if let Some(hash) = path.file_name().and_then(|n| n.to_str()) {
+                    if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                        objects.push((hash.to_string(), path));
+                    }
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Get object path for a hash (same two-level layout as `BlobStore`)
+    fn object_path(&self, hash: &str) -> Result<PathBuf> {
+        check_hash(hash)?;
+        Ok(self
+            .root
+            .join("objects")
+            .join(&hash[0..2])
+            .join(&hash[2..4])
+            .join(hash))
+    }
+}
+
+/// Run blocking work on the blocking thread pool
+async fn run_blocking<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::Backup(format!("Blob pack task failed: {}", e)))
+}
+
+/// Check that a hash read from a manifest is a SHA-256 hex digest before it
+/// is used to build a path
+pub fn check_hash(hash: &str) -> Result<()> {
+    if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(AppError::Generic(format!("Invalid blob hash: {:?}", hash)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn hash_of(data: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        format!("{:x}", hasher.finalize())
+    }
+
+    #[tokio::test]
+    async fn test_write_read_and_key_reuse() {
+        let temp = TempDir::new().unwrap();
+        let pack = BlobPack::new(temp.path());
+
+        let key = pack.open_key("password").await.unwrap();
+        let data = b"pack blob";
+        let hash = hash_of(data);
+
+        assert!(!pack.contains(&hash));
+        pack.write(&hash, data, &key).await.unwrap();
+        assert!(pack.contains(&hash));
+
+        // Reopening with the same password yields the same key
+        let key = pack.open_key("password").await.unwrap();
+        assert_eq!(pack.read(&hash, &key).await.unwrap(), data);
+
+        assert!(pack.open_key("other password").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_malformed_hash_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let pack = BlobPack::new(temp_dir.path());
+        let key = pack.open_key("password").await.unwrap();
+
+        for hash in [
+            "",
+            "ab",
+            "../../etc/passwd",
+            &"é".repeat(32),
+            &"g".repeat(64),
+        ] {
+            assert!(!pack.contains(hash));
+            assert!(pack.read(hash, &key).await.is_err());
+            assert!(pack.write(hash, b"data", &key).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sweep_keeps_referenced_blobs() {
+        let temp = TempDir::new().unwrap();
+        let pack = BlobPack::new(temp.path());
+        let key = pack.open_key("password").await.unwrap();
+
+        let shared = hash_of(b"shared");
+        let old_only = hash_of(b"old only");
+        pack.write(&shared, b"shared", &key).await.unwrap();
+        pack.write(&old_only, b"old only", &key).await.unwrap();
+
+        fs::write(temp.path().join("new.enc"), b"").await.unwrap();
+        pack.write_refs("old.enc", &[shared.clone(), old_only.clone()])
+            .await
+            .unwrap();
+        pack.write_refs("new.enc", std::slice::from_ref(&shared))
+            .await
+            .unwrap();
+
+        // "old.enc" has no backup file, so only "new.enc" references count
+        let (removed, freed) = pack.sweep().await.unwrap();
+        assert_eq!(removed, 1);
+        assert!(freed > 0);
+        assert!(pack.contains(&shared));
+        assert!(!pack.contains(&old_only));
+        assert!(!temp.path().join("blobpack/refs/old.enc.json").exists());
+    }
+}
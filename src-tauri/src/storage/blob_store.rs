@@ -8,6 +8,7 @@
 use crate::error::{AppError, Result};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio::fs;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
@@ -86,8 +87,13 @@ impl BlobStore {
         Ok(path.exists())
     }
 
-    /// Delete a blob (reserved for future cleanup functionality)
-    #[allow(dead_code)]
+    /// Size in bytes and last modification time of a blob
+    pub async fn metadata(&self, hash: &str) -> Result<(u64, SystemTime)> {
+        let metadata = fs::metadata(self.get_path(hash)).await?;
+        Ok((metadata.len(), metadata.modified()?))
+    }
+
+    /// Delete a blob
     pub async fn delete(&self, hash: &str) -> Result<()> {
         let path = self.get_path(hash);
 
@@ -227,6 +233,18 @@ mod tests {
         assert!(!store.exists(&hash).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_metadata() {
+        let (store, _temp) = create_test_store().await;
+
+        let hash = store.write(b"Metadata test").await.unwrap();
+        let (size, modified) = store.metadata(&hash).await.unwrap();
+
+        assert_eq!(size, 13);
+        assert!(modified <= SystemTime::now());
+        assert!(store.metadata(&"0".repeat(64)).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_directory_structure() {
         let (store, _temp) = create_test_store().await;
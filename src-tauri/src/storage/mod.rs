@@ -1,7 +1,10 @@
 //! Storage module
 //!
-//! Provides blob storage for binary data (images, attachments).
+//! Provides blob storage for binary data (images, attachments) and the
+//! encrypted blob pack shared by incremental backups.
 
+pub mod blob_pack;
 pub mod blob_store;
 
+pub use blob_pack::BlobPack;
 pub use blob_store::BlobStore;
@@ -1359,3 +1359,158 @@ async fn test_inline_image_backup_and_restore() {
     let blob_data = blob_store.read(&attachments[0].blob_hash).await.unwrap();
     assert_eq!(blob_data, image_data);
 }
+
+// ===== Search Query Tests =====
+
+#[tokio::test]
+async fn test_structured_search_query() {
+    let (repo, _temp) = create_test_db().await;
+    let notes_service = NotesService::new(repo.clone());
+
+    let work = repo
+        .create_collection(CreateCollectionRequest {
+            name: "Work".to_string(),
+            description: None,
+            color: None,
+            icon: None,
+        })
+        .await
+        .unwrap();
+
+    notes_service
+        .create_note(
+            "Weekly meeting".to_string(),
+            r#"{"ops":[{"insert":"Review the exact phrase list\n"}]}"#.to_string(),
+            Some(work.id.clone()),
+        )
+        .await
+        .unwrap();
+    notes_service
+        .create_note(
+            "Meeting draft".to_string(),
+            r#"{"ops":[{"insert":"The exact phrase again\n"}]}"#.to_string(),
+            Some(work.id),
+        )
+        .await
+        .unwrap();
+    notes_service
+        .create_note(
+            "Personal meeting".to_string(),
+            r#"{"ops":[{"insert":"exact phrase\n"}]}"#.to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let results = notes_service
+        .search_notes(r#""exact phrase" -draft title:meeting collection:Work"#)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].title, "Weekly meeting");
+
+    // Malformed input reports the position of the problem
+    let err = notes_service
+        .search_notes("meeting (draft")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid search query at position 9: Unclosed '('"
+    );
+}
+
+// ===== Revision History Tests =====
+
+#[tokio::test]
+async fn test_revision_history_recovers_select_all_delete() {
+    // Regression: an autosaved select-all-delete must be recoverable without a backup
+    let (repo, _temp) = create_test_db().await;
+    let notes_service = NotesService::new(repo.clone());
+
+    let original = r#"{"ops":[{"insert":"Quarterly planning notes with action items\n"}]}"#;
+    let note = notes_service
+        .create_note("Planning".to_string(), original.to_string(), None)
+        .await
+        .unwrap();
+
+    // Autosave of an emptied editor
+    notes_service
+        .update_note(
+            note.id.clone(),
+            None,
+            Some(r#"{"ops":[{"insert":"\n"}]}"#.to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let revisions = notes_service.list_revisions(&note.id).await.unwrap();
+    assert_eq!(revisions.len(), 1);
+
+    let restored = notes_service
+        .restore_revision(&revisions[0].id)
+        .await
+        .unwrap();
+    assert_eq!(restored.content_json, original);
+
+    // Pruning the note from trash removes its history too
+    notes_service.delete_note(&note.id).await.unwrap();
+    notes_service.prune_deleted_notes().await.unwrap();
+    assert!(notes_service
+        .list_revisions(&note.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+// ===== Tag Tests =====
+
+#[tokio::test]
+async fn test_tags_span_collections() {
+    let (repo, _temp) = create_test_db().await;
+    let notes_service = NotesService::new(repo.clone());
+
+    let work = repo
+        .create_collection(CreateCollectionRequest {
+            name: "Work".to_string(),
+            description: None,
+            color: None,
+            icon: None,
+        })
+        .await
+        .unwrap();
+
+    let in_folder = notes_service
+        .create_note("Budget".to_string(), "{}".to_string(), Some(work.id))
+        .await
+        .unwrap();
+    let uncategorized = notes_service
+        .create_note("Groceries".to_string(), "{}".to_string(), None)
+        .await
+        .unwrap();
+
+    notes_service
+        .add_tag_to_note(&in_folder.id, "urgent")
+        .await
+        .unwrap();
+    let tag = notes_service
+        .add_tag_to_note(&uncategorized.id, "Urgent")
+        .await
+        .unwrap();
+
+    let tagged = notes_service.list_notes_by_tag(&tag.id).await.unwrap();
+    assert_eq!(tagged.len(), 2);
+
+    let results = notes_service.search_notes("tag:urgent").await.unwrap();
+    assert_eq!(results.len(), 2);
+
+    // Deleting the tag keeps the notes
+    notes_service.delete_tag(&tag.id).await.unwrap();
+    assert_eq!(notes_service.list_notes().await.unwrap().len(), 2);
+    assert!(notes_service
+        .list_note_tags(&in_folder.id)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -176,6 +176,7 @@ interface HotkeySettings {
   open_settings: string;
   toggle_all_notes: string;
   quick_capture: string;
+  daily_note: string;
 }
 
 interface ReminderSettings {
@@ -325,6 +326,9 @@ async function loadHotkeySettings(): Promise<void> {
     const quickCaptureInput = document.getElementById(
       'hotkey-quick-capture-input'
     ) as HTMLInputElement;
+    const dailyNoteInput = document.getElementById(
+      'hotkey-daily-note-input'
+    ) as HTMLInputElement;
 
     if (newNoteInput) {
       newNoteInput.value = hotkeys.new_note;
@@ -344,6 +348,9 @@ async function loadHotkeySettings(): Promise<void> {
     if (quickCaptureInput) {
       quickCaptureInput.value = hotkeys.quick_capture;
     }
+    if (dailyNoteInput) {
+      dailyNoteInput.value = hotkeys.daily_note;
+    }
   } catch (error) {
     logger.error('Failed to load hotkey settings', LOG_CONTEXT, error);
   }
@@ -1125,6 +1132,7 @@ function setupEventHandlers(): void {
   const quickCaptureInput = document.getElementById(
     'hotkey-quick-capture-input'
   ) as HTMLInputElement;
+  const dailyNoteInput = document.getElementById('hotkey-daily-note-input') as HTMLInputElement;
 
   saveHotkeysBtn?.addEventListener('click', async () => {
     if (
@@ -1133,7 +1141,8 @@ function setupEventHandlers(): void {
       !openSearchInput ||
       !openSettingsInput ||
       !toggleAllNotesInput ||
-      !quickCaptureInput
+      !quickCaptureInput ||
+      !dailyNoteInput
     ) {
       await showAlert('Failed to get hotkey input fields', { title: 'Error', type: 'error' });
       return;
@@ -1146,6 +1155,7 @@ function setupEventHandlers(): void {
       open_settings: openSettingsInput.value.trim(),
       toggle_all_notes: toggleAllNotesInput.value.trim(),
       quick_capture: quickCaptureInput.value.trim(),
+      daily_note: dailyNoteInput.value.trim(),
     };
 
     if (
@@ -1154,7 +1164,8 @@ function setupEventHandlers(): void {
       !hotkeys.open_search ||
       !hotkeys.open_settings ||
       !hotkeys.toggle_all_notes ||
-      !hotkeys.quick_capture
+      !hotkeys.quick_capture ||
+      !hotkeys.daily_note
     ) {
       await showAlert('All hotkey fields must be filled', { title: 'Error', type: 'warning' });
       return;
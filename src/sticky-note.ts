@@ -58,6 +58,8 @@ let reminderSettings: ReminderSettings = {
   shake_duration: 600,
   glow_enabled: true,
   sound_type: 'whoosh',
+  missed_policy: 'batch',
+  missed_skip_after_hours: 24,
 };
 
 /** Track whether the title was manually modified */
@@ -16,6 +16,40 @@ export interface Note {
   collection_id: string | null;
 }
 
+/** A link from one note to another ("[[Title]]", "[[note-id]]" or a note:// link) */
+export interface NoteLink {
+  source_note_id: string;
+  source_title: string;
+  /** Link target as written: a note title or ID */
+  target: string;
+  /** Note the target resolves to, by ID or else title; null for a dangling link */
+  target_note_id: string | null;
+  target_title: string | null;
+}
+
+/** A skeleton for new notes, with {{date}}, {{time}}, {{weekday}}, {{clipboard}} and {{collection}} variables */
+export interface NoteTemplate {
+  id: string;
+  name: string;
+  /** Title of created notes (may contain variables); empty to follow the content */
+  title: string;
+  content_json: string;
+  /** Collection created notes go into unless another is chosen */
+  collection_id: string | null;
+  /** Minutes after creation to remind about a created note */
+  reminder_offset_minutes: number | null;
+  created_at: string;
+  updated_at: string;
+}
+
+/** A date that has a daily note */
+export interface JournalEntry {
+  /** Local date, YYYY-MM-DD */
+  date: string;
+  note_id: string;
+  title: string;
+}
+
 /** Collection/Folder for organizing notes */
 export interface Collection {
   id: string;
@@ -64,6 +98,77 @@ export interface Reminder {
   shake_enabled: boolean | null;
   /** Per-reminder glow effect setting (null = use global default) */
   glow_enabled: boolean | null;
+  /** Recurrence rule, an iCalendar RRULE subset (null = one-off reminder) */
+  recurrence_rule: string | null;
+  /** Local wall-clock start of the recurring series */
+  recurrence_start: string | null;
+  /** Pending snooze; the reminder fires again at this time */
+  snoozed_until: string | null;
+  /** Handling if it came due while the app was closed (null = use global default) */
+  missed_policy: MissedReminderPolicy | null;
+  /** Age in hours after which 'skip' drops it (null = use global default) */
+  missed_skip_after_hours: number | null;
+  /** The "@remind" marker in the note's text it came from (null = created directly) */
+  inline_text: string | null;
+  /** Start of the marker in the note's plain text (UTF-16 offset) */
+  inline_start: number | null;
+  /** End of the marker in the note's plain text (UTF-16 offset, exclusive) */
+  inline_end: number | null;
+}
+
+/** How to handle a reminder that came due while the app was closed */
+export type MissedReminderPolicy = 'fire' | 'batch' | 'skip';
+
+/** A reminder that came due while the app was closed */
+export interface MissedReminder {
+  reminder_id: string;
+  note_id: string;
+  note_title: string | null;
+  /** The trigger or snooze time that was missed */
+  due_at: string;
+}
+
+/** Startup catch-up of missed reminders (payload of the 'missed-reminders' event) */
+export interface MissedReminderSummary {
+  /** Batched into this summary instead of one popup each */
+  reminders: MissedReminder[];
+  /** Older than their skip age and dropped */
+  skipped: MissedReminder[];
+  /** Left to pop up on their own ('fire' policy) */
+  fired: number;
+}
+
+/** A reminder time resolved from a natural-language phrase */
+export interface ParsedReminderTime {
+  /** When the reminder (first) fires */
+  trigger_time: string;
+  /** RRULE for repeating phrases ('every monday'), null otherwise */
+  recurrence_rule: string | null;
+}
+
+/** How long to snooze a reminder: a preset or a custom number of minutes */
+export type SnoozeDuration =
+  | 'five_minutes'
+  | 'ten_minutes'
+  | 'fifteen_minutes'
+  | 'thirty_minutes'
+  | 'one_hour'
+  | 'tomorrow_morning'
+  | { minutes: number };
+
+/** Reminder history entry */
+export interface ReminderHistoryEntry {
+  id: string;
+  reminder_id: string;
+  note_id: string;
+  /** Title of the note (null once the note is gone) */
+  note_title: string | null;
+  action: 'fired' | 'snoozed' | 'dismissed' | 'acknowledged' | 'skipped';
+  occurred_at: string;
+  /** For 'fired' and 'skipped': the trigger or snooze time that was due */
+  scheduled_for: string | null;
+  /** For 'snoozed': when the reminder fires again */
+  snoozed_until: string | null;
 }
 
 /** Settings for creating a new reminder */
@@ -90,6 +195,54 @@ export interface ReminderSettings {
   glow_enabled: boolean;
   /** Sound preset type: 'whoosh' | 'chime' | 'bell' | 'gentle' | 'alert' */
   sound_type: string;
+  /** What to do with reminders that came due while the app was closed */
+  missed_policy: MissedReminderPolicy;
+  /** Age in hours after which the 'skip' policy drops a missed reminder */
+  missed_skip_after_hours: number;
+}
+
+/** Outcome of an import (OneNote, folder or Evernote) */
+export interface ImportResult {
+  notes_imported: number;
+  collections_created: number;
+  /** OneNote section, folder, notebook or tag name → collection ID */
+  sections_mapped: Record<string, string>;
+  errors: string[];
+}
+
+/** What Evernote notes are grouped into collections by */
+export type EnexCollectionSource = 'notebook' | 'tag';
+
+/** Outcome of a Markdown export */
+export interface MarkdownExportReport {
+  directory: string;
+  notes_exported: number;
+  /** Files copied out of the blob store */
+  attachments_exported: number;
+  /** Problems that didn't stop the export (e.g. a missing blob) */
+  errors: string[];
+}
+
+/** Outcome of an HTML export */
+export interface HtmlExportReport {
+  /** The page written */
+  path: string;
+  notes_exported: number;
+  /** Images inlined as data URIs */
+  images_embedded: number;
+  /** Problems that didn't stop the export (e.g. a missing blob) */
+  errors: string[];
+}
+
+/** Outcome of a bundle export */
+export interface BundleExportReport {
+  /** The bundle written */
+  path: string;
+  notes_exported: number;
+  /** Files written to blobs/ */
+  blobs_exported: number;
+  /** Problems that didn't stop the export (e.g. a missing blob) */
+  errors: string[];
 }
 
 /** App info from backend */
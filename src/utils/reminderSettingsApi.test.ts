@@ -31,6 +31,8 @@ describe('reminderSettingsApi', () => {
         shake_duration: 600,
         glow_enabled: true,
         sound_type: 'whoosh',
+        missed_policy: 'batch',
+        missed_skip_after_hours: 24,
       };
 
       vi.mocked(invoke).mockResolvedValue(mockSettings);
@@ -50,6 +52,8 @@ describe('reminderSettingsApi', () => {
         shake_duration: 800,
         glow_enabled: false,
         sound_type: 'whoosh',
+        missed_policy: 'batch',
+        missed_skip_after_hours: 24,
       };
 
       vi.mocked(invoke).mockResolvedValue(mockSettings);
@@ -74,6 +78,8 @@ describe('reminderSettingsApi', () => {
         shake_duration: 400,
         glow_enabled: true,
         sound_type: 'whoosh',
+        missed_policy: 'batch',
+        missed_skip_after_hours: 24,
       };
 
       await updateReminderSettings(newSettings);
@@ -94,6 +100,8 @@ describe('reminderSettingsApi', () => {
         shake_duration: 600,
         glow_enabled: true,
         sound_type: 'whoosh',
+        missed_policy: 'batch',
+        missed_skip_after_hours: 24,
       };
 
       await expect(updateReminderSettings(settings)).rejects.toThrow('Failed to save settings');
@@ -111,6 +119,8 @@ describe('reminderSettingsApi', () => {
       expect(defaults.shake_duration).toBe(600);
       expect(defaults.glow_enabled).toBe(true);
       expect(defaults.sound_type).toBe('whoosh');
+      expect(defaults.missed_policy).toBe('batch');
+      expect(defaults.missed_skip_after_hours).toBe(24);
     });
 
     it('should return a new object each time', () => {
@@ -134,6 +144,8 @@ describe('reminderSettingsApi', () => {
         shake_duration: 600,
         glow_enabled: true,
         sound_type: 'whoosh',
+        missed_policy: 'batch',
+        missed_skip_after_hours: 24,
       };
 
       await expect(updateReminderSettings(settings)).resolves.toBeUndefined();
@@ -150,6 +162,8 @@ describe('reminderSettingsApi', () => {
         shake_duration: 200, // minimum valid
         glow_enabled: true,
         sound_type: 'whoosh',
+        missed_policy: 'batch',
+        missed_skip_after_hours: 24,
       };
 
       await expect(updateReminderSettings(settings)).resolves.toBeUndefined();
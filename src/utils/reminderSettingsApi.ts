@@ -32,5 +32,7 @@ export function getDefaultReminderSettings(): ReminderSettings {
     shake_duration: 600,
     glow_enabled: true,
     sound_type: 'whoosh',
+    missed_policy: 'batch',
+    missed_skip_after_hours: 24,
   };
 }
@@ -30,6 +30,14 @@ describe('remindersApi', () => {
         sound_type: null,
         shake_enabled: null,
         glow_enabled: null,
+        recurrence_rule: null,
+        recurrence_start: null,
+        snoozed_until: null,
+        missed_policy: null,
+        missed_skip_after_hours: null,
+        inline_text: null,
+        inline_start: null,
+        inline_end: null,
       };
 
       vi.mocked(invoke).mockResolvedValue(mockReminder);
@@ -70,6 +78,14 @@ describe('remindersApi', () => {
           sound_type: null,
           shake_enabled: null,
           glow_enabled: null,
+          recurrence_rule: null,
+          recurrence_start: null,
+          snoozed_until: null,
+          missed_policy: null,
+          missed_skip_after_hours: null,
+          inline_text: null,
+          inline_start: null,
+          inline_end: null,
         },
         {
           id: 'reminder-2',
@@ -81,6 +97,14 @@ describe('remindersApi', () => {
           sound_type: null,
           shake_enabled: null,
           glow_enabled: null,
+          recurrence_rule: null,
+          recurrence_start: null,
+          snoozed_until: null,
+          missed_policy: null,
+          missed_skip_after_hours: null,
+          inline_text: null,
+          inline_start: null,
+          inline_end: null,
         },
       ];
 